@@ -0,0 +1,20 @@
+// 构建脚本：启用 `grpc` 特性时由 proto/kzg.proto 生成 tonic 代码
+//
+// protoc 由 protoc-bin-vendored 提供，无需在系统中安装。
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=proto/kzg.proto");
+
+    #[cfg(feature = "grpc")]
+    {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("找不到内置的 protoc");
+        std::env::set_var("PROTOC", protoc);
+
+        tonic_build::configure()
+            .build_client(true)
+            .build_server(true)
+            .compile(&["proto/kzg.proto"], &["proto"])
+            .expect("编译 proto/kzg.proto 失败");
+    }
+}
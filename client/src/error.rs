@@ -0,0 +1,157 @@
+//! 客户端错误
+
+use std::time::Duration;
+
+use reqwest::{header, Response, StatusCode};
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// 400 / 413 / 415：请求格式、参数或编码无效，重试无意义
+    #[error("Invalid request ({status}): {message}")]
+    InvalidRequest { status: u16, message: String },
+
+    /// 401：缺少或无效的 API 密钥
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// 403：密钥缺少所需权限或 IP 被拒绝
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    /// 404：任务不存在或属于其他租户
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// 429：超出速率限制，等待后可重试
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        /// 服务端 `Retry-After` 给出的等待时间
+        retry_after: Option<Duration>,
+    },
+
+    /// 429：当日配额已用完，当天内重试无意义
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    /// 5xx：服务端错误，503 同时用于任务队列已满
+    #[error("Server error ({status}): {message}")]
+    Server {
+        status: u16,
+        message: String,
+        retry_after: Option<Duration>,
+    },
+
+    /// 其他非 2xx 状态
+    #[error("Unexpected status ({status}): {message}")]
+    UnexpectedStatus { status: u16, message: String },
+
+    /// 连接、超时等传输错误
+    #[error("Transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// 响应无法解码
+    #[error("Invalid response: {0}")]
+    Decode(String),
+
+    /// 客户端配置或参数无效，请求未发出
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+}
+
+/// 服务端配额耗尽时的错误信息，限流时的 429 另带 `Retry-After`
+const QUOTA_EXCEEDED_MESSAGE: &str = "Daily quota exceeded";
+
+/// 服务端错误响应体
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+impl ClientError {
+    /// 由非 2xx 响应构造错误，优先使用响应体中的错误信息
+    pub(crate) async fn from_response(response: Response) -> Self {
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs);
+        let text = response.text().await.unwrap_or_default();
+        let message = serde_json::from_str::<ErrorBody>(&text)
+            .map(|body| body.error)
+            .unwrap_or(text);
+
+        match status {
+            StatusCode::BAD_REQUEST | StatusCode::PAYLOAD_TOO_LARGE | StatusCode::UNSUPPORTED_MEDIA_TYPE => {
+                ClientError::InvalidRequest {
+                    status: status.as_u16(),
+                    message,
+                }
+            }
+            StatusCode::UNAUTHORIZED => ClientError::Unauthorized(message),
+            StatusCode::FORBIDDEN => ClientError::Forbidden(message),
+            StatusCode::NOT_FOUND => ClientError::NotFound(message),
+            StatusCode::TOO_MANY_REQUESTS if message == QUOTA_EXCEEDED_MESSAGE || retry_after.is_none() => {
+                ClientError::QuotaExceeded(message)
+            }
+            StatusCode::TOO_MANY_REQUESTS => ClientError::RateLimited { message, retry_after },
+            status if status.is_server_error() => ClientError::Server {
+                status: status.as_u16(),
+                message,
+                retry_after,
+            },
+            status => ClientError::UnexpectedStatus {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+
+    /// HTTP 状态码 (传输与解码错误为 `None`)
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::InvalidRequest { status, .. }
+            | ClientError::Server { status, .. }
+            | ClientError::UnexpectedStatus { status, .. } => Some(*status),
+            ClientError::Unauthorized(_) => Some(401),
+            ClientError::Forbidden(_) => Some(403),
+            ClientError::NotFound(_) => Some(404),
+            ClientError::RateLimited { .. } | ClientError::QuotaExceeded(_) => Some(429),
+            ClientError::Transport(e) => e.status().map(|status| status.as_u16()),
+            ClientError::Decode(_) | ClientError::InvalidInput(_) => None,
+        }
+    }
+
+    /// 幂等请求可否重试：限流 (配额耗尽除外)、网关错误、服务不可用以及连接或超时错误
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ClientError::RateLimited { .. } => true,
+            ClientError::Server { status, .. } => matches!(status, 502..=504),
+            ClientError::Transport(e) => e.is_connect() || e.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// 非幂等请求 (任务提交) 可否重试：只在确定服务端未受理时重试
+    pub(crate) fn is_retryable_submission(&self) -> bool {
+        match self {
+            // 限流层与队列在受理前拒绝
+            ClientError::RateLimited { .. } => true,
+            ClientError::Server { status, .. } => *status == 503,
+            ClientError::Transport(e) => e.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// 服务端建议的重试等待时间
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ClientError::RateLimited { retry_after, .. } | ClientError::Server { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
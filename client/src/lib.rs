@@ -0,0 +1,427 @@
+//! 第16章 KZG 服务的异步 Rust 客户端
+//!
+//! 接口与服务端 `GET /api/v1/openapi.json` 描述的一致：
+//!
+//! ```no_run
+//! # async fn run(blob: Vec<u8>) -> Result<(), kzg_service_client::ClientError> {
+//! use kzg_service_client::{ClientConfig, Encoding, KzgServiceClient};
+//!
+//! let client = KzgServiceClient::with_config(
+//!     "http://127.0.0.1:8080",
+//!     ClientConfig {
+//!         api_key: Some("my-key".to_string()),
+//!         encoding: Encoding::Ssz,
+//!         ..ClientConfig::default()
+//!     },
+//! )?;
+//! let commitment = client.commitment(&blob).await?;
+//! let proof = client.proof(&blob, &commitment.commitment).await?;
+//! assert!(client.verify(&blob, &commitment.commitment, &proof.proof).await?.is_valid);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 承诺、证明、验证与 cell 接口可选用 SSZ 二进制编码，省去十六进制带来的一倍体积；批量、任务与
+//! 版本化哈希接口只有 JSON。KZG 运算是纯函数，
+//! 这些请求在限流、网关错误、服务不可用和连接错误时按指数退避重试；任务提交不是幂等的，
+//! 只在确定服务端未受理 (429、503、连接失败) 时重试。
+
+pub mod error;
+pub mod types;
+
+use std::time::Duration;
+
+use reqwest::{header, Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::json;
+
+pub use error::ClientError;
+pub use types::*;
+
+const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+
+/// 承诺、证明、验证与 cell 请求的编码，批量、任务与版本化哈希请求总是 JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Encoding {
+    /// JSON，字节字段为十六进制
+    #[default]
+    Json,
+    /// SSZ (`application/octet-stream`)
+    Ssz,
+}
+
+/// 客户端配置
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// 以 `x-api-key` 头发送的 API 密钥
+    pub api_key: Option<String>,
+    /// 单次请求超时
+    pub timeout: Duration,
+    /// 最大重试次数 (不含首次请求)
+    pub max_retries: u32,
+    /// 首次重试前的等待时间，之后每次翻倍
+    pub initial_backoff: Duration,
+    /// 重试等待时间上限，也用于限制服务端 `Retry-After`
+    pub max_backoff: Duration,
+    /// 承诺、证明、验证与 cell 请求的编码
+    pub encoding: Encoding,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            api_key: None,
+            timeout: Duration::from_secs(30),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            encoding: Encoding::Json,
+        }
+    }
+}
+
+/// 重试策略
+#[derive(Debug, Clone, Copy)]
+enum Retry {
+    Idempotent,
+    Submission,
+}
+
+/// KZG 服务客户端，克隆开销很小，内部共享连接池
+#[derive(Debug, Clone)]
+pub struct KzgServiceClient {
+    http: reqwest::Client,
+    base_url: String,
+    config: ClientConfig,
+}
+
+impl KzgServiceClient {
+    /// 以默认配置连接 `base_url` (如 `http://127.0.0.1:8080`)
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        Self::with_config(base_url, ClientConfig::default())
+    }
+
+    pub fn with_config(base_url: &str, config: ClientConfig) -> Result<Self, ClientError> {
+        let url = reqwest::Url::parse(base_url)
+            .map_err(|e| ClientError::InvalidInput(format!("Invalid base URL {}: {}", base_url, e)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(ClientError::InvalidInput(format!("Unsupported URL scheme: {}", url.scheme())));
+        }
+
+        let mut headers = header::HeaderMap::new();
+        if let Some(key) = &config.api_key {
+            let value = header::HeaderValue::from_str(key)
+                .map_err(|_| ClientError::InvalidInput("API key is not a valid header value".to_string()))?;
+            headers.insert("x-api-key", value);
+        }
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(config.timeout)
+            .build()?;
+
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    // ============================================================================================
+    // EIP-4844
+    // ============================================================================================
+
+    /// 计算 blob 承诺
+    pub async fn commitment(&self, blob: &[u8]) -> Result<Commitment, ClientError> {
+        check_blob(blob)?;
+        match self.config.encoding {
+            Encoding::Json => {
+                let response: CommitmentResponse =
+                    self.post_json("/api/v1/commitment", &json!({ "blob": hex::encode(blob) })).await?;
+                response.try_into()
+            }
+            Encoding::Ssz => Commitment::from_ssz(&self.post_ssz("/api/v1/commitment", blob.to_vec()).await?),
+        }
+    }
+
+    /// 计算 blob 证明
+    pub async fn proof(&self, blob: &[u8], commitment: &[u8; BYTES_PER_POINT]) -> Result<Proof, ClientError> {
+        check_blob(blob)?;
+        match self.config.encoding {
+            Encoding::Json => {
+                let body = json!({ "blob": hex::encode(blob), "commitment": hex::encode(commitment) });
+                let response: ProofResponse = self.post_json("/api/v1/proof", &body).await?;
+                response.try_into()
+            }
+            Encoding::Ssz => Proof::from_ssz(&self.post_ssz("/api/v1/proof", [blob, commitment].concat()).await?),
+        }
+    }
+
+    /// 验证 blob 证明
+    pub async fn verify(
+        &self,
+        blob: &[u8],
+        commitment: &[u8; BYTES_PER_POINT],
+        proof: &[u8; BYTES_PER_POINT],
+    ) -> Result<Verification, ClientError> {
+        check_blob(blob)?;
+        match self.config.encoding {
+            Encoding::Json => {
+                let body = json!({
+                    "blob": hex::encode(blob),
+                    "commitment": hex::encode(commitment),
+                    "proof": hex::encode(proof),
+                });
+                let response: VerificationResponse = self.post_json("/api/v1/verify", &body).await?;
+                response.try_into()
+            }
+            Encoding::Ssz => {
+                let body = [blob, commitment, proof].concat();
+                Verification::from_ssz(&self.post_ssz("/api/v1/verify", body).await?)
+            }
+        }
+    }
+
+    /// 校验交易的 `blob_versioned_hashes` 与 sidecar 承诺是否一致
+    pub async fn validate_versioned_hashes(
+        &self,
+        blob_versioned_hashes: &[[u8; BYTES_PER_HASH]],
+        commitments: &[[u8; BYTES_PER_POINT]],
+    ) -> Result<VersionedHashValidation, ClientError> {
+        let body = json!({
+            "blob_versioned_hashes": blob_versioned_hashes.iter().map(hex::encode).collect::<Vec<_>>(),
+            "commitments": commitments.iter().map(hex::encode).collect::<Vec<_>>(),
+        });
+        self.post_json("/api/v1/versioned-hashes/validate", &body).await
+    }
+
+    /// 批量处理，单个条目失败不影响其他条目
+    pub async fn batch(&self, items: Vec<BatchItem>) -> Result<BatchResponse, ClientError> {
+        self.post_json("/api/v1/batch", &json!({ "requests": items })).await
+    }
+
+    // ============================================================================================
+    // EIP-7594
+    // ============================================================================================
+
+    /// 计算 blob 的全部 cell 及证明
+    pub async fn compute_cells(&self, blob: &[u8]) -> Result<Cells, ClientError> {
+        check_blob(blob)?;
+        match self.config.encoding {
+            Encoding::Json => {
+                let response: CellsResponse =
+                    self.post_json("/api/v1/cells", &json!({ "blob": hex::encode(blob) })).await?;
+                response.try_into()
+            }
+            Encoding::Ssz => Cells::from_ssz(&self.post_ssz("/api/v1/cells", blob.to_vec()).await?),
+        }
+    }
+
+    /// 批量验证 cell 证明，各切片按下标一一对应
+    pub async fn verify_cells(
+        &self,
+        commitments: &[[u8; BYTES_PER_POINT]],
+        cell_indices: &[u64],
+        cells: &[Vec<u8>],
+        proofs: &[[u8; BYTES_PER_POINT]],
+    ) -> Result<CellVerification, ClientError> {
+        match self.config.encoding {
+            Encoding::Json => {
+                let body = json!({
+                    "commitments": commitments.iter().map(hex::encode).collect::<Vec<_>>(),
+                    "cell_indices": cell_indices,
+                    "cells": cells.iter().map(hex::encode).collect::<Vec<_>>(),
+                    "proofs": proofs.iter().map(hex::encode).collect::<Vec<_>>(),
+                });
+                self.post_json("/api/v1/cells/verify", &body).await
+            }
+            Encoding::Ssz => {
+                check_cells(cells)?;
+                let body = ssz_lists(&[
+                    commitments.concat(),
+                    index_list(cell_indices),
+                    cells.concat(),
+                    proofs.concat(),
+                ]);
+                CellVerification::from_ssz(&self.post_ssz("/api/v1/cells/verify", body).await?)
+            }
+        }
+    }
+
+    /// 由至少一半的 cell 恢复全部 cell 及证明
+    pub async fn recover_cells(&self, cell_indices: &[u64], cells: &[Vec<u8>]) -> Result<Cells, ClientError> {
+        match self.config.encoding {
+            Encoding::Json => {
+                let body = json!({
+                    "cell_indices": cell_indices,
+                    "cells": cells.iter().map(hex::encode).collect::<Vec<_>>(),
+                });
+                let response: CellsResponse = self.post_json("/api/v1/cells/recover", &body).await?;
+                response.try_into()
+            }
+            Encoding::Ssz => {
+                check_cells(cells)?;
+                let body = ssz_lists(&[index_list(cell_indices), cells.concat()]);
+                Cells::from_ssz(&self.post_ssz("/api/v1/cells/recover", body).await?)
+            }
+        }
+    }
+
+    // ============================================================================================
+    // 异步任务
+    // ============================================================================================
+
+    /// 提交异步批量任务
+    pub async fn submit_job(&self, request: &JobRequest) -> Result<JobSubmitted, ClientError> {
+        let builder = self.request(Method::POST, "/api/v1/jobs").json(request);
+        decode_json(self.execute(builder, Retry::Submission).await?).await
+    }
+
+    /// 查询任务进度与已完成条目的结果
+    pub async fn job_status(&self, id: &str) -> Result<JobStatus, ClientError> {
+        self.get_json(&format!("/api/v1/jobs/{}", path_segment(id)?)).await
+    }
+
+    /// 轮询直到任务结束
+    pub async fn wait_for_job(&self, id: &str, poll_interval: Duration) -> Result<JobStatus, ClientError> {
+        loop {
+            let status = self.job_status(id).await?;
+            if status.state.is_finished() {
+                return Ok(status);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    // ============================================================================================
+    // 健康检查与文档
+    // ============================================================================================
+
+    /// 服务是否就绪 (`GET /health/ready`)
+    pub async fn ready(&self) -> Result<bool, ClientError> {
+        let response = self.request(Method::GET, "/health/ready").send().await?;
+        Ok(response.status().is_success())
+    }
+
+    /// 服务的 OpenAPI 文档
+    pub async fn openapi(&self) -> Result<serde_json::Value, ClientError> {
+        self.get_json("/api/v1/openapi.json").await
+    }
+
+    // ============================================================================================
+    // 请求与重试
+    // ============================================================================================
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http.request(method, format!("{}{}", self.base_url, path))
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, ClientError> {
+        let builder = self.request(Method::GET, path);
+        decode_json(self.execute(builder, Retry::Idempotent).await?).await
+    }
+
+    async fn post_json<B: Serialize + ?Sized, T: DeserializeOwned>(&self, path: &str, body: &B) -> Result<T, ClientError> {
+        let builder = self.request(Method::POST, path).json(body);
+        decode_json(self.execute(builder, Retry::Idempotent).await?).await
+    }
+
+    async fn post_ssz(&self, path: &str, body: Vec<u8>) -> Result<Vec<u8>, ClientError> {
+        let builder = self
+            .request(Method::POST, path)
+            .header(header::CONTENT_TYPE, APPLICATION_OCTET_STREAM)
+            .header(header::ACCEPT, APPLICATION_OCTET_STREAM)
+            .body(body);
+        let response = self.execute(builder, Retry::Idempotent).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// 发送请求，按策略重试直到成功、遇到不可重试的错误或用完重试次数
+    async fn execute(&self, builder: RequestBuilder, retry: Retry) -> Result<Response, ClientError> {
+        let mut attempt = 0;
+        loop {
+            // 请求体均为内存中的字节，总能克隆
+            let request = builder
+                .try_clone()
+                .ok_or_else(|| ClientError::InvalidInput("Request body cannot be retried".to_string()))?;
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => ClientError::from_response(response).await,
+                Err(e) => ClientError::Transport(e),
+            };
+
+            let retryable = match retry {
+                Retry::Idempotent => error.is_retryable(),
+                Retry::Submission => error.is_retryable_submission(),
+            };
+            if !retryable || attempt >= self.config.max_retries {
+                return Err(error);
+            }
+            tokio::time::sleep(self.backoff(attempt, &error)).await;
+            attempt += 1;
+        }
+    }
+
+    /// 第 `attempt` 次重试前的等待时间：指数退避，服务端给出 `Retry-After` 时取两者较大值
+    fn backoff(&self, attempt: u32, error: &ClientError) -> Duration {
+        let exponential = self.config.initial_backoff.saturating_mul(1 << attempt.min(16));
+        error
+            .retry_after()
+            .map_or(exponential, |retry_after| retry_after.max(exponential))
+            .min(self.config.max_backoff)
+    }
+}
+
+fn check_blob(blob: &[u8]) -> Result<(), ClientError> {
+    if blob.len() != BYTES_PER_BLOB {
+        return Err(ClientError::InvalidInput(format!(
+            "Blob must be {} bytes, got {}",
+            BYTES_PER_BLOB,
+            blob.len()
+        )));
+    }
+    Ok(())
+}
+
+/// SSZ 列表中的 cell 定长，长度不对会让服务端错位解析后续字段
+fn check_cells(cells: &[Vec<u8>]) -> Result<(), ClientError> {
+    match cells.iter().position(|cell| cell.len() != BYTES_PER_CELL) {
+        Some(i) => Err(ClientError::InvalidInput(format!(
+            "Cell {} must be {} bytes, got {}",
+            i,
+            BYTES_PER_CELL,
+            cells[i].len()
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn index_list(cell_indices: &[u64]) -> Vec<u8> {
+    cell_indices.iter().flat_map(|index| index.to_le_bytes()).collect()
+}
+
+/// 将字符串编码为单个路径段：RFC 3986 非保留字符以外的字节均百分号编码，
+/// `/`、`?`、`#` 不会改变请求的路径。空串和 `.`、`..` 在 URL 规范化时会被合并，无法作为路径段
+fn path_segment(text: &str) -> Result<String, ClientError> {
+    if matches!(text, "" | "." | "..") {
+        return Err(ClientError::InvalidInput(format!("Invalid path segment: {:?}", text)));
+    }
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    Ok(out)
+}
+
+async fn decode_json<T: DeserializeOwned>(response: Response) -> Result<T, ClientError> {
+    let bytes = response.bytes().await?;
+    serde_json::from_slice(&bytes).map_err(|e| ClientError::Decode(e.to_string()))
+}
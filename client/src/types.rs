@@ -0,0 +1,439 @@
+//! 请求与响应类型
+//!
+//! 与服务端 `/api/v1/openapi.json` 中的模式一致。JSON 中的字节字段为十六进制字符串，
+//! 承诺、证明、验证与 cell 的结果 (包括批量与任务中的条目结果) 在客户端解码为定长字节数组。
+
+use serde::{Deserialize, Serialize};
+
+use crate::ClientError;
+
+/// blob 字节数 (4096 个 32 字节域元素)
+pub const BYTES_PER_BLOB: usize = 131072;
+/// 承诺与证明的字节数 (压缩 G1 点)
+pub const BYTES_PER_POINT: usize = 48;
+/// 版本化哈希的字节数
+pub const BYTES_PER_HASH: usize = 32;
+/// cell 字节数 (64 个 32 字节域元素)
+pub const BYTES_PER_CELL: usize = 2048;
+/// 扩展后的 blob 所含的 cell 数
+pub const CELLS_PER_EXT_BLOB: usize = 128;
+
+// ================================================================================================
+// 单项操作
+// ================================================================================================
+
+/// blob 承诺
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commitment {
+    pub commitment: [u8; BYTES_PER_POINT],
+    pub versioned_hash: [u8; BYTES_PER_HASH],
+    pub processing_time_ms: u64,
+}
+
+/// blob 证明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof {
+    pub proof: [u8; BYTES_PER_POINT],
+    pub processing_time_ms: u64,
+}
+
+/// 证明验证结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    pub is_valid: bool,
+    pub versioned_hash: [u8; BYTES_PER_HASH],
+    pub processing_time_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CommitmentResponse {
+    commitment: String,
+    versioned_hash: String,
+    processing_time_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ProofResponse {
+    proof: String,
+    processing_time_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct VerificationResponse {
+    is_valid: bool,
+    versioned_hash: String,
+    processing_time_ms: u64,
+}
+
+impl TryFrom<CommitmentResponse> for Commitment {
+    type Error = ClientError;
+
+    fn try_from(response: CommitmentResponse) -> Result<Self, ClientError> {
+        Ok(Self {
+            commitment: hex_array("commitment", &response.commitment)?,
+            versioned_hash: hex_array("versioned_hash", &response.versioned_hash)?,
+            processing_time_ms: response.processing_time_ms,
+        })
+    }
+}
+
+impl TryFrom<ProofResponse> for Proof {
+    type Error = ClientError;
+
+    fn try_from(response: ProofResponse) -> Result<Self, ClientError> {
+        Ok(Self {
+            proof: hex_array("proof", &response.proof)?,
+            processing_time_ms: response.processing_time_ms,
+        })
+    }
+}
+
+impl TryFrom<VerificationResponse> for Verification {
+    type Error = ClientError;
+
+    fn try_from(response: VerificationResponse) -> Result<Self, ClientError> {
+        Ok(Self {
+            is_valid: response.is_valid,
+            versioned_hash: hex_array("versioned_hash", &response.versioned_hash)?,
+            processing_time_ms: response.processing_time_ms,
+        })
+    }
+}
+
+// ================================================================================================
+// SSZ 编码
+// ================================================================================================
+//
+// 各响应的字段均为定长，SSZ 编码就是字段的顺序拼接 (整数为小端序)：
+//
+// CommitmentResponse       = commitment (48) || versioned_hash (32) || processing_time_ms (u64)
+// ProofResponse            = proof (48) || processing_time_ms (u64)
+// VerificationResponse     = is_valid (1) || versioned_hash (32) || processing_time_ms (u64)
+// CellsResponse            = cells (CELLS_PER_EXT_BLOB × BYTES_PER_CELL) || proofs (CELLS_PER_EXT_BLOB × 48)
+//                            || processing_time_ms (u64)
+// CellVerificationResponse = is_valid (1) || cell_count (u64) || processing_time_ms (u64)
+//
+// 含列表的 cell 请求先写各字段的 4 字节小端偏移量，再依次写列表内容，见 `ssz_lists`。
+
+impl Commitment {
+    pub(crate) fn from_ssz(bytes: &[u8]) -> Result<Self, ClientError> {
+        let mut reader = SszReader::new("CommitmentResponse", bytes, BYTES_PER_POINT + BYTES_PER_HASH + 8)?;
+        Ok(Self {
+            commitment: reader.array(),
+            versioned_hash: reader.array(),
+            processing_time_ms: u64::from_le_bytes(reader.array()),
+        })
+    }
+}
+
+impl Proof {
+    pub(crate) fn from_ssz(bytes: &[u8]) -> Result<Self, ClientError> {
+        let mut reader = SszReader::new("ProofResponse", bytes, BYTES_PER_POINT + 8)?;
+        Ok(Self {
+            proof: reader.array(),
+            processing_time_ms: u64::from_le_bytes(reader.array()),
+        })
+    }
+}
+
+impl Verification {
+    pub(crate) fn from_ssz(bytes: &[u8]) -> Result<Self, ClientError> {
+        let mut reader = SszReader::new("VerificationResponse", bytes, 1 + BYTES_PER_HASH + 8)?;
+        Ok(Self {
+            is_valid: reader.boolean()?,
+            versioned_hash: reader.array(),
+            processing_time_ms: u64::from_le_bytes(reader.array()),
+        })
+    }
+}
+
+/// 按顺序读取定长字段，长度已在构造时检查
+struct SszReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> SszReader<'a> {
+    fn new(container: &str, bytes: &'a [u8], expected: usize) -> Result<Self, ClientError> {
+        if bytes.len() != expected {
+            return Err(ClientError::Decode(format!(
+                "{} must be {} bytes, got {}",
+                container,
+                expected,
+                bytes.len()
+            )));
+        }
+        Ok(Self { bytes })
+    }
+
+    fn take(&mut self, len: usize) -> &'a [u8] {
+        let (field, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        field
+    }
+
+    fn array<const N: usize>(&mut self) -> [u8; N] {
+        self.take(N).try_into().expect("length checked in SszReader::new")
+    }
+
+    fn boolean(&mut self) -> Result<bool, ClientError> {
+        match self.array() {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            [other] => Err(ClientError::Decode(format!("Invalid SSZ boolean: {}", other))),
+        }
+    }
+}
+
+/// 含变长列表的容器：先写各字段的偏移量 (u32 小端)，再依次写字段内容
+pub(crate) fn ssz_lists(fields: &[Vec<u8>]) -> Vec<u8> {
+    let header = 4 * fields.len();
+    let mut out = Vec::with_capacity(header + fields.iter().map(Vec::len).sum::<usize>());
+    let mut offset = header;
+    for field in fields {
+        out.extend_from_slice(&(offset as u32).to_le_bytes());
+        offset += field.len();
+    }
+    for field in fields {
+        out.extend_from_slice(field);
+    }
+    out
+}
+
+// ================================================================================================
+// EIP-7594 cell
+// ================================================================================================
+
+/// 一个 blob 的全部 cell 及证明 (计算或恢复的结果)，按 cell 索引排列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cells {
+    pub cells: Vec<Vec<u8>>,
+    pub proofs: Vec<[u8; BYTES_PER_POINT]>,
+    pub processing_time_ms: u64,
+}
+
+/// cell 证明批量验证结果
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct CellVerification {
+    pub is_valid: bool,
+    pub cell_count: usize,
+    pub processing_time_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CellsResponse {
+    cells: Vec<String>,
+    proofs: Vec<String>,
+    processing_time_ms: u64,
+}
+
+impl Cells {
+    pub(crate) fn from_ssz(bytes: &[u8]) -> Result<Self, ClientError> {
+        let expected = CELLS_PER_EXT_BLOB * (BYTES_PER_CELL + BYTES_PER_POINT) + 8;
+        let mut reader = SszReader::new("CellsResponse", bytes, expected)?;
+        let cells = (0..CELLS_PER_EXT_BLOB).map(|_| reader.take(BYTES_PER_CELL).to_vec()).collect();
+        let proofs = (0..CELLS_PER_EXT_BLOB).map(|_| reader.array()).collect();
+        Ok(Self {
+            cells,
+            proofs,
+            processing_time_ms: u64::from_le_bytes(reader.array()),
+        })
+    }
+}
+
+impl CellVerification {
+    pub(crate) fn from_ssz(bytes: &[u8]) -> Result<Self, ClientError> {
+        let mut reader = SszReader::new("CellVerificationResponse", bytes, 1 + 8 + 8)?;
+        Ok(Self {
+            is_valid: reader.boolean()?,
+            cell_count: u64::from_le_bytes(reader.array()) as usize,
+            processing_time_ms: u64::from_le_bytes(reader.array()),
+        })
+    }
+}
+
+impl TryFrom<CellsResponse> for Cells {
+    type Error = ClientError;
+
+    fn try_from(response: CellsResponse) -> Result<Self, ClientError> {
+        let cells = response
+            .cells
+            .iter()
+            .map(|cell| hex::decode(cell).map_err(|e| ClientError::Decode(format!("Invalid cell hex: {}", e))))
+            .collect::<Result<_, _>>()?;
+        let proofs = response
+            .proofs
+            .iter()
+            .map(|proof| hex_array("proof", proof))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            cells,
+            proofs,
+            processing_time_ms: response.processing_time_ms,
+        })
+    }
+}
+
+// ================================================================================================
+// 版本化哈希、批量与任务
+// ================================================================================================
+
+/// 交易版本化哈希校验结果
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct VersionedHashValidation {
+    pub is_valid: bool,
+    /// 由承诺计算出的版本化哈希 (十六进制)
+    pub versioned_hashes: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// 批量或任务中的一个条目，字节字段为十六进制
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BatchItem {
+    pub id: String,
+    /// `commitment`、`proof` 或 `verification`
+    pub operation: String,
+    pub blob: String,
+    pub commitment: Option<String>,
+    pub proof: Option<String>,
+}
+
+impl BatchItem {
+    /// 承诺条目
+    pub fn commitment(id: impl Into<String>, blob: &[u8]) -> Self {
+        Self {
+            id: id.into(),
+            operation: "commitment".to_string(),
+            blob: hex::encode(blob),
+            commitment: None,
+            proof: None,
+        }
+    }
+
+    /// 证明条目
+    pub fn proof(id: impl Into<String>, blob: &[u8], commitment: &[u8; BYTES_PER_POINT]) -> Self {
+        Self {
+            operation: "proof".to_string(),
+            commitment: Some(hex::encode(commitment)),
+            ..Self::commitment(id, blob)
+        }
+    }
+
+    /// 验证条目
+    pub fn verification(
+        id: impl Into<String>,
+        blob: &[u8],
+        commitment: &[u8; BYTES_PER_POINT],
+        proof: &[u8; BYTES_PER_POINT],
+    ) -> Self {
+        Self {
+            operation: "verification".to_string(),
+            proof: Some(hex::encode(proof)),
+            ..Self::proof(id, blob, commitment)
+        }
+    }
+}
+
+/// 批量或任务条目的输出，与条目的 `operation` 对应
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "BatchOutputResponse")]
+pub enum BatchOutput {
+    Commitment(Commitment),
+    Proof(Proof),
+    Verification(Verification),
+}
+
+/// 服务端的条目输出不带类型标签，按字段区分
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BatchOutputResponse {
+    Commitment(CommitmentResponse),
+    Proof(ProofResponse),
+    Verification(VerificationResponse),
+}
+
+impl TryFrom<BatchOutputResponse> for BatchOutput {
+    type Error = ClientError;
+
+    fn try_from(response: BatchOutputResponse) -> Result<Self, ClientError> {
+        Ok(match response {
+            BatchOutputResponse::Commitment(response) => Self::Commitment(response.try_into()?),
+            BatchOutputResponse::Proof(response) => Self::Proof(response.try_into()?),
+            BatchOutputResponse::Verification(response) => Self::Verification(response.try_into()?),
+        })
+    }
+}
+
+/// 单个条目的结果，失败的条目不影响其他条目
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct BatchResult {
+    pub id: String,
+    pub success: bool,
+    pub result: Option<BatchOutput>,
+    pub error: Option<String>,
+}
+
+/// 批量请求结果
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct BatchResponse {
+    pub results: Vec<BatchResult>,
+    pub total_processing_time_ms: u64,
+}
+
+/// 异步任务请求
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRequest {
+    pub requests: Vec<BatchItem>,
+    /// 任务结束后以 POST 接收最终状态的地址
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+}
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl JobState {
+    /// 任务是否已结束
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobState::Completed | JobState::Failed)
+    }
+}
+
+/// 任务提交结果
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct JobSubmitted {
+    pub id: String,
+    pub state: JobState,
+    pub total: usize,
+}
+
+/// 任务状态快照
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct JobStatus {
+    pub id: String,
+    pub tenant: String,
+    pub state: JobState,
+    pub total: usize,
+    pub completed: usize,
+    /// 已完成条目的结果，顺序与请求一致
+    pub results: Vec<BatchResult>,
+    pub error: Option<String>,
+    pub callback_url: Option<String>,
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+}
+
+fn hex_array<const N: usize>(field: &str, text: &str) -> Result<[u8; N], ClientError> {
+    let bytes = hex::decode(text.strip_prefix("0x").unwrap_or(text))
+        .map_err(|e| ClientError::Decode(format!("Invalid {} hex: {}", field, e)))?;
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| ClientError::Decode(format!("{} must be {} bytes, got {}", field, N, len)))
+}
@@ -0,0 +1,335 @@
+// KZG 服务客户端测试
+// 以本地模拟服务覆盖重试策略、SSZ 编解码 (含 cell 接口)、错误映射 (含配额耗尽不重试)、任务结果解码、
+// 任务标识的路径编码与认证头
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use kzg_service_client::{
+    BatchItem, BatchOutput, ClientConfig, ClientError, Encoding, JobRequest, JobState, KzgServiceClient, BYTES_PER_BLOB,
+    BYTES_PER_CELL, CELLS_PER_EXT_BLOB,
+};
+use serde_json::json;
+
+#[derive(Clone, Default)]
+struct Calls(Arc<AtomicUsize>);
+
+impl Calls {
+    /// 记录一次调用并返回这是第几次 (从 1 开始)
+    fn next(&self) -> usize {
+        self.0.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+fn error(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "error": message, "timestamp": 0 }))).into_response()
+}
+
+/// 前两次返回 503，之后以 SSZ 返回承诺
+async fn flaky_commitment(State(calls): State<Calls>, headers: HeaderMap, body: Bytes) -> Response {
+    if calls.next() <= 2 {
+        return error(StatusCode::SERVICE_UNAVAILABLE, "Service unavailable");
+    }
+    assert_eq!(headers[header::CONTENT_TYPE], "application/octet-stream");
+    assert_eq!(headers["x-api-key"], "secret");
+    assert_eq!(body.len(), BYTES_PER_BLOB);
+
+    let mut ssz = vec![0xaa; 48];
+    ssz.extend_from_slice(&[0x01; 32]);
+    ssz.extend_from_slice(&7u64.to_le_bytes());
+    ([(header::CONTENT_TYPE, "application/octet-stream")], ssz).into_response()
+}
+
+async fn rate_limited(State(calls): State<Calls>) -> Response {
+    calls.next();
+    let mut response = error(StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded");
+    response.headers_mut().insert(header::RETRY_AFTER, 3.into());
+    response
+}
+
+/// 配额耗尽：服务端不带 `Retry-After`
+async fn quota_exceeded(State(calls): State<Calls>) -> Response {
+    calls.next();
+    error(StatusCode::TOO_MANY_REQUESTS, "Daily quota exceeded")
+}
+
+async fn bad_gateway(State(calls): State<Calls>) -> Response {
+    calls.next();
+    error(StatusCode::BAD_GATEWAY, "upstream")
+}
+
+async fn job_status(Path(id): Path<String>) -> Response {
+    if id != "job-1" {
+        return error(StatusCode::NOT_FOUND, &format!("Not found: job {}", id));
+    }
+    Json(json!({
+        "id": id,
+        "tenant": "ci",
+        "state": "completed",
+        "total": 3,
+        "completed": 3,
+        "results": [
+            {
+                "id": "a",
+                "success": true,
+                "result": { "commitment": "aa".repeat(48), "versioned_hash": "01".repeat(32), "processing_time_ms": 4 },
+                "error": null
+            },
+            { "id": "b", "success": true, "result": { "is_valid": false, "versioned_hash": "01".repeat(32), "processing_time_ms": 2 }, "error": null },
+            { "id": "c", "success": false, "result": null, "error": "Invalid blob" }
+        ],
+        "error": null,
+        "callback_url": null,
+        "created_at": 1,
+        "finished_at": 2
+    }))
+    .into_response()
+}
+
+fn ssz(body: Vec<u8>) -> Response {
+    ([(header::CONTENT_TYPE, "application/octet-stream")], body).into_response()
+}
+
+/// 第 `i` 个 cell 的每个字节都是 `i`，证明的每个字节都是 `i + 1`
+fn all_cells_ssz() -> Response {
+    let mut body = Vec::new();
+    for i in 0..CELLS_PER_EXT_BLOB {
+        body.extend_from_slice(&[i as u8; BYTES_PER_CELL]);
+    }
+    for i in 0..CELLS_PER_EXT_BLOB {
+        body.extend_from_slice(&[i as u8 + 1; 48]);
+    }
+    body.extend_from_slice(&9u64.to_le_bytes());
+    ssz(body)
+}
+
+/// 读出 SSZ 容器头部的各字段偏移量
+fn offsets<const N: usize>(body: &[u8]) -> [usize; N] {
+    std::array::from_fn(|i| u32::from_le_bytes(body[4 * i..4 * i + 4].try_into().unwrap()) as usize)
+}
+
+async fn compute_cells(headers: HeaderMap, body: Bytes) -> Response {
+    assert_eq!(headers[header::CONTENT_TYPE], "application/octet-stream");
+    assert_eq!(headers[header::ACCEPT], "application/octet-stream");
+    assert_eq!(body.len(), BYTES_PER_BLOB);
+    all_cells_ssz()
+}
+
+/// 一个承诺、索引 [3, 5]、两个 cell 与两个证明
+async fn verify_cells(body: Bytes) -> Response {
+    let header = 16;
+    let cells = header + 48 + 16;
+    let proofs = cells + 2 * BYTES_PER_CELL;
+    assert_eq!(offsets::<4>(&body), [header, header + 48, cells, proofs]);
+    assert_eq!(body.len(), proofs + 2 * 48);
+    assert_eq!(&body[header + 48..cells], [3u64.to_le_bytes(), 5u64.to_le_bytes()].concat());
+
+    let mut response = vec![1];
+    response.extend_from_slice(&2u64.to_le_bytes());
+    response.extend_from_slice(&6u64.to_le_bytes());
+    ssz(response)
+}
+
+/// 前 64 个 cell 的索引与内容
+async fn recover_cells(body: Bytes) -> Response {
+    let cells = 8 + 64 * 8;
+    assert_eq!(offsets::<2>(&body), [8, cells]);
+    assert_eq!(body.len(), cells + 64 * BYTES_PER_CELL);
+    assert_eq!(&body[8 + 63 * 8..cells], 63u64.to_le_bytes());
+    assert!(body[cells + 63 * BYTES_PER_CELL..].iter().all(|byte| *byte == 63));
+    all_cells_ssz()
+}
+
+async fn proof() -> Response {
+    Json(json!({ "proof": "zz", "processing_time_ms": 1 })).into_response()
+}
+
+async fn serve() -> (SocketAddr, Calls) {
+    let calls = Calls::default();
+    let app = Router::new()
+        .route("/api/v1/commitment", post(flaky_commitment))
+        .route("/api/v1/verify", post(rate_limited))
+        .route("/api/v1/batch", post(bad_gateway))
+        .route("/api/v1/jobs", post(bad_gateway))
+        .route("/api/v1/jobs/:id", get(job_status))
+        .route("/api/v1/proof", post(proof))
+        .route("/api/v1/cells", post(compute_cells))
+        .route("/api/v1/cells/verify", post(verify_cells))
+        .route("/api/v1/cells/recover", post(recover_cells))
+        .with_state(calls.clone());
+    let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    (addr, calls)
+}
+
+fn client(addr: SocketAddr, config: ClientConfig) -> KzgServiceClient {
+    KzgServiceClient::with_config(
+        &format!("http://{}/", addr),
+        ClientConfig {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            ..config
+        },
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_retries_and_ssz_decoding() {
+    let (addr, calls) = serve().await;
+    let client = client(
+        addr,
+        ClientConfig {
+            api_key: Some("secret".to_string()),
+            encoding: Encoding::Ssz,
+            ..ClientConfig::default()
+        },
+    );
+
+    let commitment = client.commitment(&vec![0u8; BYTES_PER_BLOB]).await.unwrap();
+    assert_eq!(calls.count(), 3);
+    assert_eq!(commitment.commitment, [0xaa; 48]);
+    assert_eq!(commitment.versioned_hash, [0x01; 32]);
+    assert_eq!(commitment.processing_time_ms, 7);
+
+    // 客户端先检查 blob 长度，不发出请求
+    assert!(matches!(client.commitment(&[0u8; 10]).await, Err(ClientError::InvalidInput(_))));
+    assert_eq!(calls.count(), 3);
+}
+
+#[tokio::test]
+async fn test_cells_ssz() {
+    let (addr, _) = serve().await;
+    let client = client(
+        addr,
+        ClientConfig {
+            encoding: Encoding::Ssz,
+            ..ClientConfig::default()
+        },
+    );
+
+    let cells = client.compute_cells(&vec![0u8; BYTES_PER_BLOB]).await.unwrap();
+    assert_eq!((cells.cells.len(), cells.proofs.len()), (CELLS_PER_EXT_BLOB, CELLS_PER_EXT_BLOB));
+    assert_eq!(cells.processing_time_ms, 9);
+    assert_eq!(cells.cells[7], vec![7; BYTES_PER_CELL]);
+    assert_eq!(cells.proofs[7], [8; 48]);
+
+    let verification = client
+        .verify_cells(&[[0xaa; 48]], &[3, 5], &cells.cells[3..5], &cells.proofs[3..5])
+        .await
+        .unwrap();
+    assert!(verification.is_valid);
+    assert_eq!((verification.cell_count, verification.processing_time_ms), (2, 6));
+
+    let indices: Vec<u64> = (0..64).collect();
+    let recovered = client.recover_cells(&indices, &cells.cells[..64]).await.unwrap();
+    assert_eq!(recovered, cells);
+
+    // 定长 cell 在客户端检查，不发出请求
+    assert!(matches!(client.recover_cells(&[0], &[vec![0; 10]]).await, Err(ClientError::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn test_error_mapping() {
+    let (addr, calls) = serve().await;
+    let client = client(
+        addr,
+        ClientConfig {
+            max_retries: 2,
+            ..ClientConfig::default()
+        },
+    );
+    let blob = vec![0u8; BYTES_PER_BLOB];
+
+    // 429 重试用完后返回，携带 Retry-After
+    let error = client.verify(&blob, &[0; 48], &[0; 48]).await.unwrap_err();
+    assert!(matches!(&error, ClientError::RateLimited { retry_after: Some(d), .. } if *d == Duration::from_secs(3)));
+    assert_eq!((error.status(), calls.count()), (Some(429), 3));
+
+    let error = client.job_status("missing").await.unwrap_err();
+    assert!(matches!(&error, ClientError::NotFound(message) if message.contains("missing")));
+    assert!(!error.is_retryable());
+
+    // 标识整体编码为一个路径段，服务端解码后原样收到
+    let error = client.job_status("job/1 x?#%").await.unwrap_err();
+    assert!(matches!(&error, ClientError::NotFound(message) if message == "Not found: job job/1 x?#%"));
+    assert!(matches!(client.job_status("..").await, Err(ClientError::InvalidInput(_))));
+
+    assert!(matches!(client.proof(&blob, &[0; 48]).await, Err(ClientError::Decode(_))));
+
+    let status = client.wait_for_job("job-1", Duration::from_millis(1)).await.unwrap();
+    assert_eq!((status.state, status.results.len()), (JobState::Completed, 3));
+    assert!(matches!(
+        &status.results[0].result,
+        Some(BatchOutput::Commitment(commitment)) if commitment.commitment == [0xaa; 48] && commitment.processing_time_ms == 4
+    ));
+    assert!(matches!(&status.results[1].result, Some(BatchOutput::Verification(verification)) if !verification.is_valid));
+    assert_eq!((status.results[2].result.as_ref(), status.results[2].error.as_deref()), (None, Some("Invalid blob")));
+}
+
+#[tokio::test]
+async fn test_job_submission_is_not_retried_on_gateway_errors() {
+    let (addr, calls) = serve().await;
+    let client = client(addr, ClientConfig::default());
+    let blob = vec![0u8; BYTES_PER_BLOB];
+
+    // 幂等的批量请求在 502 时重试
+    let error = client.batch(vec![BatchItem::commitment("a", &blob)]).await.unwrap_err();
+    assert!(matches!(error, ClientError::Server { status: 502, .. }));
+    assert_eq!(calls.count(), 4);
+
+    // 任务提交可能已被受理，502 时不重试
+    let request = JobRequest {
+        requests: vec![BatchItem::commitment("a", &blob)],
+        callback_url: None,
+    };
+    assert!(client.submit_job(&request).await.is_err());
+    assert_eq!(calls.count(), 5);
+}
+
+#[tokio::test]
+async fn test_quota_exceeded_is_not_retried() {
+    let calls = Calls::default();
+    let app = Router::new()
+        .route("/api/v1/verify", post(quota_exceeded))
+        .route("/api/v1/jobs", post(quota_exceeded))
+        .with_state(calls.clone());
+    let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+    let addr = server.local_addr();
+    tokio::spawn(server);
+    let client = client(addr, ClientConfig::default());
+    let blob = vec![0u8; BYTES_PER_BLOB];
+
+    let error = client.verify(&blob, &[0; 48], &[0; 48]).await.unwrap_err();
+    assert!(matches!(&error, ClientError::QuotaExceeded(message) if message == "Daily quota exceeded"));
+    assert_eq!((error.status(), error.retry_after(), calls.count()), (Some(429), None, 1));
+    assert!(!error.is_retryable());
+
+    let request = JobRequest {
+        requests: vec![BatchItem::commitment("a", &blob)],
+        callback_url: None,
+    };
+    assert!(matches!(client.submit_job(&request).await, Err(ClientError::QuotaExceeded(_))));
+    assert_eq!(calls.count(), 2);
+}
+
+#[test]
+fn test_invalid_base_url() {
+    assert!(matches!(KzgServiceClient::new("not a url"), Err(ClientError::InvalidInput(_))));
+    assert!(matches!(KzgServiceClient::new("ftp://example.com"), Err(ClientError::InvalidInput(_))));
+}
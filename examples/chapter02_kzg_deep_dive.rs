@@ -13,13 +13,10 @@ use kzg::eip_4844::{
     blob_to_kzg_commitment_rust, 
     compute_blob_kzg_proof_rust,
     verify_blob_kzg_proof_rust,
-    FIELD_ELEMENTS_PER_BLOB,
 };
-use kzg::Fr;
-use rust_kzg_blst::eip_4844::load_trusted_setup_filename_rust;
-use rust_kzg_blst::{
-    types::kzg_settings::FsKZGSettings,
-    types::fr::FsFr,
+use rust_kzg_tutorial::{
+    blob::create_test_blob,
+    trusted_setup::load_trusted_setup_from_file,
 };
 use std::time::Instant;
 
@@ -51,82 +48,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-/// 智能加载受信任设置文件
-/// 会尝试多个可能的路径，自动找到文件位置
-fn load_trusted_setup_from_file() -> Result<FsKZGSettings, Box<dyn std::error::Error>> {
-    let possible_paths = [
-        "./assets/trusted_setup.txt",
-        "../assets/trusted_setup.txt", 
-        "../../assets/trusted_setup.txt",
-        "./trusted_setup.txt",
-        "./src/trusted_setup.txt",
-        "../src/trusted_setup.txt",
-    ];
-
-    println!("🔍 搜索受信任设置文件...");
-    for path in &possible_paths {
-        if std::path::Path::new(path).exists() {
-            println!("   ✅ 找到文件: {}", path);
-            return Ok(load_trusted_setup_filename_rust(path)?);
-        } else {
-            println!("   ❌ 未找到: {}", path);
-        }
-    }
-
-    Err(format!(
-        "❌ 未找到受信任设置文件!\n\
-         请确保以下任一路径存在 trusted_setup.txt:\n\
-         {:#?}\n\
-         \n\
-         📥 下载命令:\n\
-         mkdir -p assets\n\
-         cd assets\n\
-         wget https://github.com/ethereum/c-kzg-4844/raw/main/src/trusted_setup.txt",
-        possible_paths
-    ).into())
-}
-
-/// 创建有效的测试 Blob 数据
-/// Blob 必须包含 4096 个有效的域元素
-fn create_test_blob() -> Result<Vec<FsFr>, String> {
-    let mut blob = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
-
-    println!("   🔢 生成 {} 个域元素...", FIELD_ELEMENTS_PER_BLOB);
-    
-    for i in 0..FIELD_ELEMENTS_PER_BLOB {
-        // 创建有效的域元素
-        // 使用递增的小值，确保都在域内
-        let mut bytes = [0u8; 32];
-        
-        // 创建一个有趣的模式，而不是单调递增
-        let value = match i {
-            0..=255 => i as u8,
-            256..=511 => (i - 256) as u8,
-            512..=767 => ((i - 512) * 2) as u8,
-            768..=1023 => ((i - 768) / 2) as u8,
-            _ => (i % 256) as u8,
-        };
-        
-        bytes[31] = value;
-        
-        let element = FsFr::from_bytes(&bytes)
-            .map_err(|e| format!("❌ 创建第 {} 个域元素失败: {}", i, e))?;
-        blob.push(element);
-        
-        // 每完成 1000 个元素就报告进度
-        if (i + 1) % 1000 == 0 {
-            println!("     进度: {}/{}", i + 1, FIELD_ELEMENTS_PER_BLOB);
-        }
-    }
-
-    println!("   ✅ 所有域元素创建完成!");
-    Ok(blob)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use kzg::G1;
+    use kzg::{Fr, G1};
 
     #[test]
     fn test_blob_creation() -> Result<(), String> {
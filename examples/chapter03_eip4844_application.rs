@@ -1,11 +1,10 @@
 use std::time::{Duration, Instant};
-use std::collections::HashMap;
 
-use rust_kzg_blst::{
-    types::{
-        fr::FsFr,
-    },
-    eip_4844::load_trusted_setup_filename_rust,
+use rust_kzg_blst::types::fr::FsFr;
+use rust_kzg_tutorial::{
+    metrics::PerformanceProfiler,
+    trusted_setup::load_trusted_setup_from_file,
+    versioned_hash::kzg_to_versioned_hash,
 };
 
 use kzg::{
@@ -36,44 +35,6 @@ use rayon::prelude::*;
 const MAX_BLOBS_PER_BLOCK: usize = 6;
 const TARGET_SLOT_TIME: Duration = Duration::from_secs(12);
 
-/// 性能分析器，用于收集和分析各种操作的性能数据
-pub struct PerformanceProfiler {
-    metrics: HashMap<String, Vec<Duration>>,
-}
-
-impl PerformanceProfiler {
-    pub fn new() -> Self {
-        Self {
-            metrics: HashMap::new(),
-        }
-    }
-    
-    pub fn record_metric(&mut self, operation: &str, duration: Duration) {
-        self.metrics.entry(operation.to_string()).or_insert_with(Vec::new).push(duration);
-    }
-    
-    pub fn print_performance_summary(&self) {
-        println!("\n📊 性能分析报告");
-        println!("{}", "=".repeat(50));
-        
-        for (operation, times) in &self.metrics {
-            if times.is_empty() {
-                continue;
-            }
-            
-            let avg_time = times.iter().sum::<Duration>() / times.len() as u32;
-            let min_time = times.iter().min().unwrap();
-            let max_time = times.iter().max().unwrap();
-            
-            println!("🔹 {:<25}: 平均 {:8.2}ms, 范围 [{:6.2}ms - {:6.2}ms]", 
-                    operation, 
-                    avg_time.as_secs_f64() * 1000.0,
-                    min_time.as_secs_f64() * 1000.0,
-                    max_time.as_secs_f64() * 1000.0);
-        }
-    }
-}
-
 /// 生成测试用的随机 blob 数据
 fn generate_random_blob() -> Result<Vec<FsFr>, String> {
     let mut blob = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
@@ -139,8 +100,7 @@ fn demonstrate_blob_to_commitment() -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "-".repeat(40));
     
     // 加载受信任设置
-    let trusted_setup_path = find_trusted_setup_file()?;
-    let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path)?;
+    let kzg_settings = load_trusted_setup_from_file()?;
     
     // 创建测试 blob
     let blob = create_test_blob()?;
@@ -153,6 +113,7 @@ fn demonstrate_blob_to_commitment() -> Result<(), Box<dyn std::error::Error>> {
     
     println!("   🔹 KZG 承诺计算耗时: {:.2}ms", commit_time.as_secs_f64() * 1000.0);
     println!("   🔹 承诺十六进制表示: {:?}", hex::encode(commitment.to_bytes()));
+    println!("   🔹 版本化哈希: 0x{}", hex::encode(kzg_to_versioned_hash(&commitment.to_bytes())));
     
     // 验证承诺的确定性
     let commitment2 = blob_to_kzg_commitment_rust(&blob, &kzg_settings)?;
@@ -167,8 +128,7 @@ fn demonstrate_proof_generation_verification() -> Result<(), Box<dyn std::error:
     println!("\n🔍 3.3 KZG 证明生成与验证");
     println!("{}", "-".repeat(40));
     
-    let trusted_setup_path = find_trusted_setup_file()?;
-    let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path)?;
+    let kzg_settings = load_trusted_setup_from_file()?;
     
     let blob = create_test_blob()?;
     let commitment = blob_to_kzg_commitment_rust(&blob, &kzg_settings)?;
@@ -203,8 +163,7 @@ fn demonstrate_batch_verification() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n🚀 3.4 批量验证性能优势");
     println!("{}", "-".repeat(40));
     
-    let trusted_setup_path = find_trusted_setup_file()?;
-    let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path)?;
+    let kzg_settings = load_trusted_setup_from_file()?;
     
     // 准备多个 blob 进行批量测试
     let blob_count = MAX_BLOBS_PER_BLOCK;
@@ -261,8 +220,7 @@ fn demonstrate_parallel_performance() -> Result<(), Box<dyn std::error::Error>>
     println!("\n⚖️ 3.5 并行计算性能优势");
     println!("{}", "-".repeat(40));
     
-    let trusted_setup_path = find_trusted_setup_file()?;
-    let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path)?;
+    let kzg_settings = load_trusted_setup_from_file()?;
     
     let mut rng = rand::thread_rng();
     let blob_count = 20; // 更多 blob 以显示并行优势
@@ -311,8 +269,7 @@ fn demonstrate_critical_path_analysis() -> Result<(), Box<dyn std::error::Error>
     println!("\n📊 3.6 关键路径性能分析");
     println!("{}", "-".repeat(40));
     
-    let trusted_setup_path = find_trusted_setup_file()?;
-    let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path)?;
+    let kzg_settings = load_trusted_setup_from_file()?;
     
     let mut profiler = PerformanceProfiler::new();
     let iterations = 10; // 多次测试取平均值
@@ -345,14 +302,14 @@ fn demonstrate_critical_path_analysis() -> Result<(), Box<dyn std::error::Error>
     
     // 分析瓶颈
     println!("\n🎯 性能瓶颈分析:");
-    if let (Some(commit_times), Some(proof_times), Some(verify_times)) = (
-        profiler.metrics.get("blob_to_commitment"),
-        profiler.metrics.get("proof_generation"),
-        profiler.metrics.get("proof_verification")
+    if let (Some(commit_stats), Some(proof_stats), Some(verify_stats)) = (
+        profiler.stats("blob_to_commitment"),
+        profiler.stats("proof_generation"),
+        profiler.stats("proof_verification")
     ) {
-        let commit_avg = commit_times.iter().sum::<Duration>() / commit_times.len() as u32;
-        let proof_avg = proof_times.iter().sum::<Duration>() / proof_times.len() as u32;
-        let verify_avg = verify_times.iter().sum::<Duration>() / verify_times.len() as u32;
+        let commit_avg = commit_stats.average;
+        let proof_avg = proof_stats.average;
+        let verify_avg = verify_stats.average;
         
         let total = commit_avg + proof_avg + verify_avg;
         
@@ -377,8 +334,7 @@ fn demonstrate_network_performance_requirements() -> Result<(), Box<dyn std::err
     println!("\n🌐 3.7 网络级性能要求验证");
     println!("{}", "-".repeat(40));
     
-    let trusted_setup_path = find_trusted_setup_file()?;
-    let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path)?;
+    let kzg_settings = load_trusted_setup_from_file()?;
     
     // 模拟最坏情况：满负载区块
     let blobs: Result<Vec<_>, _> = (0..MAX_BLOBS_PER_BLOCK)
@@ -447,26 +403,6 @@ fn demonstrate_network_performance_requirements() -> Result<(), Box<dyn std::err
     Ok(())
 }
 
-/// 寻找受信任设置文件
-fn find_trusted_setup_file() -> Result<String, Box<dyn std::error::Error>> {
-    let possible_paths = [
-        "assets/trusted_setup.txt",
-        "../assets/trusted_setup.txt",
-        "../../rust-kzg/src/trusted_setup.txt",
-        "../rust-kzg/src/trusted_setup.txt",
-        "trusted_setup.txt",
-        "src/trusted_setup.txt",
-    ];
-    
-    for path in &possible_paths {
-        if std::path::Path::new(path).exists() {
-            return Ok(path.to_string());
-        }
-    }
-    
-    Err("无法找到 trusted_setup.txt 文件".into())
-}
-
 /// 主函数：运行所有演示
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🚀 启动 EIP-4844 应用场景演示程序");
@@ -517,8 +453,7 @@ mod tests {
     
     #[test]
     fn test_commitment_deterministic() {
-        let trusted_setup_path = find_trusted_setup_file().unwrap();
-        let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path).unwrap();
+        let kzg_settings = load_trusted_setup_from_file().unwrap();
         
         let blob = create_test_blob().unwrap();
         let commitment1 = blob_to_kzg_commitment_rust(&blob, &kzg_settings).unwrap();
@@ -529,8 +464,7 @@ mod tests {
     
     #[test]
     fn test_proof_verification() {
-        let trusted_setup_path = find_trusted_setup_file().unwrap();
-        let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path).unwrap();
+        let kzg_settings = load_trusted_setup_from_file().unwrap();
         
         let blob = create_test_blob().unwrap();
         let commitment = blob_to_kzg_commitment_rust(&blob, &kzg_settings).unwrap();
@@ -542,8 +476,7 @@ mod tests {
     
     #[test]
     fn test_batch_verification() {
-        let trusted_setup_path = find_trusted_setup_file().unwrap();
-        let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path).unwrap();
+        let kzg_settings = load_trusted_setup_from_file().unwrap();
         
         let mut blobs = Vec::new();
         let mut commitments = Vec::new();
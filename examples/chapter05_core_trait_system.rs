@@ -25,8 +25,8 @@ use rust_kzg_blst::{
         g2::FsG2,
         kzg_settings::FsKZGSettings,
     },
-    eip_4844::load_trusted_setup_filename_rust,
 };
+use rust_kzg_tutorial::trusted_setup::load_trusted_setup_from_file;
 use std::time::Instant;
 
 /// 主函数：演示核心 Trait 系统的设计
@@ -215,9 +215,8 @@ fn demonstrate_kzg_settings_trait() -> Result<(), String> {
     println!("{}", "-".repeat(40));
     
     // 加载受信任设置
-    let trusted_setup_path = find_trusted_setup_file()?;
-    let kzg_settings = load_trusted_setup_filename_rust(&trusted_setup_path)
-        .map_err(|e| format!("加载受信任设置失败: {}", e))?;
+    let kzg_settings = load_trusted_setup_from_file()
+        .map_err(|e| e.to_string())?;
     
     // === 受信任设置信息展示 ===
     println!("📊 受信任设置信息:");
@@ -420,26 +419,6 @@ fn verify_trusted_setup_properties(settings: &FsKZGSettings) -> Result<(), Strin
     Ok(())
 }
 
-/// 寻找受信任设置文件
-fn find_trusted_setup_file() -> Result<String, String> {
-    let possible_paths = [
-        "assets/trusted_setup.txt",
-        "../assets/trusted_setup.txt", 
-        "../../rust-kzg/src/trusted_setup.txt",
-        "../rust-kzg/src/trusted_setup.txt",
-        "trusted_setup.txt",
-        "src/trusted_setup.txt",
-    ];
-    
-    for path in &possible_paths {
-        if std::path::Path::new(path).exists() {
-            return Ok(path.to_string());
-        }
-    }
-    
-    Err("无法找到 trusted_setup.txt 文件".to_string())
-}
-
 /// 辅助函数：字节数组转十六进制字符串
 fn bytes_to_hex(bytes: &[u8]) -> String {
     bytes.iter()
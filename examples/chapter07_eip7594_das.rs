@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use rust_kzg_blst::{
     types::{
@@ -6,72 +6,38 @@ use rust_kzg_blst::{
         g1::FsG1,
         kzg_settings::FsKZGSettings,
     },
-    eip_4844::load_trusted_setup_filename_rust,
     eip_7594::BlstBackend,
 };
+use rust_kzg_tutorial::{
+    blob::create_test_blob,
+    das::{
+        recover_cells_and_proofs, CellVerifier, CellVerifierConfig, CustodyAssignment, DasSimulator, NodeId, Scenario,
+        CELLS_REQUIRED_FOR_RECOVERY, CUSTODY_REQUIREMENT,
+    },
+    trusted_setup::load_trusted_setup_from_file,
+};
 
 use kzg::{
-    DAS,
-    eip_4844::{
-        blob_to_kzg_commitment_rust,
-        FIELD_ELEMENTS_PER_BLOB,
-    },
+    DAS, Fr, G1,
+    eip_4844::blob_to_kzg_commitment_rust,
     eth::{
         FIELD_ELEMENTS_PER_CELL,
         CELLS_PER_EXT_BLOB,
     },
-    Fr,
 };
 
-fn find_trusted_setup_file() -> Result<String, String> {
-    let paths = [
-        "./assets/trusted_setup.txt",
-        "../assets/trusted_setup.txt",
-        "../../assets/trusted_setup.txt",
-        "/workspaces/rust-kzg-tutorial/assets/trusted_setup.txt",
-    ];
-    
-    for path in &paths {
-        if std::path::Path::new(path).exists() {
-            println!("   ✅ 找到文件: {}", path);
-            return Ok(path.to_string());
-        }
-    }
-    
-    Err("未找到受信任设置文件".to_string())
-}
-
-fn create_random_blob() -> Result<Vec<FsFr>, String> {
-    let mut blob = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
-    
-    for i in 0..FIELD_ELEMENTS_PER_BLOB {
-        // 使用小值来创建有效的域元素
-        let mut bytes = [0u8; 32];
-        // 将索引值转换为域元素，确保在有效范围内
-        let value = (i % 255) as u8; // 使用小值
-        bytes[31] = value;
-        
-        let fr = FsFr::from_bytes(&bytes).map_err(|e| format!("创建域元素失败: {}", e))?;
-        blob.push(fr);
-    }
-    
-    Ok(blob)
-}
-
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🔄 EIP-7594 数据可用性采样 (PeerDAS) 演示");
     println!("{}", "=".repeat(60));
     
     // 1. 加载受信任设置
     println!("📁 步骤 1: 加载受信任设置...");
-    let trusted_setup_path = find_trusted_setup_file()?;
-    let settings = load_trusted_setup_filename_rust(&trusted_setup_path)
-        .map_err(|e| format!("加载受信任设置失败: {}", e))?;
+    let settings = load_trusted_setup_from_file()?;
     println!("✅ 受信任设置加载成功!");
     
     // 2. 创建测试 Blob
-    println!("\n🔢 步骤 2: 创建随机测试 Blob...");
-    let blob = create_random_blob()?;
+    println!("\n🔢 步骤 2: 创建测试 Blob...");
+    let blob = create_test_blob()?;
     println!("✅ 创建了包含 {} 个域元素的 Blob", blob.len());
     
     // 3. 生成 KZG 承诺
@@ -130,45 +96,96 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n🔄 步骤 6: 数据恢复演示...");
     println!("   模拟只有 50% 的 cells 可用的情况...");
     
-    // 只保留前一半的 cells (模拟网络中只收到一半数据)
-    let half_cells_count = CELLS_PER_EXT_BLOB / 2;
-    let cell_indices: Vec<usize> = (0..half_cells_count).collect();
-    let partial_cells: Vec<FsFr> = (0..half_cells_count)
-        .flat_map(|i| {
-            let start_idx = i * FIELD_ELEMENTS_PER_CELL;
-            let end_idx = (i + 1) * FIELD_ELEMENTS_PER_CELL;
+    // 收到的是交错的奇数列，来自不同节点，顺序是乱的，其中一列被两个节点重复发送
+    let mut cell_indices: Vec<u64> = (0..CELLS_PER_EXT_BLOB as u64).filter(|i| i % 2 == 1).rev().collect();
+    cell_indices.push(1);
+    let partial_cells: Vec<FsFr> = cell_indices
+        .iter()
+        .flat_map(|&i| {
+            let start_idx = i as usize * FIELD_ELEMENTS_PER_CELL;
+            let end_idx = start_idx + FIELD_ELEMENTS_PER_CELL;
             cells[start_idx..end_idx].iter().cloned()
         })
         .collect();
     
-    println!("   📊 使用 {} 个 cells (50%) 来恢复完整数据", half_cells_count);
-    
-    // 恢复完整的 cells
-    let mut recovered_cells = vec![FsFr::default(); CELLS_PER_EXT_BLOB * FIELD_ELEMENTS_PER_CELL];
+    println!(
+        "   📊 使用 {} 个交错的 cells (50%，另有 1 个重复) 来恢复完整数据和证明",
+        CELLS_REQUIRED_FOR_RECOVERY
+    );
     
     let start = Instant::now();
-    <FsKZGSettings as DAS<BlstBackend>>::recover_cells_and_kzg_proofs(
-        &settings,
-        &mut recovered_cells,
-        None, // 不需要恢复证明
-        &cell_indices,
-        &partial_cells,
-    ).map_err(|e| format!("数据恢复失败: {}", e))?;
+    let recovered = recover_cells_and_proofs(&settings, &cell_indices, &partial_cells)
+        .map_err(|e| format!("数据恢复失败: {}", e))?;
     let recovery_time = start.elapsed();
     
     println!("✅ 数据恢复成功!");
     println!("   ⏱️ 恢复耗时: {:?}", recovery_time);
     
-    // 验证恢复的数据是否正确
-    let original_first_cell = &cells[0..FIELD_ELEMENTS_PER_CELL];
-    let recovered_first_cell = &recovered_cells[0..FIELD_ELEMENTS_PER_CELL];
-    
-    if original_first_cell == recovered_first_cell {
-        println!("✅ 数据恢复验证成功 - 恢复的数据与原始数据一致!");
+    // 验证恢复的全部 cells 和重新计算的证明是否与原始数据一致
+    if recovered.cells == cells && recovered.proofs == proofs {
+        println!("✅ 数据恢复验证成功 - 全部 {} 个 cells 和证明与原始数据一致!", CELLS_PER_EXT_BLOB);
     } else {
         println!("❌ 数据恢复验证失败 - 恢复的数据与原始数据不一致!");
     }
     
+    // 数量不足时给出明确的错误
+    if let Err(e) = recover_cells_and_proofs(&settings, &cell_indices[1..], &partial_cells[FIELD_ELEMENTS_PER_CELL..]) {
+        println!("   ℹ️ 少一个 cell 时: {}", e);
+    }
+    
+    // 7. 列托管
+    println!("\n🗂️ 步骤 7: 节点的托管列...");
+    let node_id: NodeId = "0x5a3f0c9e".parse()?;
+    let assignment = CustodyAssignment::new(node_id, CUSTODY_REQUIREMENT)?;
+    let custody = assignment.filter_cells_and_proofs(&cells, &proofs)?;
+    println!("   🆔 节点 ID: {}", node_id);
+    println!("   📋 托管组: {:?}", assignment.groups);
+    println!("   📋 托管列: {:?}", custody.column_indices);
+    
+    let custody_commitments = vec![commitment; custody.proofs.len()];
+    let custody_valid = <FsKZGSettings as DAS<BlstBackend>>::verify_cell_kzg_proof_batch(
+        &settings,
+        &custody_commitments,
+        &custody.cell_indices(),
+        &custody.cells,
+        &custody.proofs,
+    ).map_err(|e| format!("托管列验证失败: {}", e))?;
+    println!("   {} 节点只需保存和验证 {}/{} 列", if custody_valid { "✅" } else { "❌" }, custody.proofs.len(), CELLS_PER_EXT_BLOB);
+    
+    // 8. 采样模拟
+    println!("\n🎲 步骤 8: 数据可用性采样模拟...");
+    let scenario_path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "scenarios/das/withhold_columns.toml".to_string());
+    let scenario = Scenario::from_file(&scenario_path)?;
+    println!("   📄 场景文件: {} ({})", scenario_path, scenario.description);
+    let report = DasSimulator::new(&settings, scenario)?.run()?;
+    for line in report.to_string().lines() {
+        println!("   {}", line);
+    }
+    
+    // 9. 流式验证
+    println!("\n📡 步骤 9: 流式验证 gossip 收到的 cells...");
+    let commitment_bytes = commitment.to_bytes();
+    let mut verifier = CellVerifier::new(
+        &settings,
+        CellVerifierConfig { max_pending: 32, max_delay: Duration::from_millis(50) },
+    );
+    let mut invalid = Vec::new();
+    for index in 0..CELLS_PER_EXT_BLOB {
+        // 第 42 个 cell 附带了第 43 个的证明
+        let proof_index = if index == 42 { 43 } else { index };
+        let cell: Vec<u8> = cells[index * FIELD_ELEMENTS_PER_CELL..(index + 1) * FIELD_ELEMENTS_PER_CELL]
+            .iter()
+            .flat_map(|fr| fr.to_bytes())
+            .collect();
+        let verdicts = verifier.add(index, &commitment_bytes, index as u64, &cell, &proofs[proof_index].to_bytes());
+        invalid.extend(verdicts.into_iter().filter(|verdict| !verdict.status.is_valid()).map(|verdict| verdict.tag));
+    }
+    let stats = verifier.stats();
+    println!("   📊 {} 个 cells，{} 次刷新，{} 次批量验证调用", stats.added, stats.flushes, stats.batch_calls);
+    println!("   🔎 定位到错误的 cells: {:?}", invalid);
+    
     // 性能总结
     println!("\n{}", "=".repeat(60));
     println!("📊 EIP-7594 PeerDAS 性能总结:");
@@ -12,6 +12,13 @@ cargo run --example chapter20_project_practical_cases
 # 仅运行 Rollup 处理器示例
 cargo run --example chapter20_project_practical_cases -- rollup
 
+# 离线重放信标节点 API 返回的 BlobSidecar JSON (文件，或 `-` 读取标准输入)
+curl -s $BEACON_NODE/eth/v1/beacon/blob_sidecars/head \
+  | cargo run --example chapter20_project_practical_cases -- replay -
+
+# 重放并校验交易的 blob_versioned_hashes (按 blob 顺序，0x 开头的参数)
+cargo run --example chapter20_project_practical_cases -- replay sidecars.json 0x01ab... 0x01cd...
+
 # 仅运行去中心化存储示例  
 cargo run --example chapter20_project_practical_cases -- storage
 
@@ -36,17 +43,27 @@ cargo run --example chapter20_project_practical_cases -- benchmark
 
 use kzg::eip_4844::{
     blob_to_kzg_commitment_rust, 
-    compute_blob_kzg_proof_rust,
+    compute_kzg_proof_rust,
     verify_blob_kzg_proof_rust,
     FIELD_ELEMENTS_PER_BLOB,
     BYTES_PER_FIELD_ELEMENT,
 };
-use kzg::Fr;
-use rust_kzg_blst::eip_4844::load_trusted_setup_filename_rust;
+use kzg::{Fr, G1};
 use rust_kzg_blst::{
     types::{kzg_settings::FsKZGSettings, fr::FsFr, g1::FsG1},
 };
-use std::collections::HashMap;
+use rust_kzg_tutorial::{
+    blob::create_test_blob_bytes,
+    sidecar::{load_sidecars_from_file, read_sidecars, validate_transaction_sidecars, BlobSidecar, SidecarError},
+    storage::{
+        respond_to_challenge, verify_manifest, AuditConfig, Auditor, DataShard, FileManifest,
+        FileRegistry, InMemoryShardStore, NodeId, NodeManager, NodeRegistry, NodeSelectionStrategy,
+        RepairConfig, RepairScheduler, ShardConfig, ShardManager, ShardStore, StorageNode,
+    },
+    transcript::compute_challenge,
+    trusted_setup::load_trusted_setup_from_file,
+    versioned_hash::{kzg_to_versioned_hash, parse_versioned_hash},
+};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use log::{info, error};
@@ -98,6 +115,32 @@ pub struct BlobEvent {
     pub blob_hash: [u8; 32],
     pub blob_data: Vec<u8>,
     pub timestamp: u64,
+    /// 来源自带的承诺和证明 (如 BlobSidecar)，为空时由处理器计算
+    pub kzg_commitment: Option<FsG1>,
+    pub kzg_proof: Option<FsG1>,
+}
+
+/// 主网信标链创世时间 (秒)
+const MAINNET_GENESIS_TIME: u64 = 1_606_824_023;
+/// 每个 slot 的时长 (秒)
+const SECONDS_PER_SLOT: u64 = 12;
+
+impl BlobEvent {
+    /// 把信标链 BlobSidecar 映射为 Blob 事件
+    ///
+    /// `block_number` 使用 sidecar 所在的 slot，时间戳按主网创世时间推算
+    pub fn from_sidecar(sidecar: &BlobSidecar) -> Result<Self, SidecarError> {
+        let blob_hash: [u8; 32] = Sha256::digest(&sidecar.blob).into();
+
+        Ok(Self {
+            block_number: sidecar.slot(),
+            blob_hash,
+            blob_data: sidecar.blob.clone(),
+            timestamp: MAINNET_GENESIS_TIME + sidecar.slot() * SECONDS_PER_SLOT,
+            kzg_commitment: Some(sidecar.commitment()?),
+            kzg_proof: Some(sidecar.proof()?),
+        })
+    }
 }
 
 /// 处理结果
@@ -105,6 +148,8 @@ pub struct BlobEvent {
 pub struct ProcessingResult {
     pub blob_hash: [u8; 32],
     pub commitment: FsG1,
+    /// 承诺的版本化哈希 (交易中的 `blob_versioned_hashes` 条目)
+    pub versioned_hash: [u8; 32],
     pub proof: FsG1,
     pub is_valid: bool,
     pub processing_time: std::time::Duration,
@@ -245,13 +290,21 @@ impl KZGProcessor {
         // 1. 解析 Blob 数据
         let blob_fr = self.parse_blob_data(&blob_event.blob_data)?;
         
-        // 2. 生成 KZG 承诺
-        let commitment = blob_to_kzg_commitment_rust(&blob_fr, &*self.settings)
-            .map_err(ProcessingError::KZGError)?;
-        
-        // 3. 生成证明 (使用 blob 和承诺)
-        let proof = compute_blob_kzg_proof_rust(&blob_fr, &commitment, &*self.settings)
-            .map_err(ProcessingError::KZGError)?;
+        let (commitment, proof) = match (blob_event.kzg_commitment, blob_event.kzg_proof) {
+            // 来源自带承诺和证明 (如 BlobSidecar)，直接校验
+            (Some(commitment), Some(proof)) => (commitment, proof),
+            _ => {
+                // 2. 生成 KZG 承诺
+                let commitment = blob_to_kzg_commitment_rust(&blob_fr, &*self.settings)
+                    .map_err(ProcessingError::KZGError)?;
+                
+                // 3. 生成证明：在 Fiat–Shamir 挑战点处打开多项式 (与 compute_blob_kzg_proof 等价)
+                let challenge = self.generate_challenge(&blob_fr, &commitment);
+                let (proof, _evaluation) = compute_kzg_proof_rust(&blob_fr, &challenge, &*self.settings)
+                    .map_err(ProcessingError::KZGError)?;
+                (commitment, proof)
+            }
+        };
         
         // 4. 验证证明
         let is_valid = verify_blob_kzg_proof_rust(&blob_fr, &commitment, &proof, &*self.settings)
@@ -262,6 +315,7 @@ impl KZGProcessor {
         Ok(ProcessingResult {
             blob_hash: blob_event.blob_hash,
             commitment,
+            versioned_hash: kzg_to_versioned_hash(&commitment.to_bytes()),
             proof,
             is_valid,
             processing_time,
@@ -291,22 +345,11 @@ impl KZGProcessor {
         Ok(blob_fr)
     }
     
-    /// 生成随机挑战
-    fn generate_challenge(&self, blob_hash: &[u8; 32], timestamp: u64) -> FsFr {
-        let mut hasher = Sha256::new();
-        hasher.update(blob_hash);
-        hasher.update(&timestamp.to_be_bytes());
-        hasher.update(b"KZG_CHALLENGE");
-        
-        let hash = hasher.finalize();
-        
-        // 将哈希值转换为域元素
-        FsFr::from_bytes(&hash[..32])
-            .unwrap_or_else(|_| {
-                let mut bytes = [0u8; 32];
-                bytes[31] = 1;
-                FsFr::from_bytes(&bytes).unwrap()
-            })
+    /// 生成 blob 证明的挑战点 (EIP-4844 `compute_challenge`)
+    ///
+    /// 挑战由 blob 和承诺确定，验证方可以独立重算，见 [`compute_challenge`]
+    fn generate_challenge(&self, blob_fr: &[FsFr], commitment: &FsG1) -> FsFr {
+        compute_challenge(blob_fr, commitment)
     }
 }
 
@@ -317,7 +360,7 @@ impl RollupProcessor {
         
         // 加载 KZG 设置
         let kzg_settings = Arc::new(
-            load_trusted_setup_filename_rust("./assets/trusted_setup.txt")?
+            load_trusted_setup_from_file()?
         );
         
         Ok(Self {
@@ -359,9 +402,10 @@ impl RollupProcessor {
                 // 显示详细结果
                 println!("\n📋 处理结果详情:");
                 for (i, result) in results.iter().take(5).enumerate() {
-                    println!("   [{:2}] 区块 {}: {} ({:?})", 
+                    println!("   [{:2}] 区块 {} 版本化哈希 0x{}...: {} ({:?})", 
                         i + 1,
                         result.block_number,
+                        hex::encode(&result.versioned_hash[..8]),
                         if result.is_valid { "✅ 验证通过" } else { "❌ 验证失败" },
                         result.processing_time
                     );
@@ -384,28 +428,69 @@ impl RollupProcessor {
         Ok(())
     }
     
+    /// 离线重放信标链 BlobSidecar JSON
+    ///
+    /// `sources` 为 JSON 文件路径，`-` 或为空表示从标准输入读取；
+    /// 每个 sidecar 自带的证明都用 `verify_blob_kzg_proof` 校验
+    pub async fn replay_sidecars(&self, sources: &[String]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        println!("📼 重放 BlobSidecar 数据");
+        println!("=======================");
+        
+        // 0x 开头的参数是交易的 blob_versioned_hashes，其余为 sidecar 来源
+        let (hash_args, sources): (Vec<&String>, Vec<&String>) = sources.iter().partition(|s| s.starts_with("0x"));
+        let expected_hashes = hash_args
+            .iter()
+            .map(|s| parse_versioned_hash(s))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let mut sidecars = Vec::new();
+        if sources.is_empty() || sources.iter().any(|s| *s == "-") {
+            println!("📥 从标准输入读取...");
+            sidecars.extend(read_sidecars(std::io::stdin().lock())?);
+        }
+        for path in sources.iter().filter(|s| **s != "-") {
+            let loaded = load_sidecars_from_file(path)?;
+            println!("📥 {}: {} 个 sidecar", path, loaded.len());
+            sidecars.extend(loaded);
+        }
+        
+        let events = sidecars
+            .iter()
+            .map(BlobEvent::from_sidecar)
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let processor = KZGProcessor::new(Arc::clone(&self.kzg_settings), self.config.clone());
+        let results = processor.process_blob_batch(events).await?;
+        
+        for (sidecar, result) in sidecars.iter().zip(&results) {
+            println!("   slot {} blob {}: 承诺 {}... 版本化哈希 0x{} {}",
+                sidecar.slot(),
+                sidecar.index,
+                hex::encode(&sidecar.kzg_commitment[..8]),
+                hex::encode(result.versioned_hash),
+                if result.is_valid { "✅ 证明有效" } else { "❌ 证明无效" }
+            );
+        }
+        
+        let valid = results.iter().filter(|r| r.is_valid).count();
+        println!("✅ 共 {} 个 sidecar，{} 个证明有效", results.len(), valid);
+        
+        if !expected_hashes.is_empty() {
+            match validate_transaction_sidecars(&expected_hashes, &sidecars) {
+                Ok(()) => println!("✅ 交易的 {} 个版本化哈希与 sidecar 承诺一致", expected_hashes.len()),
+                Err(e) => println!("❌ 版本化哈希校验失败: {}", e),
+            }
+        }
+        Ok(())
+    }
+    
     /// 生成测试 Blob 数据
     async fn generate_test_blobs(&self, count: usize) -> Result<Vec<BlobEvent>, Box<dyn std::error::Error + Send + Sync>> {
         let mut blobs = Vec::with_capacity(count);
-        let mut rng = rand::thread_rng();
         
         for i in 0..count {
-            // 生成随机 Blob 数据
-            let mut blob_data = vec![0u8; FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT];
-            
-            // 填充随机域元素
-            for j in 0..FIELD_ELEMENTS_PER_BLOB {
-                let start = j * BYTES_PER_FIELD_ELEMENT;
-                let end = start + BYTES_PER_FIELD_ELEMENT;
-                
-                // 生成有效的域元素（使用与 hello_kzg 相同的方法）
-                let mut field_bytes = [0u8; 32];
-                // 使用小值确保有效性
-                let value = ((i * FIELD_ELEMENTS_PER_BLOB + j) % 256) as u8;
-                field_bytes[31] = value;
-                
-                blob_data[start..end].copy_from_slice(&field_bytes);
-            }
+            // 生成有效的测试 Blob 数据
+            let blob_data = create_test_blob_bytes(i);
             
             // 生成 Blob 哈希
             let mut hasher = Sha256::new();
@@ -423,6 +508,8 @@ impl RollupProcessor {
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
                     .as_secs() + i as u64,
+                kzg_commitment: None,
+                kzg_proof: None,
             });
         }
         
@@ -434,326 +521,19 @@ impl RollupProcessor {
 // 第二个实战项目：去中心化存储验证系统
 // ================================
 
-type NodeId = [u8; 32];
-
-/// 数据分片信息
-#[derive(Debug, Clone)]
-pub struct DataShard {
-    /// 分片ID
-    pub shard_id: [u8; 32],
-    /// 原始数据块
-    pub data_chunk: Vec<u8>,
-    /// KZG 承诺
-    pub commitment: FsG1,
-    /// 存储位置
-    pub storage_locations: Vec<NodeId>,
-    /// 创建时间
-    pub created_at: u64,
-}
-
-/// 存储节点信息
-#[derive(Debug, Clone)]
-pub struct StorageNode {
-    /// 节点ID
-    pub node_id: NodeId,
-    /// 网络地址
-    pub address: String,
-    /// 存储容量
-    pub capacity: u64,
-    /// 已用容量
-    pub used_capacity: u64,
-    /// 信誉评分
-    pub reputation: f64,
-    /// 在线状态
-    pub is_online: bool,
-}
-
-impl StorageNode {
-    /// 检查节点是否有足够容量存储分片
-    fn has_capacity_for_shard(&self, shard: &DataShard) -> bool {
-        let required_space = shard.data_chunk.len() as u64;
-        (self.capacity - self.used_capacity) >= required_space
-    }
-}
-
-#[derive(Debug, Clone)]
-pub struct ShardConfig {
-    /// 分片大小 (字节)
-    pub shard_size: usize,
-    /// 冗余因子
-    pub redundancy_factor: f64,
-    /// 最小副本数
-    pub min_replicas: usize,
-}
-
-/// 数据分片管理器
-pub struct ShardManager {
-    kzg_settings: Arc<FsKZGSettings>,
-    config: ShardConfig,
-}
-
-#[derive(Debug, thiserror::Error)]
-pub enum ShardError {
-    #[error("KZG 操作错误: {0}")]
-    KZGError(String),
-    
-    #[error("无效数据: {0}")]
-    InvalidData(String),
-    
-    #[error("没有可用分片")]
-    NoShardsAvailable,
-}
-
-impl ShardManager {
-    /// 将文件分片并生成承诺
-    pub async fn shard_file(&self, file_data: &[u8]) -> Result<Vec<DataShard>, ShardError> {
-        info!("开始分片文件，大小: {} 字节", file_data.len());
-        
-        let chunk_size = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
-        let chunks = file_data.chunks(chunk_size);
-        let mut shards = Vec::new();
-        
-        for (index, chunk) in chunks.enumerate() {
-            let shard = self.create_data_shard(chunk, index).await?;
-            shards.push(shard);
-        }
-        
-        // 生成冗余数据（简化版Reed-Solomon编码）
-        let redundant_shards = self.generate_redundant_shards(&shards).await?;
-        shards.extend(redundant_shards);
-        
-        info!("文件分片完成，生成 {} 个分片", shards.len());
-        Ok(shards)
-    }
-    
-    /// 创建单个数据分片
-    async fn create_data_shard(&self, chunk: &[u8], index: usize) -> Result<DataShard, ShardError> {
-        // 填充数据到标准大小
-        let mut padded_chunk = vec![0u8; FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT];
-        
-        // 使用有效的域元素方法，而不是直接拷贝可能无效的数据
-        let mut blob_fr = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
-        for i in 0..FIELD_ELEMENTS_PER_BLOB {
-            let mut field_bytes = [0u8; 32];
-            
-            // 如果原始数据有内容，混合使用原始数据和索引
-            let data_value = if i < chunk.len() { 
-                chunk[i % chunk.len()] 
-            } else { 
-                0 
-            };
-            
-            // 创建有效的域元素值
-            let value = (((index * FIELD_ELEMENTS_PER_BLOB + i) % 256) as u8) ^ (data_value % 128);
-            field_bytes[31] = value;
-            
-            let fr = FsFr::from_bytes(&field_bytes)
-                .map_err(|e| ShardError::InvalidData(e))?;
-            blob_fr.push(fr);
-            
-            // 将有效的字节存储到 padded_chunk
-            let start = i * BYTES_PER_FIELD_ELEMENT;
-            let end = start + BYTES_PER_FIELD_ELEMENT;
-            padded_chunk[start..end].copy_from_slice(&field_bytes);
-        }
-        
-        // 生成 KZG 承诺
-        let commitment = blob_to_kzg_commitment_rust(&blob_fr, &*self.kzg_settings)
-            .map_err(|e| ShardError::KZGError(e))?;
-        
-        // 生成分片ID
-        let shard_id = self.generate_shard_id(&padded_chunk, index);
-        
-        Ok(DataShard {
-            shard_id,
-            data_chunk: padded_chunk,
-            commitment,
-            storage_locations: Vec::new(),
-            created_at: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-        })
-    }
-    
-    /// 生成冗余分片（简化的异或编码）
-    async fn generate_redundant_shards(&self, original_shards: &[DataShard]) -> Result<Vec<DataShard>, ShardError> {
-        let redundancy_count = ((original_shards.len() as f64) * self.config.redundancy_factor) as usize;
-        let mut redundant_shards = Vec::with_capacity(redundancy_count);
-        
-        for i in 0..redundancy_count {
-            let redundant_data = self.create_redundant_data(original_shards, i)?;
-            let redundant_shard = self.create_data_shard(&redundant_data, original_shards.len() + i).await?;
-            redundant_shards.push(redundant_shard);
-        }
-        
-        Ok(redundant_shards)
-    }
-    
-    /// 创建冗余数据（简化的异或编码）
-    fn create_redundant_data(&self, shards: &[DataShard], redundancy_index: usize) -> Result<Vec<u8>, ShardError> {
-        if shards.is_empty() {
-            return Err(ShardError::NoShardsAvailable);
-        }
-        
-        let data_size = shards[0].data_chunk.len();
-        let mut redundant_data = vec![0u8; data_size];
-        
-        // 使用简单的异或编码
-        for (i, shard) in shards.iter().enumerate() {
-            if (i + redundancy_index) % 2 == 0 {
-                for (j, &byte) in shard.data_chunk.iter().enumerate() {
-                    redundant_data[j] ^= byte;
-                }
-            }
-        }
-        
-        Ok(redundant_data)
-    }
-    
-    /// 生成分片ID
-    fn generate_shard_id(&self, data: &[u8], index: usize) -> [u8; 32] {
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        hasher.update(&index.to_be_bytes());
-        hasher.update(b"SHARD_ID");
-        
-        let hash = hasher.finalize();
-        let mut shard_id = [0u8; 32];
-        shard_id.copy_from_slice(&hash);
-        shard_id
-    }
-}
-
-#[derive(Debug, Clone)]
-pub enum NodeSelectionStrategy {
-    /// 基于信誉的选择
-    ReputationBased { min_reputation: f64 },
-    /// 负载均衡选择
-    LoadBalanced,
-    /// 混合策略
-    Hybrid,
-}
-
-/// 存储节点管理器
-pub struct NodeManager {
-    /// 在线节点列表
-    nodes: Arc<RwLock<HashMap<NodeId, StorageNode>>>,
-    /// 节点选择策略
-    selection_strategy: NodeSelectionStrategy,
-}
-
-#[derive(Debug, thiserror::Error)]
-pub enum NodeError {
-    #[error("可用节点不足: 需要 {required}，可用 {available}")]
-    InsufficientNodes { required: usize, available: usize },
-}
-
-impl NodeManager {
-    /// 选择存储节点
-    pub async fn select_storage_nodes(&self, shard: &DataShard, replica_count: usize) -> Result<Vec<NodeId>, NodeError> {
-        let nodes = self.nodes.read().await;
-        let available_nodes: Vec<_> = nodes
-            .values()
-            .filter(|node| node.is_online && node.has_capacity_for_shard(shard))
-            .collect();
-        
-        if available_nodes.len() < replica_count {
-            return Err(NodeError::InsufficientNodes {
-                required: replica_count,
-                available: available_nodes.len(),
-            });
-        }
-        
-        let selected_nodes = match &self.selection_strategy {
-            NodeSelectionStrategy::ReputationBased { min_reputation } => {
-                self.select_by_reputation(&available_nodes, replica_count, *min_reputation)
-            }
-            NodeSelectionStrategy::LoadBalanced => {
-                self.select_by_load(&available_nodes, replica_count)
-            }
-            NodeSelectionStrategy::Hybrid => {
-                self.select_hybrid(&available_nodes, replica_count)
-            }
-        };
-        
-        Ok(selected_nodes)
-    }
-    
-    /// 基于信誉选择节点
-    fn select_by_reputation(&self, nodes: &[&StorageNode], count: usize, min_reputation: f64) -> Vec<NodeId> {
-        let mut qualified_nodes: Vec<_> = nodes
-            .iter()
-            .filter(|node| node.reputation >= min_reputation)
-            .collect();
-        
-        // 按信誉排序
-        qualified_nodes.sort_by(|a, b| b.reputation.partial_cmp(&a.reputation).unwrap());
-        
-        qualified_nodes
-            .into_iter()
-            .take(count)
-            .map(|node| node.node_id)
-            .collect()
-    }
-    
-    /// 基于负载选择节点
-    fn select_by_load(&self, nodes: &[&StorageNode], count: usize) -> Vec<NodeId> {
-        let mut load_sorted: Vec<_> = nodes.iter().collect();
-        
-        // 按使用率排序（使用率低的优先）
-        load_sorted.sort_by(|a, b| {
-            let load_a = a.used_capacity as f64 / a.capacity as f64;
-            let load_b = b.used_capacity as f64 / b.capacity as f64;
-            load_a.partial_cmp(&load_b).unwrap()
-        });
-        
-        load_sorted
-            .into_iter()
-            .take(count)
-            .map(|node| node.node_id)
-            .collect()
-    }
-    
-    /// 混合策略选择
-    fn select_hybrid(&self, nodes: &[&StorageNode], count: usize) -> Vec<NodeId> {
-        let mut scored_nodes: Vec<_> = nodes
-            .iter()
-            .map(|node| {
-                let load_ratio = node.used_capacity as f64 / node.capacity as f64;
-                let load_score = 1.0 - load_ratio; // 负载越低分数越高
-                let reputation_score = node.reputation;
-                
-                // 综合评分：负载权重0.4，信誉权重0.6
-                let total_score = load_score * 0.4 + reputation_score * 0.6;
-                
-                (node, total_score)
-            })
-            .collect();
-        
-        // 按综合评分排序
-        scored_nodes.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        
-        scored_nodes
-            .into_iter()
-            .take(count)
-            .map(|(node, _)| node.node_id)
-            .collect()
-    }
-}
-
 /// 去中心化存储系统
 pub struct DecentralizedStorage {
     kzg_settings: Arc<FsKZGSettings>,
     shard_manager: Arc<ShardManager>,
     node_manager: Arc<NodeManager>,
+    auditor: Auditor,
 }
 
 impl DecentralizedStorage {
     /// 创建新的去中心化存储系统
     pub async fn new() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let kzg_settings = Arc::new(
-            load_trusted_setup_filename_rust("./assets/trusted_setup.txt")?
+            load_trusted_setup_from_file()?
         );
         
         let shard_config = ShardConfig {
@@ -762,18 +542,25 @@ impl DecentralizedStorage {
             min_replicas: 3,
         };
         
-        let shard_manager = Arc::new(ShardManager {
-            kzg_settings: Arc::clone(&kzg_settings),
-            config: shard_config,
-        });
+        let shard_manager = Arc::new(ShardManager::new(Arc::clone(&kzg_settings), shard_config));
         
-        // 创建模拟存储网络
-        let node_manager = Arc::new(create_mock_storage_network(10).await?);
+        // 创建模拟存储网络 (节点状态和放置信息保存在注册表文件中，重启后恢复)
+        let registry_path = std::env::var("KZG_NODE_REGISTRY")
+            .map(std::path::PathBuf::from)
+            .unwrap_or_else(|_| std::env::temp_dir().join("kzg_chapter20_node_registry.json"));
+        println!("🗂️  节点注册表: {}", registry_path.display());
+        let node_manager = Arc::new(
+            create_mock_storage_network(10, Arc::new(FileRegistry::new(registry_path))).await?
+        );
+        
+        // 存储证明审计方
+        let auditor = Auditor::new(Arc::clone(&kzg_settings), AuditConfig::default());
         
         Ok(Self {
             kzg_settings,
             shard_manager,
             node_manager,
+            auditor,
         })
     }
     
@@ -797,10 +584,40 @@ impl DecentralizedStorage {
         println!("   📊 分片数量: {} 个", shards.len());
         println!("   ⏱️  分片耗时: {:?}", shard_time);
         println!("   💾 总存储: {} 字节", shards.iter().map(|s| s.data_chunk.len()).sum::<usize>());
-        
+
+        let erasure = self.shard_manager.erasure_params(test_data.len());
+        println!("   🧩 纠删码: {} 数据 + {} 校验 (任意 {} 个分片即可恢复)",
+            erasure.data_shards, erasure.parity_shards, erasure.data_shards);
+
+        // 生成文件清单，客户端只需持有清单即可校验下载的分片
+        let manifest = FileManifest::from_shards(&shards, erasure, test_data.len() as u64)?;
+        println!("   📜 文件清单根哈希: {}", hex::encode(manifest.root_hash));
+        println!("   📜 清单大小 (JSON): {} 字节", manifest.to_json()?.len());
+
+        let mut tampered = shards[0].clone();
+        tampered.data_chunk[100] ^= 1;
+        println!("   🔍 按清单校验分片 0: {}",
+            if verify_manifest(&manifest, &shards[0], &self.kzg_settings).is_ok() { "✅ 通过" } else { "❌ 失败" });
+        println!("   🔍 按清单校验被篡改的分片 0: {}",
+            match verify_manifest(&manifest, &tampered, &self.kzg_settings) {
+                Ok(()) => "❌ 未能发现篡改".to_string(),
+                Err(e) => format!("✅ 已拒绝 ({})", e),
+            });
+
+        // 模拟丢失与校验分片数量相同的数据分片，从剩余分片恢复文件
+        println!("\n🧩 模拟丢失 {} 个数据分片后恢复文件...", erasure.parity_shards);
+        let recover_start = std::time::Instant::now();
+        let surviving: Vec<DataShard> = shards.iter().skip(erasure.parity_shards).cloned().collect();
+        let recovered = self.shard_manager.reconstruct_file(&surviving, &erasure)?;
+        println!("   {} 恢复{}，耗时: {:?}",
+            if recovered == test_data { "✅" } else { "❌" },
+            if recovered == test_data { "成功" } else { "结果与原文件不一致" },
+            recover_start.elapsed()
+        );
+
         // 3. 存储网络状态
         println!("\n🌐 存储网络状态:");
-        let nodes = self.node_manager.nodes.read().await;
+        let nodes = self.node_manager.nodes().read().await;
         println!("   📊 节点数量: {} 个", nodes.len());
         
         let total_capacity: u64 = nodes.values().map(|n| n.capacity).sum();
@@ -818,6 +635,7 @@ impl DecentralizedStorage {
         for (i, shard) in shards.iter().enumerate() {
             let alloc_start = std::time::Instant::now();
             let selected_nodes = self.node_manager.select_storage_nodes(shard, 3).await?;
+            self.node_manager.record_placement(shard, &selected_nodes).await?;
             allocation_time += alloc_start.elapsed();
             
             storage_allocations.push((shard.shard_id, selected_nodes.clone()));
@@ -836,23 +654,32 @@ impl DecentralizedStorage {
         
         println!("✅ 存储分配完成，耗时: {:?}", allocation_time);
         
-        // 5. 模拟验证过程
-        println!("\n🔍 开始数据完整性验证...");
+        // 5. 存储证明审计 (挑战-应答)
+        println!("\n🔍 开始存储证明审计...");
         let verification_start = std::time::Instant::now();
         let mut successful_verifications = 0;
         let mut failed_verifications = 0;
+        let epoch = self.auditor.current_epoch();
+        
+        // 模拟节点 0 的磁盘损坏：它保存的分片副本已不完整
+        let faulty_node: NodeId = [0u8; 32];
         
         for (i, (shard_id, node_ids)) in storage_allocations.iter().take(10).enumerate() {
             // 找到对应的分片
             if let Some(shard) = shards.iter().find(|s| s.shard_id == *shard_id) {
                 for node_id in node_ids {
-                    match self.verify_shard_on_node(shard, node_id).await {
+                    let mut stored_copy = shard.clone();
+                    if *node_id == faulty_node {
+                        stored_copy.data_chunk[2 * BYTES_PER_FIELD_ELEMENT - 1] ^= 0xff;
+                    }
+                    
+                    match self.verify_shard_on_node(&manifest, &stored_copy, node_id, epoch).await {
                         Ok(is_valid) => {
                             if is_valid {
                                 successful_verifications += 1;
                             } else {
                                 failed_verifications += 1;
-                                println!("   ❌ 验证失败: 分片 {} 在节点 {}", 
+                                println!("   ❌ 审计失败: 分片 {} 在节点 {}", 
                                     hex::encode(&shard_id[..8]), 
                                     hex::encode(&node_id[..8])
                                 );
@@ -860,20 +687,66 @@ impl DecentralizedStorage {
                         }
                         Err(e) => {
                             failed_verifications += 1;
-                            println!("   ⚠️  验证错误: {:?}", e);
+                            println!("   ⚠️  审计错误: {:?}", e);
                         }
                     }
                 }
             }
             
             if i == 0 {
-                println!("   🔍 验证分片 {} ...", hex::encode(&shard_id[..8]));
+                println!("   🔍 审计分片 {} (纪元 {}) ...", hex::encode(&shard_id[..8]), epoch);
             }
         }
         
+        if let Some(node) = self.node_manager.nodes().read().await.get(&faulty_node) {
+            println!("   📉 节点 {} 审计后的信誉: {:.3}", hex::encode(&faulty_node[..8]), node.reputation);
+        }
+        
         let verification_time = verification_start.elapsed();
+
+        // 6. 节点下线后的副本修复
+        println!("\n🛠️  模拟节点下线并修复副本...");
+        let shard_store = Arc::new(InMemoryShardStore::new());
+        for (shard_id, node_ids) in &storage_allocations {
+            if let Some(shard) = shards.iter().find(|s| s.shard_id == *shard_id) {
+                for node_id in node_ids {
+                    shard_store.put(node_id, shard)?;
+                }
+            }
+        }
+        let repair_scheduler = RepairScheduler::new(
+            Arc::clone(&self.kzg_settings),
+            Arc::clone(&self.node_manager),
+            shard_store,
+            RepairConfig {
+                min_replicas: self.shard_manager.config().min_replicas,
+                max_repairs_per_round: 8,
+                ..Default::default()
+            },
+        );
+        repair_scheduler.track_file(manifest.clone(), &shards).await?;
+
+        let offline_node = storage_allocations[0].1[0];
+        self.node_manager.set_online(&offline_node, false).await?;
+        println!("   📴 节点 {} 下线，副本不足的分片: {} 个",
+            hex::encode(&offline_node[..8]),
+            repair_scheduler.find_under_replicated().await.len());
+
+        loop {
+            let report = repair_scheduler.run_once().await;
+            if report.under_replicated == 0 || report.repaired == 0 {
+                break;
+            }
+            println!("   🔧 本轮修复 {} 个分片，新建 {} 个副本，顺延 {} 个",
+                report.repaired, report.replicas_created, report.deferred);
+        }
+        let repair_stats = repair_scheduler.stats();
+        println!("   📊 修复统计: {} 轮，成功 {}，失败 {}，传输 {} 字节",
+            repair_stats.rounds, repair_stats.repairs_succeeded,
+            repair_stats.repairs_failed, repair_stats.bytes_transferred);
+        self.node_manager.set_online(&offline_node, true).await?;
         
-        // 6. 性能统计
+        // 7. 性能统计
         println!("\n📊 系统性能统计");
         println!("=================");
         println!("📁 原始文件大小: {} 字节", test_data.len());
@@ -896,26 +769,31 @@ impl DecentralizedStorage {
         Ok(())
     }
     
-    /// 验证分片在指定节点上的完整性
-    async fn verify_shard_on_node(&self, shard: &DataShard, _node_id: &NodeId) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
-        // 模拟网络延迟
+    /// 对指定节点上的分片执行一次存储证明审计，并把结果计入节点信誉
+    ///
+    /// `stored_copy` 代表节点本地保存的分片数据，节点只能用它来应答挑战
+    async fn verify_shard_on_node(
+        &self,
+        manifest: &FileManifest,
+        stored_copy: &DataShard,
+        node_id: &NodeId,
+        epoch: u64,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        // 审计方生成本纪元的随机挑战点
+        let challenge = self.auditor.challenge(manifest, stored_copy.index, node_id, epoch)?;
+        
+        // 模拟网络延迟，节点计算打开证明
         tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        // 用清单中的承诺验证应答，节点无法给出应答同样视为审计失败
+        let passed = match respond_to_challenge(stored_copy, &challenge, &self.kzg_settings) {
+            Ok(response) => self.auditor.verify_response(manifest, &challenge, &response)?,
+            Err(_) => false,
+        };
+        self.node_manager
+            .record_audit(node_id, passed, self.auditor.config().reputation_weight)
+            .await?;
         
-        // 解析分片数据
-        let mut blob_fr = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
-        for i in 0..FIELD_ELEMENTS_PER_BLOB {
-            let start = i * BYTES_PER_FIELD_ELEMENT;
-            let end = start + BYTES_PER_FIELD_ELEMENT;
-            let field_bytes = &shard.data_chunk[start..end];
-            
-            let fr = FsFr::from_bytes(field_bytes)?;
-            blob_fr.push(fr);
-        }
-        
-        // 验证承诺
-        let actual_commitment = blob_to_kzg_commitment_rust(&blob_fr, &*self.kzg_settings)?;
-        
-        Ok(actual_commitment == shard.commitment)
+        Ok(passed)
     }
 }
 
@@ -928,8 +806,20 @@ fn generate_test_file(size: usize) -> Vec<u8> {
 }
 
 /// 创建模拟存储网络
-async fn create_mock_storage_network(node_count: usize) -> Result<NodeManager, Box<dyn std::error::Error + Send + Sync>> {
-    let mut nodes = HashMap::new();
+///
+/// 注册表中已有节点时直接恢复，否则生成 `node_count` 个模拟节点并写入注册表
+async fn create_mock_storage_network(
+    node_count: usize,
+    registry: Arc<dyn NodeRegistry>,
+) -> Result<NodeManager, Box<dyn std::error::Error + Send + Sync>> {
+    let node_manager = NodeManager::with_registry(registry, NodeSelectionStrategy::Hybrid)?;
+    
+    let restored = node_manager.nodes().read().await.len();
+    if restored > 0 {
+        println!("♻️  从注册表恢复 {} 个节点，{} 条分片放置记录",
+            restored, node_manager.placements().await.len());
+        return Ok(node_manager);
+    }
     
     for i in 0..node_count {
         let mut node_id = [0u8; 32];
@@ -944,13 +834,10 @@ async fn create_mock_storage_network(node_count: usize) -> Result<NodeManager, B
             is_online: true,
         };
         
-        nodes.insert(node_id, node);
+        node_manager.register_node(node).await?;
     }
     
-    Ok(NodeManager {
-        nodes: Arc::new(RwLock::new(nodes)),
-        selection_strategy: NodeSelectionStrategy::Hybrid,
-    })
+    Ok(node_manager)
 }
 
 // ================================
@@ -964,7 +851,7 @@ pub async fn run_benchmark() -> Result<(), Box<dyn std::error::Error + Send + Sy
     
     // 加载 KZG 设置
     let kzg_settings = Arc::new(
-        load_trusted_setup_filename_rust("./assets/trusted_setup.txt")?
+        load_trusted_setup_from_file()?
     );
     
     // 测试不同批次大小的性能
@@ -977,20 +864,7 @@ pub async fn run_benchmark() -> Result<(), Box<dyn std::error::Error + Send + Sy
         let mut test_blobs = Vec::new();
         
         for i in 0usize..batch_size {
-            let mut blob_data = vec![0u8; FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT];
-            
-            // 生成有效的域元素
-            for j in 0..FIELD_ELEMENTS_PER_BLOB {
-                let start = j * BYTES_PER_FIELD_ELEMENT;
-                let end = start + BYTES_PER_FIELD_ELEMENT;
-                
-                // 使用与其他部分相同的有效域元素生成方法
-                let mut field_bytes = [0u8; 32];
-                let value = ((i * FIELD_ELEMENTS_PER_BLOB + j) % 256) as u8;
-                field_bytes[31] = value;
-                
-                blob_data[start..end].copy_from_slice(&field_bytes);
-            }
+            let blob_data = create_test_blob_bytes(i);
             
             let mut hasher = Sha256::new();
             hasher.update(&blob_data);
@@ -1007,6 +881,8 @@ pub async fn run_benchmark() -> Result<(), Box<dyn std::error::Error + Send + Sy
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap()
                     .as_secs(),
+                kzg_commitment: None,
+                kzg_proof: None,
             });
         }
         
@@ -1059,6 +935,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             let rollup_processor = RollupProcessor::new(config).await?;
             rollup_processor.run_demo().await?;
         }
+        "replay" => {
+            // 重放信标链 BlobSidecar JSON (文件路径，或 `-` 表示标准输入)
+            let rollup_processor = RollupProcessor::new(ProcessorConfig::default()).await?;
+            rollup_processor.replay_sidecars(&args[2..]).await?;
+        }
         "storage" => {
             // 仅运行去中心化存储示例
             let storage_system = DecentralizedStorage::new().await?;
@@ -0,0 +1,108 @@
+//! Blob 构造工具
+//!
+//! EIP-4844 的 Blob 由 4096 个域元素组成，每个元素必须小于 BLS12-381 的标量域模数。
+//! 示例和测试中常用的几种构造方式集中在这里。
+
+use kzg::{
+    eip_4844::{BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT, FIELD_ELEMENTS_PER_BLOB},
+    Fr,
+};
+use rand::RngCore;
+use rust_kzg_blst::types::fr::FsFr;
+
+/// 使用自定义函数生成 Blob，`f(i)` 给出第 i 个域元素的数值
+pub fn blob_from_fn<F>(f: F) -> Vec<FsFr>
+where
+    F: Fn(usize) -> u64,
+{
+    (0..FIELD_ELEMENTS_PER_BLOB)
+        .map(|i| FsFr::from_u64(f(i)))
+        .collect()
+}
+
+/// 创建有效的测试 Blob 数据
+///
+/// 使用与 hello_kzg 相同的分段模式，结果是确定性的
+pub fn create_test_blob() -> Result<Vec<FsFr>, String> {
+    let mut blob = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
+
+    for i in 0..FIELD_ELEMENTS_PER_BLOB {
+        let mut bytes = [0u8; 32];
+        bytes[31] = match i {
+            0..=255 => i as u8,
+            256..=511 => (i - 256) as u8,
+            512..=767 => ((i - 512) * 2) as u8,
+            768..=1023 => ((i - 768) / 2) as u8,
+            _ => (i % 256) as u8,
+        };
+
+        let element = FsFr::from_bytes(&bytes)
+            .map_err(|e| format!("创建第 {} 个域元素失败: {}", i, e))?;
+        blob.push(element);
+    }
+
+    Ok(blob)
+}
+
+/// 创建随机 Blob
+///
+/// 每个域元素的最高字节置零，保证数值小于模数
+pub fn create_random_blob() -> Result<Vec<FsFr>, String> {
+    let mut rng = rand::thread_rng();
+    let mut blob = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
+
+    for i in 0..FIELD_ELEMENTS_PER_BLOB {
+        let mut bytes = [0u8; 32];
+        rng.fill_bytes(&mut bytes[1..]);
+
+        let element = FsFr::from_bytes(&bytes)
+            .map_err(|e| format!("创建第 {} 个域元素失败: {}", i, e))?;
+        blob.push(element);
+    }
+
+    Ok(blob)
+}
+
+/// 创建字节形式的测试 Blob (长度为 `BYTES_PER_BLOB`)
+///
+/// `seed` 用于区分不同的 Blob，相同的 seed 得到相同的数据
+///
+/// 每个域元素的最低字节为 `j % 256`，其前 8 字节为 `seed` (大端序)，最高字节保持为零，
+/// 因此不同的 seed 得到不同的 Blob，且 `seed = 0` 时只有最低字节非零。
+pub fn create_test_blob_bytes(seed: usize) -> Vec<u8> {
+    let mut blob_data = vec![0u8; BYTES_PER_BLOB];
+    let seed_bytes = (seed as u64).to_be_bytes();
+
+    for j in 0..FIELD_ELEMENTS_PER_BLOB {
+        let offset = j * BYTES_PER_FIELD_ELEMENT;
+        let last = offset + BYTES_PER_FIELD_ELEMENT - 1;
+        blob_data[last - seed_bytes.len()..last].copy_from_slice(&seed_bytes);
+        blob_data[last] = (j % 256) as u8;
+    }
+
+    blob_data
+}
+
+/// 将 Blob 序列化为字节 (每个域元素 32 字节，大端序)
+pub fn blob_to_bytes(blob: &[FsFr]) -> Vec<u8> {
+    blob.iter().flat_map(|fr| fr.to_bytes()).collect()
+}
+
+/// 从字节解析 Blob，要求长度恰好为 `BYTES_PER_BLOB` 且每个元素都是规范编码
+pub fn blob_from_bytes(bytes: &[u8]) -> Result<Vec<FsFr>, String> {
+    if bytes.len() != BYTES_PER_BLOB {
+        return Err(format!(
+            "无效的 Blob 大小: {}, 期望: {}",
+            bytes.len(),
+            BYTES_PER_BLOB
+        ));
+    }
+
+    bytes
+        .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .enumerate()
+        .map(|(i, chunk)| {
+            FsFr::from_bytes(chunk).map_err(|e| format!("第 {} 个域元素无效: {}", i, e))
+        })
+        .collect()
+}
@@ -0,0 +1,429 @@
+//! 数据列 sidecar
+//!
+//! 节点之间交换的不是单个 blob 的 cell，而是跨越区块内全部 blob 的列：第 `index` 列
+//! 包含每个 blob 的第 `index` 个 cell 及其证明。[`DataColumnSidecar`] 的字段与 Fulu 共识规范一致：
+//!
+//! ```text
+//! DataColumnSidecar {
+//!     index: ColumnIndex,
+//!     column: List[Cell, MAX_BLOB_COMMITMENTS_PER_BLOCK],
+//!     kzg_commitments: List[KZGCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK],
+//!     kzg_proofs: List[KZGProof, MAX_BLOB_COMMITMENTS_PER_BLOCK],
+//!     signed_block_header: SignedBeaconBlockHeader,
+//!     kzg_commitments_inclusion_proof: Vector[Bytes32, KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH],
+//! }
+//! ```
+//!
+//! 区块头与包含证明在本教程中只是占位 (全零，除非由构建器提供)，不做默克尔校验。
+//! SSZ 编码中三个列表字段以 4 字节偏移量引用容器末尾的变长部分。
+
+use kzg::{
+    eip_4844::{blob_to_kzg_commitment_rust, bytes_to_blob, BYTES_PER_FIELD_ELEMENT},
+    eth::FIELD_ELEMENTS_PER_CELL,
+    Fr, G1, DAS,
+};
+use rust_kzg_blst::{
+    eip_7594::BlstBackend,
+    types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings},
+};
+
+use super::{ColumnIndex, NUMBER_OF_COLUMNS};
+use crate::sidecar::{BeaconBlockHeader, SignedBeaconBlockHeader};
+
+/// 单个 cell 的字节数
+pub const BYTES_PER_CELL: usize = FIELD_ELEMENTS_PER_CELL * BYTES_PER_FIELD_ELEMENT;
+
+/// 区块中承诺数量上限 (规范 `MAX_BLOB_COMMITMENTS_PER_BLOCK`)，也是 SSZ 列表上限
+pub const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize = 4096;
+
+/// `blob_kzg_commitments` 在区块体中的默克尔分支长度 (规范 `KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH`)
+pub const KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH: usize = 4;
+
+const BYTES_PER_POINT: usize = 48;
+const BYTES_PER_SIGNATURE: usize = 96;
+
+/// `SignedBeaconBlockHeader` 的 SSZ 长度：5 个字段的区块头 + 签名
+const SIGNED_BLOCK_HEADER_SIZE: usize = 8 + 8 + 3 * 32 + BYTES_PER_SIGNATURE;
+
+/// 容器定长部分：index + 三个偏移量 + 区块头 + 包含证明
+const FIXED_PART_SIZE: usize = 8 + 3 * 4 + SIGNED_BLOCK_HEADER_SIZE + KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH * 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ColumnError {
+    #[error("列索引 {0} 超出范围")]
+    InvalidColumnIndex(ColumnIndex),
+
+    #[error("列中没有任何 cell")]
+    EmptyColumn,
+
+    #[error("列长度不一致: cells {cells}, 承诺 {commitments}, 证明 {proofs}")]
+    LengthMismatch {
+        cells: usize,
+        commitments: usize,
+        proofs: usize,
+    },
+
+    #[error("blob 数量 {count} 超过上限 {max}")]
+    TooManyBlobs { count: usize, max: usize },
+
+    #[error("字段 {field} 长度错误: 期望 {expected} 字节，实际 {actual}")]
+    InvalidLength {
+        field: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("无效的 cell ({field}): {reason}")]
+    InvalidCell { field: String, reason: String },
+
+    #[error("无效的 G1 点 ({field}): {reason}")]
+    InvalidPoint { field: String, reason: String },
+
+    #[error("SSZ 解码失败: {0}")]
+    Ssz(String),
+
+    #[error("KZG 操作错误: {0}")]
+    KZGError(String),
+}
+
+// ================================================================================================
+// 数据列 sidecar
+// ================================================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataColumnSidecar {
+    /// 列索引
+    pub index: ColumnIndex,
+    /// 每个 blob 在该列的 cell (按 blob 顺序，各 `BYTES_PER_CELL` 字节)
+    pub column: Vec<Vec<u8>>,
+    /// 区块中全部 blob 的承诺
+    pub kzg_commitments: Vec<[u8; 48]>,
+    /// 每个 cell 的证明
+    pub kzg_proofs: Vec<[u8; 48]>,
+    pub signed_block_header: SignedBeaconBlockHeader,
+    /// 承诺列表在区块体中的默克尔包含证明 (占位)
+    pub kzg_commitments_inclusion_proof: [[u8; 32]; KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH],
+}
+
+impl DataColumnSidecar {
+    /// 列中的 cell 数，即区块中的 blob 数
+    pub fn blob_count(&self) -> usize {
+        self.column.len()
+    }
+
+    /// 含 `blob_count` 个 cell 的 sidecar 的 SSZ 编码长度
+    pub fn ssz_len(blob_count: usize) -> usize {
+        FIXED_PART_SIZE + blob_count * (BYTES_PER_CELL + 2 * BYTES_PER_POINT)
+    }
+
+    /// 规范 `verify_data_column_sidecar` 的结构检查：索引范围、非空、三个列表等长
+    pub fn validate(&self) -> Result<(), ColumnError> {
+        if self.index >= NUMBER_OF_COLUMNS {
+            return Err(ColumnError::InvalidColumnIndex(self.index));
+        }
+        if self.kzg_commitments.is_empty() {
+            return Err(ColumnError::EmptyColumn);
+        }
+        if self.column.len() != self.kzg_commitments.len() || self.column.len() != self.kzg_proofs.len() {
+            return Err(ColumnError::LengthMismatch {
+                cells: self.column.len(),
+                commitments: self.kzg_commitments.len(),
+                proofs: self.kzg_proofs.len(),
+            });
+        }
+        if self.column.len() > MAX_BLOB_COMMITMENTS_PER_BLOCK {
+            return Err(ColumnError::TooManyBlobs {
+                count: self.column.len(),
+                max: MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            });
+        }
+        Ok(())
+    }
+
+    /// 以一次 `verify_cell_kzg_proof_batch` 验证列中全部 cell
+    ///
+    /// 结构不合法时返回错误；cell 或点的字节无法解码时视为验证失败。
+    pub fn verify_kzg_proofs(&self, settings: &FsKZGSettings) -> Result<bool, ColumnError> {
+        verify_data_column_sidecars(settings, std::slice::from_ref(self))
+    }
+
+    /// SSZ 编码
+    pub fn to_ssz(&self) -> Result<Vec<u8>, ColumnError> {
+        self.validate()?;
+        for (i, cell) in self.column.iter().enumerate() {
+            check_length(&format!("column[{}]", i), BYTES_PER_CELL, cell.len())?;
+        }
+        let signature = &self.signed_block_header.signature;
+        check_length("signed_block_header.signature", BYTES_PER_SIGNATURE, signature.len())?;
+
+        let count = self.column.len();
+        let column_size = count * BYTES_PER_CELL;
+        let points_size = count * BYTES_PER_POINT;
+        let mut out = Vec::with_capacity(Self::ssz_len(count));
+
+        out.extend_from_slice(&self.index.to_le_bytes());
+        for offset in [
+            FIXED_PART_SIZE,
+            FIXED_PART_SIZE + column_size,
+            FIXED_PART_SIZE + column_size + points_size,
+        ] {
+            out.extend_from_slice(&(offset as u32).to_le_bytes());
+        }
+        let header = &self.signed_block_header.message;
+        out.extend_from_slice(&header.slot.to_le_bytes());
+        out.extend_from_slice(&header.proposer_index.to_le_bytes());
+        out.extend_from_slice(&header.parent_root);
+        out.extend_from_slice(&header.state_root);
+        out.extend_from_slice(&header.body_root);
+        out.extend_from_slice(signature);
+        for node in &self.kzg_commitments_inclusion_proof {
+            out.extend_from_slice(node);
+        }
+
+        for cell in &self.column {
+            out.extend_from_slice(cell);
+        }
+        for point in self.kzg_commitments.iter().chain(&self.kzg_proofs) {
+            out.extend_from_slice(point);
+        }
+        Ok(out)
+    }
+
+    /// SSZ 解码，检查偏移量与各列表的元素长度和上限
+    pub fn from_ssz(bytes: &[u8]) -> Result<Self, ColumnError> {
+        if bytes.len() < FIXED_PART_SIZE {
+            return Err(ColumnError::Ssz(format!(
+                "need at least {} bytes, got {}",
+                FIXED_PART_SIZE,
+                bytes.len()
+            )));
+        }
+        let mut fixed = SszReader { bytes: &bytes[..FIXED_PART_SIZE] };
+
+        let index = u64::from_le_bytes(fixed.array());
+        let offsets: [usize; 3] = std::array::from_fn(|_| u32::from_le_bytes(fixed.array()) as usize);
+        if offsets[0] != FIXED_PART_SIZE {
+            return Err(ColumnError::Ssz(format!("first offset must be {}, got {}", FIXED_PART_SIZE, offsets[0])));
+        }
+        if offsets[1] < offsets[0] || offsets[2] < offsets[1] || bytes.len() < offsets[2] {
+            return Err(ColumnError::Ssz(format!("offsets out of order: {:?}", offsets)));
+        }
+
+        let message = BeaconBlockHeader {
+            slot: u64::from_le_bytes(fixed.array()),
+            proposer_index: u64::from_le_bytes(fixed.array()),
+            parent_root: fixed.array(),
+            state_root: fixed.array(),
+            body_root: fixed.array(),
+        };
+        let signature = fixed.array::<BYTES_PER_SIGNATURE>().to_vec();
+        let kzg_commitments_inclusion_proof = std::array::from_fn(|_| fixed.array());
+
+        let column = split_list("column", &bytes[offsets[0]..offsets[1]], BYTES_PER_CELL)?
+            .map(<[u8]>::to_vec)
+            .collect();
+        let kzg_commitments = split_points("kzg_commitments", &bytes[offsets[1]..offsets[2]])?;
+        let kzg_proofs = split_points("kzg_proofs", &bytes[offsets[2]..])?;
+
+        Ok(Self {
+            index,
+            column,
+            kzg_commitments,
+            kzg_proofs,
+            signed_block_header: SignedBeaconBlockHeader { message, signature },
+            kzg_commitments_inclusion_proof,
+        })
+    }
+}
+
+/// 以一次 `verify_cell_kzg_proof_batch` 验证多个 sidecar 中的全部 cell
+///
+/// 任一 sidecar 结构不合法时返回错误；cell 或点的字节无法解码时视为验证失败。
+pub fn verify_data_column_sidecars(settings: &FsKZGSettings, sidecars: &[DataColumnSidecar]) -> Result<bool, ColumnError> {
+    let total: usize = sidecars.iter().map(DataColumnSidecar::blob_count).sum();
+    let mut commitments = Vec::with_capacity(total);
+    let mut cell_indices = Vec::with_capacity(total);
+    let mut cells = Vec::with_capacity(total * FIELD_ELEMENTS_PER_CELL);
+    let mut proofs = Vec::with_capacity(total);
+
+    for sidecar in sidecars {
+        sidecar.validate()?;
+        let items = sidecar.kzg_commitments.iter().zip(&sidecar.column).zip(&sidecar.kzg_proofs);
+        for ((commitment, cell), proof) in items {
+            let (Ok(commitment), Ok(cell), Ok(proof)) = (
+                decode_point("kzg_commitments", commitment),
+                decode_cell("column", cell),
+                decode_point("kzg_proofs", proof),
+            ) else {
+                return Ok(false);
+            };
+            commitments.push(commitment);
+            cell_indices.push(sidecar.index as usize);
+            cells.extend(cell);
+            proofs.push(proof);
+        }
+    }
+    if commitments.is_empty() {
+        return Ok(true);
+    }
+
+    <FsKZGSettings as DAS<BlstBackend>>::verify_cell_kzg_proof_batch(settings, &commitments, &cell_indices, &cells, &proofs)
+        .map_err(ColumnError::KZGError)
+}
+
+// ================================================================================================
+// 构建器
+// ================================================================================================
+
+/// 由区块中的全部 blob 构建 [`NUMBER_OF_COLUMNS`] 个数据列 sidecar
+pub struct DataColumnSidecarBuilder<'a> {
+    settings: &'a FsKZGSettings,
+    signed_block_header: SignedBeaconBlockHeader,
+    kzg_commitments_inclusion_proof: [[u8; 32]; KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH],
+}
+
+impl<'a> DataColumnSidecarBuilder<'a> {
+    pub fn new(settings: &'a FsKZGSettings) -> Self {
+        Self {
+            settings,
+            signed_block_header: SignedBeaconBlockHeader {
+                message: BeaconBlockHeader {
+                    slot: 0,
+                    proposer_index: 0,
+                    parent_root: [0; 32],
+                    state_root: [0; 32],
+                    body_root: [0; 32],
+                },
+                signature: vec![0; BYTES_PER_SIGNATURE],
+            },
+            kzg_commitments_inclusion_proof: [[0; 32]; KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH],
+        }
+    }
+
+    /// 写入每个 sidecar 的区块头 (默认全零)
+    pub fn signed_block_header(mut self, header: SignedBeaconBlockHeader) -> Self {
+        self.signed_block_header = header;
+        self
+    }
+
+    /// 写入每个 sidecar 的承诺包含证明 (默认全零)
+    pub fn kzg_commitments_inclusion_proof(mut self, proof: [[u8; 32]; KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH]) -> Self {
+        self.kzg_commitments_inclusion_proof = proof;
+        self
+    }
+
+    /// 对每个 blob 计算承诺与 `compute_cells_and_kzg_proofs`，再按列转置
+    pub fn build<B: AsRef<[u8]>>(&self, blobs: &[B]) -> Result<Vec<DataColumnSidecar>, ColumnError> {
+        if blobs.is_empty() {
+            return Err(ColumnError::EmptyColumn);
+        }
+        if blobs.len() > MAX_BLOB_COMMITMENTS_PER_BLOCK {
+            return Err(ColumnError::TooManyBlobs {
+                count: blobs.len(),
+                max: MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            });
+        }
+
+        let columns = NUMBER_OF_COLUMNS as usize;
+        let mut sidecars: Vec<DataColumnSidecar> = (0..NUMBER_OF_COLUMNS)
+            .map(|index| DataColumnSidecar {
+                index,
+                column: Vec::with_capacity(blobs.len()),
+                kzg_commitments: Vec::with_capacity(blobs.len()),
+                kzg_proofs: Vec::with_capacity(blobs.len()),
+                signed_block_header: self.signed_block_header.clone(),
+                kzg_commitments_inclusion_proof: self.kzg_commitments_inclusion_proof,
+            })
+            .collect();
+
+        let mut commitments = Vec::with_capacity(blobs.len());
+        for blob in blobs {
+            let blob = bytes_to_blob(blob.as_ref()).map_err(ColumnError::KZGError)?;
+            commitments.push(blob_to_kzg_commitment_rust(&blob, self.settings).map_err(ColumnError::KZGError)?.to_bytes());
+
+            let mut cells = vec![FsFr::default(); columns * FIELD_ELEMENTS_PER_CELL];
+            let mut proofs = vec![FsG1::default(); columns];
+            <FsKZGSettings as DAS<BlstBackend>>::compute_cells_and_kzg_proofs(
+                self.settings,
+                Some(&mut cells),
+                Some(&mut proofs),
+                &blob,
+            )
+            .map_err(ColumnError::KZGError)?;
+
+            for ((sidecar, cell), proof) in sidecars.iter_mut().zip(cells.chunks_exact(FIELD_ELEMENTS_PER_CELL)).zip(&proofs) {
+                sidecar.column.push(cell.iter().flat_map(|fr| fr.to_bytes()).collect());
+                sidecar.kzg_proofs.push(proof.to_bytes());
+            }
+        }
+
+        for sidecar in &mut sidecars {
+            sidecar.kzg_commitments = commitments.clone();
+        }
+        Ok(sidecars)
+    }
+}
+
+// ================================================================================================
+// 编解码辅助
+// ================================================================================================
+
+fn check_length(field: &str, expected: usize, actual: usize) -> Result<(), ColumnError> {
+    if expected != actual {
+        return Err(ColumnError::InvalidLength {
+            field: field.to_string(),
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+pub(super) fn decode_point(field: &str, bytes: &[u8; 48]) -> Result<FsG1, ColumnError> {
+    FsG1::from_bytes(bytes).map_err(|reason| ColumnError::InvalidPoint {
+        field: field.to_string(),
+        reason,
+    })
+}
+
+pub(super) fn decode_cell(field: &str, cell: &[u8]) -> Result<Vec<FsFr>, ColumnError> {
+    check_length(field, BYTES_PER_CELL, cell.len())?;
+    cell.chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .map(|element| {
+            FsFr::from_bytes(element).map_err(|reason| ColumnError::InvalidCell {
+                field: field.to_string(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+/// 把 SSZ 列表的字节按定长元素切分，检查整除与上限
+fn split_list<'b>(field: &str, bytes: &'b [u8], element: usize) -> Result<std::slice::ChunksExact<'b, u8>, ColumnError> {
+    if bytes.len() % element != 0 {
+        return Err(ColumnError::Ssz(format!("{} length {} is not a multiple of {}", field, bytes.len(), element)));
+    }
+    if bytes.len() / element > MAX_BLOB_COMMITMENTS_PER_BLOCK {
+        return Err(ColumnError::Ssz(format!("{} exceeds {} elements", field, MAX_BLOB_COMMITMENTS_PER_BLOCK)));
+    }
+    Ok(bytes.chunks_exact(element))
+}
+
+fn split_points(field: &str, bytes: &[u8]) -> Result<Vec<[u8; 48]>, ColumnError> {
+    Ok(split_list(field, bytes, BYTES_PER_POINT)?
+        .map(|point| point.try_into().expect("chunks are 48 bytes"))
+        .collect())
+}
+
+/// 按顺序读取定长字段，长度由调用方保证
+struct SszReader<'b> {
+    bytes: &'b [u8],
+}
+
+impl SszReader<'_> {
+    fn array<const N: usize>(&mut self) -> [u8; N] {
+        let (field, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        field.try_into().expect("fixed part length checked")
+    }
+}
@@ -0,0 +1,279 @@
+//! 列托管分配
+//!
+//! 按 Fulu 共识规范 (`das-core.md`) 由节点 ID 与托管组数量确定节点必须保存的列：
+//!
+//! - [`get_custody_groups`]：从 `node_id` 起逐个递增，取 `sha256(uint_to_bytes(id))` 前 8 字节
+//!   (小端序) 模 [`NUMBER_OF_CUSTODY_GROUPS`]，收集到足够多互不相同的组后排序返回
+//! - [`compute_columns_for_custody_group`]：组 `g` 托管列 `NUMBER_OF_CUSTODY_GROUPS * i + g`
+//!
+//! [`CustodyAssignment`] 汇总一个节点的托管列，并把 `compute_cells_and_kzg_proofs`
+//! 的输出筛选为节点自己的托管集合。
+
+use std::fmt;
+use std::str::FromStr;
+
+use kzg::{eip_4844::bytes_to_blob, eth::FIELD_ELEMENTS_PER_CELL, DAS};
+use rust_kzg_blst::{
+    eip_7594::BlstBackend,
+    types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings},
+};
+use sha2::{Digest, Sha256};
+
+use super::{ColumnIndex, CustodyIndex, NUMBER_OF_COLUMNS, NUMBER_OF_CUSTODY_GROUPS};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CustodyError {
+    #[error("托管组数量 {requested} 超过上限 {max}")]
+    TooManyGroups { requested: u64, max: u64 },
+
+    #[error("托管组索引 {0} 超出范围")]
+    InvalidGroup(CustodyIndex),
+
+    #[error("无效的节点 ID: {0}")]
+    InvalidNodeId(String),
+
+    #[error("{field} 数量错误: 期望 {expected}，实际 {actual}")]
+    LengthMismatch {
+        field: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("KZG 操作错误: {0}")]
+    KZGError(String),
+}
+
+// ================================================================================================
+// 节点 ID
+// ================================================================================================
+
+/// 节点 ID (规范中的 `uint256`)，以 32 字节大端序保存，与 discv5 节点 ID 的字节表示一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct NodeId(pub [u8; 32]);
+
+impl NodeId {
+    pub const MAX: NodeId = NodeId([0xff; 32]);
+
+    /// 加一，`UINT256_MAX` 回绕为 0 (规范中的溢出处理)
+    fn increment(&mut self) {
+        for byte in self.0.iter_mut().rev() {
+            let (value, overflow) = byte.overflowing_add(1);
+            *byte = value;
+            if !overflow {
+                return;
+            }
+        }
+    }
+
+    /// 规范中的 `uint_to_bytes(uint256)`：32 字节小端序
+    fn to_le_bytes(self) -> [u8; 32] {
+        let mut bytes = self.0;
+        bytes.reverse();
+        bytes
+    }
+
+    /// 十进制解析，规范测试向量中的节点 ID 为十进制整数
+    fn from_decimal(text: &str) -> Option<Self> {
+        let mut value = [0u8; 32];
+        for digit in text.bytes() {
+            let mut carry = u32::from(digit.checked_sub(b'0').filter(|d| *d < 10)?);
+            for byte in value.iter_mut().rev() {
+                let product = u32::from(*byte) * 10 + carry;
+                *byte = product as u8;
+                carry = product >> 8;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(NodeId(value))
+    }
+}
+
+impl From<[u8; 32]> for NodeId {
+    fn from(bytes: [u8; 32]) -> Self {
+        NodeId(bytes)
+    }
+}
+
+/// 接受十进制整数或 `0x` 前缀的十六进制 (至多 32 字节，大端序)
+impl FromStr for NodeId {
+    type Err = CustodyError;
+
+    fn from_str(text: &str) -> Result<Self, CustodyError> {
+        let invalid = || CustodyError::InvalidNodeId(text.to_string());
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(invalid());
+        }
+        let Some(hex_digits) = text.strip_prefix("0x") else {
+            return NodeId::from_decimal(text).ok_or_else(invalid);
+        };
+        if hex_digits.is_empty() || hex_digits.len() > 64 {
+            return Err(invalid());
+        }
+        let padded = format!("{:0>64}", hex_digits);
+        let bytes = hex::decode(padded).map_err(|_| invalid())?;
+        Ok(NodeId(bytes.try_into().map_err(|_| invalid())?))
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+// ================================================================================================
+// 规范函数
+// ================================================================================================
+
+/// 规范 `get_custody_groups`：节点托管的组索引，升序
+pub fn get_custody_groups(node_id: &NodeId, custody_group_count: u64) -> Result<Vec<CustodyIndex>, CustodyError> {
+    if custody_group_count > NUMBER_OF_CUSTODY_GROUPS {
+        return Err(CustodyError::TooManyGroups {
+            requested: custody_group_count,
+            max: NUMBER_OF_CUSTODY_GROUPS,
+        });
+    }
+    // 托管全部组时跳过计算
+    if custody_group_count == NUMBER_OF_CUSTODY_GROUPS {
+        return Ok((0..NUMBER_OF_CUSTODY_GROUPS).collect());
+    }
+
+    let mut current_id = *node_id;
+    let mut taken = [false; NUMBER_OF_CUSTODY_GROUPS as usize];
+    let mut groups = Vec::with_capacity(custody_group_count as usize);
+    while (groups.len() as u64) < custody_group_count {
+        let digest = Sha256::digest(current_id.to_le_bytes());
+        let prefix: [u8; 8] = digest[0..8].try_into().expect("sha256 digest is 32 bytes");
+        let group = u64::from_le_bytes(prefix) % NUMBER_OF_CUSTODY_GROUPS;
+        if !taken[group as usize] {
+            taken[group as usize] = true;
+            groups.push(group);
+        }
+        current_id.increment();
+    }
+    groups.sort_unstable();
+    Ok(groups)
+}
+
+/// 规范 `compute_columns_for_custody_group`：一个托管组对应的列索引
+pub fn compute_columns_for_custody_group(custody_group: CustodyIndex) -> Result<Vec<ColumnIndex>, CustodyError> {
+    if custody_group >= NUMBER_OF_CUSTODY_GROUPS {
+        return Err(CustodyError::InvalidGroup(custody_group));
+    }
+    let columns_per_group = NUMBER_OF_COLUMNS / NUMBER_OF_CUSTODY_GROUPS;
+    Ok((0..columns_per_group)
+        .map(|i| NUMBER_OF_CUSTODY_GROUPS * i + custody_group)
+        .collect())
+}
+
+// ================================================================================================
+// 托管集合
+// ================================================================================================
+
+/// 一个节点的托管分配
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustodyAssignment {
+    pub node_id: NodeId,
+    pub custody_group_count: u64,
+    /// 托管组索引，升序
+    pub groups: Vec<CustodyIndex>,
+    /// 托管列索引，升序
+    pub columns: Vec<ColumnIndex>,
+}
+
+/// 筛选后的托管 cell 与证明，`cells` 按 `column_indices` 的顺序平铺，
+/// 可直接传给 `verify_cell_kzg_proof_batch`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustodyCells<F, P> {
+    pub column_indices: Vec<ColumnIndex>,
+    pub cells: Vec<F>,
+    pub proofs: Vec<P>,
+}
+
+impl<F, P> CustodyCells<F, P> {
+    /// `verify_cell_kzg_proof_batch` 使用的 cell 索引
+    pub fn cell_indices(&self) -> Vec<usize> {
+        self.column_indices.iter().map(|&column| column as usize).collect()
+    }
+}
+
+impl CustodyAssignment {
+    pub fn new(node_id: NodeId, custody_group_count: u64) -> Result<Self, CustodyError> {
+        let groups = get_custody_groups(&node_id, custody_group_count)?;
+        let mut columns = Vec::new();
+        for &group in &groups {
+            columns.extend(compute_columns_for_custody_group(group)?);
+        }
+        columns.sort_unstable();
+        Ok(Self {
+            node_id,
+            custody_group_count,
+            groups,
+            columns,
+        })
+    }
+
+    /// 节点是否托管该列
+    pub fn custodies(&self, column: ColumnIndex) -> bool {
+        self.columns.binary_search(&column).is_ok()
+    }
+
+    /// 从一个 blob 的全部 cell (平铺，`NUMBER_OF_COLUMNS * FIELD_ELEMENTS_PER_CELL` 个元素)
+    /// 和证明 (`NUMBER_OF_COLUMNS` 个) 中取出托管列
+    pub fn filter_cells_and_proofs<F: Clone, P: Clone>(
+        &self,
+        cells: &[F],
+        proofs: &[P],
+    ) -> Result<CustodyCells<F, P>, CustodyError> {
+        let columns = NUMBER_OF_COLUMNS as usize;
+        check_length("cells", columns * FIELD_ELEMENTS_PER_CELL, cells.len())?;
+        check_length("proofs", columns, proofs.len())?;
+
+        let mut custody = CustodyCells {
+            column_indices: self.columns.clone(),
+            cells: Vec::with_capacity(self.columns.len() * FIELD_ELEMENTS_PER_CELL),
+            proofs: Vec::with_capacity(self.columns.len()),
+        };
+        for &column in &self.columns {
+            let column = column as usize;
+            let start = column * FIELD_ELEMENTS_PER_CELL;
+            custody.cells.extend_from_slice(&cells[start..start + FIELD_ELEMENTS_PER_CELL]);
+            custody.proofs.push(proofs[column].clone());
+        }
+        Ok(custody)
+    }
+
+    /// 计算 blob 的全部 cell 和证明并筛选出托管列
+    pub fn compute_custody_cells(
+        &self,
+        settings: &FsKZGSettings,
+        blob: &[u8],
+    ) -> Result<CustodyCells<FsFr, FsG1>, CustodyError> {
+        let blob = bytes_to_blob(blob).map_err(CustodyError::KZGError)?;
+        let columns = NUMBER_OF_COLUMNS as usize;
+        let mut cells = vec![FsFr::default(); columns * FIELD_ELEMENTS_PER_CELL];
+        let mut proofs = vec![FsG1::default(); columns];
+        <FsKZGSettings as DAS<BlstBackend>>::compute_cells_and_kzg_proofs(
+            settings,
+            Some(&mut cells),
+            Some(&mut proofs),
+            &blob,
+        )
+        .map_err(CustodyError::KZGError)?;
+        self.filter_cells_and_proofs(&cells, &proofs)
+    }
+}
+
+fn check_length(field: &'static str, expected: usize, actual: usize) -> Result<(), CustodyError> {
+    if expected != actual {
+        return Err(CustodyError::LengthMismatch {
+            field,
+            expected,
+            actual,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! 第7章扩展：PeerDAS 数据可用性采样
+//!
+//! 第7章的示例在本地计算并恢复单个 blob 的 cell，这里补充节点之间交换数据所需的部分：
+//!
+//! - [`custody`]：按共识规范由节点 ID 确定托管的列
+//! - [`column`]：跨越区块内全部 blob 的数据列 sidecar，构建、SSZ 编解码与批量验证
+//! - [`recovery`]：由任意不少于一半的 cell 子集 (乱序、交错、含重复) 恢复全部 cell 与证明
+//! - [`simulator`]：进程内的采样模拟，对手扣留部分 cell，统计检测概率、带宽与延迟
+//! - [`verifier`]：gossip 场景下的流式 cell 验证，按数量或时间批量刷新，失败时二分定位错误的 cell
+
+pub mod column;
+pub mod custody;
+pub mod recovery;
+pub mod simulator;
+pub mod verifier;
+
+pub use column::{
+    verify_data_column_sidecars, ColumnError, DataColumnSidecar, DataColumnSidecarBuilder, BYTES_PER_CELL,
+    KZG_COMMITMENTS_INCLUSION_PROOF_DEPTH, MAX_BLOB_COMMITMENTS_PER_BLOCK,
+};
+pub use custody::{
+    compute_columns_for_custody_group, get_custody_groups, CustodyAssignment, CustodyCells, CustodyError, NodeId,
+};
+pub use recovery::{recover_cells_and_proofs, RecoveredCells, RecoveryError, CELLS_REQUIRED_FOR_RECOVERY};
+pub use simulator::{
+    AdversaryConfig, BandwidthStats, DasSimulator, LatencyStats, NetworkConfig, SamplingConfig, Scenario, SimulationError,
+    SimulationReport, WithholdStrategy,
+};
+pub use verifier::{CellStatus, CellVerdict, CellVerifier, CellVerifierConfig, VerifierStats};
+
+use kzg::eth::CELLS_PER_EXT_BLOB;
+
+/// 托管组索引 (规范 `CustodyIndex`)
+pub type CustodyIndex = u64;
+
+/// 列索引 (规范 `ColumnIndex`)，与扩展 blob 中的 cell 索引一致
+pub type ColumnIndex = u64;
+
+/// 扩展 blob 的列数 (规范 `NUMBER_OF_COLUMNS`)
+pub const NUMBER_OF_COLUMNS: u64 = CELLS_PER_EXT_BLOB as u64;
+
+/// 托管组数 (规范 `NUMBER_OF_CUSTODY_GROUPS`)
+pub const NUMBER_OF_CUSTODY_GROUPS: u64 = 128;
+
+/// 每个节点至少托管的组数 (规范 `CUSTODY_REQUIREMENT`)
+pub const CUSTODY_REQUIREMENT: u64 = 4;
+
+/// 每个采样节点每个时隙采样的列数 (规范 `SAMPLES_PER_SLOT`)
+pub const SAMPLES_PER_SLOT: u64 = 8;
@@ -0,0 +1,120 @@
+//! 由任意 cell 子集恢复
+//!
+//! 网络中收到的 cell 来自不同的托管节点，索引既不连续也没有顺序，同一列还可能从多个
+//! 节点重复收到。[`recover_cells_and_proofs`] 接受任意不少于一半 ([`CELLS_REQUIRED_FOR_RECOVERY`])
+//! 的互不相同的 cell：
+//!
+//! - 索引可以乱序、交错或随机分布，恢复前按索引升序整理
+//! - 内容相同的重复 cell 只保留一份；同一索引内容不同则报错，避免用不一致的数据恢复
+//! - 返回全部 cell 以及重新计算的全部证明
+
+use std::collections::btree_map::{BTreeMap, Entry};
+
+use kzg::{
+    eth::{CELLS_PER_EXT_BLOB, FIELD_ELEMENTS_PER_CELL},
+    DAS,
+};
+use rust_kzg_blst::{
+    eip_7594::BlstBackend,
+    types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings},
+};
+
+use super::ColumnIndex;
+
+/// 恢复所需的最少不同 cell 数
+pub const CELLS_REQUIRED_FOR_RECOVERY: usize = CELLS_PER_EXT_BLOB / 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RecoveryError {
+    #[error("cells 元素数量错误: {indices} 个索引需要 {expected} 个域元素，实际 {actual}")]
+    LengthMismatch {
+        indices: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("cell 索引 {0} 超出范围")]
+    InvalidCellIndex(ColumnIndex),
+
+    #[error("cell 索引 {0} 重复出现且内容不一致")]
+    ConflictingDuplicate(ColumnIndex),
+
+    #[error("恢复至少需要 {required} 个不同的 cell，实际只有 {received} 个")]
+    NotEnoughCells { required: usize, received: usize },
+
+    #[error("KZG 操作错误: {0}")]
+    KZGError(String),
+}
+
+/// 恢复结果：全部 cell (平铺，`CELLS_PER_EXT_BLOB * FIELD_ELEMENTS_PER_CELL` 个元素) 与证明
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveredCells {
+    pub cells: Vec<FsFr>,
+    pub proofs: Vec<FsG1>,
+}
+
+impl RecoveredCells {
+    /// 第 `index` 个 cell 的域元素
+    pub fn cell(&self, index: usize) -> &[FsFr] {
+        &self.cells[index * FIELD_ELEMENTS_PER_CELL..(index + 1) * FIELD_ELEMENTS_PER_CELL]
+    }
+}
+
+/// 由任意不少于一半的 cell 恢复全部 cell 并重新计算证明
+///
+/// `cells` 按 `cell_indices` 的顺序平铺，每个索引对应 `FIELD_ELEMENTS_PER_CELL` 个域元素。
+pub fn recover_cells_and_proofs(
+    settings: &FsKZGSettings,
+    cell_indices: &[ColumnIndex],
+    cells: &[FsFr],
+) -> Result<RecoveredCells, RecoveryError> {
+    let expected = cell_indices.len() * FIELD_ELEMENTS_PER_CELL;
+    if cells.len() != expected {
+        return Err(RecoveryError::LengthMismatch {
+            indices: cell_indices.len(),
+            expected,
+            actual: cells.len(),
+        });
+    }
+
+    // 按索引去重并排序
+    let mut unique: BTreeMap<usize, &[FsFr]> = BTreeMap::new();
+    for (&index, cell) in cell_indices.iter().zip(cells.chunks_exact(FIELD_ELEMENTS_PER_CELL)) {
+        let position = usize::try_from(index)
+            .ok()
+            .filter(|position| *position < CELLS_PER_EXT_BLOB)
+            .ok_or(RecoveryError::InvalidCellIndex(index))?;
+        match unique.entry(position) {
+            Entry::Vacant(entry) => {
+                entry.insert(cell);
+            }
+            Entry::Occupied(entry) if *entry.get() != cell => {
+                return Err(RecoveryError::ConflictingDuplicate(index));
+            }
+            Entry::Occupied(_) => {}
+        }
+    }
+    if unique.len() < CELLS_REQUIRED_FOR_RECOVERY {
+        return Err(RecoveryError::NotEnoughCells {
+            required: CELLS_REQUIRED_FOR_RECOVERY,
+            received: unique.len(),
+        });
+    }
+
+    let indices: Vec<usize> = unique.keys().copied().collect();
+    let sorted_cells: Vec<FsFr> = unique.values().flat_map(|cell| cell.iter().cloned()).collect();
+
+    let mut recovered = RecoveredCells {
+        cells: vec![FsFr::default(); CELLS_PER_EXT_BLOB * FIELD_ELEMENTS_PER_CELL],
+        proofs: vec![FsG1::default(); CELLS_PER_EXT_BLOB],
+    };
+    <FsKZGSettings as DAS<BlstBackend>>::recover_cells_and_kzg_proofs(
+        settings,
+        &mut recovered.cells,
+        Some(&mut recovered.proofs),
+        &indices,
+        &sorted_cells,
+    )
+    .map_err(RecoveryError::KZGError)?;
+    Ok(recovered)
+}
@@ -0,0 +1,594 @@
+//! 数据可用性采样模拟
+//!
+//! 在单个进程内模拟一个 PeerDAS 网络，用来研究采样的检测能力与开销：
+//!
+//! - 对等节点按 [`CustodyAssignment`] 托管列，采样请求只发给托管该列的节点
+//! - 对手 (出块者) 按比例扣留 cell：整列扣留 ([`WithholdStrategy::Columns`]) 或随机扣留单个 cell
+//!   ([`WithholdStrategy::Cells`])，还可以给一部分 cell 附上错误的证明
+//! - 采样节点每个时隙随机选择 `samples_per_slot` 列向托管节点请求，收到的 cell 以一次
+//!   `verify_cell_kzg_proof_batch` 验证；任何一列未收到或验证失败即判定数据不可用
+//!
+//! 网络延迟与带宽是模拟值 (链路往返延迟 + 传输时间，未响应的请求按超时计)，KZG 验证则真实执行并计时。
+//! 场景以 TOML 描述，示例见仓库中的 `scenarios/das/`。
+
+use std::fmt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use kzg::{
+    eip_4844::{blob_to_kzg_commitment_rust, bytes_to_blob},
+    eth::{CELLS_PER_EXT_BLOB, FIELD_ELEMENTS_PER_CELL},
+    DAS,
+};
+use rand::{
+    rngs::StdRng,
+    seq::{index, SliceRandom},
+    Rng, SeedableRng,
+};
+use rust_kzg_blst::{
+    eip_7594::BlstBackend,
+    types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings},
+};
+use serde::Deserialize;
+
+use super::{
+    CustodyAssignment, CustodyError, DataColumnSidecar, NodeId, CELLS_REQUIRED_FOR_RECOVERY, CUSTODY_REQUIREMENT,
+    MAX_BLOB_COMMITMENTS_PER_BLOCK, NUMBER_OF_CUSTODY_GROUPS, SAMPLES_PER_SLOT,
+};
+use crate::blob::create_test_blob_bytes;
+
+/// 采样请求的字节数：区块根 + 列索引
+const REQUEST_BYTES: u64 = 32 + 8;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SimulationError {
+    #[error("无效的场景: {0}")]
+    InvalidScenario(String),
+
+    #[error("读取场景文件失败: {0}")]
+    Io(String),
+
+    #[error("场景解析失败: {0}")]
+    Parse(String),
+
+    #[error(transparent)]
+    Custody(#[from] CustodyError),
+
+    #[error("KZG 操作错误: {0}")]
+    KZGError(String),
+}
+
+// ================================================================================================
+// 场景
+// ================================================================================================
+
+/// 模拟场景
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 随机种子，相同的场景得到相同的报告 (验证耗时除外)
+    #[serde(default)]
+    pub seed: u64,
+    /// 区块中的 blob 数
+    #[serde(default = "default_blobs")]
+    pub blobs: usize,
+    /// 模拟的时隙数，每个时隙重新选择扣留的 cell 与采样的列
+    pub trials: usize,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub adversary: AdversaryConfig,
+    #[serde(default)]
+    pub sampling: SamplingConfig,
+}
+
+/// 对等网络
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// 托管数据的对等节点数
+    pub peers: usize,
+    /// 每个对等节点托管的组数
+    pub custody_group_count: u64,
+    /// 对等节点链路往返延迟的范围 (毫秒)，每个节点在其中均匀取值
+    pub min_latency_ms: f64,
+    pub max_latency_ms: f64,
+    /// 对等节点上行带宽 (Mbit/s)
+    pub bandwidth_mbps: f64,
+    /// 请求超时 (毫秒)，未收到响应的采样按此计入延迟
+    pub timeout_ms: f64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            peers: 100,
+            custody_group_count: CUSTODY_REQUIREMENT,
+            min_latency_ms: 20.0,
+            max_latency_ms: 200.0,
+            bandwidth_mbps: 50.0,
+            timeout_ms: 2000.0,
+        }
+    }
+}
+
+/// 对手扣留 cell 的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WithholdStrategy {
+    /// 扣留整列 (全部 blob 的同一列)，以最少的扣留量使数据不可恢复
+    #[default]
+    Columns,
+    /// 在全部 (blob, 列) 中随机扣留单个 cell
+    Cells,
+}
+
+/// 对手行为
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdversaryConfig {
+    /// 扣留的 cell 比例
+    pub withhold_fraction: f64,
+    pub strategy: WithholdStrategy,
+    /// 未扣留的 cell 中附带错误证明的比例
+    pub corrupt_fraction: f64,
+}
+
+/// 采样节点
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SamplingConfig {
+    pub nodes: usize,
+    pub samples_per_slot: usize,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            nodes: 10,
+            samples_per_slot: SAMPLES_PER_SLOT as usize,
+        }
+    }
+}
+
+fn default_blobs() -> usize {
+    1
+}
+
+impl Scenario {
+    /// 解析并检查 TOML 场景
+    pub fn from_toml(text: &str) -> Result<Self, SimulationError> {
+        let scenario: Scenario = toml::from_str(text).map_err(|e| SimulationError::Parse(e.to_string()))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, SimulationError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| SimulationError::Io(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml(&text)
+    }
+
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        let network = &self.network;
+        let adversary = &self.adversary;
+        let columns = CELLS_PER_EXT_BLOB;
+        let checks = [
+            (self.trials > 0, "trials 必须大于 0".to_string()),
+            (
+                (1..=MAX_BLOB_COMMITMENTS_PER_BLOCK).contains(&self.blobs),
+                format!("blobs 必须在 1..={} 之间", MAX_BLOB_COMMITMENTS_PER_BLOCK),
+            ),
+            (network.peers > 0, "network.peers 必须大于 0".to_string()),
+            (
+                network.custody_group_count <= NUMBER_OF_CUSTODY_GROUPS,
+                format!("network.custody_group_count 不能超过 {}", NUMBER_OF_CUSTODY_GROUPS),
+            ),
+            (
+                0.0 <= network.min_latency_ms && network.min_latency_ms <= network.max_latency_ms,
+                "network 延迟范围无效".to_string(),
+            ),
+            (network.bandwidth_mbps > 0.0, "network.bandwidth_mbps 必须大于 0".to_string()),
+            (network.timeout_ms >= 0.0, "network.timeout_ms 不能为负".to_string()),
+            (
+                (0.0..=1.0).contains(&adversary.withhold_fraction),
+                "adversary.withhold_fraction 必须在 0..=1 之间".to_string(),
+            ),
+            (
+                (0.0..=1.0).contains(&adversary.corrupt_fraction),
+                "adversary.corrupt_fraction 必须在 0..=1 之间".to_string(),
+            ),
+            (self.sampling.nodes > 0, "sampling.nodes 必须大于 0".to_string()),
+            (
+                (1..=columns).contains(&self.sampling.samples_per_slot),
+                format!("sampling.samples_per_slot 必须在 1..={} 之间", columns),
+            ),
+        ];
+        match checks.into_iter().find(|(ok, _)| !ok) {
+            Some((_, message)) => Err(SimulationError::InvalidScenario(format!("{}: {}", self.name, message))),
+            None => Ok(()),
+        }
+    }
+}
+
+// ================================================================================================
+// 报告
+// ================================================================================================
+
+/// 模拟报告
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationReport {
+    pub scenario: String,
+    pub trials: usize,
+    pub sampling_nodes: usize,
+    pub samples_per_node: usize,
+    /// 没有任何对等节点托管的列数
+    pub uncustodied_columns: usize,
+    /// 网络中可取得的 cell 不足以恢复全部 blob 的时隙数
+    pub unrecoverable_trials: usize,
+    /// 判定数据不可用的次数 (节点 × 时隙)
+    pub rejections: usize,
+    /// 观测到的检测概率：`rejections / (trials * sampling_nodes)`
+    pub detection_probability: f64,
+    /// 按每个时隙可取得的列数计算的理论检测概率 (不放回抽样) 的平均值，不含错误证明的影响
+    pub expected_detection_probability: f64,
+    /// 未收到响应的采样数
+    pub failed_samples: usize,
+    /// 批量验证未通过的次数
+    pub invalid_batches: usize,
+    pub bandwidth: BandwidthStats,
+    pub latency: LatencyStats,
+    /// 批量验证的真实耗时总和
+    pub verification_time: Duration,
+}
+
+/// 采样节点的下行流量
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BandwidthStats {
+    pub request_bytes: u64,
+    pub response_bytes: u64,
+    /// 每个节点每个时隙的平均字节数 (请求 + 响应)
+    pub bytes_per_node: f64,
+}
+
+impl BandwidthStats {
+    pub fn total_bytes(&self) -> u64 {
+        self.request_bytes + self.response_bytes
+    }
+}
+
+/// 一个节点完成一个时隙全部采样的模拟网络延迟 (并行请求，取最慢的一个)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LatencyStats {
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyStats {
+    fn from_samples(mut samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_by(f64::total_cmp);
+        let percentile = |q: f64| samples[((samples.len() - 1) as f64 * q).round() as usize];
+        Self {
+            mean_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+            p50_ms: percentile(0.5),
+            p95_ms: percentile(0.95),
+            max_ms: samples[samples.len() - 1],
+        }
+    }
+}
+
+impl fmt::Display for SimulationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "场景: {}", self.scenario)?;
+        writeln!(
+            f,
+            "  时隙 {}，采样节点 {}，每节点每时隙 {} 个样本",
+            self.trials, self.sampling_nodes, self.samples_per_node
+        )?;
+        writeln!(
+            f,
+            "  无人托管的列: {}，不可恢复的时隙: {}/{}",
+            self.uncustodied_columns, self.unrecoverable_trials, self.trials
+        )?;
+        writeln!(
+            f,
+            "  检测概率: {:.4} (理论 {:.4})，拒绝 {} 次，未响应样本 {}，验证失败 {}",
+            self.detection_probability,
+            self.expected_detection_probability,
+            self.rejections,
+            self.failed_samples,
+            self.invalid_batches
+        )?;
+        writeln!(
+            f,
+            "  带宽: 共 {} 字节 (请求 {}，响应 {})，每节点每时隙 {:.0} 字节",
+            self.bandwidth.total_bytes(),
+            self.bandwidth.request_bytes,
+            self.bandwidth.response_bytes,
+            self.bandwidth.bytes_per_node
+        )?;
+        writeln!(
+            f,
+            "  延迟: 平均 {:.1} ms，p50 {:.1} ms，p95 {:.1} ms，最大 {:.1} ms",
+            self.latency.mean_ms, self.latency.p50_ms, self.latency.p95_ms, self.latency.max_ms
+        )?;
+        write!(f, "  验证耗时: {:?}", self.verification_time)
+    }
+}
+
+// ================================================================================================
+// 模拟器
+// ================================================================================================
+
+/// 一个时隙中对手的选择，按 `blob * CELLS_PER_EXT_BLOB + column` 索引
+struct SlotState {
+    withheld: Vec<bool>,
+    corrupted: Vec<bool>,
+}
+
+/// 一个采样节点在一个时隙中的结果
+#[derive(Default)]
+struct NodeOutcome {
+    failed_samples: usize,
+    valid: bool,
+    request_bytes: u64,
+    response_bytes: u64,
+    latency_ms: f64,
+    verification_time: Duration,
+}
+
+/// 一次采样验证的输入
+#[derive(Default)]
+struct SampleBatch {
+    commitments: Vec<FsG1>,
+    cell_indices: Vec<usize>,
+    cells: Vec<FsFr>,
+    proofs: Vec<FsG1>,
+}
+
+/// 进程内 DAS 模拟器
+///
+/// 构造时计算场景中全部 blob 的 cell 与证明，并为每个对等节点生成节点 ID 与链路延迟；
+/// [`run`](Self::run) 逐时隙模拟对手与采样节点。
+pub struct DasSimulator<'a> {
+    settings: &'a FsKZGSettings,
+    scenario: Scenario,
+    commitments: Vec<FsG1>,
+    cells: Vec<Vec<FsFr>>,
+    proofs: Vec<Vec<FsG1>>,
+    /// 每列的托管节点
+    custodians: Vec<Vec<usize>>,
+    /// 每个对等节点的链路往返延迟 (毫秒)
+    peer_latency_ms: Vec<f64>,
+}
+
+impl<'a> DasSimulator<'a> {
+    pub fn new(settings: &'a FsKZGSettings, scenario: Scenario) -> Result<Self, SimulationError> {
+        scenario.validate()?;
+        let network = &scenario.network;
+        let mut rng = StdRng::seed_from_u64(scenario.seed);
+
+        let mut custodians = vec![Vec::new(); CELLS_PER_EXT_BLOB];
+        let mut peer_latency_ms = Vec::with_capacity(network.peers);
+        for peer in 0..network.peers {
+            let assignment = CustodyAssignment::new(NodeId(rng.gen()), network.custody_group_count)?;
+            for column in assignment.columns {
+                custodians[column as usize].push(peer);
+            }
+            peer_latency_ms.push(rng.gen_range(network.min_latency_ms..=network.max_latency_ms));
+        }
+
+        let mut commitments = Vec::with_capacity(scenario.blobs);
+        let mut cells = Vec::with_capacity(scenario.blobs);
+        let mut proofs = Vec::with_capacity(scenario.blobs);
+        for i in 0..scenario.blobs {
+            let blob = bytes_to_blob(&create_test_blob_bytes(i)).map_err(SimulationError::KZGError)?;
+            commitments.push(blob_to_kzg_commitment_rust(&blob, settings).map_err(SimulationError::KZGError)?);
+            let mut blob_cells = vec![FsFr::default(); CELLS_PER_EXT_BLOB * FIELD_ELEMENTS_PER_CELL];
+            let mut blob_proofs = vec![FsG1::default(); CELLS_PER_EXT_BLOB];
+            <FsKZGSettings as DAS<BlstBackend>>::compute_cells_and_kzg_proofs(
+                settings,
+                Some(&mut blob_cells),
+                Some(&mut blob_proofs),
+                &blob,
+            )
+            .map_err(SimulationError::KZGError)?;
+            cells.push(blob_cells);
+            proofs.push(blob_proofs);
+        }
+
+        Ok(Self {
+            settings,
+            scenario,
+            commitments,
+            cells,
+            proofs,
+            custodians,
+            peer_latency_ms,
+        })
+    }
+
+    pub fn scenario(&self) -> &Scenario {
+        &self.scenario
+    }
+
+    /// 没有任何对等节点托管的列数
+    pub fn uncustodied_columns(&self) -> usize {
+        self.custodians.iter().filter(|peers| peers.is_empty()).count()
+    }
+
+    pub fn run(&self) -> Result<SimulationReport, SimulationError> {
+        let scenario = &self.scenario;
+        let samples = scenario.sampling.samples_per_slot;
+        // 与构造时生成节点 ID 的随机流分开
+        let mut rng = StdRng::seed_from_u64(scenario.seed.wrapping_add(1));
+
+        let mut report = SimulationReport {
+            scenario: scenario.name.clone(),
+            trials: scenario.trials,
+            sampling_nodes: scenario.sampling.nodes,
+            samples_per_node: samples,
+            uncustodied_columns: self.uncustodied_columns(),
+            unrecoverable_trials: 0,
+            rejections: 0,
+            detection_probability: 0.0,
+            expected_detection_probability: 0.0,
+            failed_samples: 0,
+            invalid_batches: 0,
+            bandwidth: BandwidthStats::default(),
+            latency: LatencyStats::default(),
+            verification_time: Duration::ZERO,
+        };
+        let mut latencies = Vec::with_capacity(scenario.trials * scenario.sampling.nodes);
+        let mut expected = 0.0;
+
+        for _ in 0..scenario.trials {
+            let slot = self.adversary_slot(&mut rng);
+            let available_columns = (0..CELLS_PER_EXT_BLOB).filter(|&column| self.serves(&slot, column)).count();
+            expected += 1.0 - all_available_probability(available_columns, samples);
+            if !self.recoverable(&slot) {
+                report.unrecoverable_trials += 1;
+            }
+
+            for _ in 0..scenario.sampling.nodes {
+                let outcome = self.sample(&slot, &mut rng)?;
+                if outcome.failed_samples > 0 || !outcome.valid {
+                    report.rejections += 1;
+                }
+                if !outcome.valid {
+                    report.invalid_batches += 1;
+                }
+                report.failed_samples += outcome.failed_samples;
+                report.bandwidth.request_bytes += outcome.request_bytes;
+                report.bandwidth.response_bytes += outcome.response_bytes;
+                report.verification_time += outcome.verification_time;
+                latencies.push(outcome.latency_ms);
+            }
+        }
+
+        let rounds = (scenario.trials * scenario.sampling.nodes) as f64;
+        report.detection_probability = report.rejections as f64 / rounds;
+        report.expected_detection_probability = expected / scenario.trials as f64;
+        report.bandwidth.bytes_per_node = report.bandwidth.total_bytes() as f64 / rounds;
+        report.latency = LatencyStats::from_samples(latencies);
+        Ok(report)
+    }
+
+    /// 按策略选出本时隙扣留与附带错误证明的 cell
+    fn adversary_slot(&self, rng: &mut StdRng) -> SlotState {
+        let adversary = &self.scenario.adversary;
+        let total = self.scenario.blobs * CELLS_PER_EXT_BLOB;
+        let mut withheld = vec![false; total];
+        match adversary.strategy {
+            WithholdStrategy::Columns => {
+                let count = (adversary.withhold_fraction * CELLS_PER_EXT_BLOB as f64).round() as usize;
+                for column in index::sample(rng, CELLS_PER_EXT_BLOB, count) {
+                    for blob in 0..self.scenario.blobs {
+                        withheld[blob * CELLS_PER_EXT_BLOB + column] = true;
+                    }
+                }
+            }
+            WithholdStrategy::Cells => {
+                let count = (adversary.withhold_fraction * total as f64).round() as usize;
+                for cell in index::sample(rng, total, count) {
+                    withheld[cell] = true;
+                }
+            }
+        }
+
+        let mut corrupted = vec![false; total];
+        let served: Vec<usize> = (0..total).filter(|&cell| !withheld[cell]).collect();
+        let count = (adversary.corrupt_fraction * served.len() as f64).round() as usize;
+        for &cell in served.choose_multiple(rng, count) {
+            corrupted[cell] = true;
+        }
+        SlotState { withheld, corrupted }
+    }
+
+    /// 该列是否有节点托管，且全部 blob 的 cell 都未被扣留
+    fn serves(&self, slot: &SlotState, column: usize) -> bool {
+        !self.custodians[column].is_empty()
+            && (0..self.scenario.blobs).all(|blob| !slot.withheld[blob * CELLS_PER_EXT_BLOB + column])
+    }
+
+    /// 每个 blob 在网络中可取得的 cell 是否足以恢复
+    fn recoverable(&self, slot: &SlotState) -> bool {
+        (0..self.scenario.blobs).all(|blob| {
+            let available = (0..CELLS_PER_EXT_BLOB)
+                .filter(|&column| {
+                    !self.custodians[column].is_empty() && !slot.withheld[blob * CELLS_PER_EXT_BLOB + column]
+                })
+                .count();
+            available >= CELLS_REQUIRED_FOR_RECOVERY
+        })
+    }
+
+    /// 一个采样节点在一个时隙中的采样与验证
+    fn sample(&self, slot: &SlotState, rng: &mut StdRng) -> Result<NodeOutcome, SimulationError> {
+        let network = &self.scenario.network;
+        let blobs = self.scenario.blobs;
+        let response_bytes = DataColumnSidecar::ssz_len(blobs) as u64;
+        let transfer_ms = response_bytes as f64 * 8.0 / (network.bandwidth_mbps * 1000.0);
+
+        let mut outcome = NodeOutcome {
+            valid: true,
+            ..NodeOutcome::default()
+        };
+        let mut batch = SampleBatch::default();
+        for column in index::sample(rng, CELLS_PER_EXT_BLOB, self.scenario.sampling.samples_per_slot) {
+            outcome.request_bytes += REQUEST_BYTES;
+            let peer = self.custodians[column].choose(rng);
+            let Some(&peer) = peer.filter(|_| self.serves(slot, column)) else {
+                // 无人托管或被扣留：等待到超时
+                outcome.failed_samples += 1;
+                outcome.latency_ms = outcome.latency_ms.max(network.timeout_ms);
+                continue;
+            };
+
+            outcome.response_bytes += response_bytes;
+            outcome.latency_ms = outcome.latency_ms.max(self.peer_latency_ms[peer] + transfer_ms);
+            for blob in 0..blobs {
+                let start = column * FIELD_ELEMENTS_PER_CELL;
+                let proof_column = if slot.corrupted[blob * CELLS_PER_EXT_BLOB + column] {
+                    (column + 1) % CELLS_PER_EXT_BLOB
+                } else {
+                    column
+                };
+                batch.commitments.push(self.commitments[blob]);
+                batch.cell_indices.push(column);
+                batch.cells.extend_from_slice(&self.cells[blob][start..start + FIELD_ELEMENTS_PER_CELL]);
+                batch.proofs.push(self.proofs[blob][proof_column]);
+            }
+        }
+
+        if !batch.proofs.is_empty() {
+            let start = Instant::now();
+            outcome.valid = <FsKZGSettings as DAS<BlstBackend>>::verify_cell_kzg_proof_batch(
+                self.settings,
+                &batch.commitments,
+                &batch.cell_indices,
+                &batch.cells,
+                &batch.proofs,
+            )
+            .map_err(SimulationError::KZGError)?;
+            outcome.verification_time = start.elapsed();
+        }
+        Ok(outcome)
+    }
+}
+
+/// 从 `CELLS_PER_EXT_BLOB` 列中不放回地抽取 `samples` 列，全部落在 `available` 列中的概率
+fn all_available_probability(available: usize, samples: usize) -> f64 {
+    let total = CELLS_PER_EXT_BLOB;
+    (0..samples)
+        .map(|i| available.saturating_sub(i) as f64 / (total - i) as f64)
+        .product()
+}
@@ -0,0 +1,274 @@
+//! 流式 cell 验证
+//!
+//! gossip 中的 cell 陆续到达：逐个验证享受不到批量验证的摊销，等齐全部 128 列再验证又拖慢转发。
+//! [`CellVerifier`] 累积 (承诺, 索引, cell, 证明)，在以下任一条件满足时以一次
+//! `verify_cell_kzg_proof_batch` 验证全部待验证的 cell：
+//!
+//! - 待验证数量达到 [`CellVerifierConfig::max_pending`]
+//! - 最早的待验证 cell 已等待 [`CellVerifierConfig::max_delay`] (在 [`add`](CellVerifier::add)
+//!   或 [`poll`](CellVerifier::poll) 时检查，调用方可按 [`deadline`](CellVerifier::deadline) 定时)
+//!
+//! 批量验证失败时二分定位错误的 cell：左半通过则错误必在右半，无需再验证右半。
+//! KZG 库返回错误 (如点不在子群中) 时同样二分，只有出错的 cell 得到 [`CellStatus::Error`]，
+//! 其余 cell 照常得出结论，不会因一个坏输入整批丢失。
+//! 每个 cell 的结果 ([`CellVerdict`]) 带着调用方提供的标签返回，同一批次内按加入顺序排列；
+//! 无法解码的输入不进入批次，在 `add` 时立即返回。
+
+use std::time::{Duration, Instant};
+
+use kzg::DAS;
+use rust_kzg_blst::{
+    eip_7594::BlstBackend,
+    types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings},
+};
+
+use super::column::{decode_cell, decode_point};
+use super::{ColumnError, ColumnIndex, NUMBER_OF_COLUMNS};
+
+/// 刷新阈值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellVerifierConfig {
+    /// 待验证 cell 达到该数量时立即验证
+    pub max_pending: usize,
+    /// 最早的待验证 cell 等待超过该时长时验证
+    pub max_delay: Duration,
+}
+
+impl Default for CellVerifierConfig {
+    fn default() -> Self {
+        Self {
+            max_pending: NUMBER_OF_COLUMNS as usize,
+            max_delay: Duration::from_millis(50),
+        }
+    }
+}
+
+/// 单个 cell 的验证结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CellStatus {
+    Valid,
+    /// 证明与 cell、承诺不匹配
+    InvalidProof,
+    /// 索引越界或字节无法解码，未参与验证
+    Malformed(ColumnError),
+    /// KZG 库单独验证该 cell 时返回错误，未能得出结论
+    Error(ColumnError),
+}
+
+impl CellStatus {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, CellStatus::Valid)
+    }
+}
+
+/// 返回给调用方的结果，`tag` 是加入时提供的标签 (如来源节点、消息 ID)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellVerdict<T> {
+    pub tag: T,
+    pub index: ColumnIndex,
+    pub status: CellStatus,
+}
+
+/// 累计统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VerifierStats {
+    pub added: u64,
+    pub flushes: u64,
+    /// `verify_cell_kzg_proof_batch` 调用次数，含二分时的调用
+    pub batch_calls: u64,
+    pub valid: u64,
+    pub invalid: u64,
+    pub malformed: u64,
+    pub errors: u64,
+}
+
+struct PendingCell<T> {
+    tag: T,
+    index: ColumnIndex,
+    commitment: FsG1,
+    cell: Vec<FsFr>,
+    proof: FsG1,
+}
+
+/// 流式 cell 验证累加器
+pub struct CellVerifier<'a, T> {
+    settings: &'a FsKZGSettings,
+    config: CellVerifierConfig,
+    pending: Vec<PendingCell<T>>,
+    /// 最早的待验证 cell 的加入时间
+    oldest: Option<Instant>,
+    stats: VerifierStats,
+}
+
+impl<'a, T> CellVerifier<'a, T> {
+    pub fn new(settings: &'a FsKZGSettings, config: CellVerifierConfig) -> Self {
+        Self {
+            settings,
+            config,
+            pending: Vec::with_capacity(config.max_pending),
+            oldest: None,
+            stats: VerifierStats::default(),
+        }
+    }
+
+    /// 加入一个 cell，达到阈值时验证全部待验证的 cell 并返回结果
+    ///
+    /// 无法解码的输入立即返回 [`CellStatus::Malformed`]；未触发验证时其余结果为空。
+    pub fn add(
+        &mut self,
+        tag: T,
+        commitment: &[u8; 48],
+        index: ColumnIndex,
+        cell: &[u8],
+        proof: &[u8; 48],
+    ) -> Vec<CellVerdict<T>> {
+        self.stats.added += 1;
+        let mut verdicts = Vec::new();
+        match decode(commitment, index, cell, proof) {
+            Ok((commitment, cell, proof)) => {
+                self.oldest.get_or_insert_with(Instant::now);
+                self.pending.push(PendingCell {
+                    tag,
+                    index,
+                    commitment,
+                    cell,
+                    proof,
+                });
+            }
+            Err(error) => {
+                self.stats.malformed += 1;
+                verdicts.push(CellVerdict {
+                    tag,
+                    index,
+                    status: CellStatus::Malformed(error),
+                });
+            }
+        }
+
+        if self.pending.len() >= self.config.max_pending || self.is_due(Instant::now()) {
+            verdicts.extend(self.flush());
+        }
+        verdicts
+    }
+
+    /// 等待时间到期时验证，供调用方在没有新 cell 到达时定时调用
+    pub fn poll(&mut self) -> Vec<CellVerdict<T>> {
+        if self.is_due(Instant::now()) {
+            return self.flush();
+        }
+        Vec::new()
+    }
+
+    /// 立即验证全部待验证的 cell，每个 cell 都得到一个结果
+    pub fn flush(&mut self) -> Vec<CellVerdict<T>> {
+        let batch = std::mem::take(&mut self.pending);
+        self.oldest = None;
+        if batch.is_empty() {
+            return Vec::new();
+        }
+        self.stats.flushes += 1;
+
+        let statuses = match self.verify(&batch) {
+            Ok(()) => vec![CellStatus::Valid; batch.len()],
+            Err(failure) => self.bisect(&batch, failure),
+        };
+        batch
+            .into_iter()
+            .zip(statuses)
+            .map(|(pending, status)| {
+                match status {
+                    CellStatus::Valid => self.stats.valid += 1,
+                    CellStatus::InvalidProof => self.stats.invalid += 1,
+                    CellStatus::Error(_) => self.stats.errors += 1,
+                    CellStatus::Malformed(_) => unreachable!("malformed cells are never pending"),
+                }
+                CellVerdict {
+                    tag: pending.tag,
+                    index: pending.index,
+                    status,
+                }
+            })
+            .collect()
+    }
+
+    /// 最早的待验证 cell 到期的时间，没有待验证的 cell 时为 `None`
+    pub fn deadline(&self) -> Option<Instant> {
+        self.oldest.map(|oldest| oldest + self.config.max_delay)
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn stats(&self) -> VerifierStats {
+        self.stats
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.deadline().is_some_and(|deadline| now >= deadline)
+    }
+
+    /// 已知整批验证未通过，二分找出每个证明错误或出错的 cell
+    fn bisect(&mut self, batch: &[PendingCell<T>], failure: CellStatus) -> Vec<CellStatus> {
+        let mut statuses = vec![CellStatus::Valid; batch.len()];
+        // 栈中的区间都已知至少含一个未通过的 cell；直接验证过的区间带着验证结果，
+        // 由 "左半通过" 推断出的右半为 `None`
+        let mut failing = vec![(0, batch.len(), Some(failure))];
+        while let Some((start, end, failure)) = failing.pop() {
+            if end - start == 1 {
+                statuses[start] = match failure {
+                    Some(failure) => failure,
+                    None => self.verify(&batch[start..end]).err().unwrap_or(CellStatus::Valid),
+                };
+                continue;
+            }
+            let mid = start + (end - start) / 2;
+            match self.verify(&batch[start..mid]) {
+                Ok(()) => failing.push((mid, end, None)),
+                Err(failure) => {
+                    failing.push((start, mid, Some(failure)));
+                    if let Err(failure) = self.verify(&batch[mid..end]) {
+                        failing.push((mid, end, Some(failure)));
+                    }
+                }
+            }
+        }
+        statuses
+    }
+
+    /// 验证一组 cell，未通过时返回 [`CellStatus::InvalidProof`] 或 [`CellStatus::Error`]
+    fn verify(&mut self, cells: &[PendingCell<T>]) -> Result<(), CellStatus> {
+        self.stats.batch_calls += 1;
+        let commitments: Vec<FsG1> = cells.iter().map(|pending| pending.commitment).collect();
+        let indices: Vec<usize> = cells.iter().map(|pending| pending.index as usize).collect();
+        let flat: Vec<FsFr> = cells.iter().flat_map(|pending| pending.cell.iter().cloned()).collect();
+        let proofs: Vec<FsG1> = cells.iter().map(|pending| pending.proof).collect();
+        match <FsKZGSettings as DAS<BlstBackend>>::verify_cell_kzg_proof_batch(
+            self.settings,
+            &commitments,
+            &indices,
+            &flat,
+            &proofs,
+        ) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(CellStatus::InvalidProof),
+            Err(error) => Err(CellStatus::Error(ColumnError::KZGError(error))),
+        }
+    }
+}
+
+fn decode(
+    commitment: &[u8; 48],
+    index: ColumnIndex,
+    cell: &[u8],
+    proof: &[u8; 48],
+) -> Result<(FsG1, Vec<FsFr>, FsG1), ColumnError> {
+    if index >= NUMBER_OF_COLUMNS {
+        return Err(ColumnError::InvalidColumnIndex(index));
+    }
+    Ok((
+        decode_point("commitment", commitment)?,
+        decode_cell("cell", cell)?,
+        decode_point("proof", proof)?,
+    ))
+}
@@ -0,0 +1,39 @@
+//! # rust-kzg-tutorial
+//!
+//! Rust KZG 教程的配套库。各章节示例中反复出现的辅助代码集中在这里，
+//! 示例程序和下游项目都可以直接引用：
+//!
+//! - [`trusted_setup`]：受信任设置文件的查找与加载
+//! - [`blob`]：测试 Blob 的构造与字节转换
+//! - [`das`]：PeerDAS 列托管与数据列 sidecar (第7章扩展)
+//! - [`metrics`]：计时与性能统计
+//! - [`service`]：第16章的生产环境 KZG 服务
+//! - [`sidecar`]：信标链 `BlobSidecar` JSON 加载与校验
+//! - [`storage`]：第20章的去中心化存储 (分片、编码、节点选择)
+//! - [`transcript`]：Fiat–Shamir 挑战派生 (EIP-4844 `compute_challenge`)
+//! - [`versioned_hash`]：承诺的版本化哈希与交易校验
+//!
+//! 公共模块的路径和签名遵循语义化版本，当前版本见 [`VERSION`]。
+//!
+//! ```no_run
+//! use rust_kzg_tutorial::{blob::create_test_blob, trusted_setup::load_trusted_setup_from_file};
+//! use kzg::eip_4844::blob_to_kzg_commitment_rust;
+//!
+//! let settings = load_trusted_setup_from_file()?;
+//! let blob = create_test_blob()?;
+//! let commitment = blob_to_kzg_commitment_rust(&blob, &settings)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+pub mod blob;
+pub mod das;
+pub mod metrics;
+pub mod service;
+pub mod sidecar;
+pub mod storage;
+pub mod transcript;
+pub mod trusted_setup;
+pub mod versioned_hash;
+
+/// 库版本号 (与 Cargo.toml 保持一致)
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,229 @@
+//! 计时与性能统计工具
+//!
+//! - [`PerformanceProfiler`]：按操作名收集耗时样本，生成统计报告 (单线程使用)
+//! - [`PerformanceMonitor`]：基于原子计数器的承诺/证明/验证统计 (可跨线程共享)
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// 单个操作的耗时统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperationStats {
+    pub count: usize,
+    pub total: Duration,
+    pub average: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub p95: Duration,
+}
+
+impl OperationStats {
+    fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let count = sorted.len();
+        let total: Duration = sorted.iter().sum();
+        let p95_idx = ((count as f64 * 0.95) as usize).min(count - 1);
+
+        Some(Self {
+            count,
+            total,
+            average: total / count as u32,
+            min: sorted[0],
+            max: sorted[count - 1],
+            p95: sorted[p95_idx],
+        })
+    }
+}
+
+/// 性能分析器，用于收集和分析各种操作的性能数据
+#[derive(Debug, Default)]
+pub struct PerformanceProfiler {
+    samples: HashMap<String, Vec<Duration>>,
+}
+
+impl PerformanceProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次操作耗时
+    pub fn record_metric(&mut self, operation: &str, duration: Duration) {
+        self.samples
+            .entry(operation.to_string())
+            .or_default()
+            .push(duration);
+    }
+
+    /// 测量闭包执行时间并记录
+    pub fn measure<F, R>(&mut self, operation: &str, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let start = Instant::now();
+        let result = f();
+        self.record_metric(operation, start.elapsed());
+        result
+    }
+
+    /// 获取指定操作的统计信息
+    pub fn stats(&self, operation: &str) -> Option<OperationStats> {
+        self.samples
+            .get(operation)
+            .and_then(|samples| OperationStats::from_samples(samples))
+    }
+
+    /// 已记录的操作名 (按字母排序)
+    pub fn operations(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.samples.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// 生成文本形式的性能报告
+    pub fn report(&self) -> String {
+        let mut report = String::from("📊 性能分析报告\n");
+        report.push_str(&"=".repeat(50));
+        report.push('\n');
+
+        for operation in self.operations() {
+            if let Some(stats) = self.stats(operation) {
+                report.push_str(&format!(
+                    "🔹 {:<25}: 平均 {:8.2}ms, 范围 [{:6.2}ms - {:6.2}ms], P95 {:6.2}ms ({} 次)\n",
+                    operation,
+                    stats.average.as_secs_f64() * 1000.0,
+                    stats.min.as_secs_f64() * 1000.0,
+                    stats.max.as_secs_f64() * 1000.0,
+                    stats.p95.as_secs_f64() * 1000.0,
+                    stats.count,
+                ));
+            }
+        }
+
+        report
+    }
+
+    pub fn print_performance_summary(&self) {
+        println!("\n{}", self.report());
+    }
+}
+
+/// 实时性能指标收集器
+#[derive(Debug)]
+pub struct PerformanceMonitor {
+    commitment_count: AtomicU64,
+    proof_count: AtomicU64,
+    verification_count: AtomicU64,
+
+    total_commitment_time: AtomicU64,
+    total_proof_time: AtomicU64,
+    total_verification_time: AtomicU64,
+
+    error_count: AtomicU64,
+
+    start_time: Instant,
+}
+
+#[derive(Debug, Clone)]
+pub struct PerformanceReport {
+    pub uptime: Duration,
+    pub total_operations: u64,
+    pub operations_per_second: f64,
+    pub average_commitment_time: Duration,
+    pub average_proof_time: Duration,
+    pub average_verification_time: Duration,
+    pub error_rate: f64,
+}
+
+impl Default for PerformanceMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PerformanceMonitor {
+    pub fn new() -> Self {
+        Self {
+            commitment_count: AtomicU64::new(0),
+            proof_count: AtomicU64::new(0),
+            verification_count: AtomicU64::new(0),
+            total_commitment_time: AtomicU64::new(0),
+            total_proof_time: AtomicU64::new(0),
+            total_verification_time: AtomicU64::new(0),
+            error_count: AtomicU64::new(0),
+            start_time: Instant::now(),
+        }
+    }
+
+    /// 记录承诺操作
+    pub fn record_commitment(&self, duration: Duration) {
+        self.commitment_count.fetch_add(1, Ordering::Relaxed);
+        self.total_commitment_time
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// 记录证明操作
+    pub fn record_proof(&self, duration: Duration) {
+        self.proof_count.fetch_add(1, Ordering::Relaxed);
+        self.total_proof_time
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// 记录验证操作
+    pub fn record_verification(&self, duration: Duration) {
+        self.verification_count.fetch_add(1, Ordering::Relaxed);
+        self.total_verification_time
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// 记录错误
+    pub fn record_error(&self) {
+        self.error_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 生成性能报告
+    pub fn generate_report(&self) -> PerformanceReport {
+        fn average(total_nanos: u64, count: u64) -> Duration {
+            total_nanos
+                .checked_div(count)
+                .map(Duration::from_nanos)
+                .unwrap_or(Duration::ZERO)
+        }
+
+        let uptime = self.start_time.elapsed();
+        let commitment_count = self.commitment_count.load(Ordering::Relaxed);
+        let proof_count = self.proof_count.load(Ordering::Relaxed);
+        let verification_count = self.verification_count.load(Ordering::Relaxed);
+        let total_operations = commitment_count + proof_count + verification_count;
+
+        PerformanceReport {
+            uptime,
+            total_operations,
+            operations_per_second: if uptime.as_secs_f64() > 0.0 {
+                total_operations as f64 / uptime.as_secs_f64()
+            } else {
+                0.0
+            },
+            average_commitment_time: average(
+                self.total_commitment_time.load(Ordering::Relaxed),
+                commitment_count,
+            ),
+            average_proof_time: average(self.total_proof_time.load(Ordering::Relaxed), proof_count),
+            average_verification_time: average(
+                self.total_verification_time.load(Ordering::Relaxed),
+                verification_count,
+            ),
+            error_rate: if total_operations > 0 {
+                self.error_count.load(Ordering::Relaxed) as f64 / total_operations as f64
+            } else {
+                0.0
+            },
+        }
+    }
+}
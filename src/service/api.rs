@@ -0,0 +1,366 @@
+//! API 请求和响应结构
+//!
+//! JSON 请求中的字节字段为十六进制字符串，解码后统一转换为 `*Input` 结构；
+//! 二进制 (SSZ) 请求体直接解码为 `*Input`，见 [`super::codec`]。
+//!
+//! 响应结构保存原始字节，只在 JSON 序列化时编码为十六进制字符串，SSZ 响应直接拼接字节。
+
+use kzg::eip_4844::{BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_PROOF};
+use serde::{Deserialize, Serialize, Serializer};
+use utoipa::ToSchema;
+
+use super::{Scope, ServiceError};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CommitmentRequest {
+    pub blob: String, // hex encoded blob
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CommitmentResponse {
+    #[serde(serialize_with = "serialize_hex")]
+    #[schema(value_type = String)]
+    pub commitment: [u8; BYTES_PER_COMMITMENT], // hex encoded commitment
+    #[serde(serialize_with = "serialize_hex")]
+    #[schema(value_type = String)]
+    pub versioned_hash: [u8; 32], // 0x01 || sha256(commitment)[1..]
+    pub processing_time_ms: u64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ProofRequest {
+    pub blob: String,
+    pub commitment: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProofResponse {
+    #[serde(serialize_with = "serialize_hex")]
+    #[schema(value_type = String)]
+    pub proof: [u8; BYTES_PER_PROOF],
+    pub processing_time_ms: u64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VerificationRequest {
+    pub blob: String,
+    pub commitment: String,
+    pub proof: String,
+}
+
+/// 已解码的承诺请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitmentInput {
+    pub blob: Vec<u8>,
+}
+
+/// 已解码的证明请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofInput {
+    pub blob: Vec<u8>,
+    pub commitment: [u8; BYTES_PER_COMMITMENT],
+}
+
+/// 已解码的验证请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationInput {
+    pub blob: Vec<u8>,
+    pub commitment: [u8; BYTES_PER_COMMITMENT],
+    pub proof: [u8; BYTES_PER_PROOF],
+}
+
+impl TryFrom<CommitmentRequest> for CommitmentInput {
+    type Error = ServiceError;
+
+    fn try_from(request: CommitmentRequest) -> Result<Self, ServiceError> {
+        Ok(Self {
+            blob: decode_blob_hex(&request.blob)?,
+        })
+    }
+}
+
+impl TryFrom<ProofRequest> for ProofInput {
+    type Error = ServiceError;
+
+    fn try_from(request: ProofRequest) -> Result<Self, ServiceError> {
+        Ok(Self {
+            blob: decode_blob_hex(&request.blob)?,
+            commitment: decode_point_hex("commitment", &request.commitment)?,
+        })
+    }
+}
+
+impl TryFrom<VerificationRequest> for VerificationInput {
+    type Error = ServiceError;
+
+    fn try_from(request: VerificationRequest) -> Result<Self, ServiceError> {
+        Ok(Self {
+            blob: decode_blob_hex(&request.blob)?,
+            commitment: decode_point_hex("commitment", &request.commitment)?,
+            proof: decode_point_hex("proof", &request.proof)?,
+        })
+    }
+}
+
+fn decode_blob_hex(blob: &str) -> Result<Vec<u8>, ServiceError> {
+    let bytes = hex::decode(blob).map_err(|e| ServiceError::InvalidHexEncoding(e.to_string()))?;
+    if bytes.len() != BYTES_PER_BLOB {
+        return Err(ServiceError::InvalidBlobSize {
+            expected: BYTES_PER_BLOB,
+            actual: bytes.len(),
+        });
+    }
+    Ok(bytes)
+}
+
+fn decode_points_hex(field: &str, points: &[String]) -> Result<Vec<[u8; 48]>, ServiceError> {
+    points.iter().map(|point| decode_point_hex(field, point)).collect()
+}
+
+fn decode_cells_hex(cells: &[String]) -> Result<Vec<Vec<u8>>, ServiceError> {
+    cells
+        .iter()
+        .map(|cell| hex::decode(cell).map_err(|e| ServiceError::InvalidHexEncoding(e.to_string())))
+        .collect()
+}
+
+/// 响应中的字节字段序列化为十六进制字符串
+fn serialize_hex<S: Serializer, T: AsRef<[u8]>>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(bytes))
+}
+
+/// 字节字段列表序列化为十六进制字符串数组
+fn serialize_hex_list<S: Serializer, T: AsRef<[u8]>>(items: &[T], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(items.iter().map(hex::encode))
+}
+
+fn decode_point_hex(field: &str, point: &str) -> Result<[u8; 48], ServiceError> {
+    let bytes = hex::decode(point).map_err(|e| ServiceError::InvalidHexEncoding(e.to_string()))?;
+    <[u8; 48]>::try_from(bytes.as_slice()).map_err(|_| {
+        ServiceError::InvalidRequest(format!("{} must be 48 bytes, got {}", field, bytes.len()))
+    })
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerificationResponse {
+    pub is_valid: bool,
+    #[serde(serialize_with = "serialize_hex")]
+    #[schema(value_type = String)]
+    pub versioned_hash: [u8; 32],
+    pub processing_time_ms: u64,
+}
+
+/// 交易版本化哈希校验请求
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct VersionedHashValidationRequest {
+    /// 交易中的 `blob_versioned_hashes` (按顺序)
+    pub blob_versioned_hashes: Vec<String>,
+    /// 对应 sidecar 的承诺 (按顺序)
+    pub commitments: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VersionedHashValidationResponse {
+    pub is_valid: bool,
+    /// 由承诺计算出的版本化哈希
+    pub versioned_hashes: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// EIP-7594：计算 blob 的全部 cell 和证明
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CellsRequest {
+    pub blob: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CellsResponse {
+    /// 按索引排列的全部 cell (十六进制)
+    #[serde(serialize_with = "serialize_hex_list")]
+    #[schema(value_type = Vec<String>)]
+    pub cells: Vec<Vec<u8>>,
+    #[serde(serialize_with = "serialize_hex_list")]
+    #[schema(value_type = Vec<String>)]
+    pub proofs: Vec<[u8; BYTES_PER_PROOF]>,
+    pub processing_time_ms: u64,
+}
+
+/// EIP-7594：批量验证 cell 证明，各数组按下标一一对应
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CellVerificationRequest {
+    pub commitments: Vec<String>,
+    pub cell_indices: Vec<u64>,
+    pub cells: Vec<String>,
+    pub proofs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CellVerificationResponse {
+    pub is_valid: bool,
+    pub cell_count: usize,
+    pub processing_time_ms: u64,
+}
+
+/// 已解码的 cell 验证请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellVerificationInput {
+    pub commitments: Vec<[u8; BYTES_PER_COMMITMENT]>,
+    pub cell_indices: Vec<u64>,
+    pub cells: Vec<Vec<u8>>,
+    pub proofs: Vec<[u8; BYTES_PER_PROOF]>,
+}
+
+impl TryFrom<CellVerificationRequest> for CellVerificationInput {
+    type Error = ServiceError;
+
+    fn try_from(request: CellVerificationRequest) -> Result<Self, ServiceError> {
+        Ok(Self {
+            commitments: decode_points_hex("commitment", &request.commitments)?,
+            cell_indices: request.cell_indices,
+            cells: decode_cells_hex(&request.cells)?,
+            proofs: decode_points_hex("proof", &request.proofs)?,
+        })
+    }
+}
+
+/// EIP-7594：由至少一半的 cell 恢复全部 cell 和证明
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CellRecoveryRequest {
+    pub cell_indices: Vec<u64>,
+    pub cells: Vec<String>,
+}
+
+/// 已解码的 cell 恢复请求
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellRecoveryInput {
+    pub cell_indices: Vec<u64>,
+    pub cells: Vec<Vec<u8>>,
+}
+
+impl TryFrom<CellRecoveryRequest> for CellRecoveryInput {
+    type Error = ServiceError;
+
+    fn try_from(request: CellRecoveryRequest) -> Result<Self, ServiceError> {
+        Ok(Self {
+            cell_indices: request.cell_indices,
+            cells: decode_cells_hex(&request.cells)?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CellRecoveryResponse {
+    #[serde(serialize_with = "serialize_hex_list")]
+    #[schema(value_type = Vec<String>)]
+    pub cells: Vec<Vec<u8>>,
+    #[serde(serialize_with = "serialize_hex_list")]
+    #[schema(value_type = Vec<String>)]
+    pub proofs: Vec<[u8; BYTES_PER_PROOF]>,
+    pub processing_time_ms: u64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchRequest {
+    pub requests: Vec<BatchItem>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct BatchItem {
+    pub id: String,
+    pub operation: String, // "commitment" | "proof" | "verification"
+    pub blob: String,
+    pub commitment: Option<String>,
+    pub proof: Option<String>,
+}
+
+/// 已解码的批量操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchOperation {
+    Commitment(CommitmentInput),
+    Proof(ProofInput),
+    Verification(VerificationInput),
+}
+
+impl BatchOperation {
+    /// 执行该操作所需的密钥权限
+    pub fn scope(&self) -> Scope {
+        match self {
+            Self::Commitment(_) => Scope::Commit,
+            Self::Proof(_) => Scope::Prove,
+            Self::Verification(_) => Scope::Verify,
+        }
+    }
+}
+
+/// 批量操作的结果
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum BatchOutput {
+    Commitment(CommitmentResponse),
+    Proof(ProofResponse),
+    Verification(VerificationResponse),
+}
+
+impl BatchOutput {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("响应结构总能序列化")
+    }
+}
+
+impl TryFrom<BatchItem> for BatchOperation {
+    type Error = ServiceError;
+
+    fn try_from(item: BatchItem) -> Result<Self, ServiceError> {
+        match (item.operation.as_str(), item.commitment, item.proof) {
+            ("commitment", _, _) => Ok(Self::Commitment(CommitmentRequest { blob: item.blob }.try_into()?)),
+            ("proof", Some(commitment), _) => Ok(Self::Proof(ProofRequest { blob: item.blob, commitment }.try_into()?)),
+            ("proof", None, _) => Err(ServiceError::InvalidRequest(
+                "Missing commitment for proof operation".to_string(),
+            )),
+            ("verification", Some(commitment), Some(proof)) => Ok(Self::Verification(
+                VerificationRequest { blob: item.blob, commitment, proof }.try_into()?,
+            )),
+            ("verification", _, _) => Err(ServiceError::InvalidRequest(
+                "Missing commitment or proof for verification".to_string(),
+            )),
+            (operation, _, _) => Err(ServiceError::InvalidRequest(format!("Unknown operation: {}", operation))),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchResponse {
+    pub results: Vec<BatchResult>,
+    pub total_processing_time_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BatchResult {
+    pub id: String,
+    pub success: bool,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+impl BatchResult {
+    pub fn failure(id: String, error: impl Into<String>) -> Self {
+        Self {
+            id,
+            success: false,
+            result: None,
+            error: Some(error.into()),
+        }
+    }
+
+    pub fn from_outcome<E: std::fmt::Display>(id: String, outcome: Result<serde_json::Value, E>) -> Self {
+        match outcome {
+            Ok(value) => Self {
+                id,
+                success: true,
+                result: Some(value),
+                error: None,
+            },
+            Err(e) => Self::failure(id, e.to_string()),
+        }
+    }
+}
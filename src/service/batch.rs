@@ -0,0 +1,125 @@
+//! 批量处理
+//!
+//! KZG 运算是纯 CPU 计算，直接在 tokio 工作线程上执行会阻塞其他请求。
+//! [`CpuPool`] 把计算放到 `spawn_blocking` 线程上，并用信号量限制并发数。
+//!
+//! 批量验证先用一次 `verify_blob_kzg_proof_batch` 聚合验证全部条目，
+//! 失败时二分查找无效条目 ([`verify_blob_batch_bisect`])：k 个坏条目最多需要
+//! O(k·log n) 次批量调用，全部有效时只需一次。
+
+use std::sync::Arc;
+
+use kzg::eip_4844::verify_blob_kzg_proof_batch_rust;
+use rust_kzg_blst::types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings};
+use tokio::sync::Semaphore;
+
+use super::ServiceError;
+
+/// 有界 CPU 计算池
+#[derive(Debug, Clone)]
+pub struct CpuPool {
+    permits: Arc<Semaphore>,
+    size: usize,
+}
+
+impl CpuPool {
+    /// `size` 为同时执行的计算任务数上限 (至少为 1)
+    pub fn new(size: usize) -> Self {
+        let size = size.max(1);
+        Self {
+            permits: Arc::new(Semaphore::new(size)),
+            size,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// 在阻塞线程上执行计算，池满时等待空闲名额
+    ///
+    /// 名额随闭包移入阻塞线程：调用方的 future 被丢弃后，已开始的计算仍占用名额直到结束。
+    pub async fn run<T, F>(&self, task: F) -> Result<T, ServiceError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| ServiceError::ServiceUnavailable)?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            task()
+        })
+        .await
+        .map_err(|e| ServiceError::InternalError(format!("计算任务异常退出: {}", e)))
+    }
+}
+
+/// 批量验证结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchVerification {
+    /// 每个条目是否有效 (与输入顺序一致)
+    pub valid: Vec<bool>,
+    /// 调用批量验证的次数
+    pub batch_calls: usize,
+}
+
+/// 聚合验证一批 blob 证明，失败时二分定位无效条目
+pub fn verify_blob_batch_bisect(
+    blobs: &[Vec<FsFr>],
+    commitments: &[FsG1],
+    proofs: &[FsG1],
+    kzg_settings: &FsKZGSettings,
+) -> Result<BatchVerification, String> {
+    if blobs.len() != commitments.len() || blobs.len() != proofs.len() {
+        return Err(format!(
+            "批量验证输入长度不一致: blobs {}, commitments {}, proofs {}",
+            blobs.len(),
+            commitments.len(),
+            proofs.len()
+        ));
+    }
+
+    let mut result = BatchVerification {
+        valid: vec![false; blobs.len()],
+        batch_calls: 0,
+    };
+    bisect(blobs, commitments, proofs, kzg_settings, 0, blobs.len(), &mut result)?;
+    Ok(result)
+}
+
+fn bisect(
+    blobs: &[Vec<FsFr>],
+    commitments: &[FsG1],
+    proofs: &[FsG1],
+    kzg_settings: &FsKZGSettings,
+    start: usize,
+    end: usize,
+    result: &mut BatchVerification,
+) -> Result<(), String> {
+    if start == end {
+        return Ok(());
+    }
+
+    result.batch_calls += 1;
+    let ok = verify_blob_kzg_proof_batch_rust(
+        &blobs[start..end],
+        &commitments[start..end],
+        &proofs[start..end],
+        kzg_settings,
+    )?;
+
+    if ok {
+        result.valid[start..end].fill(true);
+    } else if end - start > 1 {
+        let mid = start + (end - start) / 2;
+        bisect(blobs, commitments, proofs, kzg_settings, start, mid, result)?;
+        bisect(blobs, commitments, proofs, kzg_settings, mid, end, result)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+//! 结果缓存
+//!
+//! 缓存键由解码后 blob 的 SHA-256 摘要构成，证明和验证结果再附加承诺与证明字节，
+//! 避免用完整的十六进制 blob 作键。淘汰同时受条目数和总字节数约束，
+//! 命中、未命中和淘汰次数可由 [`CacheManager::stats`] 读取并导出到 Prometheus。
+
+use std::mem::size_of;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+/// blob 字节的 SHA-256 摘要
+pub type BlobDigest = [u8; 32];
+
+/// 计算解码后 blob 的摘要
+pub fn blob_digest(blob: &[u8]) -> BlobDigest {
+    Sha256::digest(blob).into()
+}
+
+/// 内容寻址的缓存键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheKey {
+    /// blob → 承诺
+    Commitment(BlobDigest),
+    /// (blob, 承诺) → 证明
+    Proof(BlobDigest, [u8; 48]),
+    /// (blob, 承诺, 证明) → 验证结果
+    Verification(BlobDigest, [u8; 48], [u8; 48]),
+}
+
+/// 缓存统计快照
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub bytes: usize,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// 命中率 (0.0 到 1.0)，尚无查询时为 0
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// 按条目数和总字节数约束的 LRU 缓存管理器
+pub struct CacheManager {
+    cache: Arc<Mutex<CacheState>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+struct CacheState {
+    entries: lru::LruCache<CacheKey, CacheEntry>,
+    bytes: usize,
+    ttl_seconds: u64,
+    max_bytes: usize,
+}
+
+impl CacheState {
+    /// 淘汰最久未用的条目直到满足字节上限，返回淘汰数
+    fn evict_to_fit(&mut self) -> usize {
+        let mut evicted = 0;
+        while self.bytes > self.max_bytes {
+            match self.entries.pop_lru() {
+                Some((_, old)) => {
+                    self.bytes -= old.size();
+                    evicted += 1;
+                }
+                None => break,
+            }
+        }
+        evicted
+    }
+}
+
+#[derive(Clone)]
+struct CacheEntry {
+    data: Vec<u8>,
+    created_at: u64,
+    /// 计算结果时可信设置的代号
+    generation: u64,
+}
+
+impl CacheEntry {
+    /// 计入字节上限的大小 (键与数据)
+    fn size(&self) -> usize {
+        size_of::<CacheKey>() + self.data.len()
+    }
+}
+
+impl CacheManager {
+    pub fn new(capacity: usize, ttl_seconds: u64, max_bytes: usize) -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(CacheState {
+                entries: lru::LruCache::new(NonZeroUsize::new(capacity.max(1)).unwrap()),
+                bytes: 0,
+                ttl_seconds,
+                max_bytes,
+            })),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// 获取 `generation` 代可信设置下的缓存项
+    ///
+    /// 代号不同的条目计为未命中，其中过期或属于更早一代的被移除。
+    pub async fn get(&self, key: &CacheKey, generation: u64) -> Option<Vec<u8>> {
+        let mut cache = self.cache.lock().await;
+        let now = now_seconds();
+        let ttl_seconds = cache.ttl_seconds;
+
+        let data = match cache.entries.get(key) {
+            Some(entry) if entry.generation > generation => None,
+            Some(entry) if entry.generation == generation && now.saturating_sub(entry.created_at) < ttl_seconds => {
+                Some(entry.data.clone())
+            }
+            Some(_) => {
+                if let Some(expired) = cache.entries.pop(key) {
+                    cache.bytes -= expired.size();
+                }
+                None
+            }
+            None => None,
+        };
+
+        let counter = if data.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        data
+    }
+
+    /// 设置 `generation` 代可信设置下算出的缓存项，返回因超出条目数或字节上限而淘汰的条目数
+    ///
+    /// 单个条目超过字节上限时不缓存。
+    pub async fn set(&self, key: CacheKey, data: Vec<u8>, generation: u64) -> usize {
+        let entry = CacheEntry {
+            data,
+            created_at: now_seconds(),
+            generation,
+        };
+        let size = entry.size();
+        let mut cache = self.cache.lock().await;
+        if size > cache.max_bytes {
+            return 0;
+        }
+
+        let mut evicted = 0;
+
+        cache.bytes += size;
+        if let Some((old_key, old)) = cache.entries.push(key, entry) {
+            cache.bytes -= old.size();
+            if old_key != key {
+                evicted += 1;
+            }
+        }
+        evicted += cache.evict_to_fit();
+
+        self.evictions.fetch_add(evicted as u64, Ordering::Relaxed);
+        evicted
+    }
+
+    /// 调整条目数、有效期与字节上限，保留仍然放得下的最近条目
+    ///
+    /// 返回因容量缩小而淘汰的条目数。
+    pub async fn resize(&self, capacity: usize, ttl_seconds: u64, max_bytes: usize) -> usize {
+        let mut cache = self.cache.lock().await;
+        cache.ttl_seconds = ttl_seconds;
+        cache.max_bytes = max_bytes;
+
+        let mut evicted = 0;
+        let capacity = NonZeroUsize::new(capacity.max(1)).unwrap();
+        while cache.entries.len() > capacity.get() {
+            if let Some((_, old)) = cache.entries.pop_lru() {
+                cache.bytes -= old.size();
+                evicted += 1;
+            }
+        }
+        cache.entries.resize(capacity);
+        evicted += cache.evict_to_fit();
+
+        self.evictions.fetch_add(evicted as u64, Ordering::Relaxed);
+        evicted
+    }
+
+    /// 清空全部条目 (例如可信设置更换后)，不计入淘汰次数
+    pub async fn clear(&self) {
+        let mut cache = self.cache.lock().await;
+        cache.entries.clear();
+        cache.bytes = 0;
+    }
+
+    /// 当前统计信息
+    pub async fn stats(&self) -> CacheStats {
+        let cache = self.cache.lock().await;
+        CacheStats {
+            entries: cache.entries.len(),
+            bytes: cache.bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn now_seconds() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
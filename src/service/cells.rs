@@ -0,0 +1,173 @@
+//! EIP-7594 (PeerDAS) cell 运算
+//!
+//! 扩展后的 blob 被切分为 [`CELLS_PER_EXT_BLOB`] 个 cell，每个 cell 含
+//! [`FIELD_ELEMENTS_PER_CELL`] 个域元素 ([`BYTES_PER_CELL`] 字节)。服务提供三种运算：
+//!
+//! - 计算全部 cell 及其 KZG 证明
+//! - 批量验证 (承诺, cell 索引, cell, 证明) 元组
+//! - 由至少一半的 cell 恢复全部 cell 和证明
+//!
+//! 这里的函数只处理字节，十六进制编解码与指标记录由 [`super::ProductionKzgService`] 负责。
+
+use std::collections::HashSet;
+
+use kzg::{
+    eip_4844::BYTES_PER_FIELD_ELEMENT,
+    eth::{CELLS_PER_EXT_BLOB, FIELD_ELEMENTS_PER_CELL},
+    Fr, G1, DAS,
+};
+use rust_kzg_blst::{
+    eip_7594::BlstBackend,
+    types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings},
+};
+
+use super::ServiceError;
+use crate::das::{self, RecoveryError};
+
+/// 单个 cell 的字节数
+pub const BYTES_PER_CELL: usize = FIELD_ELEMENTS_PER_CELL * BYTES_PER_FIELD_ELEMENT;
+
+/// JSON 请求中单个 (承诺, 索引, cell, 证明) 元组的最大字节数 (十六进制翻倍，另留字段开销)
+pub const MAX_JSON_BYTES_PER_CELL: usize = 2 * (BYTES_PER_CELL + 2 * 48) + 64;
+
+/// 全部 cell 及其证明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellsAndProofs {
+    pub cells: Vec<Vec<u8>>,
+    pub proofs: Vec<[u8; 48]>,
+}
+
+/// 计算 blob 扩展后的全部 cell 和证明
+pub fn compute_cells_and_proofs(settings: &FsKZGSettings, blob: &[u8]) -> Result<CellsAndProofs, ServiceError> {
+    let blob_fr = super::decode_blob(blob)?;
+
+    let mut cells = vec![FsFr::default(); CELLS_PER_EXT_BLOB * FIELD_ELEMENTS_PER_CELL];
+    let mut proofs = vec![FsG1::default(); CELLS_PER_EXT_BLOB];
+    <FsKZGSettings as DAS<BlstBackend>>::compute_cells_and_kzg_proofs(
+        settings,
+        Some(&mut cells),
+        Some(&mut proofs),
+        &blob_fr,
+    )
+    .map_err(ServiceError::KzgError)?;
+
+    Ok(encode_cells_and_proofs(&cells, &proofs))
+}
+
+/// 批量验证 cell 证明，各切片按下标一一对应
+pub fn verify_cells(
+    settings: &FsKZGSettings,
+    commitments: &[[u8; 48]],
+    cell_indices: &[u64],
+    cells: &[Vec<u8>],
+    proofs: &[[u8; 48]],
+) -> Result<bool, ServiceError> {
+    let count = cell_indices.len();
+    if commitments.len() != count || cells.len() != count || proofs.len() != count {
+        return Err(ServiceError::InvalidRequest(format!(
+            "length mismatch: commitments {}, cell_indices {}, cells {}, proofs {}",
+            commitments.len(),
+            count,
+            cells.len(),
+            proofs.len()
+        )));
+    }
+
+    let indices = check_indices(cell_indices)?;
+    let commitments = decode_points("commitments", commitments)?;
+    let proofs = decode_points("proofs", proofs)?;
+    let cells = decode_cells(cells)?;
+
+    <FsKZGSettings as DAS<BlstBackend>>::verify_cell_kzg_proof_batch(settings, &commitments, &indices, &cells, &proofs)
+        .map_err(ServiceError::KzgError)
+}
+
+/// 由至少一半互不相同的 cell 恢复全部 cell 和证明
+pub fn recover_cells_and_proofs(
+    settings: &FsKZGSettings,
+    cell_indices: &[u64],
+    cells: &[Vec<u8>],
+) -> Result<CellsAndProofs, ServiceError> {
+    if cells.len() != cell_indices.len() {
+        return Err(ServiceError::InvalidRequest(format!(
+            "length mismatch: cell_indices {}, cells {}",
+            cell_indices.len(),
+            cells.len()
+        )));
+    }
+
+    let indices = check_indices(cell_indices)?;
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = indices.iter().find(|index| !seen.insert(**index)) {
+        return Err(ServiceError::InvalidRequest(format!("duplicate cell index {}", duplicate)));
+    }
+    if indices.len() < CELLS_PER_EXT_BLOB / 2 {
+        return Err(ServiceError::InvalidRequest(format!(
+            "at least {} cells are required for recovery, got {}",
+            CELLS_PER_EXT_BLOB / 2,
+            indices.len()
+        )));
+    }
+    let cells = decode_cells(cells)?;
+
+    let recovered = das::recover_cells_and_proofs(settings, cell_indices, &cells).map_err(|e| match e {
+        RecoveryError::KZGError(e) => ServiceError::KzgError(e),
+        other => ServiceError::InvalidRequest(other.to_string()),
+    })?;
+
+    Ok(encode_cells_and_proofs(&recovered.cells, &recovered.proofs))
+}
+
+fn check_indices(cell_indices: &[u64]) -> Result<Vec<usize>, ServiceError> {
+    cell_indices
+        .iter()
+        .map(|&index| {
+            usize::try_from(index)
+                .ok()
+                .filter(|index| *index < CELLS_PER_EXT_BLOB)
+                .ok_or_else(|| ServiceError::InvalidRequest(format!("cell index {} out of range", index)))
+        })
+        .collect()
+}
+
+fn decode_points(field: &str, points: &[[u8; 48]]) -> Result<Vec<FsG1>, ServiceError> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            FsG1::from_bytes(point)
+                .map_err(|e| ServiceError::InvalidRequest(format!("invalid G1 point {}[{}]: {}", field, i, e)))
+        })
+        .collect()
+}
+
+fn decode_cells(cells: &[Vec<u8>]) -> Result<Vec<FsFr>, ServiceError> {
+    let mut out = Vec::with_capacity(cells.len() * FIELD_ELEMENTS_PER_CELL);
+    for (i, cell) in cells.iter().enumerate() {
+        if cell.len() != BYTES_PER_CELL {
+            return Err(ServiceError::InvalidRequest(format!(
+                "cells[{}] must be {} bytes, got {}",
+                i,
+                BYTES_PER_CELL,
+                cell.len()
+            )));
+        }
+        for element in cell.chunks_exact(BYTES_PER_FIELD_ELEMENT) {
+            out.push(
+                FsFr::from_bytes(element)
+                    .map_err(|e| ServiceError::InvalidRequest(format!("cells[{}]: {}", i, e)))?,
+            );
+        }
+    }
+    Ok(out)
+}
+
+fn encode_cells_and_proofs(cells: &[FsFr], proofs: &[FsG1]) -> CellsAndProofs {
+    CellsAndProofs {
+        cells: cells
+            .chunks_exact(FIELD_ELEMENTS_PER_CELL)
+            .map(|cell| cell.iter().flat_map(|fr| fr.to_bytes()).collect())
+            .collect(),
+        proofs: proofs.iter().map(|proof| proof.to_bytes()).collect(),
+    }
+}
@@ -0,0 +1,503 @@
+//! 请求/响应编码协商
+//!
+//! `/api/v1/commitment`、`/api/v1/proof`、`/api/v1/verify` 除 JSON (十六进制字段) 外
+//! 还接受 SSZ 编码的二进制请求体，省去十六进制带来的一倍体积和解码开销：
+//!
+//! - `Content-Type: application/json` (或缺省)：JSON 请求
+//! - `Content-Type: application/octet-stream` / `application/ssz`：SSZ 请求
+//!
+//! 响应编码由 `Accept` 按 q 值决定 (q=0 表示不接受)，通配符或未指定时与请求编码一致。
+//! 与信标节点 API 的约定相同，`application/octet-stream` 即 SSZ 编码。各容器的字段均为定长，
+//! SSZ 编码就是字段的顺序拼接 (整数为小端序)：
+//!
+//! ```text
+//! CommitmentRequest    = blob (BYTES_PER_BLOB)
+//! ProofRequest         = blob || commitment (48)
+//! VerificationRequest  = blob || commitment (48) || proof (48)
+//!
+//! CommitmentResponse   = commitment (48) || versioned_hash (32) || processing_time_ms (u64)
+//! ProofResponse        = proof (48) || processing_time_ms (u64)
+//! VerificationResponse = is_valid (1) || versioned_hash (32) || processing_time_ms (u64)
+//! ```
+//!
+//! EIP-7594 cell 接口同样支持 SSZ。`/api/v1/cells` 的请求与 `CommitmentRequest` 相同；
+//! 含列表的请求按 SSZ 规则先写各字段的 4 字节小端偏移量，再依次写列表内容：
+//!
+//! ```text
+//! CellVerificationRequest  = offsets (4 × u32) || commitments (List[48]) || cell_indices (List[u64])
+//!                            || cells (List[BYTES_PER_CELL]) || proofs (List[48])
+//! CellRecoveryRequest      = offsets (2 × u32) || cell_indices (List[u64]) || cells (List[BYTES_PER_CELL])
+//!
+//! CellsResponse            = cells (CELLS_PER_EXT_BLOB × BYTES_PER_CELL) || proofs (CELLS_PER_EXT_BLOB × 48)
+//!                            || processing_time_ms (u64)
+//! CellRecoveryResponse     = 同 CellsResponse
+//! CellVerificationResponse = is_valid (1) || cell_count (u64) || processing_time_ms (u64)
+//! ```
+//!
+//! `/api/v1/batch`、`/api/v1/jobs` 与 `/api/v1/versioned-hashes/validate` 的条目带有字符串标识和
+//! 错误信息，只使用 JSON ([`JsonOnly`])：二进制请求体返回 415，`Accept` 优先二进制编码时返回 406。
+//!
+//! 请求体大小上限由 `BYTES_PER_BLOB` 推导，见 [`MAX_JSON_REQUEST_BYTES`]。
+
+use axum::{
+    async_trait,
+    body::{Body, Bytes},
+    extract::FromRequest,
+    http::{header, HeaderMap, HeaderValue, Request, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use kzg::eip_4844::{BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_PROOF};
+use kzg::eth::CELLS_PER_EXT_BLOB;
+use serde::Serialize;
+
+use super::cells::BYTES_PER_CELL;
+use super::{
+    CellRecoveryInput, CellRecoveryRequest, CellRecoveryResponse, CellVerificationInput, CellVerificationRequest,
+    CellVerificationResponse, CellsResponse, CommitmentInput, CommitmentRequest, CommitmentResponse, ProofInput,
+    ProofRequest, ProofResponse, ServiceError, VerificationInput, VerificationRequest, VerificationResponse,
+};
+
+pub const APPLICATION_JSON: &str = "application/json";
+pub const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+pub const APPLICATION_SSZ: &str = "application/ssz";
+
+/// 最大二进制请求体 (验证请求：blob + 承诺 + 证明)
+pub const MAX_BINARY_REQUEST_BYTES: usize = BYTES_PER_BLOB + BYTES_PER_COMMITMENT + BYTES_PER_PROOF;
+
+/// 最大 JSON 请求体：十六进制使字节字段翻倍，另留出字段名等开销
+pub const MAX_JSON_REQUEST_BYTES: usize = 2 * MAX_BINARY_REQUEST_BYTES + 4096;
+
+/// 请求/响应编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    OctetStream,
+    Ssz,
+}
+
+impl Encoding {
+    /// 解析媒体类型，忽略参数 (如 `; charset=utf-8`)
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        let essence = media_type.split(';').next().unwrap_or("").trim();
+        if essence.eq_ignore_ascii_case(APPLICATION_JSON) {
+            Some(Encoding::Json)
+        } else if essence.eq_ignore_ascii_case(APPLICATION_OCTET_STREAM) {
+            Some(Encoding::OctetStream)
+        } else if essence.eq_ignore_ascii_case(APPLICATION_SSZ) {
+            Some(Encoding::Ssz)
+        } else {
+            None
+        }
+    }
+
+    /// 请求体编码，缺省为 JSON
+    pub fn from_content_type(headers: &HeaderMap) -> Result<Self, ServiceError> {
+        match headers.get(header::CONTENT_TYPE) {
+            None => Ok(Encoding::Json),
+            Some(value) => {
+                let text = value.to_str().unwrap_or_default();
+                Self::from_media_type(text).ok_or_else(|| ServiceError::UnsupportedMediaType(text.to_string()))
+            }
+        }
+    }
+
+    /// 响应编码：取 `Accept` 中 q 值最高的支持类型 (同为最高时取靠前者)
+    ///
+    /// `*/*` 与 `application/*` 代表请求编码；q=0 的类型不参与选择；没有可选类型时沿用请求编码。
+    pub fn negotiate(headers: &HeaderMap, request: Encoding) -> Self {
+        let mut best: Option<(Encoding, f32)> = None;
+        for range in headers
+            .get_all(header::ACCEPT)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+        {
+            let (essence, quality) = parse_media_range(range);
+            if quality <= 0.0 {
+                continue;
+            }
+            let encoding = if essence == "*/*" || essence.eq_ignore_ascii_case("application/*") {
+                Some(request)
+            } else {
+                Self::from_media_type(essence)
+            };
+            if let Some(encoding) = encoding {
+                if best.map_or(true, |(_, best_quality)| quality > best_quality) {
+                    best = Some((encoding, quality));
+                }
+            }
+        }
+        best.map_or(request, |(encoding, _)| encoding)
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Encoding::Json => APPLICATION_JSON,
+            Encoding::OctetStream => APPLICATION_OCTET_STREAM,
+            Encoding::Ssz => APPLICATION_SSZ,
+        }
+    }
+
+    pub fn is_binary(&self) -> bool {
+        !matches!(self, Encoding::Json)
+    }
+}
+
+/// 拆分 `Accept` 中的一项为媒体类型与 q 值 (缺省或无法解析时为 1)
+fn parse_media_range(range: &str) -> (&str, f32) {
+    let mut parts = range.split(';');
+    let essence = parts.next().unwrap_or("").trim();
+    let quality = parts
+        .filter_map(|param| {
+            let (name, value) = param.split_once('=')?;
+            if !name.trim().eq_ignore_ascii_case("q") {
+                return None;
+            }
+            value.trim().parse::<f32>().ok()
+        })
+        .next()
+        .unwrap_or(1.0);
+    (essence, quality.clamp(0.0, 1.0))
+}
+
+// ================================
+// SSZ 编解码
+// ================================
+
+/// 可从 JSON 或 SSZ 请求体解码的请求
+pub trait DecodeRequest: Sized {
+    type Json: serde::de::DeserializeOwned;
+
+    fn from_json(json: Self::Json) -> Result<Self, ServiceError>;
+
+    fn from_ssz(bytes: &[u8]) -> Result<Self, ServiceError>;
+}
+
+/// 可编码为 SSZ 的响应
+pub trait EncodeSsz {
+    fn to_ssz(&self) -> Vec<u8>;
+}
+
+impl DecodeRequest for CommitmentInput {
+    type Json = CommitmentRequest;
+
+    fn from_json(json: CommitmentRequest) -> Result<Self, ServiceError> {
+        json.try_into()
+    }
+
+    fn from_ssz(bytes: &[u8]) -> Result<Self, ServiceError> {
+        if bytes.len() != BYTES_PER_BLOB {
+            return Err(ServiceError::InvalidBlobSize {
+                expected: BYTES_PER_BLOB,
+                actual: bytes.len(),
+            });
+        }
+        Ok(Self { blob: bytes.to_vec() })
+    }
+}
+
+impl DecodeRequest for ProofInput {
+    type Json = ProofRequest;
+
+    fn from_json(json: ProofRequest) -> Result<Self, ServiceError> {
+        json.try_into()
+    }
+
+    fn from_ssz(bytes: &[u8]) -> Result<Self, ServiceError> {
+        let (blob, rest) = split_fixed(bytes, "ProofRequest", BYTES_PER_BLOB + BYTES_PER_COMMITMENT)?;
+        Ok(Self {
+            blob: blob.to_vec(),
+            commitment: rest.try_into().expect("长度已检查"),
+        })
+    }
+}
+
+impl DecodeRequest for VerificationInput {
+    type Json = VerificationRequest;
+
+    fn from_json(json: VerificationRequest) -> Result<Self, ServiceError> {
+        json.try_into()
+    }
+
+    fn from_ssz(bytes: &[u8]) -> Result<Self, ServiceError> {
+        let (blob, rest) = split_fixed(bytes, "VerificationRequest", MAX_BINARY_REQUEST_BYTES)?;
+        let (commitment, proof) = rest.split_at(BYTES_PER_COMMITMENT);
+        Ok(Self {
+            blob: blob.to_vec(),
+            commitment: commitment.try_into().expect("长度已检查"),
+            proof: proof.try_into().expect("长度已检查"),
+        })
+    }
+}
+
+/// 检查定长容器的总长度，并拆出开头的 blob
+fn split_fixed<'a>(bytes: &'a [u8], container: &str, expected: usize) -> Result<(&'a [u8], &'a [u8]), ServiceError> {
+    if bytes.len() != expected {
+        return Err(ServiceError::InvalidRequest(format!(
+            "SSZ {} must be {} bytes, got {}",
+            container,
+            expected,
+            bytes.len()
+        )));
+    }
+    Ok(bytes.split_at(BYTES_PER_BLOB))
+}
+
+impl EncodeSsz for CommitmentResponse {
+    fn to_ssz(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(BYTES_PER_COMMITMENT + 32 + 8);
+        out.extend_from_slice(&self.commitment);
+        out.extend_from_slice(&self.versioned_hash);
+        out.extend_from_slice(&self.processing_time_ms.to_le_bytes());
+        out
+    }
+}
+
+impl EncodeSsz for ProofResponse {
+    fn to_ssz(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(BYTES_PER_PROOF + 8);
+        out.extend_from_slice(&self.proof);
+        out.extend_from_slice(&self.processing_time_ms.to_le_bytes());
+        out
+    }
+}
+
+impl EncodeSsz for VerificationResponse {
+    fn to_ssz(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(1 + 32 + 8);
+        out.push(self.is_valid as u8);
+        out.extend_from_slice(&self.versioned_hash);
+        out.extend_from_slice(&self.processing_time_ms.to_le_bytes());
+        out
+    }
+}
+
+impl DecodeRequest for CellVerificationInput {
+    type Json = CellVerificationRequest;
+
+    fn from_json(json: CellVerificationRequest) -> Result<Self, ServiceError> {
+        json.try_into()
+    }
+
+    fn from_ssz(bytes: &[u8]) -> Result<Self, ServiceError> {
+        let [commitments, cell_indices, cells, proofs] = split_offsets(bytes, "CellVerificationRequest")?;
+        Ok(Self {
+            commitments: fixed_list(commitments, "commitments", BYTES_PER_COMMITMENT)?
+                .map(|point| point.try_into().expect("长度已检查"))
+                .collect(),
+            cell_indices: u64_list(cell_indices)?,
+            cells: fixed_list(cells, "cells", BYTES_PER_CELL)?.map(<[u8]>::to_vec).collect(),
+            proofs: fixed_list(proofs, "proofs", BYTES_PER_PROOF)?
+                .map(|point| point.try_into().expect("长度已检查"))
+                .collect(),
+        })
+    }
+}
+
+impl DecodeRequest for CellRecoveryInput {
+    type Json = CellRecoveryRequest;
+
+    fn from_json(json: CellRecoveryRequest) -> Result<Self, ServiceError> {
+        json.try_into()
+    }
+
+    fn from_ssz(bytes: &[u8]) -> Result<Self, ServiceError> {
+        let [cell_indices, cells] = split_offsets(bytes, "CellRecoveryRequest")?;
+        Ok(Self {
+            cell_indices: u64_list(cell_indices)?,
+            cells: fixed_list(cells, "cells", BYTES_PER_CELL)?.map(<[u8]>::to_vec).collect(),
+        })
+    }
+}
+
+/// 按开头的 `N` 个 4 字节小端偏移量拆分只含变长字段的 SSZ 容器
+fn split_offsets<'a, const N: usize>(bytes: &'a [u8], container: &str) -> Result<[&'a [u8]; N], ServiceError> {
+    let invalid = |reason: String| ServiceError::InvalidRequest(format!("SSZ {}: {}", container, reason));
+
+    let header = 4 * N;
+    if bytes.len() < header {
+        return Err(invalid(format!("expected at least {} bytes, got {}", header, bytes.len())));
+    }
+    let mut offsets = [0usize; N];
+    for (i, offset) in offsets.iter_mut().enumerate() {
+        let raw: [u8; 4] = bytes[4 * i..4 * i + 4].try_into().expect("长度已检查");
+        *offset = u32::from_le_bytes(raw) as usize;
+    }
+    if offsets[0] != header {
+        return Err(invalid(format!("first offset must be {}, got {}", header, offsets[0])));
+    }
+
+    let mut fields = [&bytes[..0]; N];
+    for (i, field) in fields.iter_mut().enumerate() {
+        let start = offsets[i];
+        let end = offsets.get(i + 1).copied().unwrap_or(bytes.len());
+        if start > end || end > bytes.len() {
+            return Err(invalid(format!("offset {} out of order or out of bounds", i)));
+        }
+        *field = &bytes[start..end];
+    }
+    Ok(fields)
+}
+
+/// 拆分定长元素组成的 SSZ 列表
+fn fixed_list<'a>(
+    bytes: &'a [u8],
+    field: &str,
+    size: usize,
+) -> Result<std::slice::ChunksExact<'a, u8>, ServiceError> {
+    if bytes.len() % size != 0 {
+        return Err(ServiceError::InvalidRequest(format!(
+            "SSZ {} length {} is not a multiple of {}",
+            field,
+            bytes.len(),
+            size
+        )));
+    }
+    Ok(bytes.chunks_exact(size))
+}
+
+fn u64_list(bytes: &[u8]) -> Result<Vec<u64>, ServiceError> {
+    Ok(fixed_list(bytes, "cell_indices", 8)?
+        .map(|index| u64::from_le_bytes(index.try_into().expect("长度已检查")))
+        .collect())
+}
+
+impl EncodeSsz for CellsResponse {
+    fn to_ssz(&self) -> Vec<u8> {
+        encode_all_cells(&self.cells, &self.proofs, self.processing_time_ms)
+    }
+}
+
+impl EncodeSsz for CellRecoveryResponse {
+    fn to_ssz(&self) -> Vec<u8> {
+        encode_all_cells(&self.cells, &self.proofs, self.processing_time_ms)
+    }
+}
+
+impl EncodeSsz for CellVerificationResponse {
+    fn to_ssz(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(1 + 8 + 8);
+        out.push(self.is_valid as u8);
+        out.extend_from_slice(&(self.cell_count as u64).to_le_bytes());
+        out.extend_from_slice(&self.processing_time_ms.to_le_bytes());
+        out
+    }
+}
+
+/// 全部 cell 和证明均为定长向量，顺序拼接
+fn encode_all_cells(cells: &[Vec<u8>], proofs: &[[u8; BYTES_PER_PROOF]], processing_time_ms: u64) -> Vec<u8> {
+    let mut out = Vec::with_capacity(CELLS_PER_EXT_BLOB * (BYTES_PER_CELL + BYTES_PER_PROOF) + 8);
+    for cell in cells {
+        out.extend_from_slice(cell);
+    }
+    for proof in proofs {
+        out.extend_from_slice(proof);
+    }
+    out.extend_from_slice(&processing_time_ms.to_le_bytes());
+    out
+}
+
+// ================================
+// 提取器与响应
+// ================================
+
+/// 按 `Content-Type` 解码的请求体，同时记录协商出的响应编码
+#[derive(Debug)]
+pub struct Negotiated<T> {
+    pub body: T,
+    pub accept: Encoding,
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S, Body> for Negotiated<T>
+where
+    S: Send + Sync,
+    T: DecodeRequest,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+        let encoding = Encoding::from_content_type(request.headers()).map_err(IntoResponse::into_response)?;
+        let accept = Encoding::negotiate(request.headers(), encoding);
+
+        // Bytes 提取器遵循路由上的 DefaultBodyLimit，超限返回 413
+        let bytes = Bytes::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let body = if encoding.is_binary() {
+            T::from_ssz(&bytes)
+        } else {
+            serde_json::from_slice::<T::Json>(&bytes)
+                .map_err(|e| ServiceError::InvalidRequest(e.to_string()))
+                .and_then(T::from_json)
+        }
+        .map_err(IntoResponse::into_response)?;
+
+        Ok(Self { body, accept })
+    }
+}
+
+/// 只接受 JSON 的请求体
+///
+/// 缺省 `Content-Type` 视为 JSON；二进制请求体返回 415，`Accept` 优先二进制编码时返回 406。
+#[derive(Debug)]
+pub struct JsonOnly<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S, Body> for JsonOnly<T>
+where
+    S: Send + Sync,
+    T: serde::de::DeserializeOwned,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+        let path = request.uri().path().to_string();
+        let encoding = Encoding::from_content_type(request.headers()).map_err(IntoResponse::into_response)?;
+        if encoding.is_binary() {
+            return Err(ServiceError::UnsupportedMediaType(format!("{} only accepts {}", path, APPLICATION_JSON))
+                .into_response());
+        }
+        if Encoding::negotiate(request.headers(), Encoding::Json).is_binary() {
+            return Err(ServiceError::NotAcceptable(format!("{} only responds with {}", path, APPLICATION_JSON))
+                .into_response());
+        }
+
+        let bytes = Bytes::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        serde_json::from_slice(&bytes)
+            .map(Self)
+            .map_err(|e| ServiceError::InvalidRequest(e.to_string()).into_response())
+    }
+}
+
+/// 按协商结果编码的响应
+#[derive(Debug)]
+pub struct Encoded<T> {
+    pub encoding: Encoding,
+    pub body: T,
+}
+
+impl<T> Encoded<T> {
+    pub fn new(encoding: Encoding, body: T) -> Self {
+        Self { encoding, body }
+    }
+}
+
+impl<T: Serialize + EncodeSsz> IntoResponse for Encoded<T> {
+    fn into_response(self) -> Response {
+        if !self.encoding.is_binary() {
+            return Json(self.body).into_response();
+        }
+
+        (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, HeaderValue::from_static(self.encoding.content_type()))],
+            self.body.to_ssz(),
+        )
+            .into_response()
+    }
+}
@@ -0,0 +1,354 @@
+//! 服务配置
+//!
+//! 配置从 TOML 文件加载，路径由环境变量 `KZG_CONFIG_PATH` 指定。
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::security::Scope;
+
+/// 生产环境配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProductionConfig {
+    pub server: ServerConfig,
+    pub kzg: KzgConfig,
+    pub monitoring: MonitoringConfig,
+    pub security: SecurityConfig,
+    pub performance: PerformanceConfig,
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub jobs: JobsConfig,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    pub workers: Option<usize>,
+    /// gRPC 监听端口 (与 `host` 组成地址)，需启用 `grpc` 特性；未设置时不启动
+    #[serde(default)]
+    pub grpc_port: Option<u16>,
+    pub max_connections: u32,
+    pub request_timeout_seconds: u64,
+    pub keep_alive_seconds: u64,
+    pub graceful_shutdown_timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KzgConfig {
+    pub trusted_setup_path: String,
+    pub max_blob_size: usize,
+    pub enable_parallel: bool,
+    pub thread_pool_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MonitoringConfig {
+    pub enabled: bool,
+    pub prometheus_port: u16,
+    pub metrics_path: String,
+    pub collection_interval_seconds: u64,
+    pub retention_days: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SecurityConfig {
+    pub enable_tls: bool,
+    pub cert_path: Option<String>,
+    pub key_path: Option<String>,
+    pub enable_auth: bool,
+    /// 旧式明文密钥，启动时加盐哈希并授予 commit/prove/verify 权限，之后从运行中的配置清空
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    /// 加盐哈希形式的密钥
+    #[serde(default)]
+    pub keys: Vec<ApiKeyConfig>,
+    /// 额外的密钥文件 (TOML，`[[keys]]` 数组)
+    #[serde(default)]
+    pub key_file: Option<String>,
+    pub rate_limit: RateLimitConfig,
+    pub cors: CorsConfig,
+}
+
+/// 单个 API 密钥
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiKeyConfig {
+    /// 密钥标识，用于日志和配额统计
+    pub id: String,
+    /// `sha256$<salt hex>$<digest hex>`，由 `security::hash_api_key` 生成
+    pub hash: String,
+    pub scopes: Vec<Scope>,
+    /// 该密钥的速率限制，未设置时使用 `per_client_requests_per_second`/`per_client_burst_size`
+    #[serde(default)]
+    pub requests_per_second: Option<u64>,
+    #[serde(default)]
+    pub burst_size: Option<u64>,
+    /// 每日 (UTC) 条目配额：单个请求计 1，批量请求和任务按条目数计，查询任务状态不计
+    ///
+    /// 用量只保存在内存中，服务重启后清零。
+    #[serde(default)]
+    pub daily_quota: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyFile {
+    #[serde(default)]
+    keys: Vec<ApiKeyConfig>,
+}
+
+/// 从 TOML 密钥文件加载密钥
+pub fn load_key_file(path: &str) -> Result<Vec<ApiKeyConfig>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read key file {}", path))?;
+    let file: KeyFile = toml::from_str(&content)
+        .with_context(|| format!("Failed to parse key file {}", path))?;
+    Ok(file.keys)
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RateLimitConfig {
+    pub requests_per_second: u64,
+    pub burst_size: u64,
+    pub enable_per_ip: bool,
+    pub window_seconds: u64,
+    /// 每个客户端 (API 密钥或 IP) 的默认速率，密钥可单独覆盖
+    #[serde(default = "default_per_client_requests_per_second")]
+    pub per_client_requests_per_second: u64,
+    #[serde(default = "default_per_client_burst_size")]
+    pub per_client_burst_size: u64,
+    /// 客户端空闲多久后回收其限流状态
+    #[serde(default = "default_client_idle_seconds")]
+    pub client_idle_seconds: u64,
+    /// 各类操作消耗的令牌数
+    #[serde(default)]
+    pub costs: RequestCosts,
+}
+
+/// 各类操作消耗的令牌数
+///
+/// 批量请求按条目计费：N 个 blob 的批量请求消耗 N 个令牌。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RequestCosts {
+    pub commitment: u64,
+    pub proof: u64,
+    pub verification: u64,
+    pub batch_item: u64,
+    pub cells: u64,
+    pub cell_verification: u64,
+    pub cell_recovery: u64,
+    /// 其他受限路由 (版本化哈希校验、管理接口等)
+    pub other: u64,
+}
+
+impl Default for RequestCosts {
+    fn default() -> Self {
+        Self {
+            commitment: 2,
+            proof: 3,
+            verification: 1,
+            batch_item: 1,
+            cells: 8,
+            cell_verification: 2,
+            cell_recovery: 8,
+            other: 1,
+        }
+    }
+}
+
+fn default_per_client_requests_per_second() -> u64 {
+    50
+}
+
+fn default_per_client_burst_size() -> u64 {
+    100
+}
+
+fn default_client_idle_seconds() -> u64 {
+    600
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CorsConfig {
+    pub allow_origins: Vec<String>,
+    pub allow_methods: Vec<String>,
+    pub allow_headers: Vec<String>,
+    pub expose_headers: Vec<String>,
+    pub max_age_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PerformanceConfig {
+    pub enable_caching: bool,
+    pub cache_size: usize,
+    pub cache_ttl_seconds: u64,
+    /// 缓存总字节数上限
+    #[serde(default = "default_cache_max_bytes")]
+    pub cache_max_bytes: usize,
+    pub batch_processing: bool,
+    pub max_batch_size: usize,
+    /// 单个 cell 请求中 cell 数量上限
+    #[serde(default = "default_max_cells_per_request")]
+    pub max_cells_per_request: usize,
+}
+
+fn default_max_cells_per_request() -> usize {
+    1024
+}
+
+fn default_cache_max_bytes() -> usize {
+    64 * 1024 * 1024
+}
+
+/// 异步批量任务
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct JobsConfig {
+    /// 未完成 (排队或执行中) 任务总数上限
+    pub max_queued_jobs: usize,
+    /// 单个租户未完成任务数上限
+    pub max_jobs_per_tenant: usize,
+    /// 单个任务的条目数上限
+    pub max_items_per_job: usize,
+    /// 未完成任务的条目总字节数上限 (十六进制字段的长度之和)
+    pub max_queued_bytes: usize,
+    /// 每次调度执行的条目数，租户之间按块轮转
+    pub chunk_size: usize,
+    /// 并发执行的任务块数
+    pub workers: usize,
+    /// 已完成任务的保留时间，过期后从内存移除并压缩任务日志
+    pub retention_seconds: u64,
+    /// 任务日志文件 (JSON Lines)，设置后排队中的任务在重启后恢复
+    pub journal_path: Option<String>,
+    /// 完成回调的超时与重试次数
+    pub callback_timeout_seconds: u64,
+    pub callback_retries: u32,
+    /// 允许的回调主机，为空时不限主机 (仍须是公网地址)
+    pub callback_allowed_hosts: Vec<String>,
+    /// 允许回调到回环、私有与链路本地地址，仅用于回调接收方部署在内网的场景
+    pub callback_allow_private_networks: bool,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            max_queued_jobs: 256,
+            max_jobs_per_tenant: 16,
+            max_items_per_job: 512,
+            max_queued_bytes: 256 * 1024 * 1024,
+            chunk_size: 16,
+            workers: 2,
+            retention_seconds: 3600,
+            journal_path: None,
+            callback_timeout_seconds: 10,
+            callback_retries: 3,
+            callback_allowed_hosts: vec![],
+            callback_allow_private_networks: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LoggingConfig {
+    pub level: String,
+    pub format: String, // "json" | "pretty"
+    pub output: String, // "stdout" | "file"
+    pub file_path: Option<String>,
+    pub rotate_size_mb: u64,
+    pub max_files: u32,
+}
+
+impl Default for ProductionConfig {
+    fn default() -> Self {
+        Self {
+            server: ServerConfig {
+                host: "0.0.0.0".to_string(),
+                port: 8080,
+                workers: None,
+                grpc_port: None,
+                max_connections: 10000,
+                request_timeout_seconds: 30,
+                keep_alive_seconds: 60,
+                graceful_shutdown_timeout_seconds: 30,
+            },
+            kzg: KzgConfig {
+                trusted_setup_path: "assets/trusted_setup.txt".to_string(),
+                max_blob_size: 131072, // 128KB
+                enable_parallel: true,
+                thread_pool_size: None,
+            },
+            monitoring: MonitoringConfig {
+                enabled: true,
+                prometheus_port: 9090,
+                metrics_path: "/metrics".to_string(),
+                collection_interval_seconds: 15,
+                retention_days: 30,
+            },
+            security: SecurityConfig {
+                enable_tls: false,
+                cert_path: None,
+                key_path: None,
+                enable_auth: false,
+                api_keys: vec![],
+                keys: vec![],
+                key_file: None,
+                rate_limit: RateLimitConfig {
+                    requests_per_second: 1000,
+                    burst_size: 100,
+                    enable_per_ip: true,
+                    window_seconds: 60,
+                    per_client_requests_per_second: default_per_client_requests_per_second(),
+                    per_client_burst_size: default_per_client_burst_size(),
+                    client_idle_seconds: default_client_idle_seconds(),
+                    costs: RequestCosts::default(),
+                },
+                cors: CorsConfig {
+                    allow_origins: vec!["*".to_string()],
+                    allow_methods: vec!["GET".to_string(), "POST".to_string()],
+                    allow_headers: vec!["Content-Type".to_string(), "Authorization".to_string()],
+                    expose_headers: vec![],
+                    max_age_seconds: 3600,
+                },
+            },
+            performance: PerformanceConfig {
+                enable_caching: true,
+                cache_size: 1000,
+                cache_ttl_seconds: 300,
+                cache_max_bytes: default_cache_max_bytes(),
+                batch_processing: true,
+                max_batch_size: 100,
+                max_cells_per_request: default_max_cells_per_request(),
+            },
+            logging: LoggingConfig {
+                level: "info".to_string(),
+                format: "json".to_string(),
+                output: "stdout".to_string(),
+                file_path: None,
+                rotate_size_mb: 100,
+                max_files: 10,
+            },
+            jobs: JobsConfig::default(),
+        }
+    }
+}
+
+/// 加载配置
+pub async fn load_config() -> Result<ProductionConfig> {
+    // 从环境变量或配置文件加载配置
+    let config_path = std::env::var("KZG_CONFIG_PATH")
+        .unwrap_or_else(|_| "config/production.toml".to_string());
+    
+    if std::path::Path::new(&config_path).exists() {
+        let config_str = tokio::fs::read_to_string(&config_path).await
+            .context("Failed to read config file")?;
+        
+        let config: ProductionConfig = toml::from_str(&config_str)
+            .context("Failed to parse config file")?;
+        
+        Ok(config)
+    } else {
+        info!("配置文件不存在，使用默认配置: {}", config_path);
+        Ok(ProductionConfig::default())
+    }
+}
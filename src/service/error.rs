@@ -0,0 +1,126 @@
+//! 错误处理
+
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use super::security::AuthError;
+
+#[derive(Debug, Error)]
+pub enum ServiceError {
+    #[error("Invalid blob size: expected {expected}, got {actual}")]
+    InvalidBlobSize { expected: usize, actual: usize },
+    
+    #[error("Invalid hex encoding: {0}")]
+    InvalidHexEncoding(String),
+    
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+    
+    #[error("Not acceptable: {0}")]
+    NotAcceptable(String),
+    
+    #[error("KZG operation failed: {0}")]
+    KzgError(String),
+    
+    #[error("Rate limit exceeded")]
+    RateLimitExceeded { retry_after: Duration },
+    
+    #[error("Unauthorized")]
+    Unauthorized,
+    
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+    
+    #[error("Daily quota exceeded")]
+    QuotaExceeded,
+    
+    #[error("Not found: {0}")]
+    NotFound(String),
+    
+    #[error("Queue full: {0}")]
+    QueueFull(String),
+    
+    #[error("Internal server error: {0}")]
+    InternalError(String),
+    
+    #[error("Service unavailable")]
+    ServiceUnavailable,
+    
+    #[error("Request timeout")]
+    Timeout,
+}
+
+/// 错误响应体，所有 JSON 接口的非 2xx 响应共用
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+    /// Unix 时间戳 (秒)
+    pub timestamp: u64,
+}
+
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> Response {
+        let retry_after = match &self {
+            ServiceError::RateLimitExceeded { retry_after } => Some(*retry_after),
+            _ => None,
+        };
+        let (status, error_message) = match self {
+            ServiceError::InvalidBlobSize { .. } | 
+            ServiceError::InvalidHexEncoding(_) |
+            ServiceError::InvalidRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            ServiceError::UnsupportedMediaType(_) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string()),
+            ServiceError::NotAcceptable(_) => (StatusCode::NOT_ACCEPTABLE, self.to_string()),
+            ServiceError::RateLimitExceeded { .. } => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
+            ServiceError::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string()),
+            ServiceError::Forbidden(_) => (StatusCode::FORBIDDEN, self.to_string()),
+            ServiceError::QuotaExceeded => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
+            ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            ServiceError::QueueFull(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            ServiceError::ServiceUnavailable => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            ServiceError::Timeout => (StatusCode::REQUEST_TIMEOUT, self.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string()),
+        };
+        
+        let body = ErrorBody {
+            error: error_message,
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        };
+        
+        let mut response = (status, Json(body)).into_response();
+        if let Some(retry_after) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after_seconds(retry_after)));
+        }
+        response
+    }
+}
+
+impl From<AuthError> for ServiceError {
+    fn from(error: AuthError) -> Self {
+        match error {
+            AuthError::MissingKey | AuthError::InvalidKey => ServiceError::Unauthorized,
+            AuthError::MissingScope(_) => ServiceError::Forbidden(error.to_string()),
+            AuthError::QuotaExceeded => ServiceError::QuotaExceeded,
+            AuthError::InvalidHash(_) => ServiceError::InternalError(error.to_string()),
+        }
+    }
+}
+
+/// `Retry-After` 的秒数 (向上取整，至少 1 秒，最多一天)
+pub fn retry_after_seconds(retry_after: Duration) -> u64 {
+    let seconds = retry_after.as_secs().saturating_add(u64::from(retry_after.subsec_nanos() > 0));
+    seconds.clamp(1, 24 * 60 * 60)
+}
@@ -0,0 +1,436 @@
+//! gRPC 接口 (`grpc` 特性)
+//!
+//! 基于 tonic 的 `kzg.v1.KzgService`，定义见 `proto/kzg.proto`。与 REST API 共用
+//! [`ProductionKzgService`] 核心，字节字段直接传输原始字节。每个调用先经过
+//! [`ProductionKzgService::authorize`] 的黑名单和 API 密钥检查，速率限制由与 REST
+//! 共用的 [`super::RateLimitLayer`] 在服务之前完成。
+//!
+//! 监听端口由 `ServerConfig::grpc_port` 配置，[`super::start_http_server`] 会同时启动
+//! REST 和 gRPC 两个监听器。
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
+
+use anyhow::{Context, Result};
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tonic::{Request, Response, Status, Streaming};
+use tracing::info;
+
+use super::{
+    codec::MAX_BINARY_REQUEST_BYTES, error::retry_after_seconds, rate_limit_layer::api_key, restrict_operation,
+    ApiKeyIdentity, BatchOperation, BatchOutput, CellRecoveryInput, CellVerificationInput, CellsAndProofs,
+    CommitmentInput, ProductionKzgService, ProofInput, RateLimitHandle, Scope, ServiceError, VerificationInput,
+};
+
+/// tonic 生成的消息与服务定义
+pub mod proto {
+    tonic::include_proto!("kzg.v1");
+}
+
+use proto::kzg_service_server::{KzgService, KzgServiceServer};
+
+/// 流式批量接口的输出缓冲 (条目数)
+const STREAM_BUFFER: usize = 64;
+
+impl From<ServiceError> for Status {
+    fn from(error: ServiceError) -> Self {
+        let message = error.to_string();
+        match error {
+            ServiceError::InvalidBlobSize { .. }
+            | ServiceError::InvalidHexEncoding(_)
+            | ServiceError::InvalidRequest(_)
+            | ServiceError::UnsupportedMediaType(_)
+            | ServiceError::NotAcceptable(_) => Status::invalid_argument(message),
+            ServiceError::RateLimitExceeded { retry_after } => {
+                let mut status = Status::resource_exhausted(message);
+                status
+                    .metadata_mut()
+                    .insert("retry-after", retry_after_seconds(retry_after).into());
+                status
+            }
+            ServiceError::QuotaExceeded => Status::resource_exhausted(message),
+            ServiceError::Unauthorized => Status::unauthenticated(message),
+            ServiceError::Forbidden(_) => Status::permission_denied(message),
+            ServiceError::NotFound(_) => Status::not_found(message),
+            ServiceError::ServiceUnavailable | ServiceError::QueueFull(_) => Status::unavailable(message),
+            ServiceError::Timeout => Status::deadline_exceeded(message),
+            ServiceError::KzgError(_) | ServiceError::InternalError(_) => Status::internal("Internal server error"),
+        }
+    }
+}
+
+/// gRPC 服务实现
+#[derive(Clone)]
+pub struct GrpcKzgService {
+    service: ProductionKzgService,
+}
+
+impl GrpcKzgService {
+    pub fn new(service: ProductionKzgService) -> Self {
+        Self { service }
+    }
+
+    /// 生成 tonic 服务，消息大小上限按批量上限放大
+    pub async fn into_server(self) -> KzgServiceServer<Self> {
+        let max_batch_size = self.service.config.read().await.performance.max_batch_size;
+        let max_message = MAX_BINARY_REQUEST_BYTES
+            .saturating_mul(max_batch_size.max(1))
+            .saturating_add(64 * 1024);
+
+        KzgServiceServer::new(self)
+            .max_decoding_message_size(max_message)
+            .max_encoding_message_size(max_message)
+    }
+
+    /// 与 REST 相同的访问控制，返回请求方的密钥身份 (按一个条目计入每日配额)
+    async fn authorize(&self, caller: Caller, scopes: &[Scope]) -> Result<Option<ApiKeyIdentity>, Status> {
+        self.service
+            .authorize(caller.api_key.as_deref(), caller.client_ip.as_deref(), scopes, 1)
+            .await
+            .map_err(Status::from)
+    }
+}
+
+/// 请求方的密钥与地址
+///
+/// 流式请求体不是 `Sync`，先从请求中取出再等待访问控制。
+struct Caller {
+    api_key: Option<String>,
+    client_ip: Option<String>,
+}
+
+impl Caller {
+    fn of<T>(request: &Request<T>) -> Self {
+        // metadata 即 HTTP/2 头部，与 REST 共用密钥解析
+        let headers = request.metadata().clone().into_headers();
+        Self {
+            api_key: api_key(&headers).map(str::to_string),
+            client_ip: request.remote_addr().map(|addr| addr.ip().to_string()),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl KzgService for GrpcKzgService {
+    async fn create_commitment(
+        &self,
+        request: Request<proto::BlobRequest>,
+    ) -> Result<Response<proto::CommitmentResponse>, Status> {
+        self.authorize(Caller::of(&request), &[Scope::Commit]).await?;
+        let input = CommitmentInput { blob: request.into_inner().blob };
+        let response = self.service.commit_blob(input).await?;
+        Ok(Response::new(commitment_response(response)))
+    }
+
+    async fn generate_proof(
+        &self,
+        request: Request<proto::ProofRequest>,
+    ) -> Result<Response<proto::ProofResponse>, Status> {
+        self.authorize(Caller::of(&request), &[Scope::Prove]).await?;
+        let input = proof_input(request.into_inner())?;
+        let response = self.service.prove_blob(input).await?;
+        Ok(Response::new(proof_response(response)))
+    }
+
+    async fn verify_proof(
+        &self,
+        request: Request<proto::VerificationRequest>,
+    ) -> Result<Response<proto::VerificationResponse>, Status> {
+        self.authorize(Caller::of(&request), &[Scope::Verify]).await?;
+        let input = verification_input(request.into_inner())?;
+        let response = self.service.verify_blob(input).await?;
+        Ok(Response::new(verification_response(response)))
+    }
+
+    async fn batch(&self, request: Request<proto::BatchRequest>) -> Result<Response<proto::BatchResponse>, Status> {
+        let identity = self.authorize(Caller::of(&request), &[]).await?;
+        let start = std::time::Instant::now();
+        // 超限的批量在补扣前拒绝；准入时已按一个条目计费
+        self.service.check_batch_size(request.get_ref().items.len()).await?;
+        let extra_items = request.get_ref().items.len().saturating_sub(1);
+        let rate_limit = request.extensions().get::<RateLimitHandle>().cloned();
+        self.service
+            .charge_batch_items(rate_limit.as_ref(), identity.as_ref(), extra_items)
+            .await?;
+
+        let results = match run_items(&self.service, request.into_inner().items, identity.as_ref()).await {
+            Ok(results) => results,
+            Err(status) => {
+                self.service
+                    .refund_batch_items(rate_limit.as_ref(), identity.as_ref(), extra_items)
+                    .await;
+                return Err(status);
+            }
+        };
+        Ok(Response::new(proto::BatchResponse {
+            results,
+            total_processing_time_ms: start.elapsed().as_millis() as u64,
+        }))
+    }
+
+    type BatchStreamStream = Pin<Box<dyn Stream<Item = Result<proto::BatchResult, Status>> + Send + 'static>>;
+
+    async fn batch_stream(
+        &self,
+        request: Request<Streaming<proto::BatchItem>>,
+    ) -> Result<Response<Self::BatchStreamStream>, Status> {
+        let identity = self.authorize(Caller::of(&request), &[]).await?;
+        let rate_limit = request.extensions().get::<RateLimitHandle>().cloned();
+
+        let service = self.service.clone();
+        let chunk_size = service.config.read().await.performance.max_batch_size.max(1);
+        let mut inbound = request.into_inner();
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+
+        // 按 max_batch_size 分组处理，有界通道在客户端读取过慢时形成背压
+        tokio::spawn(async move {
+            let mut chunk = Vec::with_capacity(chunk_size);
+            let mut charged_admission = false;
+            loop {
+                let next = inbound.message().await;
+                let finished = !matches!(next, Ok(Some(_)));
+                match next {
+                    Ok(Some(item)) => chunk.push(item),
+                    Ok(None) => {}
+                    Err(status) => {
+                        let _ = sender.send(Err(status)).await;
+                        return;
+                    }
+                }
+
+                if chunk.len() >= chunk_size || (finished && !chunk.is_empty()) {
+                    let items = std::mem::replace(&mut chunk, Vec::with_capacity(chunk_size));
+                    // 准入时已按一个条目计费
+                    let billable = if charged_admission { items.len() } else { items.len() - 1 };
+                    charged_admission = true;
+                    if let Err(e) = service
+                        .charge_batch_items(rate_limit.as_ref(), identity.as_ref(), billable)
+                        .await
+                    {
+                        let _ = sender.send(Err(e.into())).await;
+                        return;
+                    }
+                    match run_items(&service, items, identity.as_ref()).await {
+                        Ok(results) => {
+                            for result in results {
+                                if sender.send(Ok(result)).await.is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                        Err(status) => {
+                            service
+                                .refund_batch_items(rate_limit.as_ref(), identity.as_ref(), billable)
+                                .await;
+                            let _ = sender.send(Err(status)).await;
+                            return;
+                        }
+                    }
+                }
+
+                if finished {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(FlushBeforeError {
+            inner: ReceiverStream::new(receiver),
+            deferred: None,
+        })))
+    }
+
+    async fn compute_cells(
+        &self,
+        request: Request<proto::BlobRequest>,
+    ) -> Result<Response<proto::CellsResponse>, Status> {
+        self.authorize(Caller::of(&request), &[Scope::Prove]).await?;
+        let start = std::time::Instant::now();
+        let input = CommitmentInput { blob: request.into_inner().blob };
+        let result = self.service.compute_cell_proofs(input).await?;
+        Ok(Response::new(cells_response(result, start)))
+    }
+
+    async fn verify_cells(
+        &self,
+        request: Request<proto::CellVerificationRequest>,
+    ) -> Result<Response<proto::CellVerificationResponse>, Status> {
+        self.authorize(Caller::of(&request), &[Scope::Verify]).await?;
+        let start = std::time::Instant::now();
+        let request = request.into_inner();
+        let cell_count = request.cells.len();
+        let input = CellVerificationInput {
+            commitments: points("commitments", request.commitments)?,
+            cell_indices: request.cell_indices,
+            cells: request.cells,
+            proofs: points("proofs", request.proofs)?,
+        };
+        let is_valid = self.service.verify_cell_batch(input).await?;
+        Ok(Response::new(proto::CellVerificationResponse {
+            is_valid,
+            cell_count: cell_count as u64,
+            processing_time_ms: start.elapsed().as_millis() as u64,
+        }))
+    }
+
+    async fn recover_cells(
+        &self,
+        request: Request<proto::CellRecoveryRequest>,
+    ) -> Result<Response<proto::CellsResponse>, Status> {
+        self.authorize(Caller::of(&request), &[Scope::Prove]).await?;
+        let start = std::time::Instant::now();
+        let request = request.into_inner();
+        let input = CellRecoveryInput {
+            cell_indices: request.cell_indices,
+            cells: request.cells,
+        };
+        let result = self.service.recover_cell_batch(input).await?;
+        Ok(Response::new(cells_response(result, start)))
+    }
+}
+
+/// 在错误之前先让出一次轮询
+///
+/// tonic 把同一次轮询中就绪的消息编码进同一个缓冲区，中途遇到错误时整个缓冲区被丢弃，
+/// 客户端会丢失错误之前已计费、已算出的结果。先返回 `Pending` 让已编码的消息发出，
+/// 下次轮询再交出错误。
+struct FlushBeforeError<S> {
+    inner: S,
+    deferred: Option<Status>,
+}
+
+impl<S, T> Stream for FlushBeforeError<S>
+where
+    S: Stream<Item = Result<T, Status>> + Unpin,
+{
+    type Item = Result<T, Status>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(status) = self.deferred.take() {
+            return Poll::Ready(Some(Err(status)));
+        }
+        match Pin::new(&mut self.inner).poll_next(cx) {
+            Poll::Ready(Some(Err(status))) => {
+                self.deferred = Some(status);
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            other => other,
+        }
+    }
+}
+
+/// 启动 gRPC 服务器，收到关闭信号后优雅退出
+pub async fn start_grpc_server(service: ProductionKzgService, addr: SocketAddr) -> Result<()> {
+    info!("启动 gRPC 服务器: {}", addr);
+
+    let rate_limit = service.rate_limit_layer();
+    let server = GrpcKzgService::new(service).into_server().await;
+    tonic::transport::Server::builder()
+        .layer(rate_limit)
+        .add_service(server)
+        .serve_with_shutdown(addr, super::server::shutdown_signal())
+        .await
+        .context("gRPC server error")?;
+
+    info!("gRPC 服务器已关闭");
+    Ok(())
+}
+
+// ================================
+// 消息转换
+// ================================
+
+async fn run_items(
+    service: &ProductionKzgService,
+    items: Vec<proto::BatchItem>,
+    identity: Option<&ApiKeyIdentity>,
+) -> Result<Vec<proto::BatchResult>, Status> {
+    let (ids, operations): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .map(|item| (item.id, restrict_operation(batch_operation(item.operation), identity)))
+        .unzip();
+    let outcomes = service.run_batch(operations).await?;
+
+    Ok(ids
+        .into_iter()
+        .zip(outcomes)
+        .map(|(id, outcome)| {
+            use proto::batch_result::Outcome;
+            let outcome = match outcome {
+                Ok(BatchOutput::Commitment(response)) => Outcome::Commitment(commitment_response(response)),
+                Ok(BatchOutput::Proof(response)) => Outcome::Proof(proof_response(response)),
+                Ok(BatchOutput::Verification(response)) => Outcome::Verification(verification_response(response)),
+                Err(e) => Outcome::Error(e.to_string()),
+            };
+            proto::BatchResult { id, outcome: Some(outcome) }
+        })
+        .collect())
+}
+
+fn batch_operation(operation: Option<proto::batch_item::Operation>) -> Result<BatchOperation, ServiceError> {
+    use proto::batch_item::Operation;
+    match operation {
+        Some(Operation::Commitment(request)) => Ok(BatchOperation::Commitment(CommitmentInput { blob: request.blob })),
+        Some(Operation::Proof(request)) => Ok(BatchOperation::Proof(proof_input(request)?)),
+        Some(Operation::Verification(request)) => Ok(BatchOperation::Verification(verification_input(request)?)),
+        None => Err(ServiceError::InvalidRequest("Missing operation".to_string())),
+    }
+}
+
+fn proof_input(request: proto::ProofRequest) -> Result<ProofInput, ServiceError> {
+    Ok(ProofInput {
+        blob: request.blob,
+        commitment: point("commitment", &request.commitment)?,
+    })
+}
+
+fn verification_input(request: proto::VerificationRequest) -> Result<VerificationInput, ServiceError> {
+    Ok(VerificationInput {
+        blob: request.blob,
+        commitment: point("commitment", &request.commitment)?,
+        proof: point("proof", &request.proof)?,
+    })
+}
+
+fn point(field: &str, bytes: &[u8]) -> Result<[u8; 48], ServiceError> {
+    <[u8; 48]>::try_from(bytes)
+        .map_err(|_| ServiceError::InvalidRequest(format!("{} must be 48 bytes, got {}", field, bytes.len())))
+}
+
+fn points(field: &str, values: Vec<Vec<u8>>) -> Result<Vec<[u8; 48]>, ServiceError> {
+    values.iter().map(|bytes| point(field, bytes)).collect()
+}
+
+fn commitment_response(response: super::CommitmentResponse) -> proto::CommitmentResponse {
+    proto::CommitmentResponse {
+        commitment: response.commitment.to_vec(),
+        versioned_hash: response.versioned_hash.to_vec(),
+        processing_time_ms: response.processing_time_ms,
+    }
+}
+
+fn proof_response(response: super::ProofResponse) -> proto::ProofResponse {
+    proto::ProofResponse {
+        proof: response.proof.to_vec(),
+        processing_time_ms: response.processing_time_ms,
+    }
+}
+
+fn verification_response(response: super::VerificationResponse) -> proto::VerificationResponse {
+    proto::VerificationResponse {
+        is_valid: response.is_valid,
+        versioned_hash: response.versioned_hash.to_vec(),
+        processing_time_ms: response.processing_time_ms,
+    }
+}
+
+fn cells_response(result: CellsAndProofs, start: std::time::Instant) -> proto::CellsResponse {
+    proto::CellsResponse {
+        cells: result.cells,
+        proofs: result.proofs.iter().map(|proof| proof.to_vec()).collect(),
+        processing_time_ms: start.elapsed().as_millis() as u64,
+    }
+}
@@ -0,0 +1,116 @@
+//! 健康检查
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+
+/// 健康检查器
+pub struct HealthChecker {
+    kzg_health: Arc<Mutex<bool>>,
+    system_health: Arc<Mutex<SystemHealth>>,
+    external_dependencies: Arc<Mutex<HashMap<String, bool>>>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SystemHealth {
+    pub memory_usage: f64,
+    pub cpu_usage: f64,
+    pub disk_usage: f64,
+    pub network_connectivity: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct HealthStatus {
+    pub status: String,
+    pub timestamp: u64,
+    pub services: HashMap<String, ServiceStatus>,
+    pub system: SystemHealth,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ServiceStatus {
+    pub healthy: bool,
+    pub last_check: u64,
+    pub error_message: Option<String>,
+}
+
+impl Default for HealthChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthChecker {
+    pub fn new() -> Self {
+        Self {
+            kzg_health: Arc::new(Mutex::new(true)),
+            system_health: Arc::new(Mutex::new(SystemHealth {
+                memory_usage: 0.0,
+                cpu_usage: 0.0,
+                disk_usage: 0.0,
+                network_connectivity: true,
+            })),
+            external_dependencies: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+    
+    /// 执行健康检查
+    pub async fn check_health(&self) -> HealthStatus {
+        let mut services = HashMap::new();
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        // KZG 服务健康检查
+        let kzg_healthy = *self.kzg_health.lock().await;
+        services.insert("kzg".to_string(), ServiceStatus {
+            healthy: kzg_healthy,
+            last_check: timestamp,
+            error_message: if kzg_healthy { None } else { Some("KZG service unhealthy".to_string()) },
+        });
+        
+        // 外部依赖健康检查
+        let deps = self.external_dependencies.lock().await;
+        for (name, healthy) in deps.iter() {
+            services.insert(name.clone(), ServiceStatus {
+                healthy: *healthy,
+                last_check: timestamp,
+                error_message: if *healthy { None } else { Some(format!("{} service unhealthy", name)) },
+            });
+        }
+        
+        // 系统健康状态
+        let system = self.system_health.lock().await.clone();
+        
+        // 整体状态判断
+        let overall_healthy = services.values().all(|s| s.healthy) 
+            && system.memory_usage < 90.0
+            && system.cpu_usage < 90.0
+            && system.disk_usage < 90.0;
+        
+        HealthStatus {
+            status: if overall_healthy { "healthy".to_string() } else { "unhealthy".to_string() },
+            timestamp,
+            services,
+            system,
+        }
+    }
+    
+    /// 检查就绪状态
+    pub async fn check_readiness(&self) -> bool {
+        let kzg_healthy = *self.kzg_health.lock().await;
+        let system = self.system_health.lock().await;
+        
+        kzg_healthy && system.network_connectivity
+    }
+    
+    /// 执行活跃检查
+    pub async fn check_liveness(&self) -> bool {
+        // 简单的活跃检查 - 服务是否响应
+        true
+    }
+}
@@ -0,0 +1,801 @@
+//! 异步批量任务
+//!
+//! `POST /api/v1/jobs` 把批量请求放入有界队列并立即返回任务标识，`GET /api/v1/jobs/{id}`
+//! 查询进度和已完成条目的结果，避免大批量请求在负载均衡器超时前无法返回。
+//!
+//! 任务按块 (`jobs.chunk_size` 个条目) 执行，租户 (API 密钥标识或客户端 IP) 之间按块轮转，
+//! 一个租户的大任务不会阻塞其他租户；同一租户的任务先进先出。队列按任务数与条目字节数限界，
+//! 条目在任务结束前一直留在内存中。
+//!
+//! 设置 `jobs.journal_path` 后，提交与结束事件追加写入 JSON Lines 日志，重启时未结束的任务
+//! 重新排队并从头执行；已结束任务过了保留期被移除时，日志随之压缩为只含未结束任务的记录。
+//! 任务结束后若指定了回调地址，以 POST 发送最终状态。
+//!
+//! 回调地址由提交者指定，为避免服务被用来访问内网，默认只允许全局单播地址：
+//! 提交时检查 IP 字面量，发送前检查 DNS 解析出的全部地址，连接只使用检查过的地址，
+//! 且不跟随重定向。
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use super::config::JobsConfig;
+use super::{
+    restrict_operation, ApiKeyIdentity, BatchItem, BatchOperation, BatchResult, ProductionKzgService, Scope,
+    ServiceError,
+};
+
+/// 异步任务请求：批量条目及可选的完成回调
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct JobRequest {
+    pub requests: Vec<BatchItem>,
+    #[serde(default)]
+    pub callback_url: Option<String>,
+}
+
+/// 任务提交结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobSubmitted {
+    pub id: String,
+    pub state: JobState,
+    pub total: usize,
+}
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// 提交的任务 (写入日志的部分)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSpec {
+    pub id: String,
+    pub tenant: String,
+    /// 提交者的权限范围，`None` 表示未启用认证
+    pub scopes: Option<Vec<Scope>>,
+    pub items: Vec<BatchItem>,
+    pub callback_url: Option<String>,
+    pub created_at: u64,
+}
+
+/// 任务状态快照
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct JobStatus {
+    pub id: String,
+    pub tenant: String,
+    pub state: JobState,
+    pub total: usize,
+    pub completed: usize,
+    /// 已完成条目的结果，顺序与请求一致
+    pub results: Vec<BatchResult>,
+    pub error: Option<String>,
+    pub callback_url: Option<String>,
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+}
+
+/// 交给执行者的一块条目
+#[derive(Debug, Clone)]
+pub struct JobChunk {
+    pub job_id: String,
+    pub tenant: String,
+    pub scopes: Option<Vec<Scope>>,
+    pub items: Vec<BatchItem>,
+}
+
+impl JobChunk {
+    /// 解码条目，提交者无权执行的条目以 Forbidden 错误返回
+    pub fn operations(&self) -> Vec<Result<BatchOperation, ServiceError>> {
+        let identity = self.scopes.as_ref().map(|scopes| ApiKeyIdentity {
+            id: self.tenant.clone(),
+            scopes: scopes.clone(),
+            rate: None,
+        });
+        self.items
+            .iter()
+            .cloned()
+            .map(|item| restrict_operation(BatchOperation::try_from(item), identity.as_ref()))
+            .collect()
+    }
+}
+
+struct Job {
+    spec: JobSpec,
+    total: usize,
+    /// 条目占用的字节数，任务结束时从队列预算中释放
+    bytes: usize,
+    state: JobState,
+    /// 已交给执行者的条目数
+    dispatched: usize,
+    results: Vec<BatchResult>,
+    error: Option<String>,
+    finished_at: Option<u64>,
+}
+
+impl Job {
+    fn new(spec: JobSpec) -> Self {
+        Self {
+            total: spec.items.len(),
+            bytes: items_bytes(&spec.items),
+            spec,
+            state: JobState::Queued,
+            dispatched: 0,
+            results: Vec::new(),
+            error: None,
+            finished_at: None,
+        }
+    }
+
+    fn status(&self) -> JobStatus {
+        JobStatus {
+            id: self.spec.id.clone(),
+            tenant: self.spec.tenant.clone(),
+            state: self.state,
+            total: self.total,
+            completed: self.results.len(),
+            results: self.results.clone(),
+            error: self.error.clone(),
+            callback_url: self.spec.callback_url.clone(),
+            created_at: self.spec.created_at,
+            finished_at: self.finished_at,
+        }
+    }
+}
+
+// ================================================================================================
+// 调度
+// ================================================================================================
+
+#[derive(Default)]
+struct QueueState {
+    jobs: HashMap<String, Job>,
+    /// 各租户等待调度的任务
+    pending: HashMap<String, VecDeque<String>>,
+    /// 有待调度任务的租户，按轮转顺序
+    rotation: VecDeque<String>,
+    /// 未结束 (排队或执行中) 的任务数
+    unfinished: usize,
+    unfinished_by_tenant: HashMap<String, usize>,
+    /// 未结束任务的条目字节数
+    unfinished_bytes: usize,
+}
+
+impl QueueState {
+    fn enqueue(&mut self, spec: JobSpec) {
+        self.reserve(&spec.tenant, items_bytes(&spec.items));
+        self.insert(spec);
+    }
+
+    /// 为即将加入的任务占用名额与字节预算
+    fn reserve(&mut self, tenant: &str, bytes: usize) {
+        self.unfinished += 1;
+        *self.unfinished_by_tenant.entry(tenant.to_string()).or_default() += 1;
+        self.unfinished_bytes += bytes;
+    }
+
+    /// 归还 [`reserve`](Self::reserve) 占用的名额与字节预算
+    fn release(&mut self, tenant: &str, bytes: usize) {
+        self.unfinished -= 1;
+        if let Some(count) = self.unfinished_by_tenant.get_mut(tenant) {
+            *count -= 1;
+            if *count == 0 {
+                self.unfinished_by_tenant.remove(tenant);
+            }
+        }
+        self.unfinished_bytes -= bytes;
+    }
+
+    /// 加入已占用名额的任务
+    fn insert(&mut self, spec: JobSpec) {
+        let (id, tenant) = (spec.id.clone(), spec.tenant.clone());
+        self.jobs.insert(id.clone(), Job::new(spec));
+        self.schedule(&tenant, id, false);
+    }
+
+    /// 放回租户队列，`front` 为真时排在该租户最前 (继续执行已开始的任务)
+    fn schedule(&mut self, tenant: &str, id: String, front: bool) {
+        let queue = self.pending.entry(tenant.to_string()).or_default();
+        if front {
+            queue.push_front(id);
+        } else {
+            queue.push_back(id);
+        }
+        if !self.rotation.iter().any(|t| t == tenant) {
+            self.rotation.push_back(tenant.to_string());
+        }
+    }
+
+    /// 轮到的租户交出其首个任务的下一块；任务在该块完成前不再被调度
+    fn next_chunk(&mut self, chunk_size: usize) -> Option<JobChunk> {
+        while let Some(tenant) = self.rotation.pop_front() {
+            let Some(queue) = self.pending.get_mut(&tenant) else {
+                continue;
+            };
+            let id = queue.pop_front();
+            if queue.is_empty() {
+                self.pending.remove(&tenant);
+            } else {
+                self.rotation.push_back(tenant.clone());
+            }
+
+            // 已结束 (失败) 的任务直接丢弃
+            let Some(job) = id.and_then(|id| self.jobs.get_mut(&id)).filter(|job| job.finished_at.is_none()) else {
+                continue;
+            };
+            job.state = JobState::Running;
+            let end = (job.dispatched + chunk_size).min(job.total);
+            let items = job.spec.items[job.dispatched..end].to_vec();
+            job.dispatched = end;
+
+            return Some(JobChunk {
+                job_id: job.spec.id.clone(),
+                tenant,
+                scopes: job.spec.scopes.clone(),
+                items,
+            });
+        }
+        None
+    }
+
+    /// 记录一块的结果，任务全部完成时返回最终状态
+    fn complete_chunk(&mut self, id: &str, results: Vec<BatchResult>) -> Option<JobStatus> {
+        let job = self.jobs.get_mut(id).filter(|job| job.state == JobState::Running)?;
+        job.results.extend(results);
+        if job.dispatched < job.total {
+            let tenant = job.spec.tenant.clone();
+            self.schedule(&tenant, id.to_string(), true);
+            None
+        } else {
+            self.finish(id, JobState::Completed, None)
+        }
+    }
+
+    fn finish(&mut self, id: &str, state: JobState, error: Option<String>) -> Option<JobStatus> {
+        let job = self.jobs.get_mut(id)?;
+        if job.finished_at.is_some() {
+            return None;
+        }
+        job.state = state;
+        job.error = error;
+        job.finished_at = Some(now_seconds());
+        // 结果已保存，不再需要原始条目
+        job.spec.items = Vec::new();
+        let bytes = job.bytes;
+        let status = job.status();
+
+        self.release(&status.tenant, bytes);
+        Some(status)
+    }
+
+    /// 移除保留期已过的已结束任务，返回移除的数量
+    fn purge_finished(&mut self, retention_seconds: u64) -> usize {
+        let now = now_seconds();
+        let before = self.jobs.len();
+        self.jobs
+            .retain(|_, job| job.finished_at.map_or(true, |at| now.saturating_sub(at) < retention_seconds));
+        before - self.jobs.len()
+    }
+}
+
+/// 有界、按租户公平调度的任务队列
+pub struct JobQueue {
+    config: JobsConfig,
+    state: Mutex<QueueState>,
+    ready: Notify,
+    journal: Option<Mutex<Journal>>,
+}
+
+impl JobQueue {
+    /// 创建队列，配置了日志时恢复其中未结束的任务
+    pub fn new(config: &JobsConfig) -> Result<Self> {
+        let mut state = QueueState::default();
+        let journal = match &config.journal_path {
+            Some(path) => {
+                let (journal, recovered) = Journal::open(path)?;
+                if !recovered.is_empty() {
+                    info!("Recovered {} unfinished jobs from {}", recovered.len(), path);
+                }
+                recovered.into_iter().for_each(|spec| state.enqueue(spec));
+                Some(Mutex::new(journal))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            config: config.clone(),
+            state: Mutex::new(state),
+            ready: Notify::new(),
+            journal,
+        })
+    }
+
+    /// 提交任务，返回任务标识
+    ///
+    /// `tenant` 用于公平调度与查询权限，`scopes` 为提交者的权限范围。
+    pub fn submit(
+        &self,
+        tenant: &str,
+        scopes: Option<Vec<Scope>>,
+        request: JobRequest,
+    ) -> Result<JobSubmitted, ServiceError> {
+        let total = request.requests.len();
+        if total == 0 {
+            return Err(ServiceError::InvalidRequest("job has no items".to_string()));
+        }
+        if total > self.config.max_items_per_job {
+            return Err(ServiceError::InvalidRequest(format!(
+                "job size {} exceeds limit {}",
+                total, self.config.max_items_per_job
+            )));
+        }
+        if let Some(url) = &request.callback_url {
+            self.check_callback_url(url)?;
+        }
+
+        let bytes = items_bytes(&request.requests);
+        if bytes > self.config.max_queued_bytes {
+            return Err(ServiceError::InvalidRequest(format!(
+                "job size {} bytes exceeds limit {}",
+                bytes, self.config.max_queued_bytes
+            )));
+        }
+
+        self.purge_finished();
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.unfinished >= self.config.max_queued_jobs {
+                return Err(ServiceError::QueueFull(format!("{} jobs pending", state.unfinished)));
+            }
+            if state.unfinished_bytes + bytes > self.config.max_queued_bytes {
+                return Err(ServiceError::QueueFull(format!("{} bytes pending", state.unfinished_bytes)));
+            }
+            let tenant_jobs = state.unfinished_by_tenant.get(tenant).copied().unwrap_or(0);
+            if tenant_jobs >= self.config.max_jobs_per_tenant {
+                return Err(ServiceError::QueueFull(format!("tenant has {} unfinished jobs", tenant_jobs)));
+            }
+            state.reserve(tenant, bytes);
+        }
+
+        let spec = JobSpec {
+            id: new_job_id(),
+            tenant: tenant.to_string(),
+            scopes,
+            items: request.requests,
+            callback_url: request.callback_url,
+            created_at: now_seconds(),
+        };
+        // 名额已占用，写日志 (含 fsync) 时不持有队列锁，执行者与查询不被阻塞
+        if let Err(e) = self.journal(&JournalRecord::Submitted { job: Cow::Borrowed(&spec) }) {
+            self.state.lock().unwrap().release(tenant, bytes);
+            return Err(ServiceError::InternalError(format!("job journal write failed: {}", e)));
+        }
+
+        let submitted = JobSubmitted {
+            id: spec.id.clone(),
+            state: JobState::Queued,
+            total,
+        };
+        self.state.lock().unwrap().insert(spec);
+        self.ready.notify_one();
+        Ok(submitted)
+    }
+
+    /// 取下一块待执行条目，没有时返回 `None`
+    pub fn next_chunk(&self) -> Option<JobChunk> {
+        self.state.lock().unwrap().next_chunk(self.config.chunk_size.max(1))
+    }
+
+    /// 等待下一块待执行条目
+    pub async fn wait_chunk(&self) -> JobChunk {
+        loop {
+            if let Some(chunk) = self.next_chunk() {
+                return chunk;
+            }
+            self.ready.notified().await;
+        }
+    }
+
+    /// 记录一块的结果，任务全部完成时返回最终状态
+    pub fn complete_chunk(&self, id: &str, results: Vec<BatchResult>) -> Option<JobStatus> {
+        let finished = self.state.lock().unwrap().complete_chunk(id, results);
+        match &finished {
+            Some(status) => self.journal_finished(&status.id),
+            None => self.ready.notify_one(),
+        }
+        finished
+    }
+
+    /// 以错误结束任务，返回最终状态
+    pub fn fail(&self, id: &str, error: String) -> Option<JobStatus> {
+        let finished = self.state.lock().unwrap().finish(id, JobState::Failed, Some(error));
+        if let Some(status) = &finished {
+            self.journal_finished(&status.id);
+        }
+        finished
+    }
+
+    /// 任务状态，保留期已过的任务返回 `None`
+    pub fn status(&self, id: &str) -> Option<JobStatus> {
+        self.purge_finished();
+        self.state.lock().unwrap().jobs.get(id).map(Job::status)
+    }
+
+    /// 未结束 (排队或执行中) 的任务数
+    pub fn unfinished(&self) -> usize {
+        self.state.lock().unwrap().unfinished
+    }
+
+    /// 回调地址只允许 http/https，配置了主机白名单时还须在名单内，IP 字面量须是公网地址
+    fn check_callback_url(&self, url: &str) -> Result<(), ServiceError> {
+        let invalid = |reason: &str| ServiceError::InvalidRequest(format!("callback_url {}: {}", url, reason));
+        let parsed = reqwest::Url::parse(url).map_err(|e| invalid(&e.to_string()))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(invalid("scheme must be http or https"));
+        }
+        let host = parsed.host_str().ok_or_else(|| invalid("missing host"))?;
+        let allowed = &self.config.callback_allowed_hosts;
+        if !allowed.is_empty() && !allowed.iter().any(|h| h.eq_ignore_ascii_case(host)) {
+            return Err(invalid("host is not allowed"));
+        }
+        if let Some(ip) = ip_literal(host) {
+            if !self.callback_ip_allowed(ip) {
+                return Err(invalid("address is not public"));
+            }
+        }
+        Ok(())
+    }
+
+    fn callback_ip_allowed(&self, ip: IpAddr) -> bool {
+        self.config.callback_allow_private_networks || is_public(ip)
+    }
+
+    /// 为一次回调建立客户端：解析主机并检查全部地址，连接固定到检查过的地址，
+    /// 避免检查与连接之间 DNS 记录被改指内网；不跟随重定向，不经过代理
+    async fn callback_client(&self, url: &str) -> Result<reqwest::Client> {
+        let parsed = reqwest::Url::parse(url)?;
+        let host = parsed.host_str().ok_or_else(|| anyhow!("missing host"))?;
+        let port = parsed.port_or_known_default().ok_or_else(|| anyhow!("missing port"))?;
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(self.config.callback_timeout_seconds))
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy();
+
+        match ip_literal(host) {
+            Some(ip) if !self.callback_ip_allowed(ip) => bail!("address {} is not public", ip),
+            Some(_) => {}
+            None => {
+                let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+                if addrs.is_empty() {
+                    bail!("{} did not resolve", host);
+                }
+                if let Some(addr) = addrs.iter().find(|addr| !self.callback_ip_allowed(addr.ip())) {
+                    bail!("{} resolves to non-public address {}", host, addr.ip());
+                }
+                builder = builder.resolve_to_addrs(host, &addrs);
+            }
+        }
+        builder.build().context("Failed to build callback client")
+    }
+
+    /// 以 POST 发送任务最终状态，失败时按指数退避重试
+    ///
+    /// 目标地址不被允许时不再重试；重定向响应视为失败。
+    pub async fn deliver_callback(&self, url: &str, status: &JobStatus) -> bool {
+        for attempt in 0..=self.config.callback_retries {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_millis(500 << (attempt - 1).min(6))).await;
+            }
+            let client = match self.callback_client(url).await {
+                Ok(client) => client,
+                Err(e) => {
+                    warn!("Job {} callback refused: {}", status.id, e);
+                    return false;
+                }
+            };
+            match client.post(url).header("x-kzg-job-id", &status.id).json(status).send().await {
+                Ok(response) if response.status().is_success() => return true,
+                Ok(response) => warn!("Job {} callback returned {}", status.id, response.status()),
+                Err(e) => warn!("Job {} callback failed: {}", status.id, e),
+            }
+        }
+        false
+    }
+
+    /// 移除保留期已过的已结束任务，有任务被移除时压缩日志
+    fn purge_finished(&self) {
+        let purged = self.state.lock().unwrap().purge_finished(self.config.retention_seconds);
+        if purged == 0 {
+            return;
+        }
+        if let Some(journal) = &self.journal {
+            // 压缩失败时日志只是多出已结束任务的记录，重启时仍会被跳过
+            if let Err(e) = journal.lock().unwrap().compact() {
+                warn!("Failed to compact job journal: {:#}", e);
+            }
+        }
+    }
+
+    fn journal(&self, record: &JournalRecord<'_>) -> std::io::Result<()> {
+        match &self.journal {
+            Some(journal) => journal.lock().unwrap().append(record),
+            None => Ok(()),
+        }
+    }
+
+    fn journal_finished(&self, id: &str) {
+        if let Err(e) = self.journal(&JournalRecord::Finished { id: Cow::Borrowed(id) }) {
+            // 重启后该任务会再执行一次，结果不变
+            warn!("Failed to journal completion of job {}: {}", id, e);
+        }
+    }
+}
+
+// ================================================================================================
+// 日志
+// ================================================================================================
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+enum JournalRecord<'a> {
+    Submitted { job: Cow<'a, JobSpec> },
+    Finished { id: Cow<'a, str> },
+}
+
+/// 追加写入的任务日志
+struct Journal {
+    path: String,
+    file: File,
+}
+
+impl Journal {
+    /// 打开日志并压缩为只含未结束任务的提交记录，返回这些任务
+    fn open(path: &str) -> Result<(Self, Vec<JobSpec>)> {
+        let submitted = Self::read_unfinished(path)?;
+        let file = Self::rewrite(path, &submitted)?;
+        Ok((
+            Self {
+                path: path.to_string(),
+                file,
+            },
+            submitted,
+        ))
+    }
+
+    /// 压缩为只含未结束任务的提交记录
+    ///
+    /// 调用方持有日志锁，压缩期间没有新的记录写入。
+    fn compact(&mut self) -> Result<()> {
+        let submitted = Self::read_unfinished(&self.path)?;
+        self.file = Self::rewrite(&self.path, &submitted)?;
+        Ok(())
+    }
+
+    /// 读取日志中未结束任务的提交记录，无法解析的行 (例如写入中断的最后一行) 被跳过
+    fn read_unfinished(path: &str) -> Result<Vec<JobSpec>> {
+        let mut submitted = Vec::new();
+        let mut finished = HashSet::new();
+        if Path::new(path).exists() {
+            let file = File::open(path).with_context(|| format!("Failed to open job journal {}", path))?;
+            for (number, line) in BufReader::new(file).lines().enumerate() {
+                let line = line.with_context(|| format!("Failed to read job journal {}", path))?;
+                match serde_json::from_str::<JournalRecord<'static>>(&line) {
+                    Ok(JournalRecord::Submitted { job }) => submitted.push(job.into_owned()),
+                    Ok(JournalRecord::Finished { id }) => {
+                        finished.insert(id.into_owned());
+                    }
+                    Err(e) => warn!("Skipping job journal line {}: {}", number + 1, e),
+                }
+            }
+        }
+        submitted.retain(|job| !finished.contains(&job.id));
+        Ok(submitted)
+    }
+
+    /// 把提交记录写入临时文件后替换日志，返回用于追加的句柄
+    ///
+    /// 句柄在替换前打开，替换失败时原日志和原句柄都不受影响。
+    fn rewrite(path: &str, submitted: &[JobSpec]) -> Result<File> {
+        let compacted = format!("{}.tmp", path);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&compacted)
+            .with_context(|| format!("Failed to create job journal {}", compacted))?;
+        for job in submitted {
+            write_record(&mut file, &JournalRecord::Submitted { job: Cow::Borrowed(job) })?;
+        }
+        file.sync_all()?;
+        std::fs::rename(&compacted, path).with_context(|| format!("Failed to replace job journal {}", path))?;
+        Ok(file)
+    }
+
+    fn append(&mut self, record: &JournalRecord<'_>) -> std::io::Result<()> {
+        write_record(&mut self.file, record)?;
+        self.file.sync_data()
+    }
+}
+
+fn write_record(file: &mut File, record: &JournalRecord<'_>) -> std::io::Result<()> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
+
+// ================================================================================================
+// 执行
+// ================================================================================================
+
+impl ProductionKzgService {
+    /// 提交异步任务
+    ///
+    /// 租户为密钥标识，未启用认证时为客户端 IP。
+    pub fn submit_job(
+        &self,
+        request: JobRequest,
+        identity: Option<&ApiKeyIdentity>,
+        client_ip: Option<&str>,
+    ) -> Result<JobSubmitted, ServiceError> {
+        let tenant = tenant(identity, client_ip);
+        let submitted = self
+            .jobs
+            .submit(&tenant, identity.map(|identity| identity.scopes.clone()), request)?;
+        self.metrics.jobs_submitted_total.inc();
+        self.metrics.jobs_pending.set(self.jobs.unfinished() as i64);
+        Ok(submitted)
+    }
+
+    /// 查询任务状态，启用认证时只能查询本租户的任务 (管理员除外)
+    pub fn job_status(
+        &self,
+        id: &str,
+        identity: Option<&ApiKeyIdentity>,
+        client_ip: Option<&str>,
+    ) -> Result<JobStatus, ServiceError> {
+        let not_found = || ServiceError::NotFound(format!("job {}", id));
+        let status = self.jobs.status(id).ok_or_else(not_found)?;
+        let is_admin = identity.is_some_and(|identity| identity.has_scope(Scope::Admin));
+        if !is_admin && status.tenant != tenant(identity, client_ip) {
+            return Err(not_found());
+        }
+        Ok(status)
+    }
+
+    /// 启动任务执行者
+    pub(crate) fn spawn_job_workers(&self, workers: usize) {
+        for _ in 0..workers.max(1) {
+            let service = self.clone();
+            tokio::spawn(async move {
+                loop {
+                    let chunk = service.jobs.wait_chunk().await;
+                    service.run_job_chunk(chunk).await;
+                }
+            });
+        }
+    }
+
+    async fn run_job_chunk(&self, chunk: JobChunk) {
+        let ids: Vec<_> = chunk.items.iter().map(|item| item.id.clone()).collect();
+        let finished = match self.run_batch(chunk.operations()).await {
+            Ok(outcomes) => {
+                let results = ids
+                    .into_iter()
+                    .zip(outcomes)
+                    .map(|(id, outcome)| BatchResult::from_outcome(id, outcome.map(|output| output.to_json())))
+                    .collect();
+                self.jobs.complete_chunk(&chunk.job_id, results)
+            }
+            Err(e) => self.jobs.fail(&chunk.job_id, e.to_string()),
+        };
+
+        let Some(status) = finished else {
+            return;
+        };
+        self.metrics.jobs_pending.set(self.jobs.unfinished() as i64);
+        match status.state {
+            JobState::Failed => self.metrics.jobs_failed_total.inc(),
+            _ => self.metrics.jobs_completed_total.inc(),
+        }
+        if let Some(url) = status.callback_url.clone() {
+            let service = self.clone();
+            tokio::spawn(async move {
+                if !service.jobs.deliver_callback(&url, &status).await {
+                    service.metrics.job_callback_failures_total.inc();
+                }
+            });
+        }
+    }
+}
+
+fn tenant(identity: Option<&ApiKeyIdentity>, client_ip: Option<&str>) -> String {
+    match (identity, client_ip) {
+        (Some(identity), _) => identity.id.clone(),
+        (None, Some(ip)) => ip.to_string(),
+        (None, None) => "anonymous".to_string(),
+    }
+}
+
+/// 条目在内存中占用的主要字节数
+fn items_bytes(items: &[BatchItem]) -> usize {
+    items
+        .iter()
+        .map(|item| {
+            item.id.len()
+                + item.blob.len()
+                + item.commitment.as_ref().map_or(0, String::len)
+                + item.proof.as_ref().map_or(0, String::len)
+        })
+        .sum()
+}
+
+/// URL 主机部分中的 IP 字面量 (IPv6 带方括号)
+fn ip_literal(host: &str) -> Option<IpAddr> {
+    host.trim_start_matches('[').trim_end_matches(']').parse().ok()
+}
+
+/// 是否为公网地址
+///
+/// IPv4 排除未指定、回环、私有、链路本地、运营商级 NAT、IETF 协议分配 (192.0.0.0/24)、
+/// 基准测试 (198.18.0.0/15)、组播、保留 (240.0.0.0/4，含广播) 与文档地址，
+/// IPv4 映射的 IPv6 地址按其 IPv4 地址判断。
+/// 其余 IPv6 地址只允许全局单播 (2000::/3)，并排除其中的 IETF 协议分配 (2001::/23，含 Teredo)、
+/// 文档 (2001:db8::/32) 与 6to4 (2002::/16)；NAT64 (64:ff9b::/96、64:ff9b:1::/48) 等可能转发到
+/// 内网 IPv4 的前缀因此都被拒绝。
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                || a >= 240
+                || (a == 100 && b & 0xc0 == 64)
+                || (a == 192 && b == 0 && c == 0)
+                || (a == 198 && b & 0xfe == 18))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(mapped));
+            }
+            let [first, second, ..] = ip.segments();
+            first & 0xe000 == 0x2000
+                && !(first == 0x2001 && second < 0x0200)
+                && !(first == 0x2001 && second == 0x0db8)
+                && first != 0x2002
+        }
+    }
+}
+
+fn new_job_id() -> String {
+    let mut id = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut id);
+    hex::encode(id)
+}
+
+fn now_seconds() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
@@ -0,0 +1,301 @@
+//! Prometheus 监控指标
+
+//!
+//! 每个 [`KzgMetrics`] 使用自己的 [`Registry`]，`/metrics` 编码的就是该注册表，
+//! 同一进程中可以创建多个服务实例 (例如测试) 而不会重复注册。
+
+use anyhow::Result;
+use prometheus::core::Collector;
+use prometheus::{Gauge, Histogram, HistogramOpts, HistogramVec, IntCounter, IntGauge, Registry};
+
+/// KZG 服务监控指标
+pub struct KzgMetrics {
+    registry: Registry,
+    
+    // HTTP 请求指标
+    pub http_requests_total: IntCounter,
+    pub http_request_duration: HistogramVec,
+    pub http_requests_in_flight: IntGauge,
+    
+    // KZG 业务指标
+    pub kzg_commitments_total: IntCounter,
+    pub kzg_proofs_total: IntCounter,
+    pub kzg_verifications_total: IntCounter,
+    pub kzg_das_operations_total: IntCounter,
+    pub kzg_cells_computed_total: IntCounter,
+    pub kzg_cells_verified_total: IntCounter,
+    pub kzg_cells_recovered_total: IntCounter,
+    
+    // 性能指标
+    pub commitment_duration: Histogram,
+    pub proof_duration: Histogram,
+    pub verification_duration: Histogram,
+    pub das_duration: Histogram,
+    pub cell_computation_duration: Histogram,
+    pub cell_verification_duration: Histogram,
+    pub cell_recovery_duration: Histogram,
+    
+    // 系统指标
+    pub memory_usage_bytes: Gauge,
+    pub cpu_usage_percent: Gauge,
+    pub active_connections: IntGauge,
+    pub cache_hit_rate: Gauge,
+    pub cache_hits_total: IntCounter,
+    pub cache_misses_total: IntCounter,
+    pub cache_evictions_total: IntCounter,
+    pub cache_entries: IntGauge,
+    pub cache_bytes: IntGauge,
+    pub config_reloads_total: IntCounter,
+    pub config_reload_failures_total: IntCounter,
+    
+    // 异步任务指标
+    pub jobs_submitted_total: IntCounter,
+    pub jobs_completed_total: IntCounter,
+    pub jobs_failed_total: IntCounter,
+    pub jobs_pending: IntGauge,
+    pub job_callback_failures_total: IntCounter,
+    
+    // 错误指标
+    pub errors_total: IntCounter,
+    pub timeouts_total: IntCounter,
+    pub rate_limit_exceeded_total: IntCounter,
+}
+
+impl KzgMetrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+        Ok(Self {
+            // HTTP 指标
+            http_requests_total: int_counter(
+                &registry,
+                "kzg_http_requests_total",
+                "Total number of HTTP requests"
+            )?,
+            http_request_duration: histogram_vec(
+                &registry,
+                "kzg_http_request_duration_seconds",
+                "HTTP request duration in seconds",
+                &["method", "endpoint", "status"]
+            )?,
+            http_requests_in_flight: int_gauge(
+                &registry,
+                "kzg_http_requests_in_flight",
+                "Number of HTTP requests currently being processed"
+            )?,
+            
+            // KZG 业务指标
+            kzg_commitments_total: int_counter(
+                &registry,
+                "kzg_commitments_total",
+                "Total number of KZG commitments created"
+            )?,
+            kzg_proofs_total: int_counter(
+                &registry,
+                "kzg_proofs_total",
+                "Total number of KZG proofs generated"
+            )?,
+            kzg_verifications_total: int_counter(
+                &registry,
+                "kzg_verifications_total",
+                "Total number of KZG verifications performed"
+            )?,
+            kzg_das_operations_total: int_counter(
+                &registry,
+                "kzg_das_operations_total",
+                "Total number of DAS operations performed"
+            )?,
+            kzg_cells_computed_total: int_counter(
+                &registry,
+                "kzg_cells_computed_total",
+                "Total number of EIP-7594 cells computed"
+            )?,
+            kzg_cells_verified_total: int_counter(
+                &registry,
+                "kzg_cells_verified_total",
+                "Total number of EIP-7594 cell proofs verified"
+            )?,
+            kzg_cells_recovered_total: int_counter(
+                &registry,
+                "kzg_cells_recovered_total",
+                "Total number of EIP-7594 cells recovered"
+            )?,
+            
+            // 性能指标
+            commitment_duration: histogram(
+                &registry,
+                "kzg_commitment_duration_seconds",
+                "Time spent creating KZG commitments",
+                vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+            )?,
+            proof_duration: histogram(
+                &registry,
+                "kzg_proof_duration_seconds",
+                "Time spent generating KZG proofs",
+                vec![0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0]
+            )?,
+            verification_duration: histogram(
+                &registry,
+                "kzg_verification_duration_seconds", 
+                "Time spent verifying KZG proofs",
+                vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
+            )?,
+            das_duration: histogram(
+                &registry,
+                "kzg_das_duration_seconds",
+                "Time spent on DAS operations",
+                vec![0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0]
+            )?,
+            cell_computation_duration: histogram(
+                &registry,
+                "kzg_cell_computation_duration_seconds",
+                "Time spent computing EIP-7594 cells and proofs",
+                vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 25.0]
+            )?,
+            cell_verification_duration: histogram(
+                &registry,
+                "kzg_cell_verification_duration_seconds",
+                "Time spent verifying EIP-7594 cell proofs",
+                vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0]
+            )?,
+            cell_recovery_duration: histogram(
+                &registry,
+                "kzg_cell_recovery_duration_seconds",
+                "Time spent recovering EIP-7594 cells and proofs",
+                vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 25.0]
+            )?,
+            
+            // 系统指标
+            memory_usage_bytes: gauge(
+                &registry,
+                "kzg_memory_usage_bytes",
+                "Memory usage in bytes"
+            )?,
+            cpu_usage_percent: gauge(
+                &registry,
+                "kzg_cpu_usage_percent",
+                "CPU usage percentage"
+            )?,
+            active_connections: int_gauge(
+                &registry,
+                "kzg_active_connections",
+                "Number of active connections"
+            )?,
+            cache_hit_rate: gauge(
+                &registry,
+                "kzg_cache_hit_rate",
+                "Cache hit rate (0.0 to 1.0)"
+            )?,
+            cache_hits_total: int_counter(
+                &registry,
+                "kzg_cache_hits_total",
+                "Total number of result cache hits"
+            )?,
+            cache_misses_total: int_counter(
+                &registry,
+                "kzg_cache_misses_total",
+                "Total number of result cache misses"
+            )?,
+            cache_evictions_total: int_counter(
+                &registry,
+                "kzg_cache_evictions_total",
+                "Total number of result cache evictions"
+            )?,
+            cache_entries: int_gauge(
+                &registry,
+                "kzg_cache_entries",
+                "Number of entries in the result cache"
+            )?,
+            cache_bytes: int_gauge(
+                &registry,
+                "kzg_cache_bytes",
+                "Bytes held by the result cache"
+            )?,
+            config_reloads_total: int_counter(
+                &registry,
+                "kzg_config_reloads_total",
+                "Total number of successful configuration reloads"
+            )?,
+            config_reload_failures_total: int_counter(
+                &registry,
+                "kzg_config_reload_failures_total",
+                "Total number of rejected configuration reloads"
+            )?,
+            
+            // 异步任务指标
+            jobs_submitted_total: int_counter(
+                &registry,
+                "kzg_jobs_submitted_total",
+                "Total number of asynchronous batch jobs submitted"
+            )?,
+            jobs_completed_total: int_counter(
+                &registry,
+                "kzg_jobs_completed_total",
+                "Total number of asynchronous batch jobs completed"
+            )?,
+            jobs_failed_total: int_counter(
+                &registry,
+                "kzg_jobs_failed_total",
+                "Total number of asynchronous batch jobs failed"
+            )?,
+            jobs_pending: int_gauge(
+                &registry,
+                "kzg_jobs_pending",
+                "Number of queued or running asynchronous batch jobs"
+            )?,
+            job_callback_failures_total: int_counter(
+                &registry,
+                "kzg_job_callback_failures_total",
+                "Total number of job completion callbacks that could not be delivered"
+            )?,
+            
+            // 错误指标
+            errors_total: int_counter(
+                &registry,
+                "kzg_errors_total",
+                "Total number of errors"
+            )?,
+            timeouts_total: int_counter(
+                &registry,
+                "kzg_timeouts_total",
+                "Total number of timeouts"
+            )?,
+            rate_limit_exceeded_total: int_counter(
+                &registry,
+                "kzg_rate_limit_exceeded_total",
+                "Total number of rate limit exceeded events"
+            )?,
+            
+            registry,
+        })
+    }
+    
+    /// 本实例的指标注册表
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+}
+
+fn register<M: Collector + Clone + 'static>(registry: &Registry, metric: M) -> Result<M> {
+    registry.register(Box::new(metric.clone()))?;
+    Ok(metric)
+}
+
+fn int_counter(registry: &Registry, name: &str, help: &str) -> Result<IntCounter> {
+    register(registry, IntCounter::new(name, help)?)
+}
+
+fn int_gauge(registry: &Registry, name: &str, help: &str) -> Result<IntGauge> {
+    register(registry, IntGauge::new(name, help)?)
+}
+
+fn gauge(registry: &Registry, name: &str, help: &str) -> Result<Gauge> {
+    register(registry, Gauge::new(name, help)?)
+}
+
+fn histogram(registry: &Registry, name: &str, help: &str, buckets: Vec<f64>) -> Result<Histogram> {
+    register(registry, Histogram::with_opts(HistogramOpts::new(name, help).buckets(buckets))?)
+}
+
+fn histogram_vec(registry: &Registry, name: &str, help: &str, labels: &[&str]) -> Result<HistogramVec> {
+    register(registry, HistogramVec::new(HistogramOpts::new(name, help), labels)?)
+}
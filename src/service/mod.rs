@@ -0,0 +1,913 @@
+//! 第16章：生产环境 KZG 服务
+//!
+//! 服务核心 [`ProductionKzgService`] 以及配置、监控、健康检查、速率限制、
+//! 安全和缓存等组件。HTTP 路由与处理器位于 [`server`] 模块。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Result;
+use arc_swap::ArcSwap;
+use kzg::{
+    eip_4844::{
+        blob_to_kzg_commitment_rust, bytes_to_blob, compute_blob_kzg_proof_rust,
+        verify_blob_kzg_proof_rust, BYTES_PER_BLOB,
+    },
+    G1,
+};
+use rust_kzg_blst::{
+    eip_4844::load_trusted_setup_filename_rust,
+    types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings},
+};
+use tokio::sync::RwLock;
+use tokio::task::JoinSet;
+use tracing::{info, Level};
+
+use crate::versioned_hash::{kzg_to_versioned_hash, parse_versioned_hash, validate_blob_versioned_hashes};
+use tracing_subscriber::{filter::LevelFilter, layer::SubscriberExt, util::SubscriberInitExt};
+
+pub mod api;
+pub mod batch;
+pub mod cache;
+pub mod cells;
+pub mod codec;
+pub mod config;
+pub mod error;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod health;
+pub mod jobs;
+pub mod metrics;
+pub mod openapi;
+pub mod rate_limit;
+pub mod rate_limit_layer;
+pub mod reload;
+pub mod security;
+pub mod server;
+
+pub use api::*;
+pub use batch::{verify_blob_batch_bisect, BatchVerification, CpuPool};
+pub use cache::{blob_digest, CacheKey, CacheManager, CacheStats};
+pub use cells::{CellsAndProofs, BYTES_PER_CELL};
+pub use codec::{Encoded, Encoding, JsonOnly, Negotiated};
+pub use config::*;
+pub use error::{ErrorBody, ServiceError};
+pub use health::{HealthChecker, HealthStatus, ServiceStatus, SystemHealth};
+pub use jobs::{JobQueue, JobRequest, JobState, JobStatus, JobSubmitted};
+pub use metrics::KzgMetrics;
+pub use openapi::{openapi, ApiDoc};
+pub use rate_limit::{ClientId, ClientRate, RateLimitError, RateLimitStatus, RateLimiter, TokenBucket};
+pub use rate_limit_layer::{RateLimitHandle, RateLimitLayer};
+pub use reload::{ReloadReport, ReloadableCorsLayer};
+pub use security::{hash_api_key, legacy_key_id, ApiKeyIdentity, AuthError, Scope, SecurityManager};
+pub use server::{create_simple_router, start_http_server};
+
+/// 生产环境 KZG 服务主结构
+#[derive(Clone)]
+pub struct ProductionKzgService {
+    /// KZG 设置 (可热替换，进行中的请求继续使用取出时的版本)
+    kzg_settings: Arc<ArcSwap<FsKZGSettings>>,
+
+    /// KZG 设置的代号，每次替换后加一；缓存条目带有计算时的代号
+    settings_generation: Arc<AtomicU64>,
+    
+    /// 配置管理
+    config: Arc<RwLock<ProductionConfig>>,
+    
+    /// 监控指标
+    metrics: Arc<KzgMetrics>,
+    
+    /// 健康检查器
+    health_checker: Arc<HealthChecker>,
+    
+    /// 速率限制器
+    rate_limiter: Arc<RateLimiter>,
+    
+    /// 安全管理器
+    security_manager: Arc<SecurityManager>,
+    
+    /// 缓存管理器
+    cache_manager: Arc<CacheManager>,
+    
+    /// KZG 计算池
+    cpu_pool: CpuPool,
+
+    /// 异步批量任务队列
+    jobs: Arc<JobQueue>,
+
+    /// 日志级别重载句柄
+    log_level: reload::LogLevelHandle,
+
+    /// CORS 设置 (可热替换)
+    cors: Arc<ArcSwap<tower_http::cors::CorsLayer>>,
+}
+
+impl ProductionKzgService {
+    /// 创建新的生产 KZG 服务实例
+    pub async fn new(mut config: ProductionConfig) -> Result<Self> {
+        // 初始化日志
+        let log_level = Self::init_logging(&config.logging)?;
+        
+        info!("Initializing Production KZG Service...");
+        
+        // 加载 KZG 设置
+        info!("Loading trusted setup from: {}", config.kzg.trusted_setup_path);
+        let kzg_settings = Arc::new(ArcSwap::from_pointee(
+            load_trusted_setup_filename_rust(&config.kzg.trusted_setup_path)
+                .map_err(|e| anyhow::anyhow!("Failed to load trusted setup: {}", e))?
+        ));
+        info!("Successfully loaded KZG settings");
+        
+        // 初始化监控指标
+        let metrics = Arc::new(KzgMetrics::new()
+            .map_err(|e| anyhow::anyhow!("Failed to initialize metrics: {}", e))?);
+        
+        info!("Initialized monitoring metrics");
+        
+        // 初始化健康检查器
+        let health_checker = Arc::new(HealthChecker::new());
+        info!("Initialized health checker");
+        
+        // 初始化速率限制器
+        let rate_limiter = Arc::new(RateLimiter::new(&config.security.rate_limit));
+        info!("Initialized rate limiter");
+        
+        // 初始化安全管理器
+        let security_manager = Arc::new(SecurityManager::from_config(&config.security)?);
+        // 明文密钥已哈希，不随配置保留
+        config.security.api_keys.clear();
+        info!("Initialized security manager");
+        
+        // 初始化缓存管理器
+        let cache_manager = Arc::new(CacheManager::new(
+            config.performance.cache_size,
+            config.performance.cache_ttl_seconds,
+            config.performance.cache_max_bytes,
+        ));
+        info!("Initialized cache manager");
+        
+        // 初始化计算池
+        let cpu_pool = CpuPool::new(config.kzg.thread_pool_size.unwrap_or_else(num_cpus::get));
+        info!("Initialized CPU pool with {} workers", cpu_pool.size());
+        
+        let cors = Arc::new(ArcSwap::from_pointee(reload::cors_layer(&config.security.cors)?));
+        
+        // 初始化任务队列 (每块条目数不超过批量上限)
+        let job_workers = config.jobs.workers;
+        let jobs = Arc::new(JobQueue::new(&JobsConfig {
+            chunk_size: config.jobs.chunk_size.min(config.performance.max_batch_size),
+            ..config.jobs.clone()
+        })?);
+        info!("Initialized job queue with {} workers", job_workers);
+        
+        info!("Production KZG Service initialized successfully");
+        
+        let service = Self {
+            kzg_settings,
+            settings_generation: Arc::new(AtomicU64::new(0)),
+            config: Arc::new(RwLock::new(config)),
+            metrics,
+            health_checker,
+            rate_limiter,
+            security_manager,
+            cache_manager,
+            cpu_pool,
+            jobs,
+            log_level,
+            cors,
+        };
+        service.spawn_job_workers(job_workers);
+        
+        Ok(service)
+    }
+    
+    /// 初始化日志，返回可在运行时调整级别的句柄
+    fn init_logging(config: &LoggingConfig) -> Result<reload::LogLevelHandle> {
+        let (filter, handle) = tracing_subscriber::reload::Layer::new(LevelFilter::from_level(log_level(&config.level)));
+        
+        let installed = match config.format.as_str() {
+            "json" => {
+                tracing_subscriber::registry()
+                    .with(filter)
+                    .with(
+                        tracing_subscriber::fmt::layer()
+                            .json()
+                            .with_level(true)
+                            .with_target(true)
+                            .with_thread_ids(true)
+                            .with_file(true)
+                            .with_line_number(true)
+                    )
+                    .try_init()
+            },
+            _ => {
+                tracing_subscriber::registry()
+                    .with(filter)
+                    .with(
+                        tracing_subscriber::fmt::layer()
+                            .pretty()
+                            .with_level(true)
+                            .with_target(false)
+                    )
+                    .try_init()
+            }
+        };
+        
+        // 进程中已有全局订阅者 (例如同一进程中创建的第二个服务) 时沿用它，
+        // 此时返回的句柄不影响实际的日志级别
+        if let Err(e) = installed {
+            info!("Keeping the existing tracing subscriber: {}", e);
+        }
+        
+        Ok(handle)
+    }
+    
+    /// 速率限制器 (供自定义中间件使用)
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
+    }
+
+    /// 安全管理器 (供自定义中间件使用)
+    pub fn security_manager(&self) -> &Arc<SecurityManager> {
+        &self.security_manager
+    }
+
+    /// 监控指标 (`/metrics` 编码其注册表)
+    pub fn metrics(&self) -> &Arc<KzgMetrics> {
+        &self.metrics
+    }
+
+    /// 访问控制：IP 黑名单、API 密钥与权限范围
+    ///
+    /// 启用认证时密钥必须具备 `scopes` 中的全部权限，并按 `quota_items` 个条目计入该密钥的
+    /// 每日配额 (批量请求先计一个条目，其余由 [`Self::charge_batch_items`] 补计)；
+    /// `admin` 权限无论是否启用认证都要求有效密钥。REST 中间件和 gRPC 接入层共用此检查，
+    /// 速率限制由 [`RateLimitLayer`] 在此之前完成。
+    /// 返回通过认证的密钥身份，未启用认证时为 `None`。
+    pub async fn authorize(
+        &self,
+        api_key: Option<&str>,
+        client_ip: Option<&str>,
+        scopes: &[Scope],
+        quota_items: u64,
+    ) -> Result<Option<ApiKeyIdentity>, ServiceError> {
+        if let Some(ip) = client_ip {
+            if self.security_manager.is_ip_blocked(ip).await {
+                return Err(ServiceError::Unauthorized);
+            }
+        }
+        
+        let enable_auth = self.config.read().await.security.enable_auth;
+        if !enable_auth && !scopes.contains(&Scope::Admin) {
+            return Ok(None);
+        }
+        
+        let key = api_key.ok_or(AuthError::MissingKey)?;
+        let identity = self
+            .security_manager
+            .authenticate_items(key, scopes, quota_items)
+            .await
+            .map_err(|e| self.auth_error(e))?;
+        Ok(Some(identity))
+    }
+    
+    /// 为批量请求的其余条目补计每日配额 (未启用认证时不计)
+    async fn charge_quota(&self, identity: Option<&ApiKeyIdentity>, items: usize) -> Result<(), ServiceError> {
+        let Some(identity) = identity else {
+            return Ok(());
+        };
+        self.security_manager
+            .charge_quota(&identity.id, items as u64)
+            .await
+            .map_err(|e| self.auth_error(e))
+    }
+    
+    /// 退还未被执行的条目占用的每日配额
+    async fn refund_quota(&self, identity: Option<&ApiKeyIdentity>, items: usize) {
+        if let Some(identity) = identity {
+            self.security_manager.refund_quota(&identity.id, items as u64).await;
+        }
+    }
+    
+    /// 为批量请求或任务中准入之外的 `items` 个条目补扣令牌与每日配额
+    ///
+    /// 配额不足时退还刚补扣的令牌，失败时不留下任何扣费。调用方应先检查条目数上限
+    /// (见 [`check_batch_size`](Self::check_batch_size))，之后的处理失败时以
+    /// [`refund_batch_items`](Self::refund_batch_items) 退还。
+    pub async fn charge_batch_items(
+        &self,
+        rate_limit: Option<&RateLimitHandle>,
+        identity: Option<&ApiKeyIdentity>,
+        items: usize,
+    ) -> Result<(), ServiceError> {
+        if let Some(rate_limit) = rate_limit {
+            rate_limit.charge_items(items)?;
+        }
+        if let Err(e) = self.charge_quota(identity, items).await {
+            if let Some(rate_limit) = rate_limit {
+                rate_limit.refund_items(items);
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+    
+    /// 退还 [`charge_batch_items`](Self::charge_batch_items) 补扣的令牌与配额
+    pub async fn refund_batch_items(
+        &self,
+        rate_limit: Option<&RateLimitHandle>,
+        identity: Option<&ApiKeyIdentity>,
+        items: usize,
+    ) {
+        if let Some(rate_limit) = rate_limit {
+            rate_limit.refund_items(items);
+        }
+        self.refund_quota(identity, items).await;
+    }
+    
+    /// 批量条目数不得超过 `performance.max_batch_size`
+    pub async fn check_batch_size(&self, items: usize) -> Result<(), ServiceError> {
+        let max_batch_size = self.config.read().await.performance.max_batch_size;
+        if items > max_batch_size {
+            return Err(ServiceError::InvalidRequest(format!(
+                "batch size {} exceeds limit {}",
+                items, max_batch_size
+            )));
+        }
+        Ok(())
+    }
+    
+    fn auth_error(&self, error: AuthError) -> ServiceError {
+        if error == AuthError::QuotaExceeded {
+            self.metrics.rate_limit_exceeded_total.inc();
+        }
+        ServiceError::from(error)
+    }
+    
+    /// 与本服务共享限流状态的 tower 限流层 (REST 与 gRPC 共用)
+    pub fn rate_limit_layer(&self) -> RateLimitLayer {
+        RateLimitLayer::new(
+            Arc::clone(&self.rate_limiter),
+            Arc::clone(&self.security_manager),
+            Arc::clone(&self.metrics),
+        )
+    }
+    
+    /// 取出当前 KZG 设置及其代号
+    ///
+    /// 先读代号再取设置：替换方先写入设置再增加代号，读到新代号时必然取到新设置；
+    /// 读到旧代号而取到新设置时，结果按旧代号写入缓存会被丢弃，不会出现错配。
+    fn load_settings(&self) -> (u64, Arc<FsKZGSettings>) {
+        let generation = self.settings_generation.load(Ordering::Acquire);
+        (generation, self.kzg_settings.load_full())
+    }
+    
+    /// 查询 `generation` 代设置下的结果缓存 (未启用缓存时总是未命中)，同时更新缓存指标
+    async fn cache_get(&self, key: &CacheKey, generation: u64) -> Option<Vec<u8>> {
+        if !self.config.read().await.performance.enable_caching {
+            return None;
+        }
+        
+        let cached = self.cache_manager.get(key, generation).await;
+        if cached.is_some() {
+            self.metrics.cache_hits_total.inc();
+        } else {
+            self.metrics.cache_misses_total.inc();
+        }
+        self.update_cache_metrics().await;
+        cached
+    }
+    
+    /// 写入 `generation` 代设置下算出的结果，同时更新缓存指标
+    ///
+    /// 计算期间设置已被替换时丢弃结果。
+    async fn cache_set(&self, key: CacheKey, data: Vec<u8>, generation: u64) {
+        if !self.config.read().await.performance.enable_caching {
+            return;
+        }
+        if generation != self.settings_generation.load(Ordering::Acquire) {
+            return;
+        }
+        
+        let evicted = self.cache_manager.set(key, data, generation).await;
+        self.metrics.cache_evictions_total.inc_by(evicted as u64);
+        self.update_cache_metrics().await;
+    }
+    
+    async fn cached_verification(&self, key: &CacheKey, generation: u64) -> Option<bool> {
+        match self.cache_get(key, generation).await?.as_slice() {
+            [flag] => Some(*flag != 0),
+            _ => None,
+        }
+    }
+    
+    async fn update_cache_metrics(&self) {
+        let stats = self.cache_manager.stats().await;
+        self.metrics.cache_hit_rate.set(stats.hit_rate());
+        self.metrics.cache_entries.set(stats.entries as i64);
+        self.metrics.cache_bytes.set(stats.bytes as i64);
+    }
+    
+    /// 计算池 (批量处理与自定义处理器共用)
+    pub fn cpu_pool(&self) -> &CpuPool {
+        &self.cpu_pool
+    }
+
+    /// 创建承诺 (JSON 十六进制请求)
+    pub async fn create_commitment(&self, request: CommitmentRequest) -> Result<CommitmentResponse, ServiceError> {
+        self.commit_blob(request.try_into()?).await
+    }
+    
+    /// 创建承诺
+    pub async fn commit_blob(&self, input: CommitmentInput) -> Result<CommitmentResponse, ServiceError> {
+        let start = Instant::now();
+        
+        // 记录指标
+        self.metrics.http_requests_total.inc();
+        self.metrics.kzg_commitments_total.inc();
+        
+        // 检查缓存
+        let (generation, settings) = self.load_settings();
+        let cache_key = CacheKey::Commitment(blob_digest(&input.blob));
+        if let Some(cached) = self.cache_get(&cache_key, generation).await {
+            if let Ok(commitment_bytes) = <[u8; 48]>::try_from(cached.as_slice()) {
+                return Ok(CommitmentResponse {
+                    commitment: commitment_bytes,
+                    versioned_hash: kzg_to_versioned_hash(&commitment_bytes),
+                    processing_time_ms: start.elapsed().as_millis() as u64,
+                });
+            }
+        }
+        
+        // 转换 blob 并生成承诺
+        let commitment_bytes = self.cpu_pool.run(move || {
+            let blob_fr = decode_blob(&input.blob)?;
+            blob_to_kzg_commitment_rust(&blob_fr, &*settings)
+                .map(|commitment| commitment.to_bytes())
+                .map_err(ServiceError::KzgError)
+        }).await??;
+        
+        // 缓存结果
+        self.cache_set(cache_key, commitment_bytes.to_vec(), generation).await;
+        
+        // 记录性能指标
+        self.metrics.commitment_duration.observe(start.elapsed().as_secs_f64());
+        
+        Ok(CommitmentResponse {
+            commitment: commitment_bytes,
+            versioned_hash: kzg_to_versioned_hash(&commitment_bytes),
+            processing_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+    
+    /// 生成证明 (JSON 十六进制请求)
+    pub async fn generate_proof(&self, request: ProofRequest) -> Result<ProofResponse, ServiceError> {
+        self.prove_blob(request.try_into()?).await
+    }
+    
+    /// 生成证明
+    pub async fn prove_blob(&self, input: ProofInput) -> Result<ProofResponse, ServiceError> {
+        let start = Instant::now();
+        
+        // 记录指标
+        self.metrics.kzg_proofs_total.inc();
+        
+        // 检查缓存
+        let (generation, settings) = self.load_settings();
+        let cache_key = CacheKey::Proof(blob_digest(&input.blob), input.commitment);
+        if let Some(cached) = self.cache_get(&cache_key, generation).await {
+            if let Ok(proof_bytes) = <[u8; 48]>::try_from(cached.as_slice()) {
+                return Ok(ProofResponse {
+                    proof: proof_bytes,
+                    processing_time_ms: start.elapsed().as_millis() as u64,
+                });
+            }
+        }
+        
+        // 转换输入并生成证明
+        let proof_bytes = self.cpu_pool.run(move || {
+            let blob_fr = decode_blob(&input.blob)?;
+            let commitment = decode_g1(&input.commitment)?;
+            compute_blob_kzg_proof_rust(&blob_fr, &commitment, &*settings)
+                .map(|proof| proof.to_bytes())
+                .map_err(ServiceError::KzgError)
+        }).await??;
+        
+        // 缓存结果
+        self.cache_set(cache_key, proof_bytes.to_vec(), generation).await;
+        
+        // 记录性能指标
+        self.metrics.proof_duration.observe(start.elapsed().as_secs_f64());
+        
+        Ok(ProofResponse {
+            proof: proof_bytes,
+            processing_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+    
+    /// 验证证明 (JSON 十六进制请求)
+    pub async fn verify_proof(&self, request: VerificationRequest) -> Result<VerificationResponse, ServiceError> {
+        self.verify_blob(request.try_into()?).await
+    }
+    
+    /// 验证证明
+    pub async fn verify_blob(&self, input: VerificationInput) -> Result<VerificationResponse, ServiceError> {
+        let start = Instant::now();
+        
+        // 记录指标
+        self.metrics.kzg_verifications_total.inc();
+        
+        // 检查缓存
+        let (generation, settings) = self.load_settings();
+        let cache_key = verification_cache_key(&input);
+        if let Some(is_valid) = self.cached_verification(&cache_key, generation).await {
+            return Ok(VerificationResponse {
+                is_valid,
+                versioned_hash: kzg_to_versioned_hash(&input.commitment),
+                processing_time_ms: start.elapsed().as_millis() as u64,
+            });
+        }
+        
+        // 转换输入并验证证明
+        let (is_valid, commitment_bytes) = self.cpu_pool.run(move || {
+            let (blob_fr, commitment, proof) = decode_verification(&input)?;
+            let is_valid = verify_blob_kzg_proof_rust(&blob_fr, &commitment, &proof, &*settings)
+                .map_err(ServiceError::KzgError)?;
+            Ok::<_, ServiceError>((is_valid, commitment.to_bytes()))
+        }).await??;
+        
+        // 缓存结果
+        self.cache_set(cache_key, vec![is_valid as u8], generation).await;
+        
+        // 记录性能指标
+        self.metrics.verification_duration.observe(start.elapsed().as_secs_f64());
+        
+        Ok(VerificationResponse {
+            is_valid,
+            versioned_hash: kzg_to_versioned_hash(&commitment_bytes),
+            processing_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+    
+    /// 批量处理 (JSON 十六进制请求)
+    ///
+    /// 结果顺序与请求一致，单个条目失败不影响其他条目。
+    ///
+    /// `identity` 为请求方的密钥身份，其无权执行的条目以 Forbidden 错误返回。
+    pub async fn process_batch(
+        &self,
+        request: BatchRequest,
+        identity: Option<&ApiKeyIdentity>,
+    ) -> Result<BatchResponse, ServiceError> {
+        let start = Instant::now();
+        
+        let (ids, operations): (Vec<_>, Vec<_>) = request
+            .requests
+            .into_iter()
+            .map(|item| (item.id.clone(), restrict_operation(BatchOperation::try_from(item), identity)))
+            .unzip();
+        let outcomes = self.run_batch(operations).await?;
+        
+        let results = ids
+            .into_iter()
+            .zip(outcomes)
+            .map(|(id, outcome)| BatchResult::from_outcome(id, outcome.map(|output| output.to_json())))
+            .collect();
+        
+        Ok(BatchResponse {
+            results,
+            total_processing_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+    
+    /// 执行一批已解码的操作 (REST 与 gRPC 共用)
+    ///
+    /// 承诺和证明条目在计算池上并行执行；验证条目汇总后做一次聚合批量验证，
+    /// 失败时二分定位无效条目。解码失败的条目原样返回其错误。
+    pub async fn run_batch(
+        &self,
+        operations: Vec<Result<BatchOperation, ServiceError>>,
+    ) -> Result<Vec<Result<BatchOutput, ServiceError>>, ServiceError> {
+        self.check_batch_size(operations.len()).await?;
+        
+        let mut outcomes: Vec<Option<Result<BatchOutput, ServiceError>>> = (0..operations.len()).map(|_| None).collect();
+        // 提前返回错误时 JoinSet 被丢弃：尚未拿到计算池名额的任务随之取消，
+        // 已在阻塞线程上运行的计算会执行完毕，并一直占用名额直到结束
+        let mut tasks = JoinSet::new();
+        let mut verifications = Vec::new();
+        
+        for (position, operation) in operations.into_iter().enumerate() {
+            match operation {
+                Ok(BatchOperation::Commitment(input)) => {
+                    let service = self.clone();
+                    tasks.spawn(async move {
+                        (position, service.commit_blob(input).await.map(BatchOutput::Commitment))
+                    });
+                }
+                Ok(BatchOperation::Proof(input)) => {
+                    let service = self.clone();
+                    tasks.spawn(async move {
+                        (position, service.prove_blob(input).await.map(BatchOutput::Proof))
+                    });
+                }
+                Ok(BatchOperation::Verification(input)) => verifications.push((position, input)),
+                Err(e) => outcomes[position] = Some(Err(e)),
+            }
+        }
+        
+        // 承诺/证明任务已在后台运行，同时进行聚合验证
+        if !verifications.is_empty() {
+            let (positions, inputs): (Vec<_>, Vec<_>) = verifications.into_iter().unzip();
+            let results = self.verify_batch(inputs).await?;
+            for (position, result) in positions.into_iter().zip(results) {
+                outcomes[position] = Some(result.map(BatchOutput::Verification));
+            }
+        }
+        
+        while let Some(joined) = tasks.join_next().await {
+            let (position, outcome) = joined.map_err(|e| ServiceError::InternalError(e.to_string()))?;
+            outcomes[position] = Some(outcome);
+        }
+        
+        Ok(outcomes.into_iter().flatten().collect())
+    }
+    
+    /// 聚合验证多个证明，点解析失败的条目单独报错，不影响其他条目
+    async fn verify_batch(
+        &self,
+        inputs: Vec<VerificationInput>,
+    ) -> Result<Vec<Result<VerificationResponse, ServiceError>>, ServiceError> {
+        let start = Instant::now();
+        self.metrics.kzg_verifications_total.inc_by(inputs.len() as u64);
+        
+        // 命中缓存的条目不再参与聚合验证
+        let (generation, settings) = self.load_settings();
+        let mut cached = Vec::with_capacity(inputs.len());
+        let mut pending = Vec::new();
+        for input in inputs {
+            let cache_key = verification_cache_key(&input);
+            match self.cached_verification(&cache_key, generation).await {
+                Some(is_valid) => cached.push(Some((is_valid, input.commitment))),
+                None => {
+                    cached.push(None);
+                    pending.push((cache_key, input));
+                }
+            }
+        }
+        let (cache_keys, inputs): (Vec<_>, Vec<_>) = pending.into_iter().unzip();
+        
+        let verified = self.cpu_pool.run(move || {
+            let decoded: Vec<_> = inputs.iter().map(decode_verification).collect();
+            
+            let mut blobs = Vec::new();
+            let mut commitments = Vec::new();
+            let mut proofs = Vec::new();
+            for (blob_fr, commitment, proof) in decoded.iter().flatten() {
+                blobs.push(blob_fr.clone());
+                commitments.push(*commitment);
+                proofs.push(*proof);
+            }
+            
+            let verification = verify_blob_batch_bisect(&blobs, &commitments, &proofs, &settings)
+                .map_err(ServiceError::KzgError)?;
+            
+            let mut valid = verification.valid.into_iter();
+            Ok::<_, ServiceError>(
+                decoded
+                    .into_iter()
+                    .map(|item| item.map(|(_, commitment, _)| (valid.next().unwrap_or(false), commitment.to_bytes())))
+                    .collect::<Vec<_>>(),
+            )
+        }).await??;
+        
+        for (cache_key, outcome) in cache_keys.into_iter().zip(&verified) {
+            if let Ok((is_valid, _)) = outcome {
+                self.cache_set(cache_key, vec![*is_valid as u8], generation).await;
+            }
+        }
+        
+        self.metrics.verification_duration.observe(start.elapsed().as_secs_f64());
+        let processing_time_ms = start.elapsed().as_millis() as u64;
+        
+        let mut verified = verified.into_iter();
+        Ok(cached
+            .into_iter()
+            .map(|hit| match hit {
+                Some(result) => Ok(result),
+                None => verified.next().unwrap_or_else(|| Err(ServiceError::InternalError("missing batch result".to_string()))),
+            })
+            .map(|outcome| {
+                outcome.map(|(is_valid, commitment_bytes)| VerificationResponse {
+                    is_valid,
+                    versioned_hash: kzg_to_versioned_hash(&commitment_bytes),
+                    processing_time_ms,
+                })
+            })
+            .collect())
+    }
+    
+    /// EIP-7594：计算全部 cell 和证明 (REST 响应)
+    pub async fn compute_cells(&self, input: CommitmentInput) -> Result<CellsResponse, ServiceError> {
+        let start = Instant::now();
+        let result = self.compute_cell_proofs(input).await?;
+        
+        Ok(CellsResponse {
+            cells: result.cells,
+            proofs: result.proofs,
+            processing_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+    
+    /// EIP-7594：计算全部 cell 和证明
+    pub async fn compute_cell_proofs(&self, input: CommitmentInput) -> Result<CellsAndProofs, ServiceError> {
+        let start = Instant::now();
+        
+        self.metrics.http_requests_total.inc();
+        self.metrics.kzg_das_operations_total.inc();
+        
+        let settings = self.kzg_settings.load_full();
+        let result = self.cpu_pool.run(move || cells::compute_cells_and_proofs(&settings, &input.blob)).await??;
+        
+        self.metrics.kzg_cells_computed_total.inc_by(result.cells.len() as u64);
+        self.metrics.cell_computation_duration.observe(start.elapsed().as_secs_f64());
+        self.metrics.das_duration.observe(start.elapsed().as_secs_f64());
+        
+        Ok(result)
+    }
+    
+    /// EIP-7594：批量验证 cell 证明 (REST 响应)
+    pub async fn verify_cells(&self, input: CellVerificationInput) -> Result<CellVerificationResponse, ServiceError> {
+        let start = Instant::now();
+        let cell_count = input.cells.len();
+        let is_valid = self.verify_cell_batch(input).await?;
+        
+        Ok(CellVerificationResponse {
+            is_valid,
+            cell_count,
+            processing_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+    
+    /// EIP-7594：批量验证 cell 证明
+    pub async fn verify_cell_batch(&self, input: CellVerificationInput) -> Result<bool, ServiceError> {
+        let start = Instant::now();
+        
+        self.metrics.http_requests_total.inc();
+        self.metrics.kzg_das_operations_total.inc();
+        
+        let cell_count = input.cells.len();
+        self.check_cell_count(cell_count).await?;
+        
+        let settings = self.kzg_settings.load_full();
+        let is_valid = self.cpu_pool.run(move || {
+            cells::verify_cells(&settings, &input.commitments, &input.cell_indices, &input.cells, &input.proofs)
+        }).await??;
+        
+        self.metrics.kzg_cells_verified_total.inc_by(cell_count as u64);
+        self.metrics.cell_verification_duration.observe(start.elapsed().as_secs_f64());
+        self.metrics.das_duration.observe(start.elapsed().as_secs_f64());
+        
+        Ok(is_valid)
+    }
+    
+    /// EIP-7594：由至少一半的 cell 恢复全部 cell 和证明 (REST 响应)
+    pub async fn recover_cells(&self, input: CellRecoveryInput) -> Result<CellRecoveryResponse, ServiceError> {
+        let start = Instant::now();
+        let result = self.recover_cell_batch(input).await?;
+        
+        Ok(CellRecoveryResponse {
+            cells: result.cells,
+            proofs: result.proofs,
+            processing_time_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+    
+    /// EIP-7594：由至少一半的 cell 恢复全部 cell 和证明
+    pub async fn recover_cell_batch(&self, input: CellRecoveryInput) -> Result<CellsAndProofs, ServiceError> {
+        let start = Instant::now();
+        
+        self.metrics.http_requests_total.inc();
+        self.metrics.kzg_das_operations_total.inc();
+        
+        let provided = input.cells.len();
+        self.check_cell_count(provided).await?;
+        
+        let settings = self.kzg_settings.load_full();
+        let result = self.cpu_pool.run(move || {
+            cells::recover_cells_and_proofs(&settings, &input.cell_indices, &input.cells)
+        }).await??;
+        
+        self.metrics.kzg_cells_recovered_total.inc_by(result.cells.len().saturating_sub(provided) as u64);
+        self.metrics.cell_recovery_duration.observe(start.elapsed().as_secs_f64());
+        self.metrics.das_duration.observe(start.elapsed().as_secs_f64());
+        
+        Ok(result)
+    }
+    
+    async fn check_cell_count(&self, count: usize) -> Result<(), ServiceError> {
+        let limit = self.config.read().await.performance.max_cells_per_request;
+        if count > limit {
+            return Err(ServiceError::InvalidRequest(format!(
+                "{} cells exceed the per-request limit of {}",
+                count, limit
+            )));
+        }
+        Ok(())
+    }
+    
+    /// 校验交易的 `blob_versioned_hashes` 与 sidecar 承诺是否一致
+    ///
+    /// 输入格式错误返回错误；哈希与承诺不匹配时返回 `is_valid: false` 及原因
+    pub async fn validate_versioned_hashes(
+        &self,
+        request: VersionedHashValidationRequest,
+    ) -> Result<VersionedHashValidationResponse, ServiceError> {
+        self.metrics.http_requests_total.inc();
+        
+        let versioned_hashes = request
+            .blob_versioned_hashes
+            .iter()
+            .map(|hash| parse_versioned_hash(hash).map_err(|e| ServiceError::InvalidRequest(e.to_string())))
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let commitments = request
+            .commitments
+            .iter()
+            .map(|commitment| {
+                let bytes = hex::decode(commitment.strip_prefix("0x").unwrap_or(commitment))
+                    .map_err(|e| ServiceError::InvalidHexEncoding(e.to_string()))?;
+                <[u8; 48]>::try_from(bytes.as_slice()).map_err(|_| {
+                    ServiceError::InvalidRequest(format!("commitment must be 48 bytes, got {}", bytes.len()))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let error = validate_blob_versioned_hashes(&versioned_hashes, &commitments).err();
+        
+        Ok(VersionedHashValidationResponse {
+            is_valid: error.is_none(),
+            versioned_hashes: commitments
+                .iter()
+                .map(|commitment| hex::encode(kzg_to_versioned_hash(commitment)))
+                .collect(),
+            error: error.map(|e| e.to_string()),
+        })
+    }
+}
+
+/// 日志级别名称，无法识别时为 INFO
+fn log_level(name: &str) -> Level {
+    match name {
+        "trace" => Level::TRACE,
+        "debug" => Level::DEBUG,
+        "info" => Level::INFO,
+        "warn" => Level::WARN,
+        "error" => Level::ERROR,
+        _ => Level::INFO,
+    }
+}
+
+/// 将 blob 字节转换为域元素
+///
+/// blob 来自客户端，长度不符或含非规范域元素都属于请求错误。
+fn decode_blob(blob: &[u8]) -> Result<Vec<FsFr>, ServiceError> {
+    if blob.len() != BYTES_PER_BLOB {
+        return Err(ServiceError::InvalidBlobSize {
+            expected: BYTES_PER_BLOB,
+            actual: blob.len(),
+        });
+    }
+    
+    bytes_to_blob(blob).map_err(|e| ServiceError::InvalidRequest(format!("invalid blob: {}", e)))
+}
+
+/// 解析压缩 G1 点 (承诺或证明)
+fn decode_g1(point: &[u8; 48]) -> Result<FsG1, ServiceError> {
+    FsG1::from_bytes(point).map_err(|e| ServiceError::InvalidRequest(format!("invalid G1 point: {}", e)))
+}
+
+fn decode_verification(input: &VerificationInput) -> Result<(Vec<FsFr>, FsG1, FsG1), ServiceError> {
+    Ok((
+        decode_blob(&input.blob)?,
+        decode_g1(&input.commitment)?,
+        decode_g1(&input.proof)?,
+    ))
+}
+
+/// 验证结果的缓存键
+fn verification_cache_key(input: &VerificationInput) -> CacheKey {
+    CacheKey::Verification(blob_digest(&input.blob), input.commitment, input.proof)
+}
+
+/// 密钥不具备对应权限的批量条目替换为 Forbidden 错误
+pub fn restrict_operation(
+    operation: Result<BatchOperation, ServiceError>,
+    identity: Option<&ApiKeyIdentity>,
+) -> Result<BatchOperation, ServiceError> {
+    let operation = operation?;
+    if let Some(identity) = identity {
+        identity.require(&[operation.scope()])?;
+    }
+    Ok(operation)
+}
@@ -0,0 +1,276 @@
+//! OpenAPI 3 文档
+//!
+//! 文档由 `api`、`jobs` 等模块的请求/响应类型和 [`super::server`] 中处理器的
+//! `#[utoipa::path]` 标注生成，在 `GET /api/v1/openapi.json` 提供。以下内容难以用标注表达，
+//! 由修改器补充：
+//!
+//! - 认证：受保护的路由 (见 `route_scopes`) 接受 `x-api-key` 或 `Authorization: Bearer`，
+//!   所需权限记录在 `x-required-scopes` 扩展字段中
+//! - 二进制编码：承诺、证明、验证与 cell 接口的 SSZ 请求与响应 (布局见 [`super::codec`])
+//! - 错误响应：各接口共用的 4xx/5xx 响应，响应体均为 [`ErrorBody`]
+
+use std::collections::HashMap;
+
+use utoipa::openapi::header::HeaderBuilder;
+use utoipa::openapi::path::PathItemType;
+use utoipa::openapi::response::{Response, ResponseBuilder};
+use utoipa::openapi::schema::{KnownFormat, ObjectBuilder, Ref, SchemaFormat, SchemaType};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityRequirement, SecurityScheme};
+use utoipa::openapi::{Content, RefOr};
+use utoipa::{Modify, OpenApi};
+
+use super::codec::{APPLICATION_OCTET_STREAM, APPLICATION_SSZ};
+use super::config::RequestCosts;
+use super::rate_limit_layer::route_cost;
+use super::server::{self, route_scopes};
+use super::{
+    BatchItem, BatchRequest, BatchResponse, BatchResult, CellRecoveryRequest, CellRecoveryResponse,
+    CellVerificationRequest, CellVerificationResponse, CellsRequest, CellsResponse, CommitmentRequest,
+    CommitmentResponse, ErrorBody, HealthStatus, JobRequest, JobState, JobStatus, JobSubmitted, ProofRequest,
+    ProofResponse, ReloadReport, ServiceStatus, SystemHealth, VerificationRequest, VerificationResponse,
+    VersionedHashValidationRequest, VersionedHashValidationResponse,
+};
+
+/// 服务的 OpenAPI 文档
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "KZG Service API",
+        description = "EIP-4844 blob 承诺、证明与验证，EIP-7594 cell 计算、验证与恢复，以及异步批量任务。"
+    ),
+    paths(
+        server::create_commitment_handler,
+        server::generate_proof_handler,
+        server::verify_proof_handler,
+        server::validate_versioned_hashes_handler,
+        server::batch_process_handler,
+        server::compute_cells_handler,
+        server::verify_cells_handler,
+        server::recover_cells_handler,
+        server::submit_job_handler,
+        server::job_status_handler,
+        server::health_handler,
+        server::liveness_handler,
+        server::readiness_handler,
+        server::metrics_handler,
+        server::openapi_handler,
+        server::get_config_handler,
+        server::get_stats_handler,
+        server::reload_handler,
+    ),
+    components(schemas(
+        CommitmentRequest,
+        CommitmentResponse,
+        ProofRequest,
+        ProofResponse,
+        VerificationRequest,
+        VerificationResponse,
+        VersionedHashValidationRequest,
+        VersionedHashValidationResponse,
+        BatchRequest,
+        BatchItem,
+        BatchResponse,
+        BatchResult,
+        CellsRequest,
+        CellsResponse,
+        CellVerificationRequest,
+        CellVerificationResponse,
+        CellRecoveryRequest,
+        CellRecoveryResponse,
+        JobRequest,
+        JobSubmitted,
+        JobState,
+        JobStatus,
+        HealthStatus,
+        ServiceStatus,
+        SystemHealth,
+        ReloadReport,
+        ErrorBody,
+    )),
+    modifiers(&Authentication, &BinaryEncoding, &ErrorResponses),
+    tags(
+        (name = "kzg", description = "EIP-4844 blob 承诺、证明与验证"),
+        (name = "das", description = "EIP-7594 cell 计算、验证与恢复"),
+        (name = "jobs", description = "异步批量任务"),
+        (name = "health", description = "健康检查"),
+        (name = "monitoring", description = "指标与 API 文档"),
+        (name = "admin", description = "管理接口，需要 admin 权限"),
+    )
+)]
+pub struct ApiDoc;
+
+/// 生成 OpenAPI 文档
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+const API_KEY_SCHEME: &str = "api_key";
+const BEARER_SCHEME: &str = "bearer";
+
+/// 认证方式与各路由所需的权限
+struct Authentication;
+
+impl Modify for Authentication {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                API_KEY_SCHEME,
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-api-key"))),
+            );
+            components.add_security_scheme(BEARER_SCHEME, SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)));
+        }
+
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            let Some(scopes) = route_scopes(path) else {
+                continue;
+            };
+            for operation in item.operations.values_mut() {
+                operation.security = Some(vec![
+                    SecurityRequirement::new(API_KEY_SCHEME, Vec::<String>::new()),
+                    SecurityRequirement::new(BEARER_SCHEME, Vec::<String>::new()),
+                ]);
+                let scopes = scopes.iter().map(|scope| scope.to_string().into()).collect();
+                operation
+                    .extensions
+                    .get_or_insert_with(HashMap::new)
+                    .insert("x-required-scopes".to_string(), serde_json::Value::Array(scopes));
+            }
+        }
+    }
+}
+
+/// 承诺、证明、验证与 cell 接口的 SSZ 请求与响应
+struct BinaryEncoding;
+
+impl Modify for BinaryEncoding {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let layouts = [
+            (
+                "/api/v1/commitment",
+                "blob (131072 字节)",
+                "commitment (48) || versioned_hash (32) || processing_time_ms (u64 小端序)",
+            ),
+            (
+                "/api/v1/proof",
+                "blob (131072 字节) || commitment (48)",
+                "proof (48) || processing_time_ms (u64 小端序)",
+            ),
+            (
+                "/api/v1/verify",
+                "blob (131072 字节) || commitment (48) || proof (48)",
+                "is_valid (1) || versioned_hash (32) || processing_time_ms (u64 小端序)",
+            ),
+            (
+                "/api/v1/cells",
+                "blob (131072 字节)",
+                "cells (128 × 2048) || proofs (128 × 48) || processing_time_ms (u64 小端序)",
+            ),
+            (
+                "/api/v1/cells/verify",
+                "4 个 u32 偏移量 || commitments (List[48]) || cell_indices (List[u64]) || cells (List[2048]) || proofs (List[48])",
+                "is_valid (1) || cell_count (u64) || processing_time_ms (u64 小端序)",
+            ),
+            (
+                "/api/v1/cells/recover",
+                "2 个 u32 偏移量 || cell_indices (List[u64]) || cells (List[2048])",
+                "cells (128 × 2048) || proofs (128 × 48) || processing_time_ms (u64 小端序)",
+            ),
+        ];
+
+        for (path, request_layout, response_layout) in layouts {
+            let Some(operation) = openapi
+                .paths
+                .paths
+                .get_mut(path)
+                .and_then(|item| item.operations.get_mut(&PathItemType::Post))
+            else {
+                continue;
+            };
+
+            if let Some(body) = operation.request_body.as_mut() {
+                body.content.insert(APPLICATION_OCTET_STREAM.to_string(), binary_content(request_layout));
+                body.content.insert(APPLICATION_SSZ.to_string(), binary_content(request_layout));
+                body.description = Some("JSON (十六进制字段) 或 SSZ 编码的请求体".to_string());
+            }
+            if let Some(RefOr::T(response)) = operation.responses.responses.get_mut("200") {
+                response.content.insert(APPLICATION_OCTET_STREAM.to_string(), binary_content(response_layout));
+                response.description.push_str("；响应编码由 Accept 决定，缺省与请求编码一致");
+            }
+            operation
+                .responses
+                .responses
+                .entry("415".to_string())
+                .or_insert_with(|| error_response("不支持的 Content-Type").into());
+        }
+    }
+}
+
+fn binary_content(layout: &str) -> Content {
+    Content::new(
+        ObjectBuilder::new()
+            .schema_type(SchemaType::String)
+            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Binary)))
+            .description(Some(format!("SSZ：{}", layout))),
+    )
+}
+
+/// 各接口共用的错误响应，已显式声明的状态码保持不变
+struct ErrorResponses;
+
+impl Modify for ErrorResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let costs = RequestCosts::default();
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            let protected = route_scopes(path).is_some();
+            let rate_limited = route_cost(path, &costs).is_some();
+            for (method, operation) in item.operations.iter_mut() {
+                let mut errors = Vec::new();
+                if *method == PathItemType::Post {
+                    errors.push(("400", "请求格式或参数无效"));
+                }
+                if protected {
+                    errors.push(("401", "缺少或无效的 API 密钥"));
+                    errors.push(("403", "密钥缺少所需权限或 IP 被拒绝"));
+                }
+                // 健康检查、监控与文档以外的路由都经由 ServiceError 返回错误
+                if rate_limited {
+                    errors.push(("500", "内部错误"));
+                }
+
+                let responses = &mut operation.responses.responses;
+                for (status, description) in errors {
+                    responses
+                        .entry(status.to_string())
+                        .or_insert_with(|| error_response(description).into());
+                }
+                if rate_limited {
+                    responses
+                        .entry("429".to_string())
+                        .or_insert_with(|| rate_limited_response().into());
+                }
+            }
+        }
+    }
+}
+
+fn error_response(description: &str) -> Response {
+    ResponseBuilder::new()
+        .description(description)
+        .content("application/json", Content::new(Ref::from_schema_name("ErrorBody")))
+        .build()
+}
+
+fn rate_limited_response() -> Response {
+    let seconds = ObjectBuilder::new().schema_type(SchemaType::Integer);
+    ResponseBuilder::new()
+        .description("超出速率限制或当日配额")
+        .content("application/json", Content::new(Ref::from_schema_name("ErrorBody")))
+        .header(
+            "Retry-After",
+            HeaderBuilder::new()
+                .schema(seconds)
+                .description(Some("建议的重试等待秒数"))
+                .build(),
+        )
+        .build()
+}
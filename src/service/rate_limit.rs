@@ -0,0 +1,330 @@
+//! 速率限制
+//!
+//! 全局令牌桶之外，每个客户端 (API 密钥或 IP) 各有一个令牌桶。请求按操作加权消耗令牌，
+//! 长时间空闲且已补满的客户端状态会被定期回收。HTTP/gRPC 接入由
+//! [`super::rate_limit_layer::RateLimitLayer`] 完成。
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use super::config::{RateLimitConfig, RequestCosts};
+
+/// 单个令牌桶，按时间连续补充
+///
+/// 供按密钥等细粒度限流使用，调用方负责同步。
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    refill_per_second: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(requests_per_second: u64, burst_size: u64) -> Self {
+        let capacity = burst_size.max(1) as f64;
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_second: requests_per_second as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// 修改速率与容量，保留已有令牌 (不超过新容量)
+    pub fn reconfigure(&mut self, requests_per_second: u64, burst_size: u64) {
+        self.refill();
+        self.capacity = burst_size.max(1) as f64;
+        self.refill_per_second = requests_per_second as f64;
+        self.tokens = self.tokens.min(self.capacity);
+    }
+
+    /// 尝试消费 `cost` 个令牌，不足时返回需要等待的时间
+    ///
+    /// 超过桶容量的消耗在令牌补满后放行并使余额为负，避免大批量请求永远无法通过。
+    pub fn try_acquire(&mut self, cost: f64) -> Result<(), Duration> {
+        self.refill();
+
+        let required = cost.min(self.capacity);
+        if self.tokens >= required {
+            self.tokens -= cost;
+            Ok(())
+        } else {
+            Err(self.time_until(required))
+        }
+    }
+
+    /// 退还 `cost` 个令牌 (不超过容量)
+    pub fn refund(&mut self, cost: f64) {
+        self.refill();
+        self.tokens = (self.tokens + cost).min(self.capacity);
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity as u64
+    }
+
+    /// 当前剩余令牌 (向下取整)
+    pub fn remaining(&self) -> u64 {
+        self.tokens.max(0.0) as u64
+    }
+
+    /// 补满所需时间
+    pub fn time_to_full(&self) -> Duration {
+        self.time_until(self.capacity)
+    }
+
+    fn is_full(&mut self) -> bool {
+        self.refill();
+        self.tokens >= self.capacity
+    }
+
+    fn time_until(&self, tokens: f64) -> Duration {
+        let missing = (tokens - self.tokens).max(0.0);
+        if missing == 0.0 {
+            Duration::ZERO
+        } else if self.refill_per_second <= 0.0 {
+            Duration::MAX
+        } else {
+            Duration::from_secs_f64(missing / self.refill_per_second)
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+/// 限流对象
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientId {
+    /// 已识别的 API 密钥 (按密钥标识)
+    ApiKey(String),
+    Ip(IpAddr),
+}
+
+/// 单个密钥的速率设置，覆盖默认的客户端速率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientRate {
+    pub requests_per_second: u64,
+    pub burst_size: u64,
+}
+
+/// 一次放行后的限流状态，用于 `X-RateLimit-*` 响应头
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub limit: u64,
+    pub remaining: u64,
+    pub reset_after: Duration,
+}
+
+struct ClientState {
+    bucket: TokenBucket,
+    /// 令牌桶当前采用的速率
+    rate: ClientRate,
+    last_seen: Instant,
+}
+
+/// 可热重载的限流策略
+struct Policy {
+    default_rate: ClientRate,
+    idle_timeout: Duration,
+    costs: RequestCosts,
+    per_ip: bool,
+}
+
+impl Policy {
+    fn from_config(config: &RateLimitConfig) -> Self {
+        Self {
+            default_rate: ClientRate {
+                requests_per_second: config.per_client_requests_per_second,
+                burst_size: config.per_client_burst_size,
+            },
+            idle_timeout: Duration::from_secs(config.client_idle_seconds),
+            costs: config.costs.clone(),
+            per_ip: config.enable_per_ip,
+        }
+    }
+}
+
+/// 基于令牌桶算法的速率限制器
+pub struct RateLimiter {
+    global: Mutex<TokenBucket>,
+    clients: Mutex<HashMap<ClientId, ClientState>>,
+    policy: RwLock<Policy>,
+    last_gc: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            global: Mutex::new(TokenBucket::new(config.requests_per_second, config.burst_size)),
+            clients: Mutex::new(HashMap::new()),
+            policy: RwLock::new(Policy::from_config(config)),
+            last_gc: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// 应用新的限流配置
+    ///
+    /// 全局令牌桶和客户端令牌桶都保留现有令牌 (不超过新容量)，重载不会让受限客户端重获突发额度。
+    /// 采用默认速率的客户端立即改用新的默认速率，专属速率的客户端在下次请求时按其密钥的速率调整。
+    pub fn reconfigure(&self, config: &RateLimitConfig) {
+        let mut clients = self.clients.lock().unwrap();
+        self.global
+            .lock()
+            .unwrap()
+            .reconfigure(config.requests_per_second, config.burst_size);
+
+        let policy = Policy::from_config(config);
+        let new_default = policy.default_rate;
+        let old_default = std::mem::replace(&mut *self.policy.write().unwrap(), policy).default_rate;
+        for state in clients.values_mut().filter(|state| state.rate == old_default) {
+            state.rate = new_default;
+            state
+                .bucket
+                .reconfigure(new_default.requests_per_second, new_default.burst_size);
+        }
+    }
+
+    /// 各类操作的令牌消耗
+    pub fn costs(&self) -> RequestCosts {
+        self.policy.read().unwrap().costs.clone()
+    }
+
+    /// 是否按 IP 分桶
+    pub fn per_ip(&self) -> bool {
+        self.policy.read().unwrap().per_ip
+    }
+
+    /// 为请求消费 `cost` 个令牌
+    ///
+    /// 先检查客户端令牌桶，再检查全局令牌桶；`rate` 为该客户端的专属速率。
+    /// 被拒绝的请求不消耗任何令牌。
+    pub fn check(
+        &self,
+        client: Option<&ClientId>,
+        rate: Option<ClientRate>,
+        cost: u64,
+    ) -> Result<RateLimitStatus, RateLimitError> {
+        self.maybe_gc();
+        let cost = cost as f64;
+
+        let mut clients = self.clients.lock().unwrap();
+        let mut global = self.global.lock().unwrap();
+
+        let default_rate = self.policy.read().unwrap().default_rate;
+        let client_bucket = client.map(|client| {
+            let rate = rate.unwrap_or(default_rate);
+            let state = clients.entry(client.clone()).or_insert_with(|| ClientState {
+                bucket: TokenBucket::new(rate.requests_per_second, rate.burst_size),
+                rate,
+                last_seen: Instant::now(),
+            });
+            // 速率在重载后变化时沿用现有令牌
+            if state.rate != rate {
+                state.rate = rate;
+                state.bucket.reconfigure(rate.requests_per_second, rate.burst_size);
+            }
+            state.last_seen = Instant::now();
+            &mut state.bucket
+        });
+
+        match client_bucket {
+            Some(bucket) => {
+                // 先确认全局令牌充足，再扣减客户端令牌
+                let mut probe = global.clone();
+                probe
+                    .try_acquire(cost)
+                    .map_err(|retry_after| RateLimitError::GlobalLimitExceeded { retry_after })?;
+                bucket.try_acquire(cost).map_err(|retry_after| RateLimitError::ClientLimitExceeded {
+                    retry_after,
+                    limit: bucket.capacity(),
+                })?;
+                *global = probe;
+                Ok(status(bucket))
+            }
+            None => {
+                global
+                    .try_acquire(cost)
+                    .map_err(|retry_after| RateLimitError::GlobalLimitExceeded { retry_after })?;
+                Ok(status(&global))
+            }
+        }
+    }
+
+    /// 退还未被执行的请求消耗的 `cost` 个令牌，返回退还后的状态
+    ///
+    /// 客户端状态已被回收时只退还全局令牌桶。
+    pub fn refund(&self, client: Option<&ClientId>, cost: u64) -> RateLimitStatus {
+        let cost = cost as f64;
+        let mut clients = self.clients.lock().unwrap();
+        let mut global = self.global.lock().unwrap();
+        global.refund(cost);
+
+        match client.and_then(|client| clients.get_mut(client)) {
+            Some(state) => {
+                state.bucket.refund(cost);
+                status(&state.bucket)
+            }
+            None => status(&global),
+        }
+    }
+
+    /// 当前跟踪的客户端数量
+    pub fn tracked_clients(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    /// 回收空闲超过 `idle_timeout` 且令牌已补满的客户端状态
+    pub fn gc(&self) -> usize {
+        let mut clients = self.clients.lock().unwrap();
+        let before = clients.len();
+        let idle_timeout = self.policy.read().unwrap().idle_timeout;
+        clients.retain(|_, state| state.last_seen.elapsed() < idle_timeout || !state.bucket.is_full());
+        before - clients.len()
+    }
+
+    fn maybe_gc(&self) {
+        let idle_timeout = self.policy.read().unwrap().idle_timeout;
+        let mut last_gc = self.last_gc.lock().unwrap();
+        if last_gc.elapsed() >= idle_timeout {
+            *last_gc = Instant::now();
+            drop(last_gc);
+            self.gc();
+        }
+    }
+}
+
+fn status(bucket: &TokenBucket) -> RateLimitStatus {
+    RateLimitStatus {
+        limit: bucket.capacity(),
+        remaining: bucket.remaining(),
+        reset_after: bucket.time_to_full(),
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RateLimitError {
+    #[error("Global rate limit exceeded")]
+    GlobalLimitExceeded { retry_after: Duration },
+
+    #[error("Client rate limit exceeded")]
+    ClientLimitExceeded { retry_after: Duration, limit: u64 },
+}
+
+impl RateLimitError {
+    /// 建议的重试等待时间
+    pub fn retry_after(&self) -> Duration {
+        match self {
+            Self::GlobalLimitExceeded { retry_after } | Self::ClientLimitExceeded { retry_after, .. } => *retry_after,
+        }
+    }
+}
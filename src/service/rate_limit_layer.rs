@@ -0,0 +1,286 @@
+//! 速率限制中间件
+//!
+//! tower [`Layer`]，同一个实例可以挂在 axum 路由和 tonic gRPC 服务上。请求按 API 密钥
+//! (能识别时) 或客户端 IP 分桶，按路由计费，响应附带 `X-RateLimit-Limit`、
+//! `X-RateLimit-Remaining`、`X-RateLimit-Reset`，被拒绝时另附 `Retry-After`。
+//!
+//! 批量请求在准入时只按一个条目计费，其余条目由处理器通过请求扩展中的
+//! [`RateLimitHandle`] 补扣；未被执行的请求 (如任务队列已满) 通过同一句柄退还。
+//! `X-RateLimit-Remaining` 取处理器最后一次扣减或退还后的余额。
+
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use axum::{
+    body::{boxed, BoxBody, Bytes, Empty, HttpBody},
+    extract::ConnectInfo,
+    http::{header, HeaderMap, HeaderValue, Request, Response, StatusCode},
+    response::IntoResponse,
+    BoxError,
+};
+use tower::{Layer, Service};
+
+use super::config::RequestCosts;
+use super::error::retry_after_seconds;
+use super::rate_limit::{ClientId, ClientRate, RateLimitError, RateLimitStatus, RateLimiter};
+use super::{KzgMetrics, SecurityManager, ServiceError};
+
+/// gRPC 服务路径前缀
+const GRPC_SERVICE_PREFIX: &str = "/kzg.v1.KzgService/";
+
+/// gRPC 状态码 RESOURCE_EXHAUSTED
+const GRPC_RESOURCE_EXHAUSTED: &str = "8";
+
+/// 路由的令牌消耗，`None` 表示不限流 (健康检查、监控与 API 文档)
+pub fn route_cost(path: &str, costs: &RequestCosts) -> Option<u64> {
+    if let Some(method) = path.strip_prefix(GRPC_SERVICE_PREFIX) {
+        return Some(match method {
+            "CreateCommitment" => costs.commitment,
+            "GenerateProof" => costs.proof,
+            "VerifyProof" => costs.verification,
+            "Batch" | "BatchStream" => costs.batch_item,
+            "ComputeCells" => costs.cells,
+            "VerifyCells" => costs.cell_verification,
+            "RecoverCells" => costs.cell_recovery,
+            _ => costs.other,
+        });
+    }
+
+    match path {
+        "/health" | "/health/live" | "/health/ready" | "/metrics" | "/api/v1/openapi.json" => None,
+        "/api/v1/commitment" => Some(costs.commitment),
+        "/api/v1/proof" => Some(costs.proof),
+        "/api/v1/verify" => Some(costs.verification),
+        "/api/v1/batch" | "/api/v1/jobs" => Some(costs.batch_item),
+        "/api/v1/cells" => Some(costs.cells),
+        "/api/v1/cells/verify" => Some(costs.cell_verification),
+        "/api/v1/cells/recover" => Some(costs.cell_recovery),
+        _ => Some(costs.other),
+    }
+}
+
+/// 请求方的限流句柄，供处理器为批量条目补扣或退还令牌
+#[derive(Clone)]
+pub struct RateLimitHandle {
+    state: Arc<LayerState>,
+    client: Option<ClientId>,
+    rate: Option<ClientRate>,
+    /// 最近一次扣减或退还后的状态，写入响应头
+    status: Arc<Mutex<RateLimitStatus>>,
+}
+
+impl RateLimitHandle {
+    /// 按批量条目扣减令牌
+    pub fn charge_items(&self, items: usize) -> Result<(), ServiceError> {
+        let cost = self.items_cost(items);
+        if cost == 0 {
+            return Ok(());
+        }
+        let status = self
+            .state
+            .limiter
+            .check(self.client.as_ref(), self.rate, cost)
+            .map_err(|e| self.state.rejected(&e))?;
+        *self.status.lock().unwrap() = status;
+        Ok(())
+    }
+
+    /// 退还 `items` 个批量条目的令牌 (含准入时计费的条目)
+    pub fn refund_items(&self, items: usize) {
+        let cost = self.items_cost(items);
+        if cost == 0 {
+            return;
+        }
+        *self.status.lock().unwrap() = self.state.limiter.refund(self.client.as_ref(), cost);
+    }
+
+    fn items_cost(&self, items: usize) -> u64 {
+        self.state.limiter.costs().batch_item.saturating_mul(items as u64)
+    }
+}
+
+struct LayerState {
+    limiter: Arc<RateLimiter>,
+    security: Arc<SecurityManager>,
+    metrics: Arc<KzgMetrics>,
+}
+
+impl LayerState {
+    /// 可识别的 API 密钥按密钥分桶，否则 (启用时) 按 IP 分桶
+    async fn client(&self, api_key: Option<String>, ip: Option<IpAddr>) -> (Option<ClientId>, Option<ClientRate>) {
+        if let Some(key) = api_key {
+            if let Some(identity) = self.security.identify(&key).await {
+                return (Some(ClientId::ApiKey(identity.id)), identity.rate);
+            }
+        }
+        match ip {
+            Some(ip) if self.limiter.per_ip() => (Some(ClientId::Ip(ip)), None),
+            _ => (None, None),
+        }
+    }
+
+    fn rejected(&self, error: &RateLimitError) -> ServiceError {
+        self.metrics.rate_limit_exceeded_total.inc();
+        ServiceError::RateLimitExceeded {
+            retry_after: error.retry_after(),
+        }
+    }
+}
+
+/// 速率限制层
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    state: Arc<LayerState>,
+}
+
+impl RateLimitLayer {
+    pub fn new(limiter: Arc<RateLimiter>, security: Arc<SecurityManager>, metrics: Arc<KzgMetrics>) -> Self {
+        Self {
+            state: Arc::new(LayerState {
+                limiter,
+                security,
+                metrics,
+            }),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitService {
+            inner,
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+/// [`RateLimitLayer`] 生成的服务
+#[derive(Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    state: Arc<LayerState>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for RateLimitService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: HttpBody<Data = Bytes> + Send + 'static,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        // 取走已就绪的服务，留下克隆体供下次调用
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let state = Arc::clone(&self.state);
+
+        Box::pin(async move {
+            let Some(cost) = route_cost(request.uri().path(), &state.limiter.costs()) else {
+                return inner.call(request).await.map(|response| response.map(boxed));
+            };
+
+            // 请求体不一定是 Sync，先取出所需信息再等待
+            let key = api_key(request.headers()).map(str::to_string);
+            let ip = client_ip(&request);
+            let (client, rate) = state.client(key, ip).await;
+            let grpc = is_grpc(request.headers());
+            match state.limiter.check(client.as_ref(), rate, cost) {
+                Ok(status) => {
+                    let status = Arc::new(Mutex::new(status));
+                    request.extensions_mut().insert(RateLimitHandle {
+                        state: Arc::clone(&state),
+                        client,
+                        rate,
+                        status: Arc::clone(&status),
+                    });
+                    let mut response = inner.call(request).await?.map(boxed);
+                    let status = *status.lock().unwrap();
+                    set_status_headers(response.headers_mut(), &status);
+                    Ok(response)
+                }
+                Err(error) => {
+                    let rejected = state.rejected(&error);
+                    Ok(reject(&error, rejected, grpc))
+                }
+            }
+        })
+    }
+}
+
+fn reject(error: &RateLimitError, rejected: ServiceError, grpc: bool) -> Response<BoxBody> {
+    let retry_after = retry_after_seconds(error.retry_after());
+
+    let mut response = if grpc {
+        // gRPC 客户端只认 grpc-status，以仅含头部的响应返回 RESOURCE_EXHAUSTED
+        let mut response = Response::new(boxed(Empty::new()));
+        let headers = response.headers_mut();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/grpc"));
+        headers.insert("grpc-status", HeaderValue::from_static(GRPC_RESOURCE_EXHAUSTED));
+        headers.insert("grpc-message", HeaderValue::from_static("Rate%20limit%20exceeded"));
+        headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        response
+    } else {
+        let mut response = rejected.into_response();
+        *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+        response
+    };
+
+    let headers = response.headers_mut();
+    if let RateLimitError::ClientLimitExceeded { limit, .. } = error {
+        headers.insert("x-ratelimit-limit", HeaderValue::from(*limit));
+    }
+    headers.insert("x-ratelimit-remaining", HeaderValue::from(0u64));
+    headers.insert("x-ratelimit-reset", HeaderValue::from(retry_after));
+    response
+}
+
+fn set_status_headers(headers: &mut HeaderMap, status: &RateLimitStatus) {
+    headers.insert("x-ratelimit-limit", HeaderValue::from(status.limit));
+    headers.insert("x-ratelimit-remaining", HeaderValue::from(status.remaining));
+    headers.insert("x-ratelimit-reset", HeaderValue::from(status.reset_after.as_secs_f64().ceil() as u64));
+}
+
+fn is_grpc(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/grpc"))
+}
+
+/// 从 `x-api-key` 或 `Authorization: Bearer` 头中取 API 密钥 (gRPC metadata 同为头部)
+pub(crate) fn api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get("x-api-key").and_then(|value| value.to_str().ok()) {
+        return Some(key);
+    }
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+/// axum 由 `ConnectInfo` 提供对端地址，tonic 由 `TcpConnectInfo` 提供
+fn client_ip<B>(request: &Request<B>) -> Option<IpAddr> {
+    if let Some(ConnectInfo(addr)) = request.extensions().get::<ConnectInfo<SocketAddr>>() {
+        return Some(addr.ip());
+    }
+    #[cfg(feature = "grpc")]
+    if let Some(info) = request.extensions().get::<tonic::transport::server::TcpConnectInfo>() {
+        return info.remote_addr().map(|addr| addr.ip());
+    }
+    None
+}
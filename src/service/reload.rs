@@ -0,0 +1,295 @@
+//! 配置与可信设置热重载
+//!
+//! 由 `SIGHUP` 或 `POST /admin/reload` 触发。重新读取配置文件后只应用可在运行时安全调整的部分：
+//! 速率限制、CORS、日志级别、缓存设置、认证开关与 API 密钥。监听地址、线程池等字段保持原值，
+//! 发生变化时在 [`ReloadReport::restart_required`] 中列出。
+//!
+//! 可信设置先在计算池中加载，并以一次承诺、证明、验证往返校验，通过后才原子替换；
+//! 进行中的请求继续使用替换前取出的设置。任何一步失败都不会改动正在使用的配置。
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use arc_swap::ArcSwap;
+use axum::http::{HeaderName, HeaderValue, Method, Request, Response};
+use kzg::eip_4844::{blob_to_kzg_commitment_rust, compute_blob_kzg_proof_rust, verify_blob_kzg_proof_rust};
+use rust_kzg_blst::{eip_4844::load_trusted_setup_filename_rust, types::kzg_settings::FsKZGSettings};
+use serde::Serialize;
+use serde_json::Value;
+use tower::{Layer, Service};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer, ExposeHeaders};
+use tracing::{info, warn};
+use tracing_subscriber::{filter::LevelFilter, reload, Registry};
+use utoipa::ToSchema;
+
+use super::config::{load_config, CorsConfig, ProductionConfig};
+use super::{decode_blob, log_level, ProductionKzgService};
+use crate::blob::create_test_blob_bytes;
+
+/// 日志级别重载句柄
+pub type LogLevelHandle = reload::Handle<LevelFilter, Registry>;
+
+/// 一次重载的结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReloadReport {
+    /// 发生变化但需要重启才能生效的字段 (如 `server.port`)
+    pub restart_required: Vec<String>,
+    /// 是否替换了可信设置
+    pub trusted_setup_reloaded: bool,
+    /// 重载后的 API 密钥数量
+    pub api_keys: usize,
+    /// 因缓存容量调整而淘汰的条目数
+    pub cache_evictions: usize,
+}
+
+// ================================================================================================
+// 配置合并
+// ================================================================================================
+
+/// 把新配置中可热重载的部分合入当前配置
+///
+/// 返回合并结果以及发生变化但未应用的字段路径。`performance.max_batch_size` 和
+/// `max_cells_per_request` 决定路由的请求体上限，同样需要重启。
+pub fn merge_reloadable(current: &ProductionConfig, new: &ProductionConfig) -> (ProductionConfig, Vec<String>) {
+    let mut merged = current.clone();
+
+    merged.kzg.trusted_setup_path = new.kzg.trusted_setup_path.clone();
+
+    merged.security.enable_auth = new.security.enable_auth;
+    merged.security.api_keys = new.security.api_keys.clone();
+    merged.security.keys = new.security.keys.clone();
+    merged.security.key_file = new.security.key_file.clone();
+    merged.security.rate_limit = new.security.rate_limit.clone();
+    merged.security.cors = new.security.cors.clone();
+
+    merged.performance.enable_caching = new.performance.enable_caching;
+    merged.performance.cache_size = new.performance.cache_size;
+    merged.performance.cache_ttl_seconds = new.performance.cache_ttl_seconds;
+    merged.performance.cache_max_bytes = new.performance.cache_max_bytes;
+    merged.performance.batch_processing = new.performance.batch_processing;
+
+    merged.logging.level = new.logging.level.clone();
+
+    let mut restart_required = Vec::new();
+    changed_fields(&to_value(new), &to_value(&merged), "", &mut restart_required);
+    (merged, restart_required)
+}
+
+fn to_value(config: &ProductionConfig) -> Value {
+    serde_json::to_value(config).unwrap_or(Value::Null)
+}
+
+/// 收集两份配置中取值不同的叶子字段路径
+fn changed_fields(new: &Value, kept: &Value, path: &str, out: &mut Vec<String>) {
+    match (new, kept) {
+        (Value::Object(new), Value::Object(kept)) => {
+            for (key, value) in new {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                changed_fields(value, kept.get(key).unwrap_or(&Value::Null), &child, out);
+            }
+        }
+        _ if new != kept => out.push(path.to_string()),
+        _ => {}
+    }
+}
+
+// ================================================================================================
+// 可信设置
+// ================================================================================================
+
+/// 加载可信设置，并以测试 blob 完成一次承诺、证明与验证往返
+pub fn load_verified_trusted_setup(path: &str) -> Result<FsKZGSettings> {
+    let settings = load_trusted_setup_filename_rust(path)
+        .map_err(|e| anyhow!("Failed to load trusted setup {}: {}", path, e))?;
+
+    let blob = decode_blob(&create_test_blob_bytes(0)).map_err(|e| anyhow!("{}", e))?;
+    let commitment = blob_to_kzg_commitment_rust(&blob, &settings).map_err(|e| anyhow!("commitment failed: {}", e))?;
+    let proof =
+        compute_blob_kzg_proof_rust(&blob, &commitment, &settings).map_err(|e| anyhow!("proof failed: {}", e))?;
+    let valid = verify_blob_kzg_proof_rust(&blob, &commitment, &proof, &settings)
+        .map_err(|e| anyhow!("verification failed: {}", e))?;
+    if !valid {
+        bail!("trusted setup {} failed the commit/prove/verify self-check", path);
+    }
+    Ok(settings)
+}
+
+// ================================================================================================
+// CORS
+// ================================================================================================
+
+/// 由配置构建 CORS 层，列表中的 `*` 表示不限
+pub fn cors_layer(config: &CorsConfig) -> Result<CorsLayer> {
+    let wildcard = |values: &[String]| values.iter().any(|value| value == "*");
+
+    let origins = if wildcard(&config.allow_origins) {
+        AllowOrigin::from(Any)
+    } else {
+        AllowOrigin::list(parse_all(&config.allow_origins, |v| HeaderValue::from_str(v).ok())?)
+    };
+    let methods = if wildcard(&config.allow_methods) {
+        AllowMethods::from(Any)
+    } else {
+        AllowMethods::list(parse_all(&config.allow_methods, |v| Method::from_bytes(v.as_bytes()).ok())?)
+    };
+    let headers = if wildcard(&config.allow_headers) {
+        AllowHeaders::from(Any)
+    } else {
+        AllowHeaders::list(parse_all(&config.allow_headers, |v| HeaderName::from_bytes(v.as_bytes()).ok())?)
+    };
+    let exposed = if wildcard(&config.expose_headers) {
+        ExposeHeaders::from(Any)
+    } else {
+        ExposeHeaders::list(parse_all(&config.expose_headers, |v| HeaderName::from_bytes(v.as_bytes()).ok())?)
+    };
+
+    Ok(CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(headers)
+        .expose_headers(exposed)
+        .max_age(std::time::Duration::from_secs(config.max_age_seconds)))
+}
+
+fn parse_all<T>(values: &[String], parse: impl Fn(&str) -> Option<T>) -> Result<Vec<T>> {
+    values
+        .iter()
+        .map(|value| parse(value).ok_or_else(|| anyhow!("Invalid CORS value: {}", value)))
+        .collect()
+}
+
+/// 每个请求读取当前 CORS 设置的中间件层
+#[derive(Clone)]
+pub struct ReloadableCorsLayer {
+    cors: Arc<ArcSwap<CorsLayer>>,
+}
+
+impl<S> Layer<S> for ReloadableCorsLayer {
+    type Service = ReloadableCors<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ReloadableCors {
+            inner,
+            cors: Arc::clone(&self.cors),
+        }
+    }
+}
+
+/// [`ReloadableCorsLayer`] 生成的服务
+#[derive(Clone)]
+pub struct ReloadableCors<S> {
+    inner: S,
+    cors: Arc<ArcSwap<CorsLayer>>,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for ReloadableCors<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone,
+    ResBody: Default,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = tower_http::cors::ResponseFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // 取走已就绪的服务，留下克隆体供下次调用
+        let clone = self.inner.clone();
+        let inner = std::mem::replace(&mut self.inner, clone);
+        self.cors.load().layer(inner).call(request)
+    }
+}
+
+// ================================================================================================
+// 重载
+// ================================================================================================
+
+impl ProductionKzgService {
+    /// 与本服务共享 CORS 设置的中间件层
+    pub fn cors_layer(&self) -> ReloadableCorsLayer {
+        ReloadableCorsLayer {
+            cors: Arc::clone(&self.cors),
+        }
+    }
+
+    /// 重新读取配置文件并应用可热重载的部分
+    ///
+    /// `reload_trusted_setup` 为真或 `kzg.trusted_setup_path` 变化时重新加载可信设置。
+    /// 新配置中各部分全部校验通过后才依次生效。
+    pub async fn reload(&self, reload_trusted_setup: bool) -> Result<ReloadReport> {
+        let result = self.apply_reload(reload_trusted_setup).await;
+        match &result {
+            Ok(report) => {
+                self.metrics.config_reloads_total.inc();
+                info!(
+                    "Configuration reloaded (trusted setup: {}, restart required: {:?})",
+                    report.trusted_setup_reloaded, report.restart_required
+                );
+            }
+            Err(e) => {
+                self.metrics.config_reload_failures_total.inc();
+                warn!("Configuration reload rejected: {:#}", e);
+            }
+        }
+        result
+    }
+
+    async fn apply_reload(&self, reload_trusted_setup: bool) -> Result<ReloadReport> {
+        let new = load_config().await?;
+        let current = self.config.read().await.clone();
+        let (mut merged, restart_required) = merge_reloadable(&current, &new);
+
+        // 先完成全部校验，失败时不改动任何状态
+        let cors = cors_layer(&merged.security.cors).context("Invalid CORS configuration")?;
+        let settings = if reload_trusted_setup || merged.kzg.trusted_setup_path != current.kzg.trusted_setup_path {
+            let path = merged.kzg.trusted_setup_path.clone();
+            info!("Reloading trusted setup from: {}", path);
+            Some(self.cpu_pool.run(move || load_verified_trusted_setup(&path)).await??)
+        } else {
+            None
+        };
+        let api_keys = self.security_manager.reload_from_config(&merged.security).await?;
+        // 与启动时一样，明文密钥哈希后不随配置保留
+        merged.security.api_keys.clear();
+
+        self.rate_limiter.reconfigure(&merged.security.rate_limit);
+        self.cors.store(Arc::new(cors));
+        let level = LevelFilter::from_level(log_level(&merged.logging.level));
+        if let Err(e) = self.log_level.modify(|filter| *filter = level) {
+            warn!("Failed to update log level: {}", e);
+        }
+
+        let performance = &merged.performance;
+        let cache_evictions = self
+            .cache_manager
+            .resize(performance.cache_size, performance.cache_ttl_seconds, performance.cache_max_bytes)
+            .await;
+
+        let trusted_setup_reloaded = settings.is_some();
+        if let Some(settings) = settings {
+            self.kzg_settings.store(Arc::new(settings));
+            // 旧设置下的结果不再有效：进行中的请求按旧代号写入的结果会被丢弃
+            self.settings_generation.fetch_add(1, Ordering::Release);
+            self.cache_manager.clear().await;
+        }
+
+        *self.config.write().await = merged;
+        self.update_cache_metrics().await;
+
+        Ok(ReloadReport {
+            restart_required,
+            trusted_setup_reloaded,
+            api_keys,
+            cache_evictions,
+        })
+    }
+}
@@ -0,0 +1,369 @@
+//! API 密钥与 IP 黑名单
+//!
+//! 密钥只以加盐哈希形式保存 (`sha256$<salt hex>$<digest hex>`，摘要为
+//! `SHA-256(salt || key)`)，每个密钥带有权限范围以及自己的速率设置和每日配额。
+//! 速率限制本身由 [`super::rate_limit::RateLimiter`] 按密钥执行。
+//! 校验时对全部密钥做常数时间比较，不因匹配位置提前返回。
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+use super::config::{ApiKeyConfig, SecurityConfig};
+use super::rate_limit::ClientRate;
+
+/// 哈希字符串的算法前缀
+const HASH_SCHEME: &str = "sha256";
+
+/// 新生成哈希的盐长度
+const SALT_BYTES: usize = 16;
+
+/// 旧式密钥标识摘要的域分隔前缀，与存储哈希的摘要区分
+const LEGACY_ID_DOMAIN: &[u8] = b"kzg-legacy-key-id";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// API 密钥权限范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Commit,
+    Prove,
+    Verify,
+    Admin,
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Scope::Commit => "commit",
+            Scope::Prove => "prove",
+            Scope::Verify => "verify",
+            Scope::Admin => "admin",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 认证与授权错误
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AuthError {
+    #[error("Missing API key")]
+    MissingKey,
+
+    #[error("Invalid API key")]
+    InvalidKey,
+
+    #[error("API key lacks scope: {0}")]
+    MissingScope(Scope),
+
+    #[error("API key daily quota exceeded")]
+    QuotaExceeded,
+
+    #[error("Invalid key hash: {0}")]
+    InvalidHash(String),
+}
+
+/// 通过认证的密钥身份
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyIdentity {
+    pub id: String,
+    pub scopes: Vec<Scope>,
+    /// 专属速率，未设置时使用默认的客户端速率
+    pub rate: Option<ClientRate>,
+}
+
+impl ApiKeyIdentity {
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    /// 要求具备全部给定权限
+    pub fn require(&self, scopes: &[Scope]) -> Result<(), AuthError> {
+        match scopes.iter().find(|scope| !self.has_scope(**scope)) {
+            Some(scope) => Err(AuthError::MissingScope(*scope)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 以随机盐哈希密钥，结果可直接写入配置或密钥文件
+pub fn hash_api_key(key: &str) -> String {
+    let mut salt = [0u8; SALT_BYTES];
+    rand::thread_rng().fill_bytes(&mut salt);
+    hash_api_key_with_salt(key, &salt)
+}
+
+/// 以给定盐哈希密钥
+pub fn hash_api_key_with_salt(key: &str, salt: &[u8]) -> String {
+    format!("{}${}${}", HASH_SCHEME, hex::encode(salt), hex::encode(digest(salt, key)))
+}
+
+fn digest(salt: &[u8], key: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(key.as_bytes());
+    hasher.finalize().into()
+}
+
+fn parse_hash(hash: &str) -> Result<(Vec<u8>, [u8; 32]), AuthError> {
+    let invalid = |reason: &str| AuthError::InvalidHash(reason.to_string());
+
+    let mut parts = hash.split('$');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(HASH_SCHEME), Some(salt), Some(digest), None) => {
+            let salt = hex::decode(salt).map_err(|_| invalid("salt is not hex"))?;
+            let digest = hex::decode(digest).map_err(|_| invalid("digest is not hex"))?;
+            let digest = <[u8; 32]>::try_from(digest.as_slice()).map_err(|_| invalid("digest must be 32 bytes"))?;
+            Ok((salt, digest))
+        }
+        _ => Err(invalid("expected sha256$<salt>$<digest>")),
+    }
+}
+
+/// 单个密钥的每日配额状态
+struct KeyLimits {
+    daily_quota: Option<u64>,
+    day: u64,
+    used_today: u64,
+}
+
+impl KeyLimits {
+    /// 计入 `items` 个条目，超出当日配额时整体拒绝且不计入
+    fn charge(&mut self, items: u64) -> Result<(), AuthError> {
+        self.roll_over();
+        if let Some(quota) = self.daily_quota {
+            if items > 0 && self.used_today.saturating_add(items) > quota {
+                return Err(AuthError::QuotaExceeded);
+            }
+        }
+        self.used_today = self.used_today.saturating_add(items);
+        Ok(())
+    }
+
+    /// 退还当日已计入的条目
+    fn refund(&mut self, items: u64) {
+        self.roll_over();
+        self.used_today = self.used_today.saturating_sub(items);
+    }
+
+    fn roll_over(&mut self) {
+        let today = now_seconds() / SECONDS_PER_DAY;
+        if today != self.day {
+            self.day = today;
+            self.used_today = 0;
+        }
+    }
+}
+
+struct StoredKey {
+    identity: ApiKeyIdentity,
+    salt: Vec<u8>,
+    digest: [u8; 32],
+    limits: Mutex<KeyLimits>,
+}
+
+impl StoredKey {
+    fn from_config(config: &ApiKeyConfig) -> Result<Self, AuthError> {
+        let (salt, digest) = parse_hash(&config.hash)?;
+        let rate = config.requests_per_second.map(|requests_per_second| ClientRate {
+            requests_per_second,
+            burst_size: config.burst_size.unwrap_or(requests_per_second),
+        });
+
+        Ok(Self {
+            identity: ApiKeyIdentity {
+                id: config.id.clone(),
+                scopes: config.scopes.clone(),
+                rate,
+            },
+            salt,
+            digest,
+            limits: Mutex::new(KeyLimits {
+                daily_quota: config.daily_quota,
+                day: now_seconds() / SECONDS_PER_DAY,
+                used_today: 0,
+            }),
+        })
+    }
+
+    fn matches(&self, key: &str) -> subtle::Choice {
+        digest(&self.salt, key).ct_eq(&self.digest)
+    }
+}
+
+/// 安全管理器
+pub struct SecurityManager {
+    api_keys: Arc<RwLock<Vec<StoredKey>>>,
+    blocked_ips: Arc<RwLock<HashSet<String>>>,
+}
+
+impl SecurityManager {
+    pub fn new(keys: &[ApiKeyConfig]) -> Result<Self, AuthError> {
+        let keys = keys.iter().map(StoredKey::from_config).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            api_keys: Arc::new(RwLock::new(keys)),
+            blocked_ips: Arc::new(RwLock::new(HashSet::new())),
+        })
+    }
+
+    /// 由安全配置构建：配置内密钥、密钥文件以及旧式明文密钥
+    ///
+    /// 旧式 `api_keys` 在启动时加盐哈希，授予 commit/prove/verify 权限，标识见 [`legacy_key_id`]；
+    /// 调用方随后应清空配置中的明文。
+    pub fn from_config(config: &SecurityConfig) -> anyhow::Result<Self> {
+        Ok(Self::new(&configured_keys(config)?)?)
+    }
+
+    /// 以新的密钥集合替换现有密钥，全部解析成功才生效
+    ///
+    /// 标识不变的密钥保留当日已用配额，返回替换后的密钥数。
+    pub async fn reload_keys(&self, keys: &[ApiKeyConfig]) -> Result<usize, AuthError> {
+        let mut fresh = keys.iter().map(StoredKey::from_config).collect::<Result<Vec<_>, _>>()?;
+
+        let mut current = self.api_keys.write().await;
+        for stored in &mut fresh {
+            if let Some(old) = current.iter().find(|old| old.identity.id == stored.identity.id) {
+                let old = old.limits.lock().unwrap();
+                let limits = stored.limits.get_mut().unwrap();
+                limits.day = old.day;
+                limits.used_today = old.used_today;
+            }
+        }
+        *current = fresh;
+        Ok(current.len())
+    }
+
+    /// 从安全配置重新加载密钥 (含密钥文件)
+    pub async fn reload_from_config(&self, config: &SecurityConfig) -> anyhow::Result<usize> {
+        Ok(self.reload_keys(&configured_keys(config)?).await?)
+    }
+
+    /// 认证密钥并检查权限，通过后按一个条目计入该密钥的每日配额
+    pub async fn authenticate(&self, key: &str, scopes: &[Scope]) -> Result<ApiKeyIdentity, AuthError> {
+        self.authenticate_items(key, scopes, 1).await
+    }
+
+    /// 认证密钥并检查权限，通过后计入 `items` 个条目 (为 0 时不计入配额)
+    pub async fn authenticate_items(
+        &self,
+        key: &str,
+        scopes: &[Scope],
+        items: u64,
+    ) -> Result<ApiKeyIdentity, AuthError> {
+        let keys = self.api_keys.read().await;
+        let stored = find_key(&keys, key).ok_or(AuthError::InvalidKey)?;
+        stored.identity.require(scopes)?;
+        stored.limits.lock().unwrap().charge(items)?;
+        Ok(stored.identity.clone())
+    }
+
+    /// 为已认证的密钥补计 `items` 个条目 (批量请求在认证时只计一个条目)
+    ///
+    /// 密钥已在重载中移除时不计入。
+    pub async fn charge_quota(&self, id: &str, items: u64) -> Result<(), AuthError> {
+        let keys = self.api_keys.read().await;
+        match keys.iter().find(|stored| stored.identity.id == id) {
+            Some(stored) => stored.limits.lock().unwrap().charge(items),
+            None => Ok(()),
+        }
+    }
+
+    /// 退还已计入的条目，用于未被执行的请求 (如任务队列已满)
+    pub async fn refund_quota(&self, id: &str, items: u64) {
+        let keys = self.api_keys.read().await;
+        if let Some(stored) = keys.iter().find(|stored| stored.identity.id == id) {
+            stored.limits.lock().unwrap().refund(items);
+        }
+    }
+
+    /// 识别密钥身份 (不检查权限，不计入配额)，供限流按密钥分桶
+    pub async fn identify(&self, key: &str) -> Option<ApiKeyIdentity> {
+        let keys = self.api_keys.read().await;
+        find_key(&keys, key).map(|stored| stored.identity.clone())
+    }
+
+    /// 验证 API 密钥 (不检查权限，不计入配额)
+    pub async fn validate_api_key(&self, key: &str) -> bool {
+        let keys = self.api_keys.read().await;
+        keys.iter()
+            .fold(subtle::Choice::from(0), |found, stored| found | stored.matches(key))
+            .into()
+    }
+
+    /// 检查 IP 是否被阻止
+    pub async fn is_ip_blocked(&self, ip: &str) -> bool {
+        let blocked = self.blocked_ips.read().await;
+        blocked.contains(ip)
+    }
+
+    /// 阻止 IP 地址
+    pub async fn block_ip(&self, ip: &str) {
+        let mut blocked = self.blocked_ips.write().await;
+        blocked.insert(ip.to_string());
+    }
+}
+
+/// 汇总配置内密钥、密钥文件与旧式明文密钥
+fn configured_keys(config: &SecurityConfig) -> anyhow::Result<Vec<ApiKeyConfig>> {
+    let mut keys = config.keys.clone();
+    if let Some(path) = &config.key_file {
+        keys.extend(super::config::load_key_file(path)?);
+    }
+    keys.extend(config.api_keys.iter().map(|key| ApiKeyConfig {
+        id: legacy_key_id(key),
+        hash: hash_api_key_with_salt(key, legacy_salt()),
+        scopes: vec![Scope::Commit, Scope::Prove, Scope::Verify],
+        requests_per_second: None,
+        burst_size: None,
+        daily_quota: None,
+    }));
+    Ok(keys)
+}
+
+/// 旧式密钥的标识取自进程随机盐哈希的前 8 字节
+///
+/// 同一进程内重载或调整顺序后标识不变；盐不公开也不落盘，日志和任务状态中的标识
+/// 无法离线穷举还原出密钥。重启后标识随盐一起变化。
+pub fn legacy_key_id(key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(legacy_salt());
+    hasher.update(LEGACY_ID_DOMAIN);
+    hasher.update(key.as_bytes());
+    let digest: [u8; 32] = hasher.finalize().into();
+    format!("legacy-{}", hex::encode(&digest[..8]))
+}
+
+/// 旧式明文密钥共用的进程随机盐，用于存储哈希和标识
+fn legacy_salt() -> &'static [u8; SALT_BYTES] {
+    static SALT: OnceLock<[u8; SALT_BYTES]> = OnceLock::new();
+    SALT.get_or_init(|| {
+        let mut salt = [0u8; SALT_BYTES];
+        rand::thread_rng().fill_bytes(&mut salt);
+        salt
+    })
+}
+
+/// 逐个比较全部密钥，匹配与否不影响耗时
+fn find_key<'a>(keys: &'a [StoredKey], key: &str) -> Option<&'a StoredKey> {
+    let mut matched = None;
+    for (i, stored) in keys.iter().enumerate() {
+        if bool::from(stored.matches(key)) {
+            matched = Some(i);
+        }
+    }
+    matched.map(|i| &keys[i])
+}
+
+fn now_seconds() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
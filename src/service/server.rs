@@ -0,0 +1,636 @@
+//! HTTP 服务器和 API 端点
+
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+
+use axum::{
+    extract::{ConnectInfo, DefaultBodyLimit, Extension, Json, Path, Query, State},
+    http::{Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
+use serde::Deserialize;
+use tokio::signal;
+use tracing::info;
+
+use kzg::eth::CELLS_PER_EXT_BLOB;
+
+use super::cells::MAX_JSON_BYTES_PER_CELL;
+use super::codec::MAX_JSON_REQUEST_BYTES;
+use super::rate_limit_layer::{api_key, RateLimitHandle};
+use super::{
+    BatchRequest, BatchResponse, CellRecoveryInput, CellRecoveryRequest, CellRecoveryResponse, CellVerificationInput,
+    CellVerificationRequest, CellVerificationResponse, CellsRequest, CellsResponse, CommitmentInput, CommitmentResponse, Encoded, HealthStatus,
+    ApiKeyIdentity, JobRequest, JobStatus, JobSubmitted, JsonOnly, Negotiated, ProductionKzgService, ProofInput, ReloadReport, Scope, ProofResponse, ServiceError, VerificationInput,
+    VerificationResponse, VersionedHashValidationRequest, VersionedHashValidationResponse,
+};
+
+/// HTTP 服务器启动 - 简化版本
+pub async fn start_http_server(service: ProductionKzgService) -> Result<()> {
+    // 只在启动时读取监听地址，不在服务期间持有配置锁
+    let (addr, grpc_port) = {
+        let config = service.config.read().await;
+        (format!("{}:{}", config.server.host, config.server.port), config.server.grpc_port)
+    };
+    
+    info!("启动 HTTP 服务器: {}", addr);
+    
+    #[cfg(unix)]
+    spawn_sighup_reload(service.clone());
+    
+    // 构建简化的应用路由
+    let app = create_simple_router(service.clone()).await;
+    
+    info!("HTTP 服务器已启动，监听地址: {}", addr);
+    
+    // 使用简化的服务器启动方式
+    let http = async {
+        axum::Server::bind(&addr.parse()?)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(shutdown_signal())
+            .await
+            .context("HTTP server error")?;
+        
+        info!("HTTP 服务器已关闭");
+        Ok::<_, anyhow::Error>(())
+    };
+    
+    match grpc_port {
+        #[cfg(feature = "grpc")]
+        Some(port) => {
+            let grpc_addr = std::net::SocketAddr::new(addr.parse::<std::net::SocketAddr>()?.ip(), port);
+            tokio::try_join!(http, super::grpc::start_grpc_server(service, grpc_addr))?;
+        }
+        #[cfg(not(feature = "grpc"))]
+        Some(port) => {
+            tracing::warn!("已配置 grpc_port {}，但未启用 grpc 特性，gRPC 服务器不会启动", port);
+            http.await?;
+        }
+        None => http.await?,
+    }
+    Ok(())
+}
+
+/// 创建简化的应用路由
+pub async fn create_simple_router(service: ProductionKzgService) -> Router {
+    // 请求体上限由 BYTES_PER_BLOB 推导，批量请求按最大条目数放大
+    let (max_batch_size, max_cells, max_job_items) = {
+        let config = service.config.read().await;
+        (
+            config.performance.max_batch_size,
+            config.performance.max_cells_per_request,
+            config.jobs.max_items_per_job,
+        )
+    };
+    let single_limit = || DefaultBodyLimit::max(MAX_JSON_REQUEST_BYTES);
+    let batch_limit = DefaultBodyLimit::max(MAX_JSON_REQUEST_BYTES.saturating_mul(max_batch_size.max(1)));
+    let jobs_limit = DefaultBodyLimit::max(MAX_JSON_REQUEST_BYTES.saturating_mul(max_job_items.max(1)));
+    let rate_limit = service.rate_limit_layer();
+    let cells_limit = |cells: usize| DefaultBodyLimit::max(MAX_JSON_BYTES_PER_CELL.saturating_mul(cells.max(1)));
+    
+    Router::new()
+        // API 路由
+        .route("/api/v1/commitment", post(create_commitment_handler).layer(single_limit()))
+        .route("/api/v1/proof", post(generate_proof_handler).layer(single_limit()))
+        .route("/api/v1/verify", post(verify_proof_handler).layer(single_limit()))
+        .route("/api/v1/batch", post(batch_process_handler).layer(batch_limit))
+        .route("/api/v1/jobs", post(submit_job_handler).layer(jobs_limit))
+        .route("/api/v1/jobs/:id", get(job_status_handler))
+        .route("/api/v1/cells", post(compute_cells_handler).layer(single_limit()))
+        .route("/api/v1/cells/verify", post(verify_cells_handler).layer(cells_limit(max_cells)))
+        .route("/api/v1/cells/recover", post(recover_cells_handler).layer(cells_limit(CELLS_PER_EXT_BLOB)))
+        .route("/api/v1/versioned-hashes/validate", post(validate_versioned_hashes_handler))
+        
+        // 健康检查路由
+        .route("/health", get(health_handler))
+        .route("/health/live", get(liveness_handler))
+        .route("/health/ready", get(readiness_handler))
+        
+        // 监控路由
+        .route("/metrics", get(metrics_handler))
+        .route("/api/v1/openapi.json", get(openapi_handler))
+        
+        // 管理路由
+        .route("/admin/config", get(get_config_handler))
+        .route("/admin/stats", get(get_stats_handler))
+        .route("/admin/reload", post(reload_handler))
+        
+        // 限流在认证之前执行，覆盖全部 API 与管理路由；CORS 在最外层直接应答预检请求
+        .route_layer(middleware::from_fn_with_state(service.clone(), access_control))
+        .layer(rate_limit)
+        .layer(service.cors_layer())
+        .with_state(service)
+}
+
+// ================================================================================================
+// 访问控制
+// ================================================================================================
+
+/// 通过访问控制的请求方身份 (未启用认证时为 `None`)
+#[derive(Debug, Clone)]
+struct Authenticated(Option<ApiKeyIdentity>);
+
+/// 各路由所需的密钥权限，`None` 表示公开路由
+///
+/// 批量接口只要求有效密钥，逐条目的权限由 [`ProductionKzgService::process_batch`] 检查。
+pub(super) fn route_scopes(path: &str) -> Option<&'static [Scope]> {
+    match path {
+        "/api/v1/commitment" => Some(&[Scope::Commit]),
+        "/api/v1/proof" | "/api/v1/cells" | "/api/v1/cells/recover" => Some(&[Scope::Prove]),
+        "/api/v1/verify" | "/api/v1/cells/verify" | "/api/v1/versioned-hashes/validate" => Some(&[Scope::Verify]),
+        "/api/v1/batch" | "/api/v1/jobs" => Some(&[]),
+        _ if path.starts_with("/api/v1/jobs/") => Some(&[]),
+        _ if path.starts_with("/admin/") => Some(&[Scope::Admin]),
+        _ => None,
+    }
+}
+
+/// 认证时计入每日配额的条目数
+///
+/// 查询任务状态不计；批量请求和任务先计一个条目，其余由处理器按条目数补计。
+fn route_quota_items(path: &str) -> u64 {
+    if path.starts_with("/api/v1/jobs/") {
+        0
+    } else {
+        1
+    }
+}
+
+/// 访问控制中间件：IP 黑名单、密钥权限与速率限制
+async fn access_control<B>(
+    State(service): State<ProductionKzgService>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, ServiceError> {
+    let path = request.uri().path();
+    let Some(scopes) = route_scopes(path) else {
+        return Ok(next.run(request).await);
+    };
+    let quota_items = route_quota_items(path);
+    
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+    let identity = service
+        .authorize(api_key(request.headers()), client_ip.as_deref(), scopes, quota_items)
+        .await?;
+    
+    request.extensions_mut().insert(Authenticated(identity));
+    Ok(next.run(request).await)
+}
+
+// ================================================================================================
+// API 处理器
+// ================================================================================================
+
+/// 创建承诺处理器
+#[utoipa::path(
+    post,
+    path = "/api/v1/commitment",
+    tag = "kzg",
+    request_body = CommitmentRequest,
+    responses((status = 200, description = "承诺与版本化哈希", body = CommitmentResponse))
+)]
+async fn create_commitment_handler(
+    State(service): State<ProductionKzgService>,
+    request: Negotiated<CommitmentInput>
+) -> Result<Encoded<CommitmentResponse>, ServiceError> {
+    let response = service.commit_blob(request.body).await?;
+    Ok(Encoded::new(request.accept, response))
+}
+
+/// 生成证明处理器
+#[utoipa::path(
+    post,
+    path = "/api/v1/proof",
+    tag = "kzg",
+    request_body = ProofRequest,
+    responses((status = 200, description = "blob 证明", body = ProofResponse))
+)]
+async fn generate_proof_handler(
+    State(service): State<ProductionKzgService>,
+    request: Negotiated<ProofInput>
+) -> Result<Encoded<ProofResponse>, ServiceError> {
+    let response = service.prove_blob(request.body).await?;
+    Ok(Encoded::new(request.accept, response))
+}
+
+/// 验证证明处理器
+#[utoipa::path(
+    post,
+    path = "/api/v1/verify",
+    tag = "kzg",
+    request_body = VerificationRequest,
+    responses((status = 200, description = "验证结果", body = VerificationResponse))
+)]
+async fn verify_proof_handler(
+    State(service): State<ProductionKzgService>,
+    request: Negotiated<VerificationInput>
+) -> Result<Encoded<VerificationResponse>, ServiceError> {
+    let response = service.verify_blob(request.body).await?;
+    Ok(Encoded::new(request.accept, response))
+}
+
+/// EIP-7594 cell 计算处理器
+#[utoipa::path(
+    post,
+    path = "/api/v1/cells",
+    tag = "das",
+    request_body = CellsRequest,
+    responses((status = 200, description = "全部 cell 及证明", body = CellsResponse))
+)]
+async fn compute_cells_handler(
+    State(service): State<ProductionKzgService>,
+    request: Negotiated<CommitmentInput>
+) -> Result<Encoded<CellsResponse>, ServiceError> {
+    let response = service.compute_cells(request.body).await?;
+    Ok(Encoded::new(request.accept, response))
+}
+
+/// EIP-7594 cell 证明验证处理器
+#[utoipa::path(
+    post,
+    path = "/api/v1/cells/verify",
+    tag = "das",
+    request_body = CellVerificationRequest,
+    responses((status = 200, description = "验证结果", body = CellVerificationResponse))
+)]
+async fn verify_cells_handler(
+    State(service): State<ProductionKzgService>,
+    request: Negotiated<CellVerificationInput>
+) -> Result<Encoded<CellVerificationResponse>, ServiceError> {
+    let response = service.verify_cells(request.body).await?;
+    Ok(Encoded::new(request.accept, response))
+}
+
+/// EIP-7594 cell 恢复处理器
+#[utoipa::path(
+    post,
+    path = "/api/v1/cells/recover",
+    tag = "das",
+    request_body = CellRecoveryRequest,
+    responses((status = 200, description = "恢复出的全部 cell 及证明", body = CellRecoveryResponse))
+)]
+async fn recover_cells_handler(
+    State(service): State<ProductionKzgService>,
+    request: Negotiated<CellRecoveryInput>
+) -> Result<Encoded<CellRecoveryResponse>, ServiceError> {
+    let response = service.recover_cells(request.body).await?;
+    Ok(Encoded::new(request.accept, response))
+}
+
+/// 交易版本化哈希校验处理器
+#[utoipa::path(
+    post,
+    path = "/api/v1/versioned-hashes/validate",
+    tag = "kzg",
+    request_body = VersionedHashValidationRequest,
+    responses((status = 200, description = "校验结果", body = VersionedHashValidationResponse))
+)]
+async fn validate_versioned_hashes_handler(
+    State(service): State<ProductionKzgService>,
+    JsonOnly(request): JsonOnly<VersionedHashValidationRequest>
+) -> Result<Json<VersionedHashValidationResponse>, ServiceError> {
+    let response = service.validate_versioned_hashes(request).await?;
+    Ok(Json(response))
+}
+
+/// 批量处理处理器
+#[utoipa::path(
+    post,
+    path = "/api/v1/batch",
+    tag = "kzg",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "逐条目结果，单个条目失败不影响其他条目", body = BatchResponse),
+        (status = 406, description = "Accept 优先二进制编码 (本接口只返回 JSON)", body = ErrorBody),
+        (status = 415, description = "二进制请求体 (本接口只接受 JSON)", body = ErrorBody)
+    )
+)]
+async fn batch_process_handler(
+    State(service): State<ProductionKzgService>,
+    Extension(Authenticated(identity)): Extension<Authenticated>,
+    Extension(rate_limit): Extension<RateLimitHandle>,
+    JsonOnly(request): JsonOnly<BatchRequest>
+) -> Result<Json<BatchResponse>, ServiceError> {
+    // 超限的批量在补扣前拒绝；准入时已按一个条目计费
+    service.check_batch_size(request.requests.len()).await?;
+    let extra_items = request.requests.len().saturating_sub(1);
+    service.charge_batch_items(Some(&rate_limit), identity.as_ref(), extra_items).await?;
+    match service.process_batch(request, identity.as_ref()).await {
+        Ok(response) => Ok(Json(response)),
+        Err(e) => {
+            service.refund_batch_items(Some(&rate_limit), identity.as_ref(), extra_items).await;
+            Err(e)
+        }
+    }
+}
+
+/// 异步任务提交处理器
+#[utoipa::path(
+    post,
+    path = "/api/v1/jobs",
+    tag = "jobs",
+    request_body = JobRequest,
+    responses(
+        (status = 202, description = "任务已排队", body = JobSubmitted),
+        (status = 503, description = "任务队列已满", body = ErrorBody)
+    )
+)]
+async fn submit_job_handler(
+    State(service): State<ProductionKzgService>,
+    Extension(Authenticated(identity)): Extension<Authenticated>,
+    Extension(rate_limit): Extension<RateLimitHandle>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    JsonOnly(request): JsonOnly<JobRequest>
+) -> Result<(StatusCode, Json<JobSubmitted>), ServiceError> {
+    // 准入时已按一个条目计费
+    let items = request.requests.len();
+    service
+        .charge_batch_items(Some(&rate_limit), identity.as_ref(), items.saturating_sub(1))
+        .await?;
+    let client_ip = connect_info.map(|ConnectInfo(addr)| addr.ip().to_string());
+    let submitted = match service.submit_job(request, identity.as_ref(), client_ip.as_deref()) {
+        Ok(submitted) => submitted,
+        Err(e) => {
+            // 任务未入队，退还全部条目 (含准入时计费的一个) 的令牌与配额
+            service.refund_batch_items(Some(&rate_limit), identity.as_ref(), items.max(1)).await;
+            return Err(e);
+        }
+    };
+    Ok((StatusCode::ACCEPTED, Json(submitted)))
+}
+
+/// 异步任务状态处理器
+#[utoipa::path(
+    get,
+    path = "/api/v1/jobs/{id}",
+    tag = "jobs",
+    params(("id" = String, Path, description = "任务标识")),
+    responses(
+        (status = 200, description = "任务进度与已完成条目的结果", body = JobStatus),
+        (status = 404, description = "任务不存在或属于其他租户", body = ErrorBody)
+    )
+)]
+async fn job_status_handler(
+    State(service): State<ProductionKzgService>,
+    Extension(Authenticated(identity)): Extension<Authenticated>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    Path(id): Path<String>
+) -> Result<Json<JobStatus>, ServiceError> {
+    let client_ip = connect_info.map(|ConnectInfo(addr)| addr.ip().to_string());
+    let status = service.job_status(&id, identity.as_ref(), client_ip.as_deref())?;
+    Ok(Json(status))
+}
+
+// ================================================================================================
+// 健康检查处理器
+// ================================================================================================
+
+/// 总体健康检查
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "各组件健康状态", body = HealthStatus))
+)]
+async fn health_handler(
+    State(service): State<ProductionKzgService>
+) -> Json<HealthStatus> {
+    Json(service.health_checker.check_health().await)
+}
+
+/// 活跃性检查
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    responses((status = 200, description = "存活"), (status = 503, description = "不可用"))
+)]
+async fn liveness_handler(
+    State(service): State<ProductionKzgService>
+) -> impl IntoResponse {
+    if service.health_checker.check_liveness().await {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+/// 就绪性检查  
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses((status = 200, description = "可以接收请求"), (status = 503, description = "尚未就绪"))
+)]
+async fn readiness_handler(
+    State(service): State<ProductionKzgService>
+) -> impl IntoResponse {
+    if service.health_checker.check_readiness().await {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+// ================================================================================================
+// 监控处理器
+// ================================================================================================
+
+/// Prometheus 指标端点
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "monitoring",
+    responses((status = 200, description = "Prometheus 文本格式指标", body = String, content_type = "text/plain"))
+)]
+async fn metrics_handler(State(service): State<ProductionKzgService>) -> impl IntoResponse {
+    let encoder = prometheus::TextEncoder::new();
+    let metric_families = service.metrics().registry().gather();
+    
+    match encoder.encode_to_string(&metric_families) {
+        Ok(output) => {
+            (
+                StatusCode::OK,
+                [("content-type", "text/plain; version=0.0.4")],
+                output
+            )
+        }
+        Err(_) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                [("content-type", "text/plain")],
+                "Failed to encode metrics".to_string()
+            )
+        }
+    }
+}
+
+/// OpenAPI 文档
+#[utoipa::path(
+    get,
+    path = "/api/v1/openapi.json",
+    tag = "monitoring",
+    responses((status = 200, description = "OpenAPI 3 文档", body = Object))
+)]
+async fn openapi_handler() -> impl IntoResponse {
+    // 文档只依赖类型定义，首次请求时生成一次
+    static DOCUMENT: OnceLock<String> = OnceLock::new();
+    let document = DOCUMENT.get_or_init(|| super::openapi().to_json().unwrap_or_default());
+    ([("content-type", "application/json")], document.as_str())
+}
+
+// ================================================================================================
+// 管理处理器
+// ================================================================================================
+
+/// 获取配置信息
+#[utoipa::path(
+    get,
+    path = "/admin/config",
+    tag = "admin",
+    responses((status = 200, description = "去除敏感字段的当前配置", body = Object))
+)]
+async fn get_config_handler(
+    State(service): State<ProductionKzgService>
+) -> Json<serde_json::Value> {
+    let config = service.config.read().await;
+    
+    // 返回安全的配置信息 (隐藏敏感信息)
+    let safe_config = serde_json::json!({
+        "server": {
+            "host": config.server.host,
+            "port": config.server.port,
+            "max_connections": config.server.max_connections,
+        },
+        "monitoring": config.monitoring,
+        "performance": config.performance,
+    });
+    
+    Json(safe_config)
+}
+
+/// 获取统计信息
+#[utoipa::path(
+    get,
+    path = "/admin/stats",
+    tag = "admin",
+    responses((status = 200, description = "运行与缓存统计", body = Object))
+)]
+async fn get_stats_handler(
+    State(service): State<ProductionKzgService>
+) -> Json<serde_json::Value> {
+    let cache = service.cache_manager.stats().await;
+    let stats = serde_json::json!({
+        "uptime_seconds": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+        "memory_usage": get_memory_usage(),
+        "active_connections": service.metrics.active_connections.get(),
+        "total_requests": service.metrics.http_requests_total.get(),
+        "cache_stats": {
+            "hit_rate": cache.hit_rate(),
+            "hits": cache.hits,
+            "misses": cache.misses,
+            "evictions": cache.evictions,
+            "entries": cache.entries,
+            "bytes": cache.bytes,
+        }
+    });
+    
+    Json(stats)
+}
+
+fn get_memory_usage() -> u64 {
+    // 简化的内存使用统计
+    0
+}
+
+#[derive(Debug, Deserialize)]
+struct ReloadParams {
+    /// 即使路径未变也重新加载可信设置
+    #[serde(default)]
+    trusted_setup: bool,
+}
+
+/// 热重载配置处理器
+#[utoipa::path(
+    post,
+    path = "/admin/reload",
+    tag = "admin",
+    params(("trusted_setup" = Option<bool>, Query, description = "即使路径未变也重新加载可信设置")),
+    responses((status = 200, description = "重载结果", body = ReloadReport))
+)]
+async fn reload_handler(
+    State(service): State<ProductionKzgService>,
+    Query(params): Query<ReloadParams>,
+) -> Result<Json<ReloadReport>, ServiceError> {
+    let report = service
+        .reload(params.trusted_setup)
+        .await
+        .map_err(|e| ServiceError::InternalError(format!("{:#}", e)))?;
+    Ok(Json(report))
+}
+
+// ================================================================================================
+// 热重载信号
+// ================================================================================================
+
+/// 收到 SIGHUP 时重新加载配置 (可信设置仅在路径变化时重新加载)
+#[cfg(unix)]
+fn spawn_sighup_reload(service: ProductionKzgService) {
+    let mut hangup = match signal::unix::signal(signal::unix::SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            tracing::warn!("无法监听 SIGHUP，热重载只能通过 /admin/reload 触发: {}", e);
+            return;
+        }
+    };
+    
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("收到 SIGHUP 信号，重新加载配置...");
+            // 结果已由 reload 记录到日志和指标
+            let _ = service.reload(false).await;
+        }
+    });
+}
+
+// ================================================================================================
+// 优雅关闭
+// ================================================================================================
+
+/// 优雅关闭信号处理
+pub(crate) async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {
+            info!("收到 Ctrl+C 信号，开始优雅关闭...");
+        },
+        _ = terminate => {
+            info!("收到 SIGTERM 信号，开始优雅关闭...");
+        },
+    }
+}
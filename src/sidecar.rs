@@ -0,0 +1,289 @@
+//! 信标链 `BlobSidecar` JSON 加载
+//!
+//! 解析信标节点 API (`/eth/v1/beacon/blob_sidecars/{block_id}`) 返回的 JSON，
+//! 便于离线重放真实网络中的 Blob 数据。支持三种形式：
+//!
+//! - 完整的 API 响应 `{"data": [sidecar, ...]}`
+//! - sidecar 数组 `[sidecar, ...]`
+//! - 单个 sidecar 对象
+//!
+//! 字节字段为 `0x` 前缀的十六进制字符串，整数字段按 API 约定为十进制字符串
+//! (也接受 JSON 数字)。解析时检查各字段长度，KZG 证明用
+//! [`BlobSidecar::verify_kzg_proof`] 校验。
+
+use std::io::Read;
+use std::path::Path;
+
+use kzg::eip_4844::{verify_blob_kzg_proof_rust, BYTES_PER_BLOB};
+use kzg::G1;
+use rust_kzg_blst::types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings};
+use serde::Deserialize;
+
+use crate::blob::blob_from_bytes;
+use crate::versioned_hash::{kzg_to_versioned_hash, validate_blob_versioned_hashes, VersionedHashError};
+
+/// Deneb 中 `kzg_commitment_inclusion_proof` 的默克尔分支长度
+pub const KZG_COMMITMENT_INCLUSION_PROOF_DEPTH: usize = 17;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SidecarError {
+    #[error("读取失败 {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("JSON 格式错误: {0}")]
+    Json(String),
+
+    #[error("字段 {field} 不是有效的十六进制: {reason}")]
+    InvalidHex { field: String, reason: String },
+
+    #[error("字段 {field} 长度错误: 期望 {expected} 字节，实际 {actual}")]
+    InvalidLength {
+        field: String,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("字段 {field} 不是有效的整数: {value}")]
+    InvalidNumber { field: String, value: String },
+
+    #[error("无效的 Blob: {0}")]
+    InvalidBlob(String),
+
+    #[error("无效的 G1 点 ({field}): {reason}")]
+    InvalidPoint { field: String, reason: String },
+
+    #[error("KZG 操作错误: {0}")]
+    KZGError(String),
+}
+
+/// 信标区块头
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeaconBlockHeader {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: [u8; 32],
+    pub state_root: [u8; 32],
+    pub body_root: [u8; 32],
+}
+
+/// 带签名的信标区块头
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedBeaconBlockHeader {
+    pub message: BeaconBlockHeader,
+    /// BLS 签名 (96 字节)
+    pub signature: Vec<u8>,
+}
+
+/// Blob sidecar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobSidecar {
+    /// Blob 在区块中的索引
+    pub index: u64,
+    /// Blob 原始字节 (`BYTES_PER_BLOB` 字节)
+    pub blob: Vec<u8>,
+    pub kzg_commitment: [u8; 48],
+    pub kzg_proof: [u8; 48],
+    pub signed_block_header: SignedBeaconBlockHeader,
+    /// 承诺在区块体中的默克尔包含证明
+    pub kzg_commitment_inclusion_proof: Vec<[u8; 32]>,
+}
+
+impl BlobSidecar {
+    /// 解析单个 sidecar 对象
+    pub fn from_json(json: &str) -> Result<Self, SidecarError> {
+        let raw: RawSidecar = serde_json::from_str(json).map_err(|e| SidecarError::Json(e.to_string()))?;
+        raw.into_sidecar()
+    }
+
+    /// 所在 slot
+    pub fn slot(&self) -> u64 {
+        self.signed_block_header.message.slot
+    }
+
+    /// 域元素形式的 Blob
+    pub fn blob_fr(&self) -> Result<Vec<FsFr>, SidecarError> {
+        blob_from_bytes(&self.blob).map_err(SidecarError::InvalidBlob)
+    }
+
+    pub fn commitment(&self) -> Result<FsG1, SidecarError> {
+        FsG1::from_bytes(&self.kzg_commitment).map_err(|reason| SidecarError::InvalidPoint {
+            field: "kzg_commitment".to_string(),
+            reason,
+        })
+    }
+
+    pub fn proof(&self) -> Result<FsG1, SidecarError> {
+        FsG1::from_bytes(&self.kzg_proof).map_err(|reason| SidecarError::InvalidPoint {
+            field: "kzg_proof".to_string(),
+            reason,
+        })
+    }
+
+    /// 承诺的版本化哈希，对应交易中的 `blob_versioned_hashes` 条目
+    pub fn versioned_hash(&self) -> [u8; 32] {
+        kzg_to_versioned_hash(&self.kzg_commitment)
+    }
+
+    /// 用 `verify_blob_kzg_proof` 校验 sidecar 自带的承诺和证明
+    pub fn verify_kzg_proof(&self, kzg_settings: &FsKZGSettings) -> Result<bool, SidecarError> {
+        let blob = self.blob_fr()?;
+        verify_blob_kzg_proof_rust(&blob, &self.commitment()?, &self.proof()?, kzg_settings)
+            .map_err(SidecarError::KZGError)
+    }
+}
+
+/// 校验交易的 `blob_versioned_hashes` 与该交易的 sidecar (按 blob 顺序) 是否一致
+pub fn validate_transaction_sidecars(
+    versioned_hashes: &[[u8; 32]],
+    sidecars: &[BlobSidecar],
+) -> Result<(), VersionedHashError> {
+    let commitments: Vec<[u8; 48]> = sidecars.iter().map(|s| s.kzg_commitment).collect();
+    validate_blob_versioned_hashes(versioned_hashes, &commitments)
+}
+
+/// 解析 API 响应、sidecar 数组或单个 sidecar
+pub fn parse_sidecars(json: &str) -> Result<Vec<BlobSidecar>, SidecarError> {
+    let document: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| SidecarError::Json(e.to_string()))?;
+
+    let items = match document {
+        serde_json::Value::Object(mut object) if object.contains_key("data") => object["data"].take(),
+        other => other,
+    };
+    let raws: Vec<RawSidecar> = match items {
+        serde_json::Value::Array(_) => serde_json::from_value(items),
+        single => serde_json::from_value(single).map(|raw| vec![raw]),
+    }
+    .map_err(|e| SidecarError::Json(e.to_string()))?;
+
+    raws.into_iter().map(RawSidecar::into_sidecar).collect()
+}
+
+/// 从任意输入流 (例如标准输入) 读取 sidecar
+pub fn read_sidecars<R: Read>(mut reader: R) -> Result<Vec<BlobSidecar>, SidecarError> {
+    let mut json = String::new();
+    reader.read_to_string(&mut json).map_err(|source| SidecarError::Io {
+        path: "<stream>".to_string(),
+        source,
+    })?;
+    parse_sidecars(&json)
+}
+
+/// 从 JSON 文件读取 sidecar
+pub fn load_sidecars_from_file(path: impl AsRef<Path>) -> Result<Vec<BlobSidecar>, SidecarError> {
+    let path = path.as_ref();
+    let json = std::fs::read_to_string(path).map_err(|source| SidecarError::Io {
+        path: path.display().to_string(),
+        source,
+    })?;
+    parse_sidecars(&json)
+}
+
+// ================================
+// JSON 原始结构
+// ================================
+
+/// 整数字段：API 使用十进制字符串，也接受 JSON 数字
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawNumber {
+    Text(String),
+    Number(u64),
+}
+
+#[derive(Deserialize)]
+struct RawSidecar {
+    index: RawNumber,
+    blob: String,
+    kzg_commitment: String,
+    kzg_proof: String,
+    signed_block_header: RawSignedHeader,
+    kzg_commitment_inclusion_proof: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawSignedHeader {
+    message: RawHeader,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct RawHeader {
+    slot: RawNumber,
+    proposer_index: RawNumber,
+    parent_root: String,
+    state_root: String,
+    body_root: String,
+}
+
+impl RawSidecar {
+    fn into_sidecar(self) -> Result<BlobSidecar, SidecarError> {
+        let header = self.signed_block_header.message;
+
+        let kzg_commitment_inclusion_proof = self
+            .kzg_commitment_inclusion_proof
+            .iter()
+            .enumerate()
+            .map(|(i, node)| fixed_hex(&format!("kzg_commitment_inclusion_proof[{}]", i), node))
+            .collect::<Result<Vec<_>, _>>()?;
+        if kzg_commitment_inclusion_proof.len() != KZG_COMMITMENT_INCLUSION_PROOF_DEPTH {
+            return Err(SidecarError::InvalidLength {
+                field: "kzg_commitment_inclusion_proof".to_string(),
+                expected: KZG_COMMITMENT_INCLUSION_PROOF_DEPTH,
+                actual: kzg_commitment_inclusion_proof.len(),
+            });
+        }
+
+        Ok(BlobSidecar {
+            index: number("index", self.index)?,
+            blob: sized_hex("blob", &self.blob, BYTES_PER_BLOB)?,
+            kzg_commitment: fixed_hex("kzg_commitment", &self.kzg_commitment)?,
+            kzg_proof: fixed_hex("kzg_proof", &self.kzg_proof)?,
+            signed_block_header: SignedBeaconBlockHeader {
+                message: BeaconBlockHeader {
+                    slot: number("slot", header.slot)?,
+                    proposer_index: number("proposer_index", header.proposer_index)?,
+                    parent_root: fixed_hex("parent_root", &header.parent_root)?,
+                    state_root: fixed_hex("state_root", &header.state_root)?,
+                    body_root: fixed_hex("body_root", &header.body_root)?,
+                },
+                signature: sized_hex("signature", &self.signed_block_header.signature, 96)?,
+            },
+            kzg_commitment_inclusion_proof,
+        })
+    }
+}
+
+fn number(field: &str, raw: RawNumber) -> Result<u64, SidecarError> {
+    match raw {
+        RawNumber::Number(value) => Ok(value),
+        RawNumber::Text(text) => text.parse().map_err(|_| SidecarError::InvalidNumber {
+            field: field.to_string(),
+            value: text,
+        }),
+    }
+}
+
+fn sized_hex(field: &str, text: &str, expected: usize) -> Result<Vec<u8>, SidecarError> {
+    let bytes = hex::decode(text.strip_prefix("0x").unwrap_or(text)).map_err(|e| SidecarError::InvalidHex {
+        field: field.to_string(),
+        reason: e.to_string(),
+    })?;
+    if bytes.len() != expected {
+        return Err(SidecarError::InvalidLength {
+            field: field.to_string(),
+            expected,
+            actual: bytes.len(),
+        });
+    }
+    Ok(bytes)
+}
+
+fn fixed_hex<const N: usize>(field: &str, text: &str) -> Result<[u8; N], SidecarError> {
+    let bytes = sized_hex(field, text, N)?;
+    Ok(bytes.try_into().expect("长度已检查"))
+}
@@ -0,0 +1,232 @@
+//! 存储证明：挑战–应答审计
+//!
+//! 1. 审计方用私有种子为每个 (纪元, 分片, 节点) 派生随机求值点 z_i (见 [`crate::transcript`])
+//! 2. 存储节点用自己保存的分片数据计算 KZG 打开证明 (`compute_kzg_proof`)，
+//!    返回 (π_i, y_i = p(z_i))
+//! 3. 审计方用清单中的分片承诺验证每个打开证明
+//!
+//! 节点没有完整的分片数据就无法对事先未知的点给出有效证明，
+//! 审计结果通过 [`super::NodeManager::record_audit`] 反馈到节点信誉。
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use kzg::eip_4844::{compute_kzg_proof_rust, verify_kzg_proof_rust};
+use rand::RngCore;
+use rust_kzg_blst::types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings};
+
+use super::manifest::{FileManifest, ManifestError};
+use super::shard::DataShard;
+use super::NodeId;
+use crate::transcript::Transcript;
+
+const AUDIT_DOMAIN: &[u8] = b"KZG_STORAGE_AUDIT_V1";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AuditError {
+    #[error("挑战与应答不匹配: {0}")]
+    ChallengeMismatch(String),
+
+    #[error("未知分片: 索引 {index}，分片总数 {total}")]
+    UnknownShard { index: usize, total: usize },
+
+    #[error("清单错误: {0}")]
+    Manifest(#[from] ManifestError),
+
+    #[error("KZG 操作错误: {0}")]
+    KZGError(String),
+
+    #[error("无效的分片数据: {0}")]
+    InvalidShard(String),
+}
+
+/// 审计配置
+#[derive(Debug, Clone)]
+pub struct AuditConfig {
+    /// 每次挑战的求值点个数
+    pub points_per_challenge: usize,
+    /// 纪元长度，同一纪元内的挑战点固定
+    pub epoch_duration: Duration,
+    /// 单次审计结果对信誉的影响权重 (0~1)
+    pub reputation_weight: f64,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            points_per_challenge: 2,
+            epoch_duration: Duration::from_secs(3600),
+            reputation_weight: 0.1,
+        }
+    }
+}
+
+/// 审计挑战
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditChallenge {
+    pub epoch: u64,
+    pub shard_index: usize,
+    pub node_id: NodeId,
+    /// 随机求值点
+    pub points: Vec<FsFr>,
+}
+
+/// 单个求值点的打开证明
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opening {
+    /// y = p(z)
+    pub value: FsFr,
+    /// KZG 打开证明
+    pub proof: FsG1,
+}
+
+/// 节点对挑战的应答
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditResponse {
+    pub epoch: u64,
+    pub shard_index: usize,
+    pub openings: Vec<Opening>,
+}
+
+/// 审计方
+pub struct Auditor {
+    kzg_settings: Arc<FsKZGSettings>,
+    config: AuditConfig,
+    /// 私有随机种子，节点无法预测挑战点
+    seed: [u8; 32],
+}
+
+impl Auditor {
+    pub fn new(kzg_settings: Arc<FsKZGSettings>, config: AuditConfig) -> Self {
+        let mut seed = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut seed);
+        Self::with_seed(kzg_settings, config, seed)
+    }
+
+    /// 使用指定种子创建审计方 (便于复现)
+    pub fn with_seed(kzg_settings: Arc<FsKZGSettings>, config: AuditConfig, seed: [u8; 32]) -> Self {
+        Self {
+            kzg_settings,
+            config,
+            seed,
+        }
+    }
+
+    pub fn config(&self) -> &AuditConfig {
+        &self.config
+    }
+
+    /// 指定时间所在的纪元
+    pub fn epoch_at(&self, time: SystemTime) -> u64 {
+        let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        elapsed.as_secs() / self.config.epoch_duration.as_secs().max(1)
+    }
+
+    /// 当前纪元
+    pub fn current_epoch(&self) -> u64 {
+        self.epoch_at(SystemTime::now())
+    }
+
+    /// 为节点上的某个分片生成挑战
+    pub fn challenge(
+        &self,
+        manifest: &FileManifest,
+        shard_index: usize,
+        node_id: &NodeId,
+        epoch: u64,
+    ) -> Result<AuditChallenge, AuditError> {
+        if shard_index >= manifest.shard_count() {
+            return Err(AuditError::UnknownShard {
+                index: shard_index,
+                total: manifest.shard_count(),
+            });
+        }
+
+        let mut transcript = Transcript::new(AUDIT_DOMAIN);
+        transcript.append_bytes(&self.seed);
+        transcript.append_u64(epoch);
+        transcript.append_bytes(&manifest.root_hash);
+        transcript.append_u64(shard_index as u64);
+        transcript.append_bytes(node_id);
+        let points = transcript.challenge_frs(self.config.points_per_challenge);
+
+        Ok(AuditChallenge {
+            epoch,
+            shard_index,
+            node_id: *node_id,
+            points,
+        })
+    }
+
+    /// 用清单中的承诺验证节点的应答
+    ///
+    /// 返回 `Ok(false)` 表示节点未能证明其持有分片；
+    /// 结构性错误 (纪元、分片或点数不匹配) 同样视为审计失败
+    pub fn verify_response(
+        &self,
+        manifest: &FileManifest,
+        challenge: &AuditChallenge,
+        response: &AuditResponse,
+    ) -> Result<bool, AuditError> {
+        manifest.verify_integrity()?;
+
+        if response.epoch != challenge.epoch
+            || response.shard_index != challenge.shard_index
+            || response.openings.len() != challenge.points.len()
+        {
+            return Ok(false);
+        }
+
+        let commitment = manifest
+            .shard_commitments
+            .get(challenge.shard_index)
+            .ok_or(AuditError::UnknownShard {
+                index: challenge.shard_index,
+                total: manifest.shard_count(),
+            })?;
+
+        for (z, opening) in challenge.points.iter().zip(&response.openings) {
+            let valid = verify_kzg_proof_rust(commitment, z, &opening.value, &opening.proof, &*self.kzg_settings)
+                .map_err(AuditError::KZGError)?;
+            if !valid {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// 存储节点侧：用本地保存的分片数据应答挑战
+pub fn respond_to_challenge(
+    shard: &DataShard,
+    challenge: &AuditChallenge,
+    kzg_settings: &FsKZGSettings,
+) -> Result<AuditResponse, AuditError> {
+    if shard.index != challenge.shard_index {
+        return Err(AuditError::ChallengeMismatch(format!(
+            "挑战分片 {}，本地分片 {}",
+            challenge.shard_index, shard.index
+        )));
+    }
+
+    let blob = shard
+        .to_blob()
+        .map_err(|e| AuditError::InvalidShard(e.to_string()))?;
+
+    let openings = challenge
+        .points
+        .iter()
+        .map(|z| {
+            let (proof, value) =
+                compute_kzg_proof_rust(&blob, z, kzg_settings).map_err(AuditError::KZGError)?;
+            Ok(Opening { value, proof })
+        })
+        .collect::<Result<Vec<_>, AuditError>>()?;
+
+    Ok(AuditResponse {
+        epoch: challenge.epoch,
+        shard_index: challenge.shard_index,
+        openings,
+    })
+}
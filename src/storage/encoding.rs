@@ -0,0 +1,138 @@
+//! 字节保持的 Blob 编码
+//!
+//! 任意字节串都可以无损地装入一个 Blob：
+//!
+//! - 每个 32 字节的域元素最高字节固定为 0，其余 31 字节存放数据，
+//!   保证元素一定小于 BLS12-381 标量域模数
+//! - 第 0 个域元素是头部：版本号、有效数据长度和填充长度
+//! - 剩余 4095 个域元素依次存放数据，末尾用 0 填充
+//!
+//! ```text
+//! 元素 0 (头部): [0x00][版本][数据长度 u32 BE][填充长度 u32 BE][0 ...]
+//! 元素 i (数据): [0x00][31 字节数据]
+//! ```
+//!
+//! [`encode_blob`] 与 [`decode_blob`] 互为逆运算，解码时会检查头部和填充，
+//! 被篡改的 Blob 不会被静默接受。
+
+use kzg::eip_4844::{BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT, FIELD_ELEMENTS_PER_BLOB};
+use rust_kzg_blst::types::fr::FsFr;
+
+use crate::blob::blob_from_bytes;
+
+/// 当前编码格式版本
+pub const ENCODING_VERSION: u8 = 1;
+
+/// 每个域元素可承载的数据字节数
+pub const USABLE_BYTES_PER_FIELD_ELEMENT: usize = BYTES_PER_FIELD_ELEMENT - 1;
+
+/// 头部占用的域元素个数
+pub const HEADER_FIELD_ELEMENTS: usize = 1;
+
+/// 单个 Blob 可承载的最大数据字节数 (4095 × 31 = 126945)
+pub const MAX_PAYLOAD_BYTES_PER_BLOB: usize =
+    (FIELD_ELEMENTS_PER_BLOB - HEADER_FIELD_ELEMENTS) * USABLE_BYTES_PER_FIELD_ELEMENT;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EncodingError {
+    #[error("数据过大: {size} 字节，单个 Blob 最多 {max} 字节")]
+    PayloadTooLarge { size: usize, max: usize },
+
+    #[error("无效的 Blob 大小: {actual}，期望: {expected}")]
+    InvalidBlobSize { expected: usize, actual: usize },
+
+    #[error("不支持的编码版本: {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("无效的头部: 数据长度 {length}，填充长度 {padding}")]
+    InvalidHeader { length: usize, padding: usize },
+
+    #[error("第 {0} 个域元素的最高字节不为 0")]
+    NonCanonicalElement(usize),
+
+    #[error("填充区域包含非零字节")]
+    NonZeroPadding,
+
+    #[error("域元素转换失败: {0}")]
+    FieldElement(String),
+}
+
+/// 将任意字节编码为 `BYTES_PER_BLOB` 字节的 Blob
+pub fn encode_blob(payload: &[u8]) -> Result<Vec<u8>, EncodingError> {
+    if payload.len() > MAX_PAYLOAD_BYTES_PER_BLOB {
+        return Err(EncodingError::PayloadTooLarge {
+            size: payload.len(),
+            max: MAX_PAYLOAD_BYTES_PER_BLOB,
+        });
+    }
+
+    let mut blob = vec![0u8; BYTES_PER_BLOB];
+
+    // 头部
+    let padding = MAX_PAYLOAD_BYTES_PER_BLOB - payload.len();
+    blob[1] = ENCODING_VERSION;
+    blob[2..6].copy_from_slice(&(payload.len() as u32).to_be_bytes());
+    blob[6..10].copy_from_slice(&(padding as u32).to_be_bytes());
+
+    // 数据：每个元素跳过最高字节
+    let data_area = &mut blob[HEADER_FIELD_ELEMENTS * BYTES_PER_FIELD_ELEMENT..];
+    for (element, chunk) in data_area
+        .chunks_exact_mut(BYTES_PER_FIELD_ELEMENT)
+        .zip(payload.chunks(USABLE_BYTES_PER_FIELD_ELEMENT))
+    {
+        element[1..1 + chunk.len()].copy_from_slice(chunk);
+    }
+
+    Ok(blob)
+}
+
+/// 将任意字节编码为域元素形式的 Blob，可直接用于生成承诺
+pub fn encode_blob_fr(payload: &[u8]) -> Result<Vec<FsFr>, EncodingError> {
+    let bytes = encode_blob(payload)?;
+    blob_from_bytes(&bytes).map_err(EncodingError::FieldElement)
+}
+
+/// 从 [`encode_blob`] 生成的 Blob 中取回原始字节
+pub fn decode_blob(blob: &[u8]) -> Result<Vec<u8>, EncodingError> {
+    if blob.len() != BYTES_PER_BLOB {
+        return Err(EncodingError::InvalidBlobSize {
+            expected: BYTES_PER_BLOB,
+            actual: blob.len(),
+        });
+    }
+
+    if let Some(index) = blob
+        .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .position(|element| element[0] != 0)
+    {
+        return Err(EncodingError::NonCanonicalElement(index));
+    }
+
+    // 解析头部
+    let header = &blob[..HEADER_FIELD_ELEMENTS * BYTES_PER_FIELD_ELEMENT];
+    if header[1] != ENCODING_VERSION {
+        return Err(EncodingError::UnsupportedVersion(header[1]));
+    }
+
+    let length = u32::from_be_bytes([header[2], header[3], header[4], header[5]]) as usize;
+    let padding = u32::from_be_bytes([header[6], header[7], header[8], header[9]]) as usize;
+    if length.checked_add(padding) != Some(MAX_PAYLOAD_BYTES_PER_BLOB) {
+        return Err(EncodingError::InvalidHeader { length, padding });
+    }
+    if header[10..].iter().any(|&b| b != 0) {
+        return Err(EncodingError::NonZeroPadding);
+    }
+
+    // 收集数据区的有效字节
+    let mut payload = Vec::with_capacity(MAX_PAYLOAD_BYTES_PER_BLOB);
+    for element in blob[header.len()..].chunks_exact(BYTES_PER_FIELD_ELEMENT) {
+        payload.extend_from_slice(&element[1..]);
+    }
+
+    if payload[length..].iter().any(|&b| b != 0) {
+        return Err(EncodingError::NonZeroPadding);
+    }
+    payload.truncate(length);
+
+    Ok(payload)
+}
@@ -0,0 +1,255 @@
+//! 基于 FFT 的系统 Reed–Solomon 纠删码
+//!
+//! 编码按域元素位置逐列进行：第 e 列由 k 个数据分片的第 e 个元素组成，
+//! 视为次数小于 K 的多项式在 K 阶单位根子群上的取值 (K 为 k 向上取整到 2 的幂，
+//! 不足的位置补已知为 0 的虚拟分片)。校验分片是同一多项式在 N 阶单位根中
+//! 不属于该子群的点上的取值，其中 N ≥ K + m。
+//!
+//! ```text
+//! 数据分片 i  → ω_N^(i·N/K)          (系统码：数据分片原样保留)
+//! 校验分片 j  → 第 j 个不在子群中的 ω_N^t
+//! ```
+//!
+//! 任意 k 个不同分片加上虚拟 0 分片恰好确定该多项式，
+//! 因此可以通过拉格朗日插值恢复全部数据分片。
+
+use kzg::{FFTFr, FFTSettings, Fr};
+use rust_kzg_blst::types::{fft_settings::FsFFTSettings, fr::FsFr};
+use serde::{Deserialize, Serialize};
+
+/// 纠删码参数：k 个数据分片 + m 个校验分片
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErasureParams {
+    /// 数据分片数 k
+    pub data_shards: usize,
+    /// 校验分片数 m
+    pub parity_shards: usize,
+}
+
+impl ErasureParams {
+    pub fn new(data_shards: usize, parity_shards: usize) -> Self {
+        Self {
+            data_shards,
+            parity_shards,
+        }
+    }
+
+    /// 分片总数 n = k + m
+    pub fn total_shards(&self) -> usize {
+        self.data_shards + self.parity_shards
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ErasureError {
+    #[error("无效的纠删码参数: {0}")]
+    InvalidParams(String),
+
+    #[error("可用分片不足: 需要 {required}，可用 {available}")]
+    InsufficientShards { required: usize, available: usize },
+
+    #[error("无效的分片索引: {index}，分片总数 {total}")]
+    InvalidShardIndex { index: usize, total: usize },
+
+    #[error("分片长度不一致: 期望 {expected}，实际 {actual}")]
+    InvalidShardLength { expected: usize, actual: usize },
+
+    #[error("FFT 计算失败: {0}")]
+    Fft(String),
+}
+
+/// 系统 Reed–Solomon 编解码器
+pub struct ReedSolomon {
+    params: ErasureParams,
+    fft_settings: FsFFTSettings,
+    /// 数据子群大小 K
+    data_domain_size: usize,
+    /// 完整求值域大小 N
+    domain_size: usize,
+    /// 每个分片索引对应的求值点
+    points: Vec<FsFr>,
+    /// 虚拟 0 分片的求值点
+    virtual_points: Vec<FsFr>,
+    /// 校验分片在 N 阶求值域中的位置
+    parity_positions: Vec<usize>,
+}
+
+impl ReedSolomon {
+    pub fn new(params: ErasureParams) -> Result<Self, ErasureError> {
+        if params.data_shards == 0 {
+            return Err(ErasureError::InvalidParams("至少需要 1 个数据分片".to_string()));
+        }
+
+        let data_domain_size = params.data_shards.next_power_of_two();
+        let domain_size = (data_domain_size + params.parity_shards)
+            .next_power_of_two()
+            .max(2);
+        let stride = domain_size / data_domain_size;
+
+        let fft_settings = FsFFTSettings::new(domain_size.trailing_zeros() as usize)
+            .map_err(ErasureError::Fft)?;
+        let root = |t: usize| fft_settings.get_roots_of_unity_at(t);
+
+        let parity_positions: Vec<usize> = (0..domain_size)
+            .filter(|t| t % stride != 0)
+            .take(params.parity_shards)
+            .collect();
+
+        let points = (0..params.data_shards)
+            .map(|i| root(i * stride))
+            .chain(parity_positions.iter().map(|&t| root(t)))
+            .collect();
+        let virtual_points = (params.data_shards..data_domain_size)
+            .map(|i| root(i * stride))
+            .collect();
+
+        Ok(Self {
+            params,
+            fft_settings,
+            data_domain_size,
+            domain_size,
+            points,
+            virtual_points,
+            parity_positions,
+        })
+    }
+
+    pub fn params(&self) -> ErasureParams {
+        self.params
+    }
+
+    /// 由 k 个等长数据分片计算 m 个校验分片
+    pub fn encode(&self, data: &[Vec<FsFr>]) -> Result<Vec<Vec<FsFr>>, ErasureError> {
+        if data.len() != self.params.data_shards {
+            return Err(ErasureError::InvalidParams(format!(
+                "需要 {} 个数据分片，实际 {}",
+                self.params.data_shards,
+                data.len()
+            )));
+        }
+
+        let len = data[0].len();
+        if let Some(shard) = data.iter().find(|shard| shard.len() != len) {
+            return Err(ErasureError::InvalidShardLength {
+                expected: len,
+                actual: shard.len(),
+            });
+        }
+
+        let mut parity = vec![Vec::with_capacity(len); self.params.parity_shards];
+        if parity.is_empty() {
+            return Ok(parity);
+        }
+
+        let mut column = vec![FsFr::zero(); self.data_domain_size];
+        for e in 0..len {
+            for (value, shard) in column.iter_mut().zip(data) {
+                *value = shard[e];
+            }
+
+            // 子群上的取值 → 系数 → 完整求值域上的取值
+            let mut coeffs = self
+                .fft_settings
+                .fft_fr(&column, true)
+                .map_err(ErasureError::Fft)?;
+            coeffs.resize(self.domain_size, FsFr::zero());
+            let evals = self
+                .fft_settings
+                .fft_fr(&coeffs, false)
+                .map_err(ErasureError::Fft)?;
+
+            for (shard, &t) in parity.iter_mut().zip(&self.parity_positions) {
+                shard.push(evals[t]);
+            }
+        }
+
+        Ok(parity)
+    }
+
+    /// 由任意 k 个不同分片恢复全部数据分片
+    ///
+    /// `available` 中的每一项为 (分片索引, 分片数据)，重复的索引会被忽略
+    pub fn reconstruct(&self, available: &[(usize, Vec<FsFr>)]) -> Result<Vec<Vec<FsFr>>, ErasureError> {
+        let k = self.params.data_shards;
+        let total = self.params.total_shards();
+
+        let mut chosen: Vec<(usize, &[FsFr])> = Vec::with_capacity(available.len());
+        for (index, shard) in available {
+            if *index >= total {
+                return Err(ErasureError::InvalidShardIndex { index: *index, total });
+            }
+            if !chosen.iter().any(|(i, _)| i == index) {
+                chosen.push((*index, shard));
+            }
+        }
+
+        if chosen.len() < k {
+            return Err(ErasureError::InsufficientShards {
+                required: k,
+                available: chosen.len(),
+            });
+        }
+
+        // 优先使用数据分片，恰好取 k 个
+        chosen.sort_by_key(|(index, _)| *index);
+        chosen.truncate(k);
+
+        let len = chosen[0].1.len();
+        if let Some((_, shard)) = chosen.iter().find(|(_, shard)| shard.len() != len) {
+            return Err(ErasureError::InvalidShardLength {
+                expected: len,
+                actual: shard.len(),
+            });
+        }
+
+        let mut data: Vec<Option<Vec<FsFr>>> = vec![None; k];
+        for (index, shard) in &chosen {
+            if *index < k {
+                data[*index] = Some(shard.to_vec());
+            }
+        }
+
+        let xs: Vec<FsFr> = chosen
+            .iter()
+            .map(|(index, _)| self.points[*index])
+            .chain(self.virtual_points.iter().copied())
+            .collect();
+
+        for (index, slot) in data.iter_mut().enumerate() {
+            if slot.is_some() {
+                continue;
+            }
+
+            // 虚拟分片取值为 0，只需要真实分片的权重
+            let weights = lagrange_weights(&xs, chosen.len(), &self.points[index]);
+            let mut shard = vec![FsFr::zero(); len];
+            for (weight, (_, values)) in weights.iter().zip(&chosen) {
+                for (out, value) in shard.iter_mut().zip(values.iter()) {
+                    *out = out.add(&weight.mul(value));
+                }
+            }
+            *slot = Some(shard);
+        }
+
+        Ok(data.into_iter().flatten().collect())
+    }
+}
+
+/// 计算前 `count` 个插值点的拉格朗日基函数在 `x` 处的取值
+fn lagrange_weights(xs: &[FsFr], count: usize, x: &FsFr) -> Vec<FsFr> {
+    xs.iter()
+        .take(count)
+        .enumerate()
+        .map(|(i, xi)| {
+            let mut numerator = FsFr::one();
+            let mut denominator = FsFr::one();
+            for (j, xj) in xs.iter().enumerate() {
+                if i != j {
+                    numerator = numerator.mul(&x.sub(xj));
+                    denominator = denominator.mul(&xi.sub(xj));
+                }
+            }
+            numerator.mul(&denominator.inverse())
+        })
+        .collect()
+}
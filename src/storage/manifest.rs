@@ -0,0 +1,253 @@
+//! 文件级承诺清单
+//!
+//! [`FileManifest`] 把一个文件的全部分片承诺 (按分片索引排列)、纠删码参数和
+//! 原始长度绑定在一起，并给出覆盖这些内容的根哈希：
+//!
+//! ```text
+//! root = SHA-256("KZG_FILE_MANIFEST_V1" || 版本 || 原始长度 u64 BE
+//!                || k u32 BE || m u32 BE || 承诺_0 || ... || 承诺_{n-1})
+//! ```
+//!
+//! 客户端只需持有清单 (或只需信任根哈希)，就可以用 [`verify_manifest`]
+//! 校验下载到的任意一个分片。
+
+use kzg::{eip_4844::blob_to_kzg_commitment_rust, G1};
+use rust_kzg_blst::types::{g1::FsG1, kzg_settings::FsKZGSettings};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::erasure::ErasureParams;
+use super::shard::{DataShard, ShardKind};
+
+/// 当前清单格式版本
+pub const MANIFEST_VERSION: u8 = 1;
+
+const MANIFEST_DOMAIN: &[u8] = b"KZG_FILE_MANIFEST_V1";
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ManifestError {
+    #[error("不支持的清单版本: {0}")]
+    UnsupportedVersion(u8),
+
+    #[error("分片数量不匹配: 期望 {expected}，实际 {actual}")]
+    InvalidShardCount { expected: usize, actual: usize },
+
+    #[error("清单根哈希不匹配")]
+    RootMismatch,
+
+    #[error("未知分片: 索引 {index}，分片总数 {total}")]
+    UnknownShard { index: usize, total: usize },
+
+    #[error("分片 {0} 的类型与清单不符")]
+    KindMismatch(usize),
+
+    #[error("分片 {0} 的数据与清单中的承诺不匹配")]
+    CommitmentMismatch(usize),
+
+    #[error("无效的承诺: {0}")]
+    InvalidCommitment(String),
+
+    #[error("KZG 操作错误: {0}")]
+    KZGError(String),
+
+    #[error("序列化错误: {0}")]
+    Serialization(String),
+}
+
+/// 文件承诺清单
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileManifest {
+    /// 清单格式版本
+    pub version: u8,
+    /// 原始文件长度 (字节)
+    pub original_length: u64,
+    /// 纠删码参数
+    pub erasure: ErasureParams,
+    /// 分片承诺，下标即分片索引
+    pub shard_commitments: Vec<FsG1>,
+    /// 覆盖以上全部字段的根哈希
+    pub root_hash: [u8; 32],
+}
+
+/// JSON 序列化格式 (承诺和哈希使用十六进制)
+#[derive(Serialize, Deserialize)]
+struct ManifestJson {
+    version: u8,
+    original_length: u64,
+    erasure: ErasureParams,
+    shard_commitments: Vec<String>,
+    root_hash: String,
+}
+
+impl FileManifest {
+    /// 由按索引排列的分片承诺创建清单
+    pub fn new(
+        original_length: u64,
+        erasure: ErasureParams,
+        shard_commitments: Vec<FsG1>,
+    ) -> Result<Self, ManifestError> {
+        if shard_commitments.len() != erasure.total_shards() {
+            return Err(ManifestError::InvalidShardCount {
+                expected: erasure.total_shards(),
+                actual: shard_commitments.len(),
+            });
+        }
+
+        let mut manifest = Self {
+            version: MANIFEST_VERSION,
+            original_length,
+            erasure,
+            shard_commitments,
+            root_hash: [0u8; 32],
+        };
+        manifest.root_hash = manifest.compute_root();
+        Ok(manifest)
+    }
+
+    /// 由 [`super::ShardManager::shard_file`] 生成的完整分片集合创建清单
+    pub fn from_shards(
+        shards: &[DataShard],
+        erasure: ErasureParams,
+        original_length: u64,
+    ) -> Result<Self, ManifestError> {
+        let total = erasure.total_shards();
+        let mut commitments = vec![None; total];
+
+        for shard in shards {
+            let slot = commitments
+                .get_mut(shard.index)
+                .ok_or(ManifestError::UnknownShard { index: shard.index, total })?;
+            *slot = Some(shard.commitment);
+        }
+
+        let commitments: Vec<FsG1> = commitments.into_iter().flatten().collect();
+        Self::new(original_length, erasure, commitments)
+    }
+
+    /// 分片总数
+    pub fn shard_count(&self) -> usize {
+        self.shard_commitments.len()
+    }
+
+    /// 指定索引的分片应当是数据分片还是校验分片
+    pub fn shard_kind(&self, index: usize) -> Option<ShardKind> {
+        if index < self.erasure.data_shards {
+            Some(ShardKind::Data)
+        } else if index < self.shard_count() {
+            Some(ShardKind::Parity)
+        } else {
+            None
+        }
+    }
+
+    /// 计算根哈希
+    pub fn compute_root(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(MANIFEST_DOMAIN);
+        hasher.update([self.version]);
+        hasher.update(self.original_length.to_be_bytes());
+        hasher.update((self.erasure.data_shards as u32).to_be_bytes());
+        hasher.update((self.erasure.parity_shards as u32).to_be_bytes());
+        for commitment in &self.shard_commitments {
+            hasher.update(commitment.to_bytes());
+        }
+
+        hasher.finalize().into()
+    }
+
+    /// 检查清单自身的一致性 (版本、分片数量和根哈希)
+    pub fn verify_integrity(&self) -> Result<(), ManifestError> {
+        if self.version != MANIFEST_VERSION {
+            return Err(ManifestError::UnsupportedVersion(self.version));
+        }
+        if self.shard_count() != self.erasure.total_shards() {
+            return Err(ManifestError::InvalidShardCount {
+                expected: self.erasure.total_shards(),
+                actual: self.shard_count(),
+            });
+        }
+        if self.compute_root() != self.root_hash {
+            return Err(ManifestError::RootMismatch);
+        }
+        Ok(())
+    }
+
+    /// 序列化为 JSON
+    pub fn to_json(&self) -> Result<String, ManifestError> {
+        let json = ManifestJson {
+            version: self.version,
+            original_length: self.original_length,
+            erasure: self.erasure,
+            shard_commitments: self
+                .shard_commitments
+                .iter()
+                .map(|c| hex::encode(c.to_bytes()))
+                .collect(),
+            root_hash: hex::encode(self.root_hash),
+        };
+
+        serde_json::to_string_pretty(&json).map_err(|e| ManifestError::Serialization(e.to_string()))
+    }
+
+    /// 从 JSON 解析清单，并检查其一致性
+    pub fn from_json(json: &str) -> Result<Self, ManifestError> {
+        let parsed: ManifestJson =
+            serde_json::from_str(json).map_err(|e| ManifestError::Serialization(e.to_string()))?;
+
+        let shard_commitments = parsed
+            .shard_commitments
+            .iter()
+            .map(|c| {
+                let bytes = hex::decode(c).map_err(|e| ManifestError::InvalidCommitment(e.to_string()))?;
+                FsG1::from_bytes(&bytes).map_err(ManifestError::InvalidCommitment)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let root_hash: [u8; 32] = hex::decode(&parsed.root_hash)
+            .map_err(|e| ManifestError::Serialization(e.to_string()))?
+            .try_into()
+            .map_err(|_| ManifestError::Serialization("根哈希长度必须为 32 字节".to_string()))?;
+
+        let manifest = Self {
+            version: parsed.version,
+            original_length: parsed.original_length,
+            erasure: parsed.erasure,
+            shard_commitments,
+            root_hash,
+        };
+        manifest.verify_integrity()?;
+        Ok(manifest)
+    }
+}
+
+/// 用清单校验下载到的分片
+///
+/// 分片自带的 `commitment` 字段不被信任：承诺由分片数据重新计算，
+/// 再与清单中相同索引的承诺比较。
+pub fn verify_manifest(
+    manifest: &FileManifest,
+    shard: &DataShard,
+    kzg_settings: &FsKZGSettings,
+) -> Result<(), ManifestError> {
+    manifest.verify_integrity()?;
+
+    let expected_kind = manifest.shard_kind(shard.index).ok_or(ManifestError::UnknownShard {
+        index: shard.index,
+        total: manifest.shard_count(),
+    })?;
+    if shard.kind != expected_kind {
+        return Err(ManifestError::KindMismatch(shard.index));
+    }
+
+    let blob = shard
+        .to_blob()
+        .map_err(|_| ManifestError::CommitmentMismatch(shard.index))?;
+    let commitment =
+        blob_to_kzg_commitment_rust(&blob, kzg_settings).map_err(ManifestError::KZGError)?;
+
+    if commitment != manifest.shard_commitments[shard.index] {
+        return Err(ManifestError::CommitmentMismatch(shard.index));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+//! 第20章：去中心化存储验证系统
+//!
+//! - [`encoding`]：字节保持的 Blob 编码 (31 字节/域元素 + 长度头部)
+//! - [`erasure`]：基于 FFT 的系统 Reed–Solomon 纠删码
+//! - [`shard`]：文件分片、KZG 承诺与文件恢复
+//! - [`manifest`]：文件级承诺清单与分片校验
+//! - [`audit`]：基于 KZG 打开证明的存储证明审计
+//! - [`node`]：存储节点、节点选择策略与分片放置
+//! - [`registry`]：节点注册表持久化 (内存 / JSON 文件)
+//! - [`repair`]：副本不足分片的修复与重新复制
+
+pub mod audit;
+pub mod encoding;
+pub mod erasure;
+pub mod manifest;
+pub mod node;
+pub mod registry;
+pub mod repair;
+pub mod shard;
+
+pub use audit::{
+    respond_to_challenge, AuditChallenge, AuditConfig, AuditError, AuditResponse, Auditor, Opening,
+};
+pub use encoding::{decode_blob, encode_blob, encode_blob_fr, EncodingError, MAX_PAYLOAD_BYTES_PER_BLOB};
+pub use erasure::{ErasureError, ErasureParams, ReedSolomon};
+pub use manifest::{verify_manifest, FileManifest, ManifestError};
+pub use node::{NodeError, NodeManager, NodeSelectionStrategy, StorageNode};
+pub use registry::{
+    FileRegistry, InMemoryRegistry, NodeRegistry, RegistryError, RegistryState, ShardPlacement,
+};
+pub use repair::{
+    InMemoryShardStore, RepairConfig, RepairError, RepairMetrics, RepairReport, RepairScheduler, RepairStats,
+    ShardStore, UnderReplicatedShard,
+};
+pub use shard::{DataShard, ShardConfig, ShardError, ShardKind, ShardManager};
+
+/// 存储节点ID
+pub type NodeId = [u8; 32];
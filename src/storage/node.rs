@@ -0,0 +1,431 @@
+//! 存储节点与节点选择
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use super::registry::{InMemoryRegistry, NodeRegistry, RegistryError, RegistryState, ShardPlacement};
+use super::shard::DataShard;
+use super::NodeId;
+
+/// 存储节点信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageNode {
+    /// 节点ID
+    #[serde(with = "super::registry::hex_id")]
+    pub node_id: NodeId,
+    /// 网络地址
+    pub address: String,
+    /// 存储容量
+    pub capacity: u64,
+    /// 已用容量
+    pub used_capacity: u64,
+    /// 信誉评分
+    pub reputation: f64,
+    /// 在线状态
+    pub is_online: bool,
+}
+
+impl StorageNode {
+    /// 检查节点是否有足够容量存储分片
+    pub fn has_capacity_for_shard(&self, shard: &DataShard) -> bool {
+        let required_space = shard.data_chunk.len() as u64;
+        self.capacity.saturating_sub(self.used_capacity) >= required_space
+    }
+
+    /// 检查节点参数：容量必须为正，信誉必须是有限值
+    pub fn validate(&self) -> Result<(), NodeError> {
+        if self.capacity == 0 {
+            return Err(NodeError::InvalidNode(format!("{}: capacity must be positive", hex::encode(self.node_id))));
+        }
+        if !self.reputation.is_finite() {
+            return Err(NodeError::InvalidNode(format!(
+                "{}: reputation {} is not finite",
+                hex::encode(self.node_id),
+                self.reputation
+            )));
+        }
+        Ok(())
+    }
+
+    /// 根据审计结果更新信誉 (指数滑动平均，结果保持在 0~1 之间)
+    pub fn apply_audit_result(&mut self, passed: bool, weight: f64) {
+        let weight = weight.clamp(0.0, 1.0);
+        let outcome = if passed { 1.0 } else { 0.0 };
+        self.reputation = (self.reputation * (1.0 - weight) + outcome * weight).clamp(0.0, 1.0);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum NodeSelectionStrategy {
+    /// 基于信誉的选择
+    ReputationBased { min_reputation: f64 },
+    /// 负载均衡选择
+    LoadBalanced,
+    /// 混合策略
+    Hybrid,
+}
+
+/// 存储节点管理器
+///
+/// 节点状态和分片放置信息在内存中维护。每次变更先在副本上构造新状态并写入 [`NodeRegistry`]，
+/// 写入成功后才替换内存状态，写入失败时内存与注册表保持一致。
+pub struct NodeManager {
+    /// 在线节点列表
+    nodes: Arc<RwLock<HashMap<NodeId, StorageNode>>>,
+    /// 分片放置信息 (分片ID → 放置记录)
+    placements: Arc<RwLock<HashMap<[u8; 32], ShardPlacement>>>,
+    /// 节点选择策略
+    selection_strategy: NodeSelectionStrategy,
+    /// 持久化存储
+    registry: Arc<dyn NodeRegistry>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NodeError {
+    #[error("可用节点不足: 需要 {required}，可用 {available}")]
+    InsufficientNodes { required: usize, available: usize },
+
+    #[error("未知节点: {0}")]
+    UnknownNode(String),
+
+    #[error("无效的节点: {0}")]
+    InvalidNode(String),
+
+    #[error("注册表错误: {0}")]
+    Registry(#[from] RegistryError),
+}
+
+impl NodeManager {
+    /// 使用给定节点创建管理器 (内存注册表)
+    ///
+    /// 与 [`Self::register_node`] 相同，容量为 0 或信誉不是有限值时返回 [`NodeError::InvalidNode`]。
+    pub fn new(
+        nodes: HashMap<NodeId, StorageNode>,
+        selection_strategy: NodeSelectionStrategy,
+    ) -> Result<Self, NodeError> {
+        for node in nodes.values() {
+            node.validate()?;
+        }
+
+        Ok(Self {
+            nodes: Arc::new(RwLock::new(nodes)),
+            placements: Arc::new(RwLock::new(HashMap::new())),
+            selection_strategy,
+            registry: Arc::new(InMemoryRegistry::new()),
+        })
+    }
+
+    /// 从注册表恢复节点和放置信息
+    ///
+    /// 容量为 0 或信誉不是有限值的节点记录视为损坏，返回 [`NodeError::InvalidNode`]。
+    pub fn with_registry(
+        registry: Arc<dyn NodeRegistry>,
+        selection_strategy: NodeSelectionStrategy,
+    ) -> Result<Self, NodeError> {
+        let state = registry.load()?;
+        for node in &state.nodes {
+            node.validate()?;
+        }
+        let nodes = state.nodes.into_iter().map(|node| (node.node_id, node)).collect();
+        let placements = state
+            .placements
+            .into_iter()
+            .map(|placement| (placement.shard_id, placement))
+            .collect();
+
+        Ok(Self {
+            nodes: Arc::new(RwLock::new(nodes)),
+            placements: Arc::new(RwLock::new(placements)),
+            selection_strategy,
+            registry,
+        })
+    }
+
+    /// 节点表 (共享引用)
+    pub fn nodes(&self) -> &Arc<RwLock<HashMap<NodeId, StorageNode>>> {
+        &self.nodes
+    }
+
+    pub fn selection_strategy(&self) -> &NodeSelectionStrategy {
+        &self.selection_strategy
+    }
+
+    /// 注册新节点或更新已有节点
+    ///
+    /// 容量为 0 或信誉不是有限值时返回 [`NodeError::InvalidNode`]。
+    pub async fn register_node(&self, node: StorageNode) -> Result<(), NodeError> {
+        node.validate()?;
+        let mut nodes = self.nodes.write().await;
+        let placements = self.placements.read().await;
+
+        let mut next = nodes.clone();
+        next.insert(node.node_id, node);
+
+        self.persist(&next, &placements)?;
+        *nodes = next;
+        Ok(())
+    }
+
+    /// 节点永久离开网络：移除节点及其持有的全部副本记录
+    pub async fn remove_node(&self, node_id: &NodeId) -> Result<Option<StorageNode>, NodeError> {
+        let mut nodes = self.nodes.write().await;
+        let mut placements = self.placements.write().await;
+
+        let mut next_nodes = nodes.clone();
+        let mut next_placements = placements.clone();
+        let removed = next_nodes.remove(node_id);
+        for placement in next_placements.values_mut() {
+            placement.nodes.retain(|id| id != node_id);
+        }
+
+        self.persist(&next_nodes, &next_placements)?;
+        *nodes = next_nodes;
+        *placements = next_placements;
+        Ok(removed)
+    }
+
+    /// 节点临时上线/下线，副本记录保留
+    pub async fn set_online(&self, node_id: &NodeId, is_online: bool) -> Result<(), NodeError> {
+        let mut nodes = self.nodes.write().await;
+        let placements = self.placements.read().await;
+
+        let mut next = nodes.clone();
+        let node = next
+            .get_mut(node_id)
+            .ok_or_else(|| NodeError::UnknownNode(hex::encode(node_id)))?;
+        node.is_online = is_online;
+
+        self.persist(&next, &placements)?;
+        *nodes = next;
+        Ok(())
+    }
+
+    /// 记录一次审计结果，返回节点更新后的信誉 (未知节点返回 `None`)
+    ///
+    /// 审计失败会按权重衰减信誉，见 [`StorageNode::apply_audit_result`]
+    pub async fn record_audit(&self, node_id: &NodeId, passed: bool, weight: f64) -> Result<Option<f64>, NodeError> {
+        let mut nodes = self.nodes.write().await;
+        let placements = self.placements.read().await;
+
+        let mut next = nodes.clone();
+        let reputation = match next.get_mut(node_id) {
+            Some(node) => {
+                node.apply_audit_result(passed, weight);
+                node.reputation
+            }
+            None => return Ok(None),
+        };
+
+        self.persist(&next, &placements)?;
+        *nodes = next;
+        Ok(Some(reputation))
+    }
+
+    /// 记录分片放置到指定节点，并累加节点已用容量
+    ///
+    /// 已经持有该分片的节点不会重复计入
+    pub async fn record_placement(&self, shard: &DataShard, node_ids: &[NodeId]) -> Result<(), NodeError> {
+        let mut nodes = self.nodes.write().await;
+        let mut placements = self.placements.write().await;
+
+        if let Some(unknown) = node_ids.iter().find(|id| !nodes.contains_key(*id)) {
+            return Err(NodeError::UnknownNode(hex::encode(unknown)));
+        }
+
+        let mut next_nodes = nodes.clone();
+        let mut next_placements = placements.clone();
+        let size = shard.data_chunk.len() as u64;
+        let placement = next_placements.entry(shard.shard_id).or_insert_with(|| ShardPlacement {
+            shard_id: shard.shard_id,
+            size,
+            nodes: Vec::new(),
+        });
+
+        for node_id in node_ids {
+            if placement.nodes.contains(node_id) {
+                continue;
+            }
+            placement.nodes.push(*node_id);
+            if let Some(node) = next_nodes.get_mut(node_id) {
+                node.used_capacity += size;
+            }
+        }
+
+        self.persist(&next_nodes, &next_placements)?;
+        *nodes = next_nodes;
+        *placements = next_placements;
+        Ok(())
+    }
+
+    /// 从节点上移除一个副本，并释放其占用的容量
+    pub async fn remove_replica(&self, shard_id: &[u8; 32], node_id: &NodeId) -> Result<bool, NodeError> {
+        let mut nodes = self.nodes.write().await;
+        let mut placements = self.placements.write().await;
+
+        let Some(placement) = placements.get(shard_id) else {
+            return Ok(false);
+        };
+        if !placement.nodes.contains(node_id) {
+            return Ok(false);
+        }
+
+        let mut next_nodes = nodes.clone();
+        let mut next_placements = placements.clone();
+        let placement = next_placements.get_mut(shard_id).expect("placement exists");
+        placement.nodes.retain(|id| id != node_id);
+        if let Some(node) = next_nodes.get_mut(node_id) {
+            node.used_capacity = node.used_capacity.saturating_sub(placement.size);
+        }
+
+        self.persist(&next_nodes, &next_placements)?;
+        *nodes = next_nodes;
+        *placements = next_placements;
+        Ok(true)
+    }
+
+    /// 查询分片的放置信息
+    pub async fn placement(&self, shard_id: &[u8; 32]) -> Option<ShardPlacement> {
+        self.placements.read().await.get(shard_id).cloned()
+    }
+
+    /// 全部分片的放置信息
+    pub async fn placements(&self) -> Vec<ShardPlacement> {
+        self.placements.read().await.values().cloned().collect()
+    }
+
+    /// 将当前状态写入注册表 (按 ID 排序，保证快照稳定)
+    fn persist(
+        &self,
+        nodes: &HashMap<NodeId, StorageNode>,
+        placements: &HashMap<[u8; 32], ShardPlacement>,
+    ) -> Result<(), NodeError> {
+        let mut state = RegistryState {
+            nodes: nodes.values().cloned().collect(),
+            placements: placements.values().cloned().collect(),
+        };
+        state.nodes.sort_by_key(|node| node.node_id);
+        state.placements.sort_by_key(|placement| placement.shard_id);
+
+        self.registry.save(&state)?;
+        Ok(())
+    }
+
+    /// 选择存储节点
+    pub async fn select_storage_nodes(&self, shard: &DataShard, replica_count: usize) -> Result<Vec<NodeId>, NodeError> {
+        self.select_storage_nodes_excluding(shard, replica_count, &[]).await
+    }
+
+    /// 选择存储节点，跳过 `exclude` 中的节点 (例如已经持有该分片的节点)
+    pub async fn select_storage_nodes_excluding(
+        &self,
+        shard: &DataShard,
+        replica_count: usize,
+        exclude: &[NodeId],
+    ) -> Result<Vec<NodeId>, NodeError> {
+        let nodes = self.nodes.read().await;
+        let available_nodes: Vec<_> = nodes
+            .values()
+            .filter(|node| node.is_online && node.has_capacity_for_shard(shard))
+            .filter(|node| !exclude.contains(&node.node_id))
+            .collect();
+
+        if available_nodes.len() < replica_count {
+            return Err(NodeError::InsufficientNodes {
+                required: replica_count,
+                available: available_nodes.len(),
+            });
+        }
+
+        let selected_nodes = match &self.selection_strategy {
+            NodeSelectionStrategy::ReputationBased { min_reputation } => {
+                self.select_by_reputation(&available_nodes, replica_count, *min_reputation)?
+            }
+            NodeSelectionStrategy::LoadBalanced => {
+                self.select_by_load(&available_nodes, replica_count)
+            }
+            NodeSelectionStrategy::Hybrid => {
+                self.select_hybrid(&available_nodes, replica_count)
+            }
+        };
+
+        Ok(selected_nodes)
+    }
+
+    /// 基于信誉选择节点
+    ///
+    /// 信誉达标的节点少于 `count` 时返回 [`NodeError::InsufficientNodes`]，`available` 为达标节点数。
+    fn select_by_reputation(
+        &self,
+        nodes: &[&StorageNode],
+        count: usize,
+        min_reputation: f64,
+    ) -> Result<Vec<NodeId>, NodeError> {
+        let mut qualified_nodes: Vec<_> = nodes
+            .iter()
+            .filter(|node| node.reputation >= min_reputation)
+            .collect();
+
+        if qualified_nodes.len() < count {
+            return Err(NodeError::InsufficientNodes {
+                required: count,
+                available: qualified_nodes.len(),
+            });
+        }
+
+        // 按信誉排序
+        qualified_nodes.sort_by(|a, b| b.reputation.total_cmp(&a.reputation));
+
+        Ok(qualified_nodes
+            .into_iter()
+            .take(count)
+            .map(|node| node.node_id)
+            .collect())
+    }
+
+    /// 基于负载选择节点
+    fn select_by_load(&self, nodes: &[&StorageNode], count: usize) -> Vec<NodeId> {
+        let mut load_sorted: Vec<_> = nodes.iter().collect();
+
+        // 按使用率排序（使用率低的优先）
+        load_sorted.sort_by(|a, b| {
+            let load_a = a.used_capacity as f64 / a.capacity as f64;
+            let load_b = b.used_capacity as f64 / b.capacity as f64;
+            load_a.total_cmp(&load_b)
+        });
+
+        load_sorted
+            .into_iter()
+            .take(count)
+            .map(|node| node.node_id)
+            .collect()
+    }
+
+    /// 混合策略选择
+    fn select_hybrid(&self, nodes: &[&StorageNode], count: usize) -> Vec<NodeId> {
+        let mut scored_nodes: Vec<_> = nodes
+            .iter()
+            .map(|node| {
+                let load_ratio = node.used_capacity as f64 / node.capacity as f64;
+                let load_score = 1.0 - load_ratio; // 负载越低分数越高
+                let reputation_score = node.reputation;
+
+                // 综合评分：负载权重0.4，信誉权重0.6
+                let total_score = load_score * 0.4 + reputation_score * 0.6;
+
+                (node, total_score)
+            })
+            .collect();
+
+        // 按综合评分排序
+        scored_nodes.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        scored_nodes
+            .into_iter()
+            .take(count)
+            .map(|(node, _)| node.node_id)
+            .collect()
+    }
+}
@@ -0,0 +1,164 @@
+//! 节点注册表持久化
+//!
+//! [`NodeManager`](super::NodeManager) 通过 [`NodeRegistry`] 保存节点状态和分片放置信息，
+//! 每次变更后写入完整快照：
+//!
+//! - [`InMemoryRegistry`]：进程内存储，适合测试和演示
+//! - [`FileRegistry`]：JSON 文件存储，先写临时文件再原子替换，重启后放置决策依然有效
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use super::node::StorageNode;
+use super::NodeId;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("注册表读写失败 {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("注册表格式错误: {0}")]
+    Format(String),
+}
+
+/// 单个分片的放置信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardPlacement {
+    #[serde(with = "hex_id")]
+    pub shard_id: [u8; 32],
+    /// 分片大小 (字节)
+    pub size: u64,
+    /// 持有副本的节点
+    #[serde(with = "hex_ids")]
+    pub nodes: Vec<NodeId>,
+}
+
+/// 注册表快照
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegistryState {
+    pub nodes: Vec<StorageNode>,
+    pub placements: Vec<ShardPlacement>,
+}
+
+/// 可插拔的节点注册表存储
+pub trait NodeRegistry: Send + Sync {
+    /// 读取最近一次保存的快照 (从未保存时返回空快照)
+    fn load(&self) -> Result<RegistryState, RegistryError>;
+
+    /// 保存完整快照
+    fn save(&self, state: &RegistryState) -> Result<(), RegistryError>;
+}
+
+/// 内存注册表
+#[derive(Debug, Default)]
+pub struct InMemoryRegistry {
+    state: Mutex<RegistryState>,
+}
+
+impl InMemoryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NodeRegistry for InMemoryRegistry {
+    fn load(&self) -> Result<RegistryState, RegistryError> {
+        Ok(self.state.lock().unwrap().clone())
+    }
+
+    fn save(&self, state: &RegistryState) -> Result<(), RegistryError> {
+        *self.state.lock().unwrap() = state.clone();
+        Ok(())
+    }
+}
+
+/// JSON 文件注册表
+#[derive(Debug, Clone)]
+pub struct FileRegistry {
+    path: PathBuf,
+}
+
+impl FileRegistry {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn io_error(&self, source: std::io::Error) -> RegistryError {
+        RegistryError::Io {
+            path: self.path.display().to_string(),
+            source,
+        }
+    }
+}
+
+impl NodeRegistry for FileRegistry {
+    fn load(&self) -> Result<RegistryState, RegistryError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => {
+                serde_json::from_str(&content).map_err(|e| RegistryError::Format(e.to_string()))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RegistryState::default()),
+            Err(e) => Err(self.io_error(e)),
+        }
+    }
+
+    fn save(&self, state: &RegistryState) -> Result<(), RegistryError> {
+        let content =
+            serde_json::to_string_pretty(state).map_err(|e| RegistryError::Format(e.to_string()))?;
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| self.io_error(e))?;
+        }
+
+        // 先写临时文件再替换，避免进程中断留下半个文件
+        let tmp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, content).map_err(|e| self.io_error(e))?;
+        std::fs::rename(&tmp_path, &self.path).map_err(|e| self.io_error(e))
+    }
+}
+
+/// 32 字节 ID 的十六进制序列化
+pub(crate) mod hex_id {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(id: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(id))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+        let s = String::deserialize(deserializer)?;
+        parse(&s).map_err(serde::de::Error::custom)
+    }
+
+    pub fn parse(s: &str) -> Result<[u8; 32], String> {
+        hex::decode(s)
+            .map_err(|e| e.to_string())?
+            .try_into()
+            .map_err(|_| format!("ID 长度必须为 32 字节: {}", s))
+    }
+}
+
+mod hex_ids {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(ids: &[[u8; 32]], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(ids.iter().map(hex::encode))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<[u8; 32]>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|s| super::hex_id::parse(s).map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
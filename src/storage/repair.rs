@@ -0,0 +1,566 @@
+//! 分片修复与重新复制
+//!
+//! 放置之后节点会下线、离开或丢失数据，[`RepairScheduler`] 定期扫描已登记的文件，
+//! 找出有效副本数低于 `min_replicas` 的分片并补足副本：
+//!
+//! 0. 只有节点在线、且从节点读回的分片通过清单校验时才算一个有效副本，
+//!    已删除或损坏的副本与离线节点一样需要补足
+//! 1. 仍有在线副本时，从副本节点复制分片
+//! 2. 没有可用副本时，从同一文件其他分片的在线副本中取回任意 k 个，
+//!    用 Reed–Solomon 重建缺失的分片
+//! 3. 取回和重建的分片都用 [`FileManifest`] 中的承诺校验，避免扩散损坏的数据
+//! 4. 新的副本节点通过 [`NodeManager`] 的选择策略挑选，并记录到注册表
+//!
+//! 每轮最多修复 `max_repairs_per_round` 个分片 (副本最少的优先)，其余顺延到下一轮。
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{info, warn};
+use rust_kzg_blst::types::fr::FsFr;
+use rust_kzg_blst::types::kzg_settings::FsKZGSettings;
+use tokio::sync::RwLock;
+
+use super::erasure::{ErasureError, ReedSolomon};
+use super::manifest::{verify_manifest, FileManifest, ManifestError};
+use super::node::{NodeError, NodeManager};
+use super::shard::{DataShard, ShardError};
+use super::NodeId;
+use crate::blob::blob_to_bytes;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RepairError {
+    #[error("节点错误: {0}")]
+    Node(#[from] NodeError),
+
+    #[error("分片错误: {0}")]
+    Shard(#[from] ShardError),
+
+    #[error("纠删码错误: {0}")]
+    Erasure(#[from] ErasureError),
+
+    #[error("清单错误: {0}")]
+    Manifest(#[from] ManifestError),
+
+    #[error("分片 {shard} 无法恢复: 需要 {required} 个有效分片，可用 {available}")]
+    Unrecoverable {
+        shard: String,
+        required: usize,
+        available: usize,
+    },
+
+    #[error("分片存储错误: {0}")]
+    Store(String),
+
+    #[error("计算任务异常退出: {0}")]
+    Task(String),
+}
+
+/// 节点上分片数据的存取接口
+pub trait ShardStore: Send + Sync {
+    /// 从节点取回分片 (节点不在线或没有该分片时返回 `None`)
+    fn get(&self, node_id: &NodeId, shard_id: &[u8; 32]) -> Option<DataShard>;
+
+    /// 把分片写入节点
+    fn put(&self, node_id: &NodeId, shard: &DataShard) -> Result<(), RepairError>;
+}
+
+/// 内存分片存储，模拟各节点的本地磁盘
+#[derive(Debug, Default)]
+pub struct InMemoryShardStore {
+    shards: Mutex<HashMap<(NodeId, [u8; 32]), DataShard>>,
+}
+
+impl InMemoryShardStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 删除节点上的一个分片 (模拟数据丢失)
+    pub fn remove(&self, node_id: &NodeId, shard_id: &[u8; 32]) -> Option<DataShard> {
+        self.shards.lock().unwrap().remove(&(*node_id, *shard_id))
+    }
+
+    /// 节点上保存的分片数量
+    pub fn shard_count(&self, node_id: &NodeId) -> usize {
+        self.shards
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|(id, _)| id == node_id)
+            .count()
+    }
+}
+
+impl ShardStore for InMemoryShardStore {
+    fn get(&self, node_id: &NodeId, shard_id: &[u8; 32]) -> Option<DataShard> {
+        self.shards.lock().unwrap().get(&(*node_id, *shard_id)).cloned()
+    }
+
+    fn put(&self, node_id: &NodeId, shard: &DataShard) -> Result<(), RepairError> {
+        self.shards
+            .lock()
+            .unwrap()
+            .insert((*node_id, shard.shard_id), shard.clone());
+        Ok(())
+    }
+}
+
+/// 修复配置
+#[derive(Debug, Clone)]
+pub struct RepairConfig {
+    /// 每个分片至少保持的在线副本数 (通常取 [`super::ShardConfig::min_replicas`])
+    pub min_replicas: usize,
+    /// 每轮最多修复的分片数
+    pub max_repairs_per_round: usize,
+    /// 后台扫描间隔
+    pub interval: Duration,
+}
+
+impl Default for RepairConfig {
+    fn default() -> Self {
+        Self {
+            min_replicas: 3,
+            max_repairs_per_round: 16,
+            interval: Duration::from_secs(60),
+        }
+    }
+}
+
+/// 修复统计快照
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairStats {
+    /// 已完成的扫描轮数
+    pub rounds: u64,
+    /// 最近一轮发现的副本不足分片数
+    pub under_replicated: u64,
+    pub repairs_succeeded: u64,
+    pub repairs_failed: u64,
+    /// 因限速顺延到下一轮的修复数
+    pub repairs_deferred: u64,
+    /// 新建的副本数
+    pub replicas_created: u64,
+    /// 通过纠删码重建的分片数
+    pub shards_reconstructed: u64,
+    /// 修复过程中读取和写入的字节数
+    pub bytes_transferred: u64,
+}
+
+/// 修复指标 (原子计数器，可跨任务共享)
+#[derive(Debug, Default)]
+pub struct RepairMetrics {
+    rounds: AtomicU64,
+    under_replicated: AtomicU64,
+    repairs_succeeded: AtomicU64,
+    repairs_failed: AtomicU64,
+    repairs_deferred: AtomicU64,
+    replicas_created: AtomicU64,
+    shards_reconstructed: AtomicU64,
+    bytes_transferred: AtomicU64,
+}
+
+impl RepairMetrics {
+    pub fn snapshot(&self) -> RepairStats {
+        RepairStats {
+            rounds: self.rounds.load(Ordering::Relaxed),
+            under_replicated: self.under_replicated.load(Ordering::Relaxed),
+            repairs_succeeded: self.repairs_succeeded.load(Ordering::Relaxed),
+            repairs_failed: self.repairs_failed.load(Ordering::Relaxed),
+            repairs_deferred: self.repairs_deferred.load(Ordering::Relaxed),
+            replicas_created: self.replicas_created.load(Ordering::Relaxed),
+            shards_reconstructed: self.shards_reconstructed.load(Ordering::Relaxed),
+            bytes_transferred: self.bytes_transferred.load(Ordering::Relaxed),
+        }
+    }
+
+    fn add_bytes(&self, bytes: usize) {
+        self.bytes_transferred.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+/// 副本不足的分片
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnderReplicatedShard {
+    /// 所属文件 (清单根哈希)
+    pub file: [u8; 32],
+    pub shard_id: [u8; 32],
+    pub index: usize,
+    /// 当前有效副本数
+    pub live_replicas: usize,
+}
+
+/// 单轮修复结果
+#[derive(Debug, Default)]
+pub struct RepairReport {
+    /// 扫描的分片数
+    pub scanned: usize,
+    /// 副本不足的分片数
+    pub under_replicated: usize,
+    /// 修复成功的分片数
+    pub repaired: usize,
+    /// 新建的副本数
+    pub replicas_created: usize,
+    /// 顺延到下一轮的分片数
+    pub deferred: usize,
+    /// 修复失败的分片及原因
+    pub failed: Vec<([u8; 32], RepairError)>,
+}
+
+/// 已登记的文件
+struct TrackedFile {
+    manifest: FileManifest,
+    /// 按分片索引排列的分片ID
+    shard_ids: Vec<[u8; 32]>,
+}
+
+/// 分片修复调度器
+pub struct RepairScheduler {
+    kzg_settings: Arc<FsKZGSettings>,
+    node_manager: Arc<NodeManager>,
+    store: Arc<dyn ShardStore>,
+    config: RepairConfig,
+    /// 修复时克隆 `Arc` 后即释放锁，KZG 计算不阻塞登记和注销
+    files: RwLock<HashMap<[u8; 32], Arc<TrackedFile>>>,
+    metrics: RepairMetrics,
+}
+
+impl RepairScheduler {
+    pub fn new(
+        kzg_settings: Arc<FsKZGSettings>,
+        node_manager: Arc<NodeManager>,
+        store: Arc<dyn ShardStore>,
+        config: RepairConfig,
+    ) -> Self {
+        Self {
+            kzg_settings,
+            node_manager,
+            store,
+            config,
+            files: RwLock::new(HashMap::new()),
+            metrics: RepairMetrics::default(),
+        }
+    }
+
+    pub fn config(&self) -> &RepairConfig {
+        &self.config
+    }
+
+    /// 修复指标快照
+    pub fn stats(&self) -> RepairStats {
+        self.metrics.snapshot()
+    }
+
+    /// 登记需要维护副本数的文件
+    ///
+    /// `shards` 必须覆盖清单中的全部分片 (顺序无关)
+    pub async fn track_file(&self, manifest: FileManifest, shards: &[DataShard]) -> Result<(), RepairError> {
+        manifest.verify_integrity()?;
+
+        let total = manifest.shard_count();
+        let mut shard_ids = vec![None; total];
+        for shard in shards {
+            let slot = shard_ids
+                .get_mut(shard.index)
+                .ok_or(ManifestError::UnknownShard { index: shard.index, total })?;
+            *slot = Some(shard.shard_id);
+        }
+        let shard_ids = shard_ids
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ManifestError::InvalidShardCount {
+                expected: total,
+                actual: shards.len(),
+            })?;
+
+        self.files
+            .write()
+            .await
+            .insert(manifest.root_hash, Arc::new(TrackedFile { manifest, shard_ids }));
+        Ok(())
+    }
+
+    /// 停止维护文件
+    pub async fn untrack_file(&self, root_hash: &[u8; 32]) -> bool {
+        self.files.write().await.remove(root_hash).is_some()
+    }
+
+    /// 找出有效副本数低于 `min_replicas` 的分片，副本最少的排在最前
+    pub async fn find_under_replicated(&self) -> Vec<UnderReplicatedShard> {
+        let files: Vec<_> = self
+            .files
+            .read()
+            .await
+            .iter()
+            .map(|(file, tracked)| (*file, Arc::clone(tracked)))
+            .collect();
+        let mut result = Vec::new();
+
+        for (file, tracked) in &files {
+            for (index, shard_id) in tracked.shard_ids.iter().enumerate() {
+                let live_replicas = self.live_holders(tracked, index).await.len();
+                if live_replicas < self.config.min_replicas {
+                    result.push(UnderReplicatedShard {
+                        file: *file,
+                        shard_id: *shard_id,
+                        index,
+                        live_replicas,
+                    });
+                }
+            }
+        }
+
+        result.sort_by_key(|s| (s.live_replicas, s.file, s.index));
+        result
+    }
+
+    /// 执行一轮扫描与修复
+    pub async fn run_once(&self) -> RepairReport {
+        let scanned = self
+            .files
+            .read()
+            .await
+            .values()
+            .map(|tracked| tracked.shard_ids.len())
+            .sum();
+        let under_replicated = self.find_under_replicated().await;
+
+        let mut report = RepairReport {
+            scanned,
+            under_replicated: under_replicated.len(),
+            deferred: under_replicated
+                .len()
+                .saturating_sub(self.config.max_repairs_per_round),
+            ..Default::default()
+        };
+
+        for target in under_replicated.iter().take(self.config.max_repairs_per_round) {
+            match self.repair_shard(target).await {
+                Ok(created) => {
+                    report.repaired += 1;
+                    report.replicas_created += created;
+                }
+                Err(e) => {
+                    warn!("分片 {} 修复失败: {}", hex::encode(&target.shard_id[..8]), e);
+                    report.failed.push((target.shard_id, e));
+                }
+            }
+        }
+
+        let metrics = &self.metrics;
+        metrics.rounds.fetch_add(1, Ordering::Relaxed);
+        metrics
+            .under_replicated
+            .store(report.under_replicated as u64, Ordering::Relaxed);
+        metrics
+            .repairs_succeeded
+            .fetch_add(report.repaired as u64, Ordering::Relaxed);
+        metrics
+            .repairs_failed
+            .fetch_add(report.failed.len() as u64, Ordering::Relaxed);
+        metrics
+            .repairs_deferred
+            .fetch_add(report.deferred as u64, Ordering::Relaxed);
+        metrics
+            .replicas_created
+            .fetch_add(report.replicas_created as u64, Ordering::Relaxed);
+
+        if report.under_replicated > 0 {
+            info!(
+                "修复轮次完成: 副本不足 {}，修复 {}，失败 {}，顺延 {}",
+                report.under_replicated,
+                report.repaired,
+                report.failed.len(),
+                report.deferred
+            );
+        }
+        report
+    }
+
+    /// 启动后台修复任务，按 `interval` 周期执行 [`Self::run_once`]
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.config.interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                self.run_once().await;
+            }
+        })
+    }
+
+    /// 修复单个分片，返回新建的副本数
+    async fn repair_shard(&self, target: &UnderReplicatedShard) -> Result<usize, RepairError> {
+        // 扫描之后文件可能已被移出维护列表
+        let Some(tracked) = self.files.read().await.get(&target.file).cloned() else {
+            return Ok(0);
+        };
+
+        let holders = self
+            .node_manager
+            .placement(&target.shard_id)
+            .await
+            .map(|placement| placement.nodes)
+            .unwrap_or_default();
+
+        // 优先从在线副本复制，失败时用纠删码重建
+        let shard = match self.fetch_verified(&tracked, target.index).await {
+            Some(shard) => shard,
+            None => self.reconstruct_shard(&tracked, target.index).await?,
+        };
+
+        let needed = self.config.min_replicas - target.live_replicas;
+        // 持有该分片的节点 (包括暂时离线的) 不再作为目标；节点不足时尽量多修
+        let targets = match self
+            .node_manager
+            .select_storage_nodes_excluding(&shard, needed, &holders)
+            .await
+        {
+            Ok(targets) => targets,
+            Err(NodeError::InsufficientNodes { available, .. }) if available > 0 => {
+                self.node_manager
+                    .select_storage_nodes_excluding(&shard, available, &holders)
+                    .await?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        for node_id in &targets {
+            self.store.put(node_id, &shard)?;
+            self.metrics.add_bytes(shard.data_chunk.len());
+        }
+        self.node_manager.record_placement(&shard, &targets).await?;
+
+        Ok(targets.len())
+    }
+
+    /// 分片的在线持有节点 (不检查节点上的数据)
+    async fn online_holders(&self, shard_id: &[u8; 32]) -> Vec<NodeId> {
+        let Some(placement) = self.node_manager.placement(shard_id).await else {
+            return Vec::new();
+        };
+        let nodes = self.node_manager.nodes().read().await;
+        placement
+            .nodes
+            .into_iter()
+            .filter(|id| nodes.get(id).is_some_and(|node| node.is_online))
+            .collect()
+    }
+
+    /// 在线且确实持有有效副本的节点
+    async fn live_holders(&self, tracked: &Arc<TrackedFile>, index: usize) -> Vec<NodeId> {
+        let mut live = Vec::new();
+        for node_id in self.online_holders(&tracked.shard_ids[index]).await {
+            if self.read_verified(tracked, index, &node_id).await.is_some() {
+                live.push(node_id);
+            }
+        }
+        live
+    }
+
+    /// 从任一在线副本取回分片，并按清单校验
+    async fn fetch_verified(&self, tracked: &Arc<TrackedFile>, index: usize) -> Option<DataShard> {
+        for node_id in self.online_holders(&tracked.shard_ids[index]).await {
+            if let Some(shard) = self.read_verified(tracked, index, &node_id).await {
+                self.metrics.add_bytes(shard.data_chunk.len());
+                return Some(shard);
+            }
+        }
+        None
+    }
+
+    /// 从节点读回分片并按清单校验，KZG 承诺在阻塞线程上计算
+    async fn read_verified(&self, tracked: &Arc<TrackedFile>, index: usize, node_id: &NodeId) -> Option<DataShard> {
+        let shard_id = tracked.shard_ids[index];
+        let shard = self.store.get(node_id, &shard_id)?;
+
+        let tracked = Arc::clone(tracked);
+        let kzg_settings = Arc::clone(&self.kzg_settings);
+        let verified = tokio::task::spawn_blocking(move || {
+            let valid = shard.index == index && verify_manifest(&tracked.manifest, &shard, &kzg_settings).is_ok();
+            valid.then_some(shard)
+        })
+        .await
+        .ok()
+        .flatten();
+
+        if verified.is_none() {
+            warn!(
+                "节点 {} 上的分片 {} 未通过清单校验",
+                hex::encode(&node_id[..8]),
+                hex::encode(&shard_id[..8])
+            );
+        }
+        verified
+    }
+
+    /// 从同一文件的其他分片重建指定索引的分片
+    async fn reconstruct_shard(&self, tracked: &Arc<TrackedFile>, index: usize) -> Result<DataShard, RepairError> {
+        let params = tracked.manifest.erasure;
+        let mut available = Vec::with_capacity(params.data_shards);
+
+        for peer in (0..tracked.manifest.shard_count()).filter(|&i| i != index) {
+            if available.len() == params.data_shards {
+                break;
+            }
+            if let Some(shard) = self.fetch_verified(tracked, peer).await {
+                available.push((peer, shard.to_blob()?));
+            }
+        }
+
+        if available.len() < params.data_shards {
+            return Err(RepairError::Unrecoverable {
+                shard: hex::encode(tracked.shard_ids[index]),
+                required: params.data_shards,
+                available: available.len(),
+            });
+        }
+
+        let tracked = Arc::clone(tracked);
+        let kzg_settings = Arc::clone(&self.kzg_settings);
+        let shard = tokio::task::spawn_blocking(move || Self::rebuild(&tracked, index, &available, &kzg_settings))
+            .await
+            .map_err(|e| RepairError::Task(e.to_string()))??;
+
+        self.metrics.shards_reconstructed.fetch_add(1, Ordering::Relaxed);
+        Ok(shard)
+    }
+
+    /// 用 k 个有效分片解码并重新编码出指定索引的分片 (CPU 密集，在阻塞线程上运行)
+    fn rebuild(
+        tracked: &TrackedFile,
+        index: usize,
+        available: &[(usize, Vec<FsFr>)],
+        kzg_settings: &FsKZGSettings,
+    ) -> Result<DataShard, RepairError> {
+        let params = tracked.manifest.erasure;
+        let rs = ReedSolomon::new(params)?;
+        let data = rs.reconstruct(available)?;
+        let blob = if index < params.data_shards {
+            data[index].clone()
+        } else {
+            rs.encode(&data)?.swap_remove(index - params.data_shards)
+        };
+
+        let shard = DataShard {
+            shard_id: tracked.shard_ids[index],
+            index,
+            kind: tracked.manifest.shard_kind(index).ok_or(ManifestError::UnknownShard {
+                index,
+                total: tracked.manifest.shard_count(),
+            })?,
+            data_chunk: blob_to_bytes(&blob),
+            commitment: tracked.manifest.shard_commitments[index],
+            storage_locations: Vec::new(),
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        };
+
+        // 重建结果同样必须与清单中的承诺一致
+        verify_manifest(&tracked.manifest, &shard, kzg_settings)?;
+        Ok(shard)
+    }
+}
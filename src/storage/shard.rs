@@ -0,0 +1,217 @@
+//! 数据分片与 KZG 承诺
+
+use std::sync::Arc;
+
+use kzg::eip_4844::blob_to_kzg_commitment_rust;
+use log::info;
+use rust_kzg_blst::types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings};
+use sha2::{Digest, Sha256};
+
+use super::encoding::{decode_blob, encode_blob, EncodingError, MAX_PAYLOAD_BYTES_PER_BLOB};
+use super::erasure::{ErasureError, ErasureParams, ReedSolomon};
+use super::NodeId;
+use crate::blob::{blob_from_bytes, blob_to_bytes};
+
+/// 分片类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShardKind {
+    /// 数据分片：承载编码后的原始文件内容
+    Data,
+    /// 校验分片：Reed–Solomon 冗余数据
+    Parity,
+}
+
+/// 数据分片信息
+#[derive(Debug, Clone)]
+pub struct DataShard {
+    /// 分片ID
+    pub shard_id: [u8; 32],
+    /// 在纠删码码字中的位置 (数据分片在前，校验分片在后)
+    pub index: usize,
+    /// 分片类型
+    pub kind: ShardKind,
+    /// 编码后的 Blob 字节 (即被承诺的数据，见 [`super::encoding`])
+    pub data_chunk: Vec<u8>,
+    /// KZG 承诺
+    pub commitment: FsG1,
+    /// 存储位置
+    pub storage_locations: Vec<NodeId>,
+    /// 创建时间
+    pub created_at: u64,
+}
+
+impl DataShard {
+    /// 取回分片承载的原始字节 (仅数据分片，校验分片没有字节编码头部)
+    pub fn payload(&self) -> Result<Vec<u8>, EncodingError> {
+        decode_blob(&self.data_chunk)
+    }
+
+    /// 将分片数据转换为域元素形式的 Blob
+    pub fn to_blob(&self) -> Result<Vec<FsFr>, ShardError> {
+        blob_from_bytes(&self.data_chunk).map_err(ShardError::InvalidData)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShardConfig {
+    /// 分片大小 (字节)
+    pub shard_size: usize,
+    /// 冗余因子
+    pub redundancy_factor: f64,
+    /// 最小副本数
+    pub min_replicas: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShardError {
+    #[error("KZG 操作错误: {0}")]
+    KZGError(String),
+
+    #[error("无效数据: {0}")]
+    InvalidData(String),
+
+    #[error("编码错误: {0}")]
+    Encoding(#[from] EncodingError),
+
+    #[error("纠删码错误: {0}")]
+    Erasure(#[from] ErasureError),
+
+    #[error("没有可用分片")]
+    NoShardsAvailable,
+}
+
+/// 数据分片管理器
+pub struct ShardManager {
+    kzg_settings: Arc<FsKZGSettings>,
+    config: ShardConfig,
+}
+
+impl ShardManager {
+    pub fn new(kzg_settings: Arc<FsKZGSettings>, config: ShardConfig) -> Self {
+        Self {
+            kzg_settings,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &ShardConfig {
+        &self.config
+    }
+
+    /// 给定文件大小对应的纠删码参数
+    ///
+    /// 数据分片数为文件按 Blob 有效容量切分后的块数 (空文件也占 1 块)，
+    /// 校验分片数为数据分片数乘以冗余因子后向上取整
+    pub fn erasure_params(&self, file_len: usize) -> ErasureParams {
+        let data_shards = file_len.div_ceil(MAX_PAYLOAD_BYTES_PER_BLOB).max(1);
+        let parity_shards = (data_shards as f64 * self.config.redundancy_factor).ceil() as usize;
+        ErasureParams::new(data_shards, parity_shards)
+    }
+
+    /// 将文件分片并生成承诺
+    ///
+    /// 返回 n = k + m 个分片，其中任意 k 个即可通过 [`Self::reconstruct_file`] 恢复文件
+    pub async fn shard_file(&self, file_data: &[u8]) -> Result<Vec<DataShard>, ShardError> {
+        info!("开始分片文件，大小: {} 字节", file_data.len());
+
+        let params = self.erasure_params(file_data.len());
+        let mut shards = Vec::with_capacity(params.total_shards());
+        let mut data_blobs = Vec::with_capacity(params.data_shards);
+
+        // 每个分片恰好装满一个 Blob 的有效数据区
+        let chunks: Vec<&[u8]> = if file_data.is_empty() {
+            vec![file_data]
+        } else {
+            file_data.chunks(MAX_PAYLOAD_BYTES_PER_BLOB).collect()
+        };
+
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            let shard = self.create_data_shard(chunk, index).await?;
+            data_blobs.push(shard.to_blob()?);
+            shards.push(shard);
+        }
+
+        // Reed–Solomon 校验分片，每个校验分片有独立的 KZG 承诺
+        let rs = ReedSolomon::new(params)?;
+        for (i, parity_blob) in rs.encode(&data_blobs)?.into_iter().enumerate() {
+            let shard = self.build_shard(&parity_blob, params.data_shards + i, ShardKind::Parity)?;
+            shards.push(shard);
+        }
+
+        info!(
+            "文件分片完成，生成 {} 个分片 ({} 数据 + {} 校验)",
+            shards.len(),
+            params.data_shards,
+            params.parity_shards
+        );
+        Ok(shards)
+    }
+
+    /// 从任意 k 个不同分片恢复原始文件
+    ///
+    /// `shards` 可以是数据分片和校验分片的任意组合，顺序无关，重复分片会被忽略
+    pub fn reconstruct_file(&self, shards: &[DataShard], params: &ErasureParams) -> Result<Vec<u8>, ShardError> {
+        let available = shards
+            .iter()
+            .map(|shard| Ok((shard.index, shard.to_blob()?)))
+            .collect::<Result<Vec<_>, ShardError>>()?;
+
+        let rs = ReedSolomon::new(*params)?;
+        let data_blobs = rs.reconstruct(&available)?;
+
+        let mut file_data = Vec::with_capacity(params.data_shards * MAX_PAYLOAD_BYTES_PER_BLOB);
+        for blob in data_blobs {
+            file_data.extend(decode_blob(&blob_to_bytes(&blob))?);
+        }
+
+        Ok(file_data)
+    }
+
+    /// 创建单个数据分片
+    ///
+    /// `chunk` 按 31 字节一组装入域元素并加上长度头部，
+    /// 可通过 [`DataShard::payload`] 原样取回
+    pub async fn create_data_shard(&self, chunk: &[u8], index: usize) -> Result<DataShard, ShardError> {
+        let encoded = encode_blob(chunk)?;
+        let blob_fr = blob_from_bytes(&encoded).map_err(ShardError::InvalidData)?;
+        self.build_shard(&blob_fr, index, ShardKind::Data)
+    }
+
+    /// 为域元素形式的 Blob 生成承诺并组装分片
+    fn build_shard(&self, blob_fr: &[FsFr], index: usize, kind: ShardKind) -> Result<DataShard, ShardError> {
+        // 生成 KZG 承诺
+        let commitment = blob_to_kzg_commitment_rust(blob_fr, &*self.kzg_settings)
+            .map_err(ShardError::KZGError)?;
+
+        let data_chunk = blob_to_bytes(blob_fr);
+
+        // 生成分片ID
+        let shard_id = self.generate_shard_id(&data_chunk, index);
+
+        Ok(DataShard {
+            shard_id,
+            index,
+            kind,
+            data_chunk,
+            commitment,
+            storage_locations: Vec::new(),
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        })
+    }
+
+    /// 生成分片ID
+    fn generate_shard_id(&self, data: &[u8], index: usize) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        hasher.update(index.to_be_bytes());
+        hasher.update(b"SHARD_ID");
+
+        let hash = hasher.finalize();
+        let mut shard_id = [0u8; 32];
+        shard_id.copy_from_slice(&hash);
+        shard_id
+    }
+}
@@ -0,0 +1,130 @@
+//! Fiat–Shamir 挑战派生
+//!
+//! [`Transcript`] 以域分隔符开头，按顺序吸收协议消息，再用 SHA-256 导出挑战。
+//! 哈希值按大端整数对 BLS12-381 标量域模数 r 取模 (见 [`hash_to_bls_field`])，
+//! 任何输入都能得到有效的域元素，不需要回退值。
+//!
+//! [`compute_challenge`] 实现 EIP-4844 blob 证明使用的挑战：
+//!
+//! ```text
+//! z = SHA-256("FSBLOBVERIFY_V1_" || 次数 (16 字节大端) || blob || commitment) mod r
+//! ```
+//!
+//! ```no_run
+//! use kzg::eip_4844::{blob_to_kzg_commitment_rust, compute_blob_kzg_proof_rust, compute_kzg_proof_rust};
+//! use rust_kzg_tutorial::{blob::create_test_blob, transcript::compute_challenge, trusted_setup::load_trusted_setup_from_file};
+//!
+//! let settings = load_trusted_setup_from_file()?;
+//! let blob = create_test_blob()?;
+//! let commitment = blob_to_kzg_commitment_rust(&blob, &settings)?;
+//!
+//! // blob 证明就是在挑战点 z 处的打开证明
+//! let z = compute_challenge(&blob, &commitment);
+//! let (proof, _y) = compute_kzg_proof_rust(&blob, &z, &settings)?;
+//! assert_eq!(proof, compute_blob_kzg_proof_rust(&blob, &commitment, &settings)?);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use kzg::eip_4844::{BYTES_PER_FIELD_ELEMENT, FIELD_ELEMENTS_PER_BLOB};
+use kzg::{Fr, G1};
+use rust_kzg_blst::types::{fr::FsFr, g1::FsG1};
+use sha2::{Digest, Sha256};
+
+/// EIP-4844 blob 证明的 Fiat–Shamir 域分隔符
+pub const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8; 16] = b"FSBLOBVERIFY_V1_";
+
+/// Fiat–Shamir 变换记录
+///
+/// 消息直接拼接，不加长度前缀；长度可变的消息应由调用方先写入长度
+#[derive(Clone)]
+pub struct Transcript {
+    hasher: Sha256,
+}
+
+impl Transcript {
+    /// 以域分隔符开始新的记录
+    pub fn new(domain: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(domain);
+        Self { hasher }
+    }
+
+    pub fn append_bytes(&mut self, bytes: &[u8]) {
+        self.hasher.update(bytes);
+    }
+
+    /// 写入 8 字节大端整数
+    pub fn append_u64(&mut self, value: u64) {
+        self.hasher.update(value.to_be_bytes());
+    }
+
+    /// 写入 16 字节大端整数 (EIP-4844 的多项式次数按此编码)
+    pub fn append_u128(&mut self, value: u128) {
+        self.hasher.update(value.to_be_bytes());
+    }
+
+    /// 写入域元素的 32 字节大端表示
+    pub fn append_fr(&mut self, fr: &FsFr) {
+        self.hasher.update(fr.to_bytes());
+    }
+
+    /// 写入 G1 点的 48 字节压缩表示
+    pub fn append_g1(&mut self, point: &FsG1) {
+        self.hasher.update(point.to_bytes());
+    }
+
+    /// 当前记录的哈希值 (不改变记录本身)
+    pub fn challenge_bytes(&self) -> [u8; 32] {
+        self.hasher.clone().finalize().into()
+    }
+
+    /// 当前记录对应的域元素挑战
+    pub fn challenge_fr(&self) -> FsFr {
+        hash_to_bls_field(&self.challenge_bytes())
+    }
+
+    /// 从同一记录派生 `count` 个独立挑战，第 i 个挑战额外吸收计数器 i
+    pub fn challenge_frs(&self, count: usize) -> Vec<FsFr> {
+        (0..count)
+            .map(|i| {
+                let mut branch = self.clone();
+                branch.append_u64(i as u64);
+                branch.challenge_fr()
+            })
+            .collect()
+    }
+}
+
+/// 把 32 字节大端整数按模 r 约简为域元素 (对应规范中的 `hash_to_bls_field`)
+pub fn hash_to_bls_field(bytes: &[u8; 32]) -> FsFr {
+    // Horner 法逐字节累加：acc = acc * 256 + b (mod r)
+    let radix = FsFr::from_u64(256);
+    bytes.iter().fold(FsFr::zero(), |acc, &b| {
+        acc.mul(&radix).add(&FsFr::from_u64(b as u64))
+    })
+}
+
+/// EIP-4844 `compute_challenge`：blob 证明的求值点
+pub fn compute_challenge(blob: &[FsFr], commitment: &FsG1) -> FsFr {
+    let mut transcript = Transcript::new(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+    transcript.append_u128(FIELD_ELEMENTS_PER_BLOB as u128);
+    for element in blob {
+        transcript.append_fr(element);
+    }
+    transcript.append_g1(commitment);
+    transcript.challenge_fr()
+}
+
+/// 字节形式的 [`compute_challenge`]，`blob` 为 `FIELD_ELEMENTS_PER_BLOB` 个 32 字节大端域元素
+pub fn compute_challenge_from_bytes(blob: &[u8], commitment: &[u8; 48]) -> Result<FsFr, String> {
+    let expected = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+    if blob.len() != expected {
+        return Err(format!("无效的 Blob 大小: {}，期望 {}", blob.len(), expected));
+    }
+
+    let mut transcript = Transcript::new(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+    transcript.append_u128(FIELD_ELEMENTS_PER_BLOB as u128);
+    transcript.append_bytes(blob);
+    transcript.append_bytes(commitment);
+    Ok(transcript.challenge_fr())
+}
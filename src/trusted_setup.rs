@@ -0,0 +1,87 @@
+//! 受信任设置文件的查找与加载
+//!
+//! 各章节示例都需要在不同的工作目录下找到 `trusted_setup.txt`，
+//! 这里统一了搜索路径，并支持通过环境变量显式指定。
+
+use std::path::{Path, PathBuf};
+
+use rust_kzg_blst::{
+    eip_4844::load_trusted_setup_filename_rust,
+    types::kzg_settings::FsKZGSettings,
+};
+use thiserror::Error;
+
+/// 显式指定受信任设置文件路径的环境变量
+pub const TRUSTED_SETUP_ENV: &str = "KZG_TRUSTED_SETUP";
+
+/// 默认搜索路径 (相对于当前工作目录)
+pub const TRUSTED_SETUP_SEARCH_PATHS: &[&str] = &[
+    "./assets/trusted_setup.txt",
+    "../assets/trusted_setup.txt",
+    "../../assets/trusted_setup.txt",
+    "./trusted_setup.txt",
+    "./src/trusted_setup.txt",
+    "../src/trusted_setup.txt",
+    "../rust-kzg/src/trusted_setup.txt",
+    "../../rust-kzg/src/trusted_setup.txt",
+];
+
+/// 随仓库分发的受信任设置文件 (编译期确定的绝对路径)
+const BUNDLED_TRUSTED_SETUP: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/trusted_setup.txt");
+
+#[derive(Debug, Error)]
+pub enum TrustedSetupError {
+    #[error("未找到受信任设置文件，已搜索: {searched:?}")]
+    NotFound { searched: Vec<String> },
+
+    #[error("加载受信任设置失败 ({path}): {reason}")]
+    LoadFailed { path: String, reason: String },
+}
+
+/// 按优先级返回所有候选路径
+///
+/// 顺序: 环境变量 `KZG_TRUSTED_SETUP` → 默认搜索路径 → 仓库自带的 assets 目录
+pub fn candidate_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::with_capacity(TRUSTED_SETUP_SEARCH_PATHS.len() + 2);
+
+    if let Ok(path) = std::env::var(TRUSTED_SETUP_ENV) {
+        paths.push(PathBuf::from(path));
+    }
+    paths.extend(TRUSTED_SETUP_SEARCH_PATHS.iter().map(PathBuf::from));
+    paths.push(PathBuf::from(BUNDLED_TRUSTED_SETUP));
+
+    paths
+}
+
+/// 寻找受信任设置文件
+pub fn find_trusted_setup_file() -> Result<PathBuf, TrustedSetupError> {
+    let candidates = candidate_paths();
+
+    candidates
+        .iter()
+        .find(|path| path.exists())
+        .cloned()
+        .ok_or_else(|| TrustedSetupError::NotFound {
+            searched: candidates.iter().map(|p| p.display().to_string()).collect(),
+        })
+}
+
+/// 从指定路径加载受信任设置
+pub fn load_trusted_setup(path: impl AsRef<Path>) -> Result<FsKZGSettings, TrustedSetupError> {
+    let path = path.as_ref();
+    let path_str = path.to_str().ok_or_else(|| TrustedSetupError::LoadFailed {
+        path: path.display().to_string(),
+        reason: "路径不是有效的 UTF-8".to_string(),
+    })?;
+
+    load_trusted_setup_filename_rust(path_str).map_err(|reason| TrustedSetupError::LoadFailed {
+        path: path_str.to_string(),
+        reason,
+    })
+}
+
+/// 自动查找并加载受信任设置
+pub fn load_trusted_setup_from_file() -> Result<FsKZGSettings, TrustedSetupError> {
+    let path = find_trusted_setup_file()?;
+    load_trusted_setup(path)
+}
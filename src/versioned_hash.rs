@@ -0,0 +1,78 @@
+//! EIP-4844 版本化哈希
+//!
+//! 交易只携带承诺的版本化哈希 (`blob_versioned_hashes`)，完整承诺随 sidecar 传播：
+//!
+//! ```text
+//! versioned_hash = 0x01 || SHA-256(commitment)[1..32]
+//! ```
+//!
+//! [`validate_blob_versioned_hashes`] 按顺序检查交易中的哈希与 sidecar 承诺是否一一对应。
+
+use sha2::{Digest, Sha256};
+
+/// KZG 承诺的版本号
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum VersionedHashError {
+    #[error("版本化哈希数量与承诺数量不一致: 哈希 {hashes}，承诺 {commitments}")]
+    CountMismatch { hashes: usize, commitments: usize },
+
+    #[error("第 {index} 个版本化哈希的版本号 0x{version:02x} 不受支持")]
+    UnsupportedVersion { index: usize, version: u8 },
+
+    #[error("第 {index} 个版本化哈希与承诺不匹配: 期望 {expected}，实际 {actual}")]
+    Mismatch {
+        index: usize,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("无效的版本化哈希: {0}")]
+    InvalidFormat(String),
+}
+
+/// 计算承诺的版本化哈希 (规范中的 `kzg_to_versioned_hash`)
+pub fn kzg_to_versioned_hash(commitment: &[u8; 48]) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// 解析十六进制版本化哈希 (`0x` 前缀可选)
+pub fn parse_versioned_hash(text: &str) -> Result<[u8; 32], VersionedHashError> {
+    hex::decode(text.strip_prefix("0x").unwrap_or(text))
+        .map_err(|e| VersionedHashError::InvalidFormat(e.to_string()))?
+        .try_into()
+        .map_err(|_| VersionedHashError::InvalidFormat(format!("长度必须为 32 字节: {}", text)))
+}
+
+/// 校验交易的 `blob_versioned_hashes` 与 sidecar 承诺按顺序一一对应
+pub fn validate_blob_versioned_hashes(
+    versioned_hashes: &[[u8; 32]],
+    commitments: &[[u8; 48]],
+) -> Result<(), VersionedHashError> {
+    if versioned_hashes.len() != commitments.len() {
+        return Err(VersionedHashError::CountMismatch {
+            hashes: versioned_hashes.len(),
+            commitments: commitments.len(),
+        });
+    }
+
+    for (index, (hash, commitment)) in versioned_hashes.iter().zip(commitments).enumerate() {
+        if hash[0] != VERSIONED_HASH_VERSION_KZG {
+            return Err(VersionedHashError::UnsupportedVersion { index, version: hash[0] });
+        }
+
+        let expected = kzg_to_versioned_hash(commitment);
+        if *hash != expected {
+            return Err(VersionedHashError::Mismatch {
+                index,
+                expected: hex::encode(expected),
+                actual: hex::encode(hash),
+            });
+        }
+    }
+
+    Ok(())
+}
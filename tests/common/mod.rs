@@ -0,0 +1,86 @@
+// 测试共用的夹具：API 密钥配置与 PeerDAS 测试 blob 的 cell
+// 各测试文件只用到其中一部分，未用到的项不视为死代码
+#![allow(dead_code)]
+
+use std::sync::OnceLock;
+
+use kzg::{
+    eip_4844::blob_to_kzg_commitment_rust,
+    eth::{CELLS_PER_EXT_BLOB, FIELD_ELEMENTS_PER_CELL},
+    G1, DAS,
+};
+use rust_kzg_blst::{
+    eip_7594::BlstBackend,
+    types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings},
+};
+use rust_kzg_tutorial::{
+    blob::{blob_from_bytes, blob_to_bytes, create_test_blob_bytes},
+    service::{config::ApiKeyConfig, hash_api_key, Scope},
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+/// 只限定权限范围的 API 密钥 (不单独限速、不限配额)
+pub fn key_config(id: &str, key: &str, scopes: &[Scope]) -> ApiKeyConfig {
+    ApiKeyConfig {
+        id: id.to_string(),
+        hash: hash_api_key(key),
+        scopes: scopes.to_vec(),
+        requests_per_second: None,
+        burst_size: None,
+        daily_quota: None,
+    }
+}
+
+/// 受信任设置与 `create_test_blob_bytes(0)` 的承诺、扩展后的全部 cell 和证明
+pub struct TestBlobCells {
+    pub settings: FsKZGSettings,
+    pub commitment: FsG1,
+    /// 按 cell 索引平铺的域元素，共 `CELLS_PER_EXT_BLOB * FIELD_ELEMENTS_PER_CELL` 个
+    pub cells: Vec<FsFr>,
+    pub proofs: Vec<FsG1>,
+}
+
+impl TestBlobCells {
+    /// 第 `index` 个 cell 的域元素
+    pub fn cell(&self, index: usize) -> &[FsFr] {
+        &self.cells[index * FIELD_ELEMENTS_PER_CELL..(index + 1) * FIELD_ELEMENTS_PER_CELL]
+    }
+
+    /// 第 `index` 个 cell 的字节
+    pub fn cell_bytes(&self, index: usize) -> Vec<u8> {
+        blob_to_bytes(self.cell(index))
+    }
+
+    pub fn commitment_bytes(&self) -> [u8; 48] {
+        self.commitment.to_bytes()
+    }
+
+    pub fn proof_bytes(&self, index: usize) -> [u8; 48] {
+        self.proofs[index].to_bytes()
+    }
+}
+
+/// 测试 blob 的全部 cell 和证明，同一测试进程内只计算一次
+pub fn test_blob_cells() -> &'static TestBlobCells {
+    static CELLS: OnceLock<TestBlobCells> = OnceLock::new();
+    CELLS.get_or_init(|| {
+        let settings = load_trusted_setup_from_file().unwrap();
+        let blob = blob_from_bytes(&create_test_blob_bytes(0)).unwrap();
+        let commitment = blob_to_kzg_commitment_rust(&blob, &settings).unwrap();
+        let mut cells = vec![FsFr::default(); CELLS_PER_EXT_BLOB * FIELD_ELEMENTS_PER_CELL];
+        let mut proofs = vec![FsG1::default(); CELLS_PER_EXT_BLOB];
+        <FsKZGSettings as DAS<BlstBackend>>::compute_cells_and_kzg_proofs(
+            &settings,
+            Some(&mut cells),
+            Some(&mut proofs),
+            &blob,
+        )
+        .unwrap();
+        TestBlobCells {
+            settings,
+            commitment,
+            cells,
+            proofs,
+        }
+    })
+}
@@ -0,0 +1,118 @@
+// 数据列 sidecar 测试
+// 覆盖多 blob 构建与逐列验证、SSZ 往返、篡改检测以及结构与编码错误
+
+use rust_kzg_tutorial::{
+    blob::create_test_blob_bytes,
+    das::{
+        verify_data_column_sidecars, ColumnError, DataColumnSidecar, DataColumnSidecarBuilder, BYTES_PER_CELL,
+        NUMBER_OF_COLUMNS,
+    },
+    sidecar::{BeaconBlockHeader, SignedBeaconBlockHeader},
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+fn header() -> SignedBeaconBlockHeader {
+    SignedBeaconBlockHeader {
+        message: BeaconBlockHeader {
+            slot: 42,
+            proposer_index: 7,
+            parent_root: [1; 32],
+            state_root: [2; 32],
+            body_root: [3; 32],
+        },
+        signature: vec![9; 96],
+    }
+}
+
+/// 不经过 KZG 运算构造的 sidecar，用于结构与编码检查
+fn raw_sidecar(blobs: usize) -> DataColumnSidecar {
+    DataColumnSidecar {
+        index: 5,
+        column: (0..blobs).map(|i| vec![i as u8; BYTES_PER_CELL]).collect(),
+        kzg_commitments: vec![[0xc0; 48]; blobs],
+        kzg_proofs: vec![[0xc0; 48]; blobs],
+        signed_block_header: header(),
+        kzg_commitments_inclusion_proof: [[4; 32]; 4],
+    }
+}
+
+#[test]
+fn test_build_and_verify_columns() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let blobs = [create_test_blob_bytes(1), create_test_blob_bytes(2)];
+    let sidecars = DataColumnSidecarBuilder::new(&settings)
+        .signed_block_header(header())
+        .build(&blobs)
+        .unwrap();
+
+    assert_eq!(sidecars.len(), NUMBER_OF_COLUMNS as usize);
+    for (index, sidecar) in sidecars.iter().enumerate() {
+        assert_eq!(sidecar.index, index as u64);
+        assert_eq!((sidecar.blob_count(), sidecar.kzg_commitments.len()), (2, 2));
+        assert_eq!(sidecar.signed_block_header.message.slot, 42);
+    }
+    assert_eq!(sidecars[0].kzg_commitments, sidecars[127].kzg_commitments);
+    assert_ne!(sidecars[0].kzg_commitments[0], sidecars[0].kzg_commitments[1]);
+
+    assert!(sidecars[0].verify_kzg_proofs(&settings).unwrap());
+    assert!(sidecars[127].verify_kzg_proofs(&settings).unwrap());
+    assert!(verify_data_column_sidecars(&settings, &sidecars).unwrap());
+
+    // SSZ 往返后仍可验证
+    let decoded = DataColumnSidecar::from_ssz(&sidecars[3].to_ssz().unwrap()).unwrap();
+    assert_eq!(decoded, sidecars[3]);
+    assert!(decoded.verify_kzg_proofs(&settings).unwrap());
+
+    // 交换两列的 cell 或证明后验证失败
+    let mut tampered = sidecars[3].clone();
+    tampered.column[1] = sidecars[4].column[1].clone();
+    assert!(!tampered.verify_kzg_proofs(&settings).unwrap());
+    assert!(!verify_data_column_sidecars(&settings, &[sidecars[0].clone(), tampered]).unwrap());
+
+    let mut tampered = sidecars[3].clone();
+    tampered.kzg_proofs.swap(0, 1);
+    assert!(!tampered.verify_kzg_proofs(&settings).unwrap());
+}
+
+#[test]
+fn test_structural_errors() {
+    let mut sidecar = raw_sidecar(2);
+    sidecar.index = NUMBER_OF_COLUMNS;
+    assert_eq!(sidecar.validate(), Err(ColumnError::InvalidColumnIndex(128)));
+
+    let mut sidecar = raw_sidecar(2);
+    sidecar.kzg_proofs.pop();
+    assert!(matches!(sidecar.validate(), Err(ColumnError::LengthMismatch { cells: 2, commitments: 2, proofs: 1 })));
+
+    assert_eq!(raw_sidecar(0).validate(), Err(ColumnError::EmptyColumn));
+
+    let mut sidecar = raw_sidecar(1);
+    sidecar.signed_block_header.signature.pop();
+    assert!(matches!(sidecar.to_ssz(), Err(ColumnError::InvalidLength { .. })));
+
+    // 无法解码的 cell 或点视为验证失败
+    let settings = load_trusted_setup_from_file().unwrap();
+    assert!(!raw_sidecar(1).verify_kzg_proofs(&settings).unwrap());
+}
+
+#[test]
+fn test_ssz_layout_and_malformed_input() {
+    let sidecar = raw_sidecar(3);
+    let bytes = sidecar.to_ssz().unwrap();
+    // 定长部分 356 字节，之后是 3 个 cell、3 个承诺与 3 个证明
+    assert_eq!(bytes.len(), 356 + 3 * BYTES_PER_CELL + 6 * 48);
+    assert_eq!(&bytes[0..8], &5u64.to_le_bytes());
+    assert_eq!(&bytes[8..12], &356u32.to_le_bytes());
+    assert_eq!(DataColumnSidecar::from_ssz(&bytes).unwrap(), sidecar);
+
+    assert!(matches!(DataColumnSidecar::from_ssz(&bytes[..100]), Err(ColumnError::Ssz(_))));
+    assert!(matches!(DataColumnSidecar::from_ssz(&bytes[..bytes.len() - 1]), Err(ColumnError::Ssz(_))));
+
+    let mut bad_offset = bytes.clone();
+    bad_offset[8..12].copy_from_slice(&360u32.to_le_bytes());
+    assert!(matches!(DataColumnSidecar::from_ssz(&bad_offset), Err(ColumnError::Ssz(_))));
+
+    let mut reversed = bytes.clone();
+    reversed[12..16].copy_from_slice(&(356u32 + 10).to_le_bytes());
+    assert!(matches!(DataColumnSidecar::from_ssz(&reversed), Err(ColumnError::Ssz(_))));
+}
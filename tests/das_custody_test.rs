@@ -0,0 +1,106 @@
+// PeerDAS 列托管测试
+// 向量由规范伪代码的 Python 转写生成，字段与 consensus-spec-tests 的 networking 用例一致
+
+use kzg::eip_4844::blob_to_kzg_commitment_rust;
+use kzg::{eth::FIELD_ELEMENTS_PER_CELL, DAS};
+use rust_kzg_blst::{eip_7594::BlstBackend, types::kzg_settings::FsKZGSettings};
+use rust_kzg_tutorial::{
+    blob::{blob_from_bytes, create_test_blob_bytes},
+    das::{
+        compute_columns_for_custody_group, get_custody_groups, CustodyAssignment, CustodyError, NodeId,
+        CUSTODY_REQUIREMENT, NUMBER_OF_CUSTODY_GROUPS,
+    },
+    trusted_setup::load_trusted_setup_from_file,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Vectors {
+    get_custody_groups: Vec<CustodyGroupsCase>,
+    compute_columns_for_custody_group: Vec<ColumnsCase>,
+}
+
+#[derive(Deserialize)]
+struct CustodyGroupsCase {
+    name: String,
+    node_id: String,
+    custody_group_count: u64,
+    result: Vec<u64>,
+}
+
+#[derive(Deserialize)]
+struct ColumnsCase {
+    name: String,
+    custody_group: u64,
+    result: Vec<u64>,
+}
+
+fn vectors() -> Vectors {
+    serde_json::from_str(include_str!("vectors/das_custody.json")).unwrap()
+}
+
+#[test]
+fn test_spec_vectors() {
+    let vectors = vectors();
+    for case in &vectors.get_custody_groups {
+        let node_id: NodeId = case.node_id.parse().unwrap();
+        let groups = get_custody_groups(&node_id, case.custody_group_count).unwrap();
+        assert_eq!(groups, case.result, "get_custody_groups/{}", case.name);
+    }
+    for case in &vectors.compute_columns_for_custody_group {
+        let columns = compute_columns_for_custody_group(case.custody_group).unwrap();
+        assert_eq!(columns, case.result, "compute_columns_for_custody_group/{}", case.name);
+    }
+}
+
+#[test]
+fn test_node_id_and_bounds() {
+    let max: NodeId = "115792089237316195423570985008687907853269984665640564039457584007913129639935".parse().unwrap();
+    assert_eq!(max, NodeId::MAX);
+    assert_eq!("0xff".parse::<NodeId>().unwrap(), "255".parse().unwrap());
+    for invalid in ["", "-1", "12a", "0x", "115792089237316195423570985008687907853269984665640564039457584007913129639936"] {
+        assert!(invalid.parse::<NodeId>().is_err(), "{:?}", invalid);
+    }
+
+    assert_eq!(
+        get_custody_groups(&NodeId::default(), NUMBER_OF_CUSTODY_GROUPS + 1),
+        Err(CustodyError::TooManyGroups { requested: 129, max: 128 })
+    );
+    assert_eq!(compute_columns_for_custody_group(128), Err(CustodyError::InvalidGroup(128)));
+
+    let assignment = CustodyAssignment::new(NodeId::MAX, CUSTODY_REQUIREMENT).unwrap();
+    assert_eq!(assignment.columns.len(), CUSTODY_REQUIREMENT as usize);
+    assert!(assignment.columns.iter().all(|&column| assignment.custodies(column)));
+    assert_eq!((0..128).filter(|&column| assignment.custodies(column)).count(), 4);
+}
+
+#[test]
+fn test_filter_custody_cells() {
+    let settings: FsKZGSettings = load_trusted_setup_from_file().unwrap();
+    let blob_bytes = create_test_blob_bytes(0);
+    let blob = blob_from_bytes(&blob_bytes).unwrap();
+    let commitment = blob_to_kzg_commitment_rust(&blob, &settings).unwrap();
+
+    let assignment = CustodyAssignment::new("0x1234".parse().unwrap(), 8).unwrap();
+    let custody = assignment.compute_custody_cells(&settings, &blob_bytes).unwrap();
+    assert_eq!(custody.column_indices, assignment.columns);
+    assert_eq!(custody.cells.len(), 8 * FIELD_ELEMENTS_PER_CELL);
+    assert_eq!(custody.proofs.len(), 8);
+
+    // 筛选结果可直接批量验证
+    let commitments = vec![commitment; custody.proofs.len()];
+    let valid = <FsKZGSettings as DAS<BlstBackend>>::verify_cell_kzg_proof_batch(
+        &settings,
+        &commitments,
+        &custody.cell_indices(),
+        &custody.cells,
+        &custody.proofs,
+    )
+    .unwrap();
+    assert!(valid);
+
+    assert!(matches!(
+        assignment.filter_cells_and_proofs(&custody.cells, &custody.proofs),
+        Err(CustodyError::LengthMismatch { field: "cells", .. })
+    ));
+}
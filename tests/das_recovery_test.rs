@@ -0,0 +1,101 @@
+// 任意 cell 子集恢复测试
+// 覆盖随机、交错、乱序含重复与全部 cell 四种子集，以及数量不足、冲突重复与越界等错误
+
+use kzg::eth::CELLS_PER_EXT_BLOB;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rust_kzg_blst::types::fr::FsFr;
+use rust_kzg_tutorial::das::{recover_cells_and_proofs, RecoveryError, CELLS_REQUIRED_FOR_RECOVERY};
+
+mod common;
+use common::test_blob_cells;
+
+/// 按 `indices` 的顺序平铺对应的原始 cell
+fn select(indices: &[u64]) -> Vec<FsFr> {
+    let fixture = test_blob_cells();
+    indices
+        .iter()
+        .flat_map(|&index| fixture.cell(index as usize).iter().cloned())
+        .collect()
+}
+
+fn assert_recovers(name: &str, indices: &[u64]) {
+    let fixture = test_blob_cells();
+    let recovered = recover_cells_and_proofs(&fixture.settings, indices, &select(indices))
+        .unwrap_or_else(|e| panic!("{}: {}", name, e));
+    assert!(recovered.cells == fixture.cells, "{}: cells differ", name);
+    assert!(recovered.proofs == fixture.proofs, "{}: proofs differ", name);
+    assert!(recovered.cell(CELLS_PER_EXT_BLOB - 1) == fixture.cell(CELLS_PER_EXT_BLOB - 1));
+}
+
+#[test]
+fn test_recover_from_random_subset() {
+    let mut rng = StdRng::seed_from_u64(7594);
+    let mut indices: Vec<u64> = (0..CELLS_PER_EXT_BLOB as u64).collect();
+    indices.shuffle(&mut rng);
+    indices.truncate(rng.gen_range(CELLS_REQUIRED_FOR_RECOVERY..CELLS_PER_EXT_BLOB));
+    assert_recovers("random", &indices);
+}
+
+#[test]
+fn test_recover_from_interleaved_subset() {
+    let odd: Vec<u64> = (0..CELLS_PER_EXT_BLOB as u64).filter(|index| index % 2 == 1).collect();
+    assert_recovers("interleaved", &odd);
+}
+
+#[test]
+fn test_recover_from_shuffled_subset_with_duplicates() {
+    // 后一半的 cell 乱序给出，其中 10 个重复一次
+    let mut rng = StdRng::seed_from_u64(4844);
+    let mut indices: Vec<u64> = (CELLS_REQUIRED_FOR_RECOVERY as u64..CELLS_PER_EXT_BLOB as u64).collect();
+    let duplicates: Vec<u64> = indices.choose_multiple(&mut rng, 10).copied().collect();
+    indices.extend(duplicates);
+    indices.shuffle(&mut rng);
+    assert_recovers("shuffled with duplicates", &indices);
+}
+
+#[test]
+fn test_recover_from_all_cells() {
+    let mut indices: Vec<u64> = (0..CELLS_PER_EXT_BLOB as u64).collect();
+    indices.reverse();
+    assert_recovers("all", &indices);
+}
+
+#[test]
+fn test_recovery_errors() {
+    let settings = &test_blob_cells().settings;
+
+    // 63 个不同的 cell，重复补足到 64 个仍然不够
+    let mut indices: Vec<u64> = (0..CELLS_REQUIRED_FOR_RECOVERY as u64 - 1).collect();
+    indices.push(0);
+    let error = recover_cells_and_proofs(settings, &indices, &select(&indices)).unwrap_err();
+    assert_eq!(error, RecoveryError::NotEnoughCells { required: 64, received: 63 });
+    assert_eq!(error.to_string(), "恢复至少需要 64 个不同的 cell，实际只有 63 个");
+    assert_eq!(
+        recover_cells_and_proofs(settings, &[], &[]),
+        Err(RecoveryError::NotEnoughCells { required: 64, received: 0 })
+    );
+
+    // 同一索引内容不一致
+    let mut indices: Vec<u64> = (0..CELLS_REQUIRED_FOR_RECOVERY as u64).collect();
+    indices.push(3);
+    let mut cells = select(&indices);
+    let last = cells.len() - 1;
+    cells[last] = FsFr::default();
+    assert_eq!(
+        recover_cells_and_proofs(settings, &indices, &cells),
+        Err(RecoveryError::ConflictingDuplicate(3))
+    );
+
+    // 索引越界与长度不一致
+    let mut indices: Vec<u64> = (0..CELLS_REQUIRED_FOR_RECOVERY as u64).collect();
+    let cells = select(&indices);
+    indices[10] = CELLS_PER_EXT_BLOB as u64;
+    assert_eq!(
+        recover_cells_and_proofs(settings, &indices, &cells),
+        Err(RecoveryError::InvalidCellIndex(128))
+    );
+    assert!(matches!(
+        recover_cells_and_proofs(settings, &indices[1..], &cells),
+        Err(RecoveryError::LengthMismatch { indices: 63, .. })
+    ));
+}
@@ -0,0 +1,132 @@
+// DAS 采样模拟测试
+// 覆盖场景文件解析与检查、诚实网络的基线、扣留整列的检测以及错误证明的验证失败
+
+use std::time::Duration;
+
+use rust_kzg_tutorial::{
+    das::{
+        AdversaryConfig, DasSimulator, DataColumnSidecar, Scenario, SimulationError, SimulationReport,
+        WithholdStrategy,
+    },
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+/// 网络稠密、每列都有人托管的小场景
+fn scenario(name: &str, adversary: AdversaryConfig) -> Scenario {
+    let mut scenario = Scenario::from_toml(&format!(
+        r#"
+        name = "{}"
+        seed = 11
+        trials = 10
+
+        [network]
+        peers = 32
+        custody_group_count = 32
+        min_latency_ms = 10.0
+        max_latency_ms = 50.0
+        bandwidth_mbps = 100.0
+        timeout_ms = 1000.0
+
+        [sampling]
+        nodes = 8
+        "#,
+        name
+    ))
+    .unwrap();
+    scenario.adversary = adversary;
+    scenario
+}
+
+#[test]
+fn test_scenario_files() {
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/scenarios/das")).unwrap() {
+        let scenario = Scenario::from_file(entry.unwrap().path()).unwrap();
+        names.push(scenario.name);
+    }
+    names.sort();
+    assert_eq!(names, ["honest", "sparse-network", "withhold-columns"]);
+
+    // 省略的部分取默认值
+    let minimal = Scenario::from_toml("name = \"minimal\"\ntrials = 1").unwrap();
+    assert_eq!((minimal.blobs, minimal.sampling.samples_per_slot), (1, 8));
+    assert_eq!(minimal.adversary.strategy, WithholdStrategy::Columns);
+
+    for invalid in [
+        "name = \"x\"\ntrials = 0",
+        "name = \"x\"\ntrials = 1\n[adversary]\nwithhold_fraction = 1.5",
+        "name = \"x\"\ntrials = 1\n[sampling]\nsamples_per_slot = 129",
+        "name = \"x\"\ntrials = 1\n[network]\nmin_latency_ms = 100.0\nmax_latency_ms = 10.0",
+    ] {
+        assert!(matches!(Scenario::from_toml(invalid), Err(SimulationError::InvalidScenario(_))), "{}", invalid);
+    }
+    for malformed in ["trials = 1", "name = \"x\"\ntrials = 1\nunknown = 2", "name = \"x\"\ntrials = 1\n[adversary]\nstrategy = \"rows\""] {
+        assert!(matches!(Scenario::from_toml(malformed), Err(SimulationError::Parse(_))), "{}", malformed);
+    }
+    assert!(matches!(Scenario::from_file("scenarios/das/missing.toml"), Err(SimulationError::Io(_))));
+}
+
+#[test]
+fn test_honest_network_baseline() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let simulator = DasSimulator::new(&settings, scenario("honest", AdversaryConfig::default())).unwrap();
+    let report = simulator.run().unwrap();
+
+    assert_eq!(report.uncustodied_columns, 0);
+    assert_eq!((report.unrecoverable_trials, report.rejections, report.failed_samples), (0, 0, 0));
+    assert_eq!((report.detection_probability, report.expected_detection_probability), (0.0, 0.0));
+
+    // 10 个时隙 × 8 个节点 × 8 个样本，每个样本一个请求和一个单 blob sidecar
+    let samples = 10 * 8 * 8;
+    assert_eq!(report.bandwidth.request_bytes, samples * 40);
+    assert_eq!(report.bandwidth.response_bytes, samples * DataColumnSidecar::ssz_len(1) as u64);
+    assert_eq!(report.bandwidth.bytes_per_node, report.bandwidth.total_bytes() as f64 / 80.0);
+
+    let transfer_ms = DataColumnSidecar::ssz_len(1) as f64 * 8.0 / 100_000.0;
+    assert!(report.latency.p50_ms >= 10.0 && report.latency.max_ms <= 50.0 + transfer_ms);
+    assert!(report.latency.p50_ms <= report.latency.p95_ms && report.latency.p95_ms <= report.latency.max_ms);
+    assert!(report.verification_time > Duration::ZERO);
+
+    // 相同的种子得到相同的报告
+    let without_time = |report: SimulationReport| SimulationReport {
+        verification_time: Duration::ZERO,
+        ..report
+    };
+    assert_eq!(without_time(simulator.run().unwrap()), without_time(report));
+}
+
+#[test]
+fn test_withheld_columns_are_detected() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let adversary = AdversaryConfig {
+        withhold_fraction: 0.6,
+        strategy: WithholdStrategy::Columns,
+        corrupt_fraction: 0.0,
+    };
+    let report = DasSimulator::new(&settings, scenario("withhold", adversary)).unwrap().run().unwrap();
+
+    // 扣留 77 列，每个节点 8 个样本全部落在 51 个可用列中的概率约 4e-4
+    assert_eq!(report.unrecoverable_trials, 10);
+    assert!(report.expected_detection_probability > 0.999);
+    assert_eq!(report.detection_probability, 1.0);
+    assert_eq!(report.invalid_batches, 0);
+    assert!(report.failed_samples >= 80);
+    assert_eq!(report.latency.max_ms, 1000.0);
+}
+
+#[test]
+fn test_corrupted_proofs_fail_verification() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let adversary = AdversaryConfig {
+        withhold_fraction: 0.0,
+        strategy: WithholdStrategy::Cells,
+        corrupt_fraction: 1.0,
+    };
+    let report = DasSimulator::new(&settings, scenario("corrupt", adversary)).unwrap().run().unwrap();
+
+    // 数据完整可恢复，但每批验证都失败
+    assert_eq!((report.unrecoverable_trials, report.failed_samples), (0, 0));
+    assert_eq!(report.invalid_batches, 80);
+    assert_eq!(report.detection_probability, 1.0);
+    assert_eq!(report.expected_detection_probability, 0.0);
+}
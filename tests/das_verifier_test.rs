@@ -0,0 +1,161 @@
+// 流式 cell 验证测试
+// 覆盖按数量与按时间刷新、二分定位错误的 cell、KZG 库报错的 cell、无法解码的输入以及统计
+
+use std::time::Duration;
+
+use rust_kzg_tutorial::das::{CellStatus, CellVerdict, CellVerifier, CellVerifierConfig, ColumnError};
+
+mod common;
+use common::test_blob_cells;
+
+fn new_verifier(max_pending: usize, max_delay: Duration) -> CellVerifier<'static, usize> {
+    CellVerifier::new(&test_blob_cells().settings, CellVerifierConfig { max_pending, max_delay })
+}
+
+/// 以 cell 索引为标签加入第 `index` 个 cell，`proof_index` 不同于 `index` 时证明是错的
+fn add(verifier: &mut CellVerifier<'static, usize>, index: usize, proof_index: usize) -> Vec<CellVerdict<usize>> {
+    let fixture = test_blob_cells();
+    verifier.add(
+        index,
+        &fixture.commitment_bytes(),
+        index as u64,
+        &fixture.cell_bytes(index),
+        &fixture.proof_bytes(proof_index),
+    )
+}
+
+#[test]
+fn test_flush_at_size_threshold() {
+    let mut verifier = new_verifier(16, Duration::from_secs(3600));
+    let mut verdicts = Vec::new();
+    for index in 0..40 {
+        let flushed = add(&mut verifier, index, index);
+        assert_eq!(flushed.is_empty(), index != 15 && index != 31, "cell {}", index);
+        verdicts.extend(flushed);
+    }
+    assert_eq!(verifier.pending(), 8);
+    assert!(verifier.deadline().is_some());
+    verdicts.extend(verifier.flush());
+    assert!(verifier.flush().is_empty());
+
+    assert_eq!(verdicts.iter().map(|verdict| verdict.tag).collect::<Vec<_>>(), (0..40).collect::<Vec<_>>());
+    assert!(verdicts.iter().all(|verdict| verdict.status.is_valid()));
+    let stats = verifier.stats();
+    assert_eq!((stats.added, stats.flushes, stats.batch_calls, stats.valid), (40, 3, 3, 40));
+}
+
+#[test]
+fn test_flush_at_time_threshold() {
+    let mut verifier = new_verifier(128, Duration::from_millis(30));
+    assert!(add(&mut verifier, 0, 0).is_empty());
+    assert!(add(&mut verifier, 1, 1).is_empty());
+    assert!(verifier.poll().is_empty());
+
+    std::thread::sleep(Duration::from_millis(40));
+    let verdicts = verifier.poll();
+    assert_eq!(verdicts.len(), 2);
+    assert_eq!(verifier.deadline(), None);
+
+    // 到期后新加入的 cell 同批验证
+    assert!(add(&mut verifier, 2, 2).is_empty());
+    std::thread::sleep(Duration::from_millis(40));
+    let verdicts = add(&mut verifier, 3, 3);
+    assert_eq!(verdicts.iter().map(|verdict| verdict.tag).collect::<Vec<_>>(), [2, 3]);
+    assert_eq!(verifier.pending(), 0);
+}
+
+#[test]
+fn test_bisection_pinpoints_bad_cells() {
+    let mut verifier = new_verifier(32, Duration::from_secs(3600));
+    let bad = [5, 6, 20];
+    let mut verdicts = Vec::new();
+    for index in 0..32 {
+        let proof_index = if bad.contains(&index) { index + 1 } else { index };
+        verdicts.extend(add(&mut verifier, index, proof_index));
+    }
+    assert_eq!(verdicts.len(), 32);
+    for verdict in &verdicts {
+        let expected = if bad.contains(&verdict.tag) { CellStatus::InvalidProof } else { CellStatus::Valid };
+        assert_eq!(verdict.status, expected, "cell {}", verdict.tag);
+    }
+
+    // 一次整批调用，之后每层至多两次
+    let stats = verifier.stats();
+    assert_eq!((stats.valid, stats.invalid, stats.flushes), (29, 3, 1));
+    assert!(stats.batch_calls <= 1 + 2 * 3 * 5, "{:?}", stats);
+
+    // 全部错误时每个 cell 都被定位
+    let mut verifier = new_verifier(128, Duration::from_secs(3600));
+    for index in 0..7 {
+        assert!(add(&mut verifier, index, index + 1).is_empty());
+    }
+    let verdicts = verifier.flush();
+    assert_eq!(verdicts.len(), 7);
+    assert!(verdicts.iter().all(|verdict| verdict.status == CellStatus::InvalidProof));
+}
+
+#[test]
+fn test_malformed_input_reported_immediately() {
+    let fixture = test_blob_cells();
+    let mut verifier = new_verifier(4, Duration::from_secs(3600));
+    let commitment = fixture.commitment_bytes();
+    let cell = &fixture.cell_bytes(0);
+    let proof = &fixture.proof_bytes(0);
+
+    let verdicts = verifier.add(1, &commitment, 128, cell, proof);
+    assert_eq!(verdicts[0].status, CellStatus::Malformed(ColumnError::InvalidColumnIndex(128)));
+
+    let verdicts = verifier.add(2, &commitment, 0, &cell[1..], proof);
+    assert!(matches!(verdicts[0].status, CellStatus::Malformed(ColumnError::InvalidLength { .. })));
+
+    let verdicts = verifier.add(3, &[0; 48], 0, cell, proof);
+    assert!(matches!(verdicts[0].status, CellStatus::Malformed(ColumnError::InvalidPoint { .. })));
+
+    assert_eq!(verifier.pending(), 0);
+    let stats = verifier.stats();
+    assert_eq!((stats.added, stats.malformed, stats.batch_calls), (3, 3, 0));
+}
+
+/// 在曲线上但不在 G1 子群中的点 (x = 4)：可以解码，但 KZG 库验证时报错
+const POINT_OUTSIDE_SUBGROUP: [u8; 48] = {
+    let mut point = [0; 48];
+    point[0] = 0x80;
+    point[47] = 4;
+    point
+};
+
+#[test]
+fn test_kzg_error_yields_error_verdicts() {
+    let fixture = test_blob_cells();
+    let mut verifier = new_verifier(128, Duration::from_millis(30));
+    for index in 0..6 {
+        let commitment = if index == 3 { POINT_OUTSIDE_SUBGROUP } else { fixture.commitment_bytes() };
+        let proof_index = if index == 4 { 5 } else { index };
+        let verdicts = verifier.add(
+            index,
+            &commitment,
+            index as u64,
+            &fixture.cell_bytes(index),
+            &fixture.proof_bytes(proof_index),
+        );
+        assert!(verdicts.is_empty());
+    }
+
+    // 到期刷新时无法解码的输入与本批结果一起返回
+    std::thread::sleep(Duration::from_millis(40));
+    let verdicts = verifier.add(6, &fixture.commitment_bytes(), 128, &fixture.cell_bytes(6), &fixture.proof_bytes(6));
+    assert_eq!(verdicts.len(), 7);
+    assert_eq!(verdicts[0].status, CellStatus::Malformed(ColumnError::InvalidColumnIndex(128)));
+    for verdict in &verdicts[1..] {
+        match verdict.tag {
+            3 => assert!(matches!(verdict.status, CellStatus::Error(ColumnError::KZGError(_))), "{:?}", verdict),
+            4 => assert_eq!(verdict.status, CellStatus::InvalidProof),
+            _ => assert_eq!(verdict.status, CellStatus::Valid, "cell {}", verdict.tag),
+        }
+    }
+    assert_eq!(verdicts[1..].iter().map(|verdict| verdict.tag).collect::<Vec<_>>(), (0..6).collect::<Vec<_>>());
+
+    assert_eq!(verifier.pending(), 0);
+    let stats = verifier.stats();
+    assert_eq!((stats.valid, stats.invalid, stats.errors, stats.malformed), (4, 1, 1, 1));
+}
@@ -0,0 +1,80 @@
+// 库辅助模块测试
+// 覆盖受信任设置查找、Blob 构造和性能统计
+
+use std::time::Duration;
+
+use kzg::eip_4844::{BYTES_PER_BLOB, FIELD_ELEMENTS_PER_BLOB};
+use rust_kzg_tutorial::{
+    blob::{blob_from_bytes, blob_to_bytes, create_random_blob, create_test_blob, create_test_blob_bytes},
+    metrics::{PerformanceMonitor, PerformanceProfiler},
+    trusted_setup::find_trusted_setup_file,
+};
+
+#[test]
+fn test_find_bundled_trusted_setup() {
+    let path = find_trusted_setup_file().expect("仓库自带的受信任设置应该能被找到");
+    assert!(path.exists());
+}
+
+#[test]
+fn test_test_blob_is_deterministic() {
+    let blob1 = create_test_blob().unwrap();
+    let blob2 = create_test_blob().unwrap();
+
+    assert_eq!(blob1.len(), FIELD_ELEMENTS_PER_BLOB);
+    assert_eq!(blob1, blob2);
+}
+
+#[test]
+fn test_blob_bytes_round_trip() {
+    let blob = create_random_blob().unwrap();
+    let bytes = blob_to_bytes(&blob);
+    assert_eq!(bytes.len(), BYTES_PER_BLOB);
+
+    let decoded = blob_from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, blob);
+
+    let seeded = create_test_blob_bytes(3);
+    assert_eq!(seeded.len(), BYTES_PER_BLOB);
+    assert!(blob_from_bytes(&seeded).is_ok());
+    assert!(blob_from_bytes(&seeded[1..]).is_err());
+
+    // 不同的 seed 得到不同的 Blob，seed 0 只有最低字节非零
+    assert_ne!(seeded, create_test_blob_bytes(4));
+    assert_ne!(create_test_blob_bytes(0), create_test_blob_bytes(256));
+    let zero = create_test_blob_bytes(0);
+    assert!(zero.chunks(32).enumerate().all(|(j, element)| element[..31] == [0; 31] && element[31] == j as u8));
+}
+
+#[test]
+fn test_profiler_stats() {
+    let mut profiler = PerformanceProfiler::new();
+    for ms in [1u64, 2, 3, 4] {
+        profiler.record_metric("commit", Duration::from_millis(ms));
+    }
+    let value = profiler.measure("noop", || 42);
+    assert_eq!(value, 42);
+
+    let stats = profiler.stats("commit").unwrap();
+    assert_eq!(stats.count, 4);
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.max, Duration::from_millis(4));
+    assert_eq!(stats.total, Duration::from_millis(10));
+    assert_eq!(profiler.operations(), vec!["commit", "noop"]);
+    assert!(profiler.stats("missing").is_none());
+}
+
+#[test]
+fn test_monitor_report() {
+    let monitor = PerformanceMonitor::new();
+    monitor.record_commitment(Duration::from_millis(10));
+    monitor.record_commitment(Duration::from_millis(20));
+    monitor.record_verification(Duration::from_millis(5));
+    monitor.record_error();
+
+    let report = monitor.generate_report();
+    assert_eq!(report.total_operations, 3);
+    assert_eq!(report.average_commitment_time, Duration::from_millis(15));
+    assert_eq!(report.average_proof_time, Duration::ZERO);
+    assert!((report.error_rate - 1.0 / 3.0).abs() < 1e-9);
+}
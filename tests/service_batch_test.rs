@@ -0,0 +1,138 @@
+// 服务批量处理测试
+// 覆盖聚合批量验证的二分定位以及计算池的并发上限
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use kzg::eip_4844::{blob_to_kzg_commitment_rust, compute_blob_kzg_proof_rust};
+use rust_kzg_blst::types::{fr::FsFr, g1::FsG1, kzg_settings::FsKZGSettings};
+use rust_kzg_tutorial::{
+    blob::{blob_from_bytes, create_test_blob_bytes},
+    service::{verify_blob_batch_bisect, CpuPool},
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+/// 生成 `count` 组内容各不相同的 (blob, 承诺, 证明)
+fn valid_items(settings: &FsKZGSettings, count: usize) -> (Vec<Vec<FsFr>>, Vec<FsG1>, Vec<FsG1>) {
+    let mut blobs = Vec::new();
+    let mut commitments = Vec::new();
+    let mut proofs = Vec::new();
+    for seed in 0..count {
+        let bytes = create_test_blob_bytes(seed);
+        let blob = blob_from_bytes(&bytes).unwrap();
+        let commitment = blob_to_kzg_commitment_rust(&blob, settings).unwrap();
+        proofs.push(compute_blob_kzg_proof_rust(&blob, &commitment, settings).unwrap());
+        commitments.push(commitment);
+        blobs.push(blob);
+    }
+    (blobs, commitments, proofs)
+}
+
+#[test]
+fn test_all_valid_uses_single_batch_call() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let (blobs, commitments, proofs) = valid_items(&settings, 8);
+
+    let result = verify_blob_batch_bisect(&blobs, &commitments, &proofs, &settings).unwrap();
+    assert_eq!(result.valid, vec![true; 8]);
+    assert_eq!(result.batch_calls, 1);
+
+    let empty = verify_blob_batch_bisect(&[], &[], &[], &settings).unwrap();
+    assert!(empty.valid.is_empty());
+    assert_eq!(empty.batch_calls, 0);
+}
+
+#[test]
+fn test_bisection_finds_invalid_items() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let (blobs, commitments, mut proofs) = valid_items(&settings, 8);
+
+    // 交换两个证明，使第 2 和第 5 项无效
+    proofs.swap(2, 5);
+
+    let result = verify_blob_batch_bisect(&blobs, &commitments, &proofs, &settings).unwrap();
+    let expected: Vec<bool> = (0..8).map(|i| i != 2 && i != 5).collect();
+    assert_eq!(result.valid, expected);
+    assert!(result.batch_calls > 1);
+    assert!(result.batch_calls < 2 * 8);
+
+    let (_, _, short) = valid_items(&settings, 7);
+    assert!(verify_blob_batch_bisect(&blobs, &commitments, &short, &settings).is_err());
+}
+
+#[tokio::test]
+async fn test_cpu_pool_bounds_concurrency() {
+    let pool = CpuPool::new(2);
+    assert_eq!(pool.size(), 2);
+    assert_eq!(CpuPool::new(0).size(), 1);
+
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+
+    let tasks: Vec<_> = (0..8)
+        .map(|i| {
+            let pool = pool.clone();
+            let running = Arc::clone(&running);
+            let peak = Arc::clone(&peak);
+            tokio::spawn(async move {
+                pool.run(move || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    i * 2
+                })
+                .await
+            })
+        })
+        .collect();
+
+    let mut outputs = Vec::new();
+    for task in tasks {
+        outputs.push(task.await.unwrap().unwrap());
+    }
+    assert_eq!(outputs, (0..8).map(|i| i * 2).collect::<Vec<_>>());
+    assert!(peak.load(Ordering::SeqCst) <= 2);
+}
+
+#[tokio::test]
+async fn test_cpu_pool_bound_survives_dropped_callers() {
+    let pool = CpuPool::new(2);
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let finished = Arc::new(AtomicUsize::new(0));
+
+    let job = |running: Arc<AtomicUsize>, peak: Arc<AtomicUsize>, finished: Arc<AtomicUsize>| {
+        move || {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(50));
+            running.fetch_sub(1, Ordering::SeqCst);
+            finished.fetch_add(1, Ordering::SeqCst);
+        }
+    };
+
+    // 调用方在计算开始后被丢弃，闭包仍在阻塞线程上运行
+    for _ in 0..4 {
+        let run = pool.run(job(Arc::clone(&running), Arc::clone(&peak), Arc::clone(&finished)));
+        let _ = tokio::time::timeout(Duration::from_millis(5), run).await;
+    }
+
+    let tasks: Vec<_> = (0..4)
+        .map(|_| {
+            let pool = pool.clone();
+            let work = job(Arc::clone(&running), Arc::clone(&peak), Arc::clone(&finished));
+            tokio::spawn(async move { pool.run(work).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    while running.load(Ordering::SeqCst) > 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(peak.load(Ordering::SeqCst) <= 2);
+    assert!(finished.load(Ordering::SeqCst) >= 4);
+}
@@ -0,0 +1,85 @@
+// 服务结果缓存测试
+// 覆盖内容寻址键、字节上限淘汰、命中统计以及可信设置代号
+
+use rust_kzg_tutorial::service::{blob_digest, CacheKey, CacheManager};
+
+#[tokio::test]
+async fn test_keys_are_content_addressed() {
+    let cache = CacheManager::new(16, 300, 1 << 20);
+    let digest = blob_digest(&[1u8; 64]);
+    assert_eq!(digest, blob_digest(&[1u8; 64]));
+    assert_ne!(digest, blob_digest(&[2u8; 64]));
+
+    let commitment = [0xaa; 48];
+    let proof = [0xbb; 48];
+    cache.set(CacheKey::Commitment(digest), commitment.to_vec(), 0).await;
+    cache.set(CacheKey::Proof(digest, commitment), proof.to_vec(), 0).await;
+    cache.set(CacheKey::Verification(digest, commitment, proof), vec![1], 0).await;
+
+    assert_eq!(cache.get(&CacheKey::Commitment(digest), 0).await, Some(commitment.to_vec()));
+    assert_eq!(cache.get(&CacheKey::Proof(digest, commitment), 0).await, Some(proof.to_vec()));
+    assert_eq!(cache.get(&CacheKey::Verification(digest, commitment, proof), 0).await, Some(vec![1]));
+    // 证明不同则是另一个键
+    assert_eq!(cache.get(&CacheKey::Verification(digest, commitment, [0xcc; 48]), 0).await, None);
+
+    let stats = cache.stats().await;
+    assert_eq!((stats.entries, stats.hits, stats.misses), (3, 3, 1));
+    assert_eq!(stats.hit_rate(), 0.75);
+}
+
+#[tokio::test]
+async fn test_eviction_is_bounded_by_bytes() {
+    let key = |i: u8| CacheKey::Commitment(blob_digest(&[i]));
+    let entry_size = std::mem::size_of::<CacheKey>() + 1000;
+    // 条目数足够，只受字节上限约束
+    let cache = CacheManager::new(100, 300, 3 * entry_size);
+
+    for i in 0..3 {
+        assert_eq!(cache.set(key(i), vec![i; 1000], 0).await, 0);
+    }
+    // 先访问 0，使 1 成为最久未使用的条目
+    assert!(cache.get(&key(0), 0).await.is_some());
+    assert_eq!(cache.set(key(3), vec![3; 1000], 0).await, 1);
+    assert!(cache.get(&key(1), 0).await.is_none());
+
+    let stats = cache.stats().await;
+    assert_eq!(stats.entries, 3);
+    assert_eq!(stats.bytes, 3 * entry_size);
+    assert_eq!(stats.evictions, 1);
+
+    // 覆盖同一个键不算淘汰，字节数按新值计算
+    assert_eq!(cache.set(key(3), vec![3; 10], 0).await, 0);
+    assert_eq!(cache.stats().await.bytes, 2 * entry_size + std::mem::size_of::<CacheKey>() + 10);
+
+    // 超过上限的单个条目不缓存
+    assert_eq!(cache.set(key(9), vec![0; 4 * entry_size], 0).await, 0);
+    assert!(cache.get(&key(9), 0).await.is_none());
+}
+
+#[tokio::test]
+async fn test_entry_count_limit_still_applies() {
+    let cache = CacheManager::new(2, 300, 1 << 20);
+    for i in 0..3u8 {
+        cache.set(CacheKey::Commitment(blob_digest(&[i])), vec![i], 0).await;
+    }
+    let stats = cache.stats().await;
+    assert_eq!((stats.entries, stats.evictions), (2, 1));
+    assert!(cache.get(&CacheKey::Commitment(blob_digest(&[0])), 0).await.is_none());
+}
+
+#[tokio::test]
+async fn test_entries_are_scoped_to_settings_generation() {
+    let cache = CacheManager::new(16, 300, 1 << 20);
+    let key = CacheKey::Commitment(blob_digest(&[7]));
+
+    // 替换设置前开始的请求晚到的写入：新一代的查询不命中并移除旧条目
+    cache.set(key, vec![1; 48], 0).await;
+    assert!(cache.get(&key, 1).await.is_none());
+    assert_eq!(cache.stats().await.entries, 0);
+
+    // 仍持有旧代号的请求也不会命中新一代的结果，且不移除它
+    cache.set(key, vec![2; 48], 1).await;
+    assert!(cache.get(&key, 0).await.is_none());
+    assert_eq!(cache.get(&key, 1).await, Some(vec![2; 48]));
+    assert_eq!(cache.stats().await.entries, 1);
+}
@@ -0,0 +1,116 @@
+// 服务 EIP-7594 cell 运算测试
+// 覆盖 cell 计算、批量验证、半数恢复以及输入校验
+
+use kzg::eip_4844::blob_to_kzg_commitment_rust;
+use kzg::eth::CELLS_PER_EXT_BLOB;
+use kzg::G1;
+use rust_kzg_tutorial::{
+    blob::{blob_from_bytes, create_test_blob_bytes},
+    service::{
+        cells::{compute_cells_and_proofs, recover_cells_and_proofs, verify_cells},
+        ServiceError, BYTES_PER_CELL,
+    },
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+#[test]
+fn test_compute_and_verify_cells() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let blob = create_test_blob_bytes(0);
+    let commitment = blob_to_kzg_commitment_rust(&blob_from_bytes(&blob).unwrap(), &settings)
+        .unwrap()
+        .to_bytes();
+
+    let result = compute_cells_and_proofs(&settings, &blob).unwrap();
+    assert_eq!(result.cells.len(), CELLS_PER_EXT_BLOB);
+    assert_eq!(result.proofs.len(), CELLS_PER_EXT_BLOB);
+    assert!(result.cells.iter().all(|cell| cell.len() == BYTES_PER_CELL));
+    // 扩展前半部分就是原始 blob
+    assert_eq!(result.cells[0], blob[..BYTES_PER_CELL]);
+
+    // 抽样验证部分 cell
+    let indices = [0u64, 5, 64, 127];
+    let cells: Vec<Vec<u8>> = indices.iter().map(|&i| result.cells[i as usize].clone()).collect();
+    let proofs: Vec<[u8; 48]> = indices.iter().map(|&i| result.proofs[i as usize]).collect();
+    let commitments = vec![commitment; indices.len()];
+    assert!(verify_cells(&settings, &commitments, &indices, &cells, &proofs).unwrap());
+
+    // cell 与索引错位
+    let shifted = [1u64, 5, 64, 127];
+    assert!(!verify_cells(&settings, &commitments, &shifted, &cells, &proofs).unwrap());
+
+    assert!(matches!(
+        compute_cells_and_proofs(&settings, &blob[1..]),
+        Err(ServiceError::InvalidBlobSize { .. })
+    ));
+
+    // 非规范域元素是客户端输入错误 (400)，而不是 KZG 内部错误 (500)
+    let mut non_canonical = blob.clone();
+    non_canonical[..32].fill(0xff);
+    assert!(matches!(
+        compute_cells_and_proofs(&settings, &non_canonical),
+        Err(ServiceError::InvalidRequest(_))
+    ));
+}
+
+#[test]
+fn test_recover_from_half_of_cells() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let mut blob = create_test_blob_bytes(0);
+    blob[30] = 9;
+    let full = compute_cells_and_proofs(&settings, &blob).unwrap();
+
+    // 只保留奇数索引的 cell
+    let indices: Vec<u64> = (0..CELLS_PER_EXT_BLOB as u64).filter(|i| i % 2 == 1).collect();
+    let cells: Vec<Vec<u8>> = indices.iter().map(|&i| full.cells[i as usize].clone()).collect();
+
+    let recovered = recover_cells_and_proofs(&settings, &indices, &cells).unwrap();
+    assert_eq!(recovered, full);
+
+    // 少于一半、重复索引、越界索引均被拒绝
+    assert!(matches!(
+        recover_cells_and_proofs(&settings, &indices[1..], &cells[1..]),
+        Err(ServiceError::InvalidRequest(_))
+    ));
+    let mut duplicated = indices.clone();
+    duplicated[1] = duplicated[0];
+    assert!(matches!(
+        recover_cells_and_proofs(&settings, &duplicated, &cells),
+        Err(ServiceError::InvalidRequest(_))
+    ));
+    let mut out_of_range = indices.clone();
+    out_of_range[0] = CELLS_PER_EXT_BLOB as u64;
+    assert!(matches!(
+        recover_cells_and_proofs(&settings, &out_of_range, &cells),
+        Err(ServiceError::InvalidRequest(_))
+    ));
+}
+
+#[test]
+fn test_verify_rejects_malformed_input() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let full = compute_cells_and_proofs(&settings, &create_test_blob_bytes(0)).unwrap();
+    // 无穷远点，格式合法
+    let mut commitment = [0u8; 48];
+    commitment[0] = 0xc0;
+
+    // 数组长度不一致
+    assert!(matches!(
+        verify_cells(&settings, &[commitment], &[0, 1], &full.cells[..1], &full.proofs[..1]),
+        Err(ServiceError::InvalidRequest(_))
+    ));
+
+    // cell 长度错误
+    let short_cell = vec![full.cells[0][..BYTES_PER_CELL - 1].to_vec()];
+    assert!(matches!(
+        verify_cells(&settings, &[commitment], &[0], &short_cell, &full.proofs[..1]),
+        Err(ServiceError::InvalidRequest(_))
+    ));
+
+    // 非规范域元素
+    let non_canonical = vec![vec![0xff; BYTES_PER_CELL]];
+    assert!(matches!(
+        verify_cells(&settings, &[commitment], &[0], &non_canonical, &full.proofs[..1]),
+        Err(ServiceError::InvalidRequest(_))
+    ));
+}
@@ -0,0 +1,260 @@
+// 服务编码协商测试
+// 覆盖 Content-Type / Accept 协商、SSZ 请求解码 (含 cell 接口)、SSZ 响应布局以及请求提取器
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::FromRequest,
+    http::{header, HeaderMap, HeaderValue, Request, StatusCode},
+    response::IntoResponse,
+};
+use kzg::eip_4844::BYTES_PER_BLOB;
+use rust_kzg_tutorial::service::{
+    codec::{DecodeRequest, EncodeSsz, MAX_BINARY_REQUEST_BYTES, MAX_JSON_REQUEST_BYTES},
+    BatchRequest, CellRecoveryInput, CellVerificationInput, CellVerificationRequest, CellVerificationResponse,
+    CommitmentInput, CommitmentResponse, Encoded, Encoding, JsonOnly, Negotiated, ProofInput,
+    ServiceError, VerificationInput, VerificationRequest, VerificationResponse, BYTES_PER_CELL,
+};
+
+fn headers(pairs: &[(header::HeaderName, &'static str)]) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for (name, value) in pairs {
+        map.append(name.clone(), HeaderValue::from_static(value));
+    }
+    map
+}
+
+fn verification_bytes() -> Vec<u8> {
+    let mut bytes = vec![0u8; BYTES_PER_BLOB];
+    bytes[31] = 7;
+    bytes.extend_from_slice(&[0xaa; 48]);
+    bytes.extend_from_slice(&[0xbb; 48]);
+    bytes
+}
+
+async fn body_bytes(mut body: impl HttpBody<Data = axum::body::Bytes> + Unpin) -> Vec<u8> {
+    let mut out = Vec::new();
+    while let Some(chunk) = body.data().await {
+        out.extend_from_slice(&chunk.ok().unwrap());
+    }
+    out
+}
+
+#[test]
+fn test_content_negotiation() {
+    assert_eq!(Encoding::from_content_type(&HeaderMap::new()).unwrap(), Encoding::Json);
+    assert_eq!(
+        Encoding::from_content_type(&headers(&[(header::CONTENT_TYPE, "application/json; charset=utf-8")])).unwrap(),
+        Encoding::Json
+    );
+    assert_eq!(
+        Encoding::from_content_type(&headers(&[(header::CONTENT_TYPE, "application/octet-stream")])).unwrap(),
+        Encoding::OctetStream
+    );
+    assert!(matches!(
+        Encoding::from_content_type(&headers(&[(header::CONTENT_TYPE, "text/plain")])),
+        Err(ServiceError::UnsupportedMediaType(_))
+    ));
+
+    // q 值相同时 Accept 中靠前的支持类型优先，未指定或不支持时沿用请求编码
+    let accept = headers(&[(header::ACCEPT, "text/html, application/ssz, application/json")]);
+    assert_eq!(Encoding::negotiate(&accept, Encoding::Json), Encoding::Ssz);
+    assert_eq!(Encoding::negotiate(&HeaderMap::new(), Encoding::OctetStream), Encoding::OctetStream);
+    assert_eq!(Encoding::negotiate(&headers(&[(header::ACCEPT, "*/*")]), Encoding::Ssz), Encoding::Ssz);
+
+    // 按 q 值选择，q=0 表示不接受
+    let accept = headers(&[(header::ACCEPT, "application/json;q=0, application/octet-stream")]);
+    assert_eq!(Encoding::negotiate(&accept, Encoding::Json), Encoding::OctetStream);
+    let accept = headers(&[(header::ACCEPT, "application/ssz;q=0.5, application/json;q=0.9")]);
+    assert_eq!(Encoding::negotiate(&accept, Encoding::Ssz), Encoding::Json);
+    let accept = headers(&[(header::ACCEPT, "application/octet-stream; q=0.2, */*;q=0.8")]);
+    assert_eq!(Encoding::negotiate(&accept, Encoding::Json), Encoding::Json);
+    let accept = headers(&[(header::ACCEPT, "application/ssz;q=0")]);
+    assert_eq!(Encoding::negotiate(&accept, Encoding::Json), Encoding::Json);
+
+    assert_eq!(MAX_BINARY_REQUEST_BYTES, BYTES_PER_BLOB + 96);
+    assert_eq!(MAX_JSON_REQUEST_BYTES, 2 * MAX_BINARY_REQUEST_BYTES + 4096);
+}
+
+#[test]
+fn test_ssz_requests_match_json() {
+    let bytes = verification_bytes();
+    let from_ssz = VerificationInput::from_ssz(&bytes).unwrap();
+    let from_json = VerificationInput::from_json(VerificationRequest {
+        blob: hex::encode(&bytes[..BYTES_PER_BLOB]),
+        commitment: hex::encode([0xaa; 48]),
+        proof: hex::encode([0xbb; 48]),
+    })
+    .unwrap();
+    assert_eq!(from_ssz, from_json);
+    assert_eq!(from_ssz.proof, [0xbb; 48]);
+
+    let proof_input = ProofInput::from_ssz(&bytes[..BYTES_PER_BLOB + 48]).unwrap();
+    assert_eq!(proof_input.commitment, [0xaa; 48]);
+    assert_eq!(CommitmentInput::from_ssz(&bytes[..BYTES_PER_BLOB]).unwrap().blob[31], 7);
+
+    // 长度必须与定长容器一致
+    assert!(matches!(
+        CommitmentInput::from_ssz(&bytes),
+        Err(ServiceError::InvalidBlobSize { expected: BYTES_PER_BLOB, .. })
+    ));
+    assert!(matches!(ProofInput::from_ssz(&bytes), Err(ServiceError::InvalidRequest(_))));
+    assert!(matches!(
+        VerificationInput::from_ssz(&bytes[1..]),
+        Err(ServiceError::InvalidRequest(_))
+    ));
+}
+
+#[test]
+fn test_ssz_response_layout() {
+    let response = CommitmentResponse {
+        commitment: [0x11; 48],
+        versioned_hash: [0x01; 32],
+        processing_time_ms: 0x0102,
+    };
+    let ssz = response.to_ssz();
+    assert_eq!(ssz.len(), 48 + 32 + 8);
+    assert_eq!(&ssz[..48], &[0x11; 48]);
+    assert_eq!(&ssz[80..], &[0x02, 0x01, 0, 0, 0, 0, 0, 0]);
+    // 字节字段只在 JSON 中编码为十六进制
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["commitment"], hex::encode([0x11; 48]));
+    assert_eq!(json["versioned_hash"], hex::encode([0x01; 32]));
+
+    let verification = VerificationResponse {
+        is_valid: true,
+        versioned_hash: [0x01; 32],
+        processing_time_ms: 5,
+    };
+    let ssz = verification.to_ssz();
+    assert_eq!(ssz.len(), 1 + 32 + 8);
+    assert_eq!(ssz[0], 1);
+}
+
+#[tokio::test]
+async fn test_extractor_and_encoded_response() {
+    let request = Request::builder()
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .body(Body::from(verification_bytes()))
+        .unwrap();
+    let negotiated = Negotiated::<VerificationInput>::from_request(request, &()).await.unwrap();
+    assert_eq!(negotiated.accept, Encoding::OctetStream);
+    assert_eq!(negotiated.body.commitment, [0xaa; 48]);
+
+    let json = serde_json::json!({ "blob": hex::encode(vec![0u8; BYTES_PER_BLOB]) }).to_string();
+    let request = Request::builder()
+        .header(header::ACCEPT, "application/ssz")
+        .body(Body::from(json))
+        .unwrap();
+    let negotiated = Negotiated::<CommitmentInput>::from_request(request, &()).await.unwrap();
+    assert_eq!(negotiated.accept, Encoding::Ssz);
+
+    let request = Request::builder()
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(Body::from("blob"))
+        .unwrap();
+    let rejection = Negotiated::<CommitmentInput>::from_request(request, &()).await.unwrap_err();
+    assert_eq!(rejection.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let request = Request::builder().body(Body::from("{\"blob\": \"zz\"}")).unwrap();
+    let rejection = Negotiated::<CommitmentInput>::from_request(request, &()).await.unwrap_err();
+    assert_eq!(rejection.status(), StatusCode::BAD_REQUEST);
+
+    let verification = VerificationResponse {
+        is_valid: false,
+        versioned_hash: hex::encode([0x01; 32]),
+        processing_time_ms: 1,
+    };
+    let response = Encoded::new(Encoding::Ssz, verification).into_response();
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/ssz");
+    let body = body_bytes(response.into_body()).await;
+    assert_eq!(body.len(), 41);
+    assert_eq!(body[0], 0);
+}
+
+#[tokio::test]
+async fn test_json_only_routes() {
+    let json = r#"{"requests": []}"#;
+    let request = Request::post("/api/v1/batch").body(Body::from(json)).unwrap();
+    let JsonOnly(batch) = JsonOnly::<BatchRequest>::from_request(request, &()).await.unwrap();
+    assert!(batch.requests.is_empty());
+
+    // 二进制请求体明确返回 415，而不是按 JSON 解析失败
+    let request = Request::post("/api/v1/batch")
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .body(Body::from(vec![0u8; 8]))
+        .unwrap();
+    let rejection = JsonOnly::<BatchRequest>::from_request(request, &()).await.unwrap_err();
+    assert_eq!(rejection.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    // Accept 优先二进制编码时返回 406；JSON 仍可接受时照常处理
+    let request = Request::post("/api/v1/batch")
+        .header(header::ACCEPT, "application/json;q=0, application/octet-stream")
+        .body(Body::from(json))
+        .unwrap();
+    let rejection = JsonOnly::<BatchRequest>::from_request(request, &()).await.unwrap_err();
+    assert_eq!(rejection.status(), StatusCode::NOT_ACCEPTABLE);
+
+    let request = Request::post("/api/v1/batch")
+        .header(header::ACCEPT, "application/ssz;q=0.1, application/json")
+        .body(Body::from(json))
+        .unwrap();
+    assert!(JsonOnly::<BatchRequest>::from_request(request, &()).await.is_ok());
+}
+
+#[test]
+fn test_cell_ssz_requests_match_json() {
+    let cell = vec![0x5a; BYTES_PER_CELL];
+    let mut bytes = Vec::new();
+    let lists: [Vec<u8>; 4] = [
+        [[0xaa; 48], [0xab; 48]].concat(),
+        [3u64, 9].iter().flat_map(|index| index.to_le_bytes()).collect(),
+        [cell.clone(), cell.clone()].concat(),
+        [[0xbb; 48], [0xbc; 48]].concat(),
+    ];
+    let mut offset = 16u32;
+    for list in &lists {
+        bytes.extend_from_slice(&offset.to_le_bytes());
+        offset += list.len() as u32;
+    }
+    for list in &lists {
+        bytes.extend_from_slice(list);
+    }
+
+    let from_ssz = CellVerificationInput::from_ssz(&bytes).unwrap();
+    let from_json = CellVerificationInput::from_json(CellVerificationRequest {
+        commitments: vec![hex::encode([0xaa; 48]), hex::encode([0xab; 48])],
+        cell_indices: vec![3, 9],
+        cells: vec![hex::encode(&cell), hex::encode(&cell)],
+        proofs: vec![hex::encode([0xbb; 48]), hex::encode([0xbc; 48])],
+    })
+    .unwrap();
+    assert_eq!(from_ssz, from_json);
+
+    // 偏移量越界或列表长度不是元素大小的整数倍
+    let mut bad_offset = bytes.clone();
+    bad_offset[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(CellVerificationInput::from_ssz(&bad_offset), Err(ServiceError::InvalidRequest(_))));
+    assert!(matches!(CellVerificationInput::from_ssz(&bytes[..10]), Err(ServiceError::InvalidRequest(_))));
+
+    let mut recovery = Vec::new();
+    recovery.extend_from_slice(&8u32.to_le_bytes());
+    recovery.extend_from_slice(&16u32.to_le_bytes());
+    recovery.extend_from_slice(&7u64.to_le_bytes());
+    recovery.extend_from_slice(&cell);
+    let input = CellRecoveryInput::from_ssz(&recovery).unwrap();
+    assert_eq!(input.cell_indices, vec![7]);
+    assert_eq!(input.cells, vec![cell.clone()]);
+    assert!(matches!(
+        CellRecoveryInput::from_ssz(&recovery[..recovery.len() - 1]),
+        Err(ServiceError::InvalidRequest(_))
+    ));
+
+    let response = CellVerificationResponse {
+        is_valid: true,
+        cell_count: 2,
+        processing_time_ms: 3,
+    };
+    let ssz = response.to_ssz();
+    assert_eq!(ssz.len(), 1 + 8 + 8);
+    assert_eq!(&ssz[..2], &[1, 2]);
+}
@@ -0,0 +1,311 @@
+// gRPC 接口测试 (需启用 `grpc` 特性)
+// 覆盖服务错误到 gRPC 状态的映射、消息转换、访问控制、流式批量的分组处理以及批量条目计费
+#![cfg(feature = "grpc")]
+
+use std::time::Duration;
+
+use tonic::{Code, Request, Status};
+use tower::Layer;
+
+use rust_kzg_tutorial::{
+    blob::create_test_blob_bytes,
+    service::{
+        config::ApiKeyConfig,
+        grpc::{
+            proto::{
+                batch_item::Operation, batch_result::Outcome, kzg_service_client::KzgServiceClient,
+                kzg_service_server::KzgServiceServer, BatchItem, BatchRequest, BlobRequest, ProofRequest,
+                VerificationRequest,
+            },
+            GrpcKzgService,
+        },
+        rate_limit_layer::RateLimitService,
+        ClientId, ClientRate, ProductionConfig, ProductionKzgService, Scope, ServiceError,
+    },
+};
+
+mod common;
+use common::key_config;
+
+/// 进程内的 gRPC 客户端，请求依次经过限流层、tonic 服务和 [`GrpcKzgService`]
+type Client = KzgServiceClient<RateLimitService<KzgServiceServer<GrpcKzgService>>>;
+
+/// 每批最多 3 个条目，便于观察流式批量的分组
+const MAX_BATCH_SIZE: usize = 3;
+
+/// 启用认证的服务，以及经过限流层的 gRPC 客户端
+async fn client() -> (ProductionKzgService, Client) {
+    let mut config = ProductionConfig::default();
+    config.performance.max_batch_size = MAX_BATCH_SIZE;
+    config.security.enable_auth = true;
+    // 测试中密钥与标识相同；计费用的密钥令牌不补充，余量完全由计费决定
+    let metered = |id: &str| ApiKeyConfig {
+        requests_per_second: Some(0),
+        burst_size: Some(10),
+        ..key_config(id, id, &[Scope::Commit])
+    };
+    config.security.keys = vec![
+        key_config("writer", "writer", &[Scope::Commit, Scope::Prove, Scope::Verify]),
+        key_config("reader", "reader", &[Scope::Verify]),
+        metered("batch"),
+        metered("stream"),
+        ApiKeyConfig {
+            daily_quota: Some(5),
+            ..metered("quota")
+        },
+    ];
+    let service = ProductionKzgService::new(config).await.unwrap();
+
+    let server = GrpcKzgService::new(service.clone()).into_server().await;
+    let channel = service.rate_limit_layer().layer(server);
+    (service, KzgServiceClient::new(channel))
+}
+
+fn with_key<T>(key: &str, message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    request.metadata_mut().insert("x-api-key", key.parse().unwrap());
+    request
+}
+
+fn commitment_item(id: &str, blob: Vec<u8>) -> BatchItem {
+    BatchItem {
+        id: id.to_string(),
+        operation: Some(Operation::Commitment(BlobRequest { blob })),
+    }
+}
+
+/// 长度错误的 blob 在服务内立即失败，不占用 KZG 计算
+fn invalid_items(count: usize) -> Vec<BatchItem> {
+    (0..count).map(|i| commitment_item(&i.to_string(), vec![0; 31])).collect()
+}
+
+/// 以密钥自己的速率 (不补充、容量 10) 查询余量，避免查询本身改变令牌桶的速率
+fn remaining(service: &ProductionKzgService, key: &str) -> u64 {
+    let client = ClientId::ApiKey(key.to_string());
+    let rate = ClientRate {
+        requests_per_second: 0,
+        burst_size: 10,
+    };
+    service.rate_limiter().check(Some(&client), Some(rate), 0).unwrap().remaining
+}
+
+#[test]
+fn test_service_error_to_status() {
+    let cases = [
+        (ServiceError::InvalidBlobSize { expected: 131072, actual: 1 }, Code::InvalidArgument),
+        (ServiceError::InvalidHexEncoding("zz".to_string()), Code::InvalidArgument),
+        (ServiceError::InvalidRequest("x".to_string()), Code::InvalidArgument),
+        (ServiceError::UnsupportedMediaType("text/plain".to_string()), Code::InvalidArgument),
+        (ServiceError::QuotaExceeded, Code::ResourceExhausted),
+        (ServiceError::Unauthorized, Code::Unauthenticated),
+        (ServiceError::Forbidden("x".to_string()), Code::PermissionDenied),
+        (ServiceError::NotFound("job".to_string()), Code::NotFound),
+        (ServiceError::ServiceUnavailable, Code::Unavailable),
+        (ServiceError::QueueFull("x".to_string()), Code::Unavailable),
+        (ServiceError::Timeout, Code::DeadlineExceeded),
+    ];
+    for (error, code) in cases {
+        let message = error.to_string();
+        let status = Status::from(error);
+        assert_eq!((status.code(), status.message()), (code, message.as_str()));
+    }
+
+    // 限流状态带重试时间，配额耗尽不带
+    let status = Status::from(ServiceError::RateLimitExceeded {
+        retry_after: Duration::from_millis(1500),
+    });
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    assert_eq!(status.metadata().get("retry-after").unwrap(), "2");
+    assert!(Status::from(ServiceError::QuotaExceeded).metadata().get("retry-after").is_none());
+
+    // 内部错误不向客户端透露细节
+    for error in [ServiceError::KzgError("bad point".to_string()), ServiceError::InternalError("io".to_string())] {
+        let status = Status::from(error);
+        assert_eq!((status.code(), status.message()), (Code::Internal, "Internal server error"));
+    }
+}
+
+/// 单次调用：访问控制、字节字段转换与往返
+#[tokio::test]
+async fn test_unary_calls() {
+    let (_service, mut client) = client().await;
+    let blob = create_test_blob_bytes(0);
+
+    let status = client
+        .create_commitment(Request::new(BlobRequest { blob: blob.clone() }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+    let status = client
+        .create_commitment(with_key("reader", BlobRequest { blob: blob.clone() }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    // `authorization: Bearer` 与 `x-api-key` 等价
+    let mut request = Request::new(BlobRequest { blob: blob.clone() });
+    request.metadata_mut().insert("authorization", "Bearer writer".parse().unwrap());
+    let commitment = client.create_commitment(request).await.unwrap().into_inner();
+    assert_eq!(commitment.commitment.len(), 48);
+    assert_eq!(commitment.versioned_hash.len(), 32);
+    assert_eq!(commitment.versioned_hash[0], 0x01);
+
+    // 点必须正好 48 字节
+    let status = client
+        .generate_proof(with_key(
+            "writer",
+            ProofRequest {
+                blob: blob.clone(),
+                commitment: commitment.commitment[..47].to_vec(),
+            },
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(status.message(), "Invalid request: commitment must be 48 bytes, got 47");
+    let status = client
+        .create_commitment(with_key("writer", BlobRequest { blob: vec![0; 31] }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+
+    let proof = client
+        .generate_proof(with_key(
+            "writer",
+            ProofRequest {
+                blob: blob.clone(),
+                commitment: commitment.commitment.clone(),
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(proof.proof.len(), 48);
+    let verification = client
+        .verify_proof(with_key(
+            "reader",
+            VerificationRequest {
+                blob,
+                commitment: commitment.commitment,
+                proof: proof.proof,
+            },
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(verification.is_valid);
+    assert_eq!(verification.versioned_hash, commitment.versioned_hash);
+}
+
+/// 单次批量：准入按一个条目计费，处理器补扣其余条目，补扣失败或批量超限时不保留补扣
+#[tokio::test]
+async fn test_batch_billing() {
+    let (service, mut client) = client().await;
+    let mut items = invalid_items(2);
+    items.push(BatchItem {
+        id: "missing".to_string(),
+        operation: None,
+    });
+    let response = client
+        .batch(with_key("batch", BatchRequest { items }))
+        .await
+        .unwrap()
+        .into_inner();
+    let ids: Vec<&str> = response.results.iter().map(|result| result.id.as_str()).collect();
+    assert_eq!(ids, ["0", "1", "missing"]);
+    match &response.results[2].outcome {
+        Some(Outcome::Error(message)) => assert_eq!(message, "Invalid request: Missing operation"),
+        other => panic!("unexpected outcome: {:?}", other),
+    }
+    // 3 个条目共消耗 3 个令牌
+    assert_eq!(remaining(&service, "batch"), 7);
+
+    // 超过批量上限的请求只消耗准入的一个令牌，在补扣前被拒绝
+    let status = client
+        .batch(with_key("batch", BatchRequest { items: invalid_items(MAX_BATCH_SIZE + 2) }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert_eq!(remaining(&service, "batch"), 6);
+    for _ in 0..2 {
+        client
+            .batch(with_key("batch", BatchRequest { items: invalid_items(2) }))
+            .await
+            .unwrap();
+    }
+    assert_eq!(remaining(&service, "batch"), 2);
+
+    // 补扣失败时返回 RESOURCE_EXHAUSTED，补扣部分不扣减令牌
+    let status = client
+        .batch(with_key("batch", BatchRequest { items: invalid_items(MAX_BATCH_SIZE) }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    assert!(status.metadata().get("retry-after").is_some());
+    assert_eq!(remaining(&service, "batch"), 1);
+
+    // 配额 5：超限的批量只计准入的一个条目，之后的 3 个条目批量仍可执行
+    let status = client
+        .batch(with_key("quota", BatchRequest { items: invalid_items(MAX_BATCH_SIZE + 2) }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    client
+        .batch(with_key("quota", BatchRequest { items: invalid_items(MAX_BATCH_SIZE) }))
+        .await
+        .unwrap();
+    assert_eq!(remaining(&service, "quota"), 6);
+
+    // 配额补计失败时退还已补扣的令牌，只保留准入的一个
+    let status = client
+        .batch(with_key("quota", BatchRequest { items: invalid_items(MAX_BATCH_SIZE) }))
+        .await
+        .unwrap_err();
+    assert_eq!((status.code(), status.message()), (Code::ResourceExhausted, "Daily quota exceeded"));
+    assert_eq!(remaining(&service, "quota"), 5);
+}
+
+/// 流式批量：按 max_batch_size 分组处理、结果按输入顺序返回，每组单独计费
+#[tokio::test]
+async fn test_batch_stream() {
+    let (service, mut client) = client().await;
+    // 7 个条目超过单批上限，分 3 组处理
+    let items = invalid_items(7);
+    let mut results = client
+        .batch_stream(with_key("stream", tokio_stream::iter(items)))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut ids = Vec::new();
+    while let Some(result) = results.message().await.unwrap() {
+        assert!(matches!(result.outcome, Some(Outcome::Error(_))));
+        ids.push(result.id);
+    }
+    assert_eq!(ids, (0..7).map(|i| i.to_string()).collect::<Vec<_>>());
+    assert_eq!(remaining(&service, "stream"), 3);
+
+    // 余量 3：准入 1 个，第一组补扣 2 个，第二组补扣失败
+    let mut results = client
+        .batch_stream(with_key("stream", tokio_stream::iter(invalid_items(7))))
+        .await
+        .unwrap()
+        .into_inner();
+    let mut received = 0;
+    let status = loop {
+        match results.message().await {
+            Ok(Some(_)) => received += 1,
+            Ok(None) => panic!("stream should fail once tokens run out"),
+            Err(status) => break status,
+        }
+    };
+    assert_eq!(received, MAX_BATCH_SIZE);
+    assert_eq!(status.code(), Code::ResourceExhausted);
+    assert_eq!(remaining(&service, "stream"), 0);
+
+    // 令牌耗尽后在准入时就被限流层拒绝
+    let status = client
+        .batch_stream(with_key("stream", tokio_stream::iter(invalid_items(1))))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::ResourceExhausted);
+}
@@ -0,0 +1,311 @@
+// 异步批量任务测试
+// 覆盖分块执行与部分结果、租户公平轮转、有界队列与字节预算、回调地址校验、回调投递以及日志恢复与压缩
+
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use axum::{http::StatusCode, response::Redirect, routing::post, Router};
+
+use rust_kzg_tutorial::service::{
+    config::JobsConfig, BatchItem, BatchResult, JobQueue, JobRequest, JobState, ServiceError,
+};
+
+fn config(chunk_size: usize) -> JobsConfig {
+    JobsConfig {
+        chunk_size,
+        ..JobsConfig::default()
+    }
+}
+
+fn request(prefix: &str, items: usize) -> JobRequest {
+    JobRequest {
+        requests: (0..items)
+            .map(|i| BatchItem {
+                id: format!("{}-{}", prefix, i),
+                operation: "commitment".to_string(),
+                blob: "00".to_string(),
+                commitment: None,
+                proof: None,
+            })
+            .collect(),
+        callback_url: None,
+    }
+}
+
+fn results(ids: &[String]) -> Vec<BatchResult> {
+    ids.iter().map(|id| BatchResult::from_outcome::<String>(id.clone(), Ok(serde_json::json!(id)))).collect()
+}
+
+/// 取下一块并以成功结果完成，返回该块的条目标识
+fn run_next(queue: &JobQueue) -> Vec<String> {
+    let chunk = queue.next_chunk().expect("chunk available");
+    let ids: Vec<_> = chunk.items.iter().map(|item| item.id.clone()).collect();
+    queue.complete_chunk(&chunk.job_id, results(&ids));
+    ids
+}
+
+#[test]
+fn test_chunks_report_partial_results() {
+    let queue = JobQueue::new(&config(2)).unwrap();
+    let job = queue.submit("alice", None, request("a", 5)).unwrap();
+    assert_eq!((job.state, job.total), (JobState::Queued, 5));
+
+    assert_eq!(run_next(&queue), vec!["a-0", "a-1"]);
+    let status = queue.status(&job.id).unwrap();
+    assert_eq!((status.state, status.completed), (JobState::Running, 2));
+    assert_eq!(status.results[1].id, "a-1");
+
+    run_next(&queue);
+    assert_eq!(run_next(&queue), vec!["a-4"]);
+    let status = queue.status(&job.id).unwrap();
+    assert_eq!((status.state, status.completed), (JobState::Completed, 5));
+    assert!(status.finished_at.is_some());
+    assert!(queue.next_chunk().is_none());
+    assert_eq!(queue.unfinished(), 0);
+}
+
+#[test]
+fn test_tenants_take_turns() {
+    let queue = JobQueue::new(&config(2)).unwrap();
+    queue.submit("big", None, request("big1", 6)).unwrap();
+    queue.submit("big", None, request("big2", 2)).unwrap();
+    queue.submit("small", None, request("small", 2)).unwrap();
+
+    // 租户按块轮转，同一租户内先进先出
+    let first: Vec<_> = (0..5).map(|_| run_next(&queue)[0].clone()).collect();
+    assert_eq!(first, vec!["big1-0", "small-0", "big1-2", "big1-4", "big2-0"]);
+
+    // 失败的任务不再被调度
+    let job = queue.submit("small", None, request("late", 4)).unwrap();
+    let chunk = queue.next_chunk().unwrap();
+    assert_eq!(chunk.job_id, job.id);
+    let status = queue.fail(&job.id, "boom".to_string()).unwrap();
+    assert_eq!((status.state, status.error.as_deref()), (JobState::Failed, Some("boom")));
+    assert!(queue.complete_chunk(&chunk.job_id, Vec::new()).is_none());
+    assert!(queue.next_chunk().is_none());
+}
+
+#[test]
+fn test_queue_is_bounded() {
+    let queue = JobQueue::new(&JobsConfig {
+        max_queued_jobs: 3,
+        max_jobs_per_tenant: 2,
+        max_items_per_job: 4,
+        ..JobsConfig::default()
+    })
+    .unwrap();
+
+    assert!(matches!(queue.submit("a", None, request("x", 0)), Err(ServiceError::InvalidRequest(_))));
+    assert!(matches!(queue.submit("a", None, request("x", 5)), Err(ServiceError::InvalidRequest(_))));
+
+    queue.submit("a", None, request("a", 1)).unwrap();
+    queue.submit("a", None, request("a", 1)).unwrap();
+    assert!(matches!(queue.submit("a", None, request("a", 1)), Err(ServiceError::QueueFull(_))));
+    queue.submit("b", None, request("b", 1)).unwrap();
+    assert!(matches!(queue.submit("c", None, request("c", 1)), Err(ServiceError::QueueFull(_))));
+
+    // 结束的任务释放名额
+    run_next(&queue);
+    assert!(queue.submit("c", None, request("c", 1)).is_ok());
+}
+
+#[test]
+fn test_queue_byte_budget() {
+    // 每个条目 5 字节 (标识 "a-0" 与 blob "00")
+    let queue = JobQueue::new(&JobsConfig {
+        max_queued_bytes: 12,
+        ..JobsConfig::default()
+    })
+    .unwrap();
+
+    assert!(matches!(queue.submit("a", None, request("a", 3)), Err(ServiceError::InvalidRequest(_))));
+    let job = queue.submit("a", None, request("a", 2)).unwrap();
+    assert!(matches!(queue.submit("b", None, request("b", 1)), Err(ServiceError::QueueFull(_))));
+
+    // 失败结束的任务同样释放字节预算
+    queue.fail(&job.id, "cancelled".to_string()).unwrap();
+    queue.submit("b", None, request("b", 2)).unwrap();
+    assert_eq!(queue.unfinished(), 1);
+}
+
+#[test]
+fn test_callback_url_validation() {
+    let queue = JobQueue::new(&JobsConfig {
+        callback_allowed_hosts: vec!["hooks.example.com".to_string()],
+        ..JobsConfig::default()
+    })
+    .unwrap();
+    let with_callback = |url: &str| JobRequest {
+        callback_url: Some(url.to_string()),
+        ..request("cb", 1)
+    };
+
+    assert!(queue.submit("a", None, with_callback("https://hooks.example.com/done")).is_ok());
+    assert!(queue.submit("a", None, with_callback("https://internal.local/done")).is_err());
+    assert!(queue.submit("a", None, with_callback("file:///etc/passwd")).is_err());
+    assert!(queue.submit("a", None, with_callback("not a url")).is_err());
+
+    // 未配置白名单时不限主机，但拒绝回环、私有、链路本地、保留与 NAT64 等非全局单播地址
+    let queue = JobQueue::new(&JobsConfig::default()).unwrap();
+    assert!(queue.submit("a", None, with_callback("https://hooks.example.com/done")).is_ok());
+    assert!(queue.submit("a", None, with_callback("http://93.184.216.34/done")).is_ok());
+    assert!(queue.submit("a", None, with_callback("http://[2606:2800:220:1::1]/done")).is_ok());
+    for url in [
+        "http://127.0.0.1:8080/done",
+        "http://10.0.0.5/done",
+        "http://192.168.1.1/done",
+        "http://169.254.169.254/latest/meta-data",
+        "http://100.64.0.1/done",
+        "http://0.0.0.0/done",
+        "http://[::1]/done",
+        "http://[fd00::1]/done",
+        "http://[fe80::1]/done",
+        "http://[::ffff:127.0.0.1]/done",
+        "http://192.0.0.8/done",
+        "http://198.18.0.1/done",
+        "http://198.19.255.254/done",
+        "http://240.0.0.1/done",
+        "http://255.255.255.255/done",
+        "http://[64:ff9b::a00:5]/done",
+        "http://[64:ff9b:1::a00:5]/done",
+        "http://[2001::1]/done",
+        "http://[2002:a00:5::1]/done",
+        "http://[::a00:5]/done",
+    ] {
+        assert!(
+            matches!(queue.submit("a", None, with_callback(url)), Err(ServiceError::InvalidRequest(_))),
+            "{}",
+            url
+        );
+    }
+
+    // 运维显式允许后可回调内网地址
+    let queue = JobQueue::new(&JobsConfig {
+        callback_allow_private_networks: true,
+        ..JobsConfig::default()
+    })
+    .unwrap();
+    assert!(queue.submit("a", None, with_callback("http://127.0.0.1:8080/done")).is_ok());
+    assert!(queue.submit("a", None, with_callback("http://169.254.169.254/")).is_ok());
+}
+
+/// 本地回调接收方：`/done` 计数并返回 200，`/redirect` 重定向到 `/done`
+fn callback_receiver() -> (String, Arc<AtomicUsize>) {
+    let received = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&received);
+    let app = Router::new()
+        .route(
+            "/done",
+            post(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { StatusCode::OK }
+            }),
+        )
+        .route("/redirect", post(|| async { Redirect::temporary("/done") }));
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(axum::Server::from_tcp(listener).unwrap().serve(app.into_make_service()));
+    (format!("127.0.0.1:{}", port), received)
+}
+
+#[tokio::test]
+async fn test_callback_delivery() {
+    let (addr, received) = callback_receiver();
+    let jobs = JobsConfig {
+        callback_retries: 0,
+        ..JobsConfig::default()
+    };
+    let queue = JobQueue::new(&jobs).unwrap();
+    let job = queue.submit("a", None, request("cb", 1)).unwrap();
+    let status = queue.status(&job.id).unwrap();
+
+    // 默认配置下域名解析到回环地址同样被拒绝，不发起连接
+    let port = addr.rsplit(':').next().unwrap();
+    assert!(!queue.deliver_callback(&format!("http://localhost:{}/done", port), &status).await);
+    assert!(!queue.deliver_callback(&format!("http://{}/done", addr), &status).await);
+    assert_eq!(received.load(Ordering::SeqCst), 0);
+
+    let queue = JobQueue::new(&JobsConfig {
+        callback_allow_private_networks: true,
+        ..jobs
+    })
+    .unwrap();
+    assert!(queue.deliver_callback(&format!("http://localhost:{}/done", port), &status).await);
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+
+    // 不跟随重定向，重定向响应视为投递失败
+    assert!(!queue.deliver_callback(&format!("http://{}/redirect", addr), &status).await);
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_journal_recovers_unfinished_jobs() {
+    let path = std::env::temp_dir().join(format!("kzg_jobs_{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let journal = JobsConfig {
+        journal_path: Some(path.to_str().unwrap().to_string()),
+        chunk_size: 4,
+        ..JobsConfig::default()
+    };
+
+    let (done, pending) = {
+        let queue = JobQueue::new(&journal).unwrap();
+        let done = queue.submit("a", None, request("done", 2)).unwrap();
+        let pending = queue.submit("b", None, request("pending", 2)).unwrap();
+        run_next(&queue);
+        (done.id, pending.id)
+    };
+    // 模拟写入中断的最后一行
+    let mut content = std::fs::read_to_string(&path).unwrap();
+    content.push_str("{\"event\":\"submi");
+    std::fs::write(&path, content).unwrap();
+
+    let queue = JobQueue::new(&journal).unwrap();
+    assert!(queue.status(&done).is_none());
+    let status = queue.status(&pending).unwrap();
+    assert_eq!((status.state, status.total, status.tenant.as_str()), (JobState::Queued, 2, "b"));
+    assert_eq!(run_next(&queue), vec!["pending-0", "pending-1"]);
+    drop(queue);
+
+    // 压缩后的日志只保留未结束的任务
+    let queue = JobQueue::new(&journal).unwrap();
+    assert_eq!(queue.unfinished(), 0);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_journal_compacted_when_finished_jobs_expire() {
+    let path = std::env::temp_dir().join(format!("kzg_jobs_compact_{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let journal = JobsConfig {
+        journal_path: Some(path.to_str().unwrap().to_string()),
+        chunk_size: 4,
+        retention_seconds: 0,
+        ..JobsConfig::default()
+    };
+
+    let queue = JobQueue::new(&journal).unwrap();
+    let done = queue.submit("a", None, request("done", 2)).unwrap();
+    run_next(&queue);
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(content.lines().count(), 2);
+    assert!(content.contains(&done.id));
+
+    // 下一次提交先移除保留期已过的任务并压缩日志，之后的记录照常追加
+    let pending = queue.submit("b", None, request("pending", 2)).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(!content.contains(&done.id));
+    assert_eq!(content.lines().count(), 1);
+    assert!(content.contains(&pending.id));
+    assert!(queue.status(&done.id).is_none());
+
+    run_next(&queue);
+    assert!(queue.status(&pending.id).is_none());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+    drop(queue);
+
+    let queue = JobQueue::new(&journal).unwrap();
+    assert_eq!(queue.unfinished(), 0);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,91 @@
+// OpenAPI 文档测试
+// 覆盖路由与类型的完整性、认证声明、二进制编码以及共用错误响应
+
+use rust_kzg_tutorial::service::openapi;
+use serde_json::Value;
+
+fn document() -> Value {
+    serde_json::to_value(openapi()).unwrap()
+}
+
+fn operation<'a>(doc: &'a Value, path: &str, method: &str) -> &'a Value {
+    let operation = &doc["paths"][path][method];
+    assert!(operation.is_object(), "missing {} {}", method, path);
+    operation
+}
+
+#[test]
+fn test_document_covers_routes_and_types() {
+    let doc = document();
+    assert!(doc["openapi"].as_str().unwrap().starts_with("3."));
+
+    for (path, method) in [
+        ("/api/v1/commitment", "post"),
+        ("/api/v1/proof", "post"),
+        ("/api/v1/verify", "post"),
+        ("/api/v1/batch", "post"),
+        ("/api/v1/cells", "post"),
+        ("/api/v1/cells/verify", "post"),
+        ("/api/v1/cells/recover", "post"),
+        ("/api/v1/versioned-hashes/validate", "post"),
+        ("/api/v1/jobs", "post"),
+        ("/api/v1/jobs/{id}", "get"),
+        ("/api/v1/openapi.json", "get"),
+        ("/health", "get"),
+        ("/metrics", "get"),
+        ("/admin/reload", "post"),
+    ] {
+        operation(&doc, path, method);
+    }
+
+    let schemas = &doc["components"]["schemas"];
+    for name in ["CommitmentRequest", "BatchItem", "CellRecoveryResponse", "JobStatus", "ErrorBody"] {
+        assert!(schemas[name].is_object(), "missing schema {}", name);
+    }
+    assert_eq!(schemas["JobState"]["enum"], serde_json::json!(["queued", "running", "completed", "failed"]));
+
+    let submit = operation(&doc, "/api/v1/jobs", "post");
+    assert_eq!(
+        submit["responses"]["202"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/JobSubmitted"
+    );
+}
+
+#[test]
+fn test_protected_routes_declare_auth() {
+    let doc = document();
+    let schemes = &doc["components"]["securitySchemes"];
+    assert_eq!(schemes["api_key"]["name"], "x-api-key");
+    assert_eq!(schemes["bearer"]["scheme"], "bearer");
+
+    let commitment = operation(&doc, "/api/v1/commitment", "post");
+    assert_eq!(commitment["x-required-scopes"], serde_json::json!(["commit"]));
+    assert!(commitment["responses"]["401"].is_object());
+    assert!(commitment["responses"]["403"].is_object());
+    assert_eq!(operation(&doc, "/admin/reload", "post")["x-required-scopes"], serde_json::json!(["admin"]));
+
+    // 公开路由不声明认证，也不限流
+    let health = operation(&doc, "/health", "get");
+    assert!(health["security"].is_null());
+    assert!(health["responses"]["429"].is_null());
+}
+
+#[test]
+fn test_binary_encoding_and_errors() {
+    let doc = document();
+    let verify = operation(&doc, "/api/v1/verify", "post");
+    for content_type in ["application/json", "application/octet-stream", "application/ssz"] {
+        assert!(verify["requestBody"]["content"][content_type].is_object(), "{}", content_type);
+    }
+    let binary = &verify["responses"]["200"]["content"]["application/octet-stream"]["schema"];
+    assert_eq!((binary["type"].as_str(), binary["format"].as_str()), (Some("string"), Some("binary")));
+
+    // 共用错误响应引用 ErrorBody，显式声明的状态码不被覆盖
+    let error_ref = |op: &Value, status: &str| op["responses"][status]["content"]["application/json"]["schema"]["$ref"].clone();
+    assert_eq!(error_ref(verify, "400"), "#/components/schemas/ErrorBody");
+    assert!(verify["responses"]["429"]["headers"]["Retry-After"].is_object());
+    let status = operation(&doc, "/api/v1/jobs/{id}", "get");
+    assert_eq!(error_ref(status, "404"), "#/components/schemas/ErrorBody");
+    assert!(status["responses"]["404"]["description"].as_str().unwrap().contains("租户"));
+    assert!(operation(&doc, "/api/v1/batch", "post")["requestBody"]["content"]["application/octet-stream"].is_null());
+}
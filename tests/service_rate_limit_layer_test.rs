@@ -0,0 +1,199 @@
+// 速率限制中间件测试
+// 覆盖放行与拒绝时的 X-RateLimit-* / Retry-After 响应头、HTTP 429 响应体、gRPC 状态以及限流句柄
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, BoxBody, HttpBody},
+    extract::ConnectInfo,
+    http::{header, Request, Response, StatusCode},
+};
+use tower::{service_fn, Layer, Service, ServiceExt};
+
+use rust_kzg_tutorial::service::{
+    config::{ApiKeyConfig, RateLimitConfig},
+    ErrorBody, KzgMetrics, ProductionConfig, RateLimitHandle, RateLimitLayer, RateLimiter,
+    SecurityManager,
+};
+
+mod common;
+use common::key_config;
+
+/// 按 IP 的客户端每秒补充 1 个令牌、容量 5；密钥 `alice` 容量 4 且不补充
+fn layer() -> RateLimitLayer {
+    let limiter = RateLimiter::new(&RateLimitConfig {
+        requests_per_second: 0,
+        burst_size: 1000,
+        enable_per_ip: true,
+        per_client_requests_per_second: 1,
+        per_client_burst_size: 5,
+        ..ProductionConfig::default().security.rate_limit
+    });
+    let security = SecurityManager::new(&[ApiKeyConfig {
+        requests_per_second: Some(0),
+        burst_size: Some(4),
+        ..key_config("alice", "alice-key", &[])
+    }])
+    .unwrap();
+    RateLimitLayer::new(Arc::new(limiter), Arc::new(security), Arc::new(KzgMetrics::new().unwrap()))
+}
+
+/// 记录调用次数的内层服务，有限流句柄时按 `x-items` 头补扣批量条目，按 `x-refund` 头退还
+fn inner(
+    calls: Arc<AtomicUsize>,
+) -> impl Service<Request<Body>, Response = Response<Body>, Error = Infallible, Future = impl Send> + Clone + Send {
+    service_fn(move |request: Request<Body>| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        let handle = request.extensions().get::<RateLimitHandle>().cloned();
+        let items: usize = request
+            .headers()
+            .get("x-items")
+            .map(|value| value.to_str().unwrap().parse().unwrap())
+            .unwrap_or(0);
+        let refund: Option<usize> = request
+            .headers()
+            .get("x-refund")
+            .map(|value| value.to_str().unwrap().parse().unwrap());
+        async move {
+            let body = match handle {
+                None => "no handle",
+                Some(handle) if handle.charge_items(items).is_ok() => {
+                    if let Some(refund) = refund {
+                        handle.refund_items(refund);
+                    }
+                    "charged"
+                }
+                Some(_) => "charge rejected",
+            };
+            Ok::<_, Infallible>(Response::new(Body::from(body)))
+        }
+    })
+}
+
+fn http_request(path: &str, items: usize) -> Request<Body> {
+    let mut request = Request::post(path)
+        .header("x-items", items.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let addr: SocketAddr = "192.0.2.7:40000".parse().unwrap();
+    request.extensions_mut().insert(ConnectInfo(addr));
+    request
+}
+
+fn grpc_request(method: &str) -> Request<Body> {
+    Request::post(format!("/kzg.v1.KzgService/{}", method))
+        .header(header::CONTENT_TYPE, "application/grpc")
+        .header("x-api-key", "alice-key")
+        .body(Body::empty())
+        .unwrap()
+}
+
+async fn body_bytes(response: Response<BoxBody>) -> Vec<u8> {
+    let mut body = response.into_body();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        bytes.extend_from_slice(&chunk.unwrap());
+    }
+    bytes
+}
+
+fn header_value<'a>(response: &'a Response<BoxBody>, name: &str) -> Option<&'a str> {
+    response.headers().get(name).map(|value| value.to_str().unwrap())
+}
+
+#[tokio::test]
+async fn test_http_allowed_and_rejected() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let service = layer().layer(inner(Arc::clone(&calls)));
+
+    // 提交承诺消耗 2 个令牌，处理器再补扣 1 个批量条目，响应头反映补扣后的余额
+    let response = service.clone().oneshot(http_request("/api/v1/commitment", 1)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header_value(&response, "x-ratelimit-limit"), Some("5"));
+    assert_eq!(header_value(&response, "x-ratelimit-remaining"), Some("2"));
+    assert_eq!(header_value(&response, "x-ratelimit-reset"), Some("3"));
+    assert_eq!(header_value(&response, "retry-after"), None);
+    assert_eq!(body_bytes(response).await, b"charged");
+
+    // 余量 2：补扣超出余量时被拒绝，准入的令牌照常消耗
+    let response = service.clone().oneshot(http_request("/api/v1/commitment", 3)).await.unwrap();
+    assert_eq!(header_value(&response, "x-ratelimit-remaining"), Some("0"));
+    assert_eq!(body_bytes(response).await, b"charge rejected");
+
+    let response = service.clone().oneshot(http_request("/api/v1/commitment", 0)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(header_value(&response, "retry-after"), Some("2"));
+    assert_eq!(header_value(&response, "x-ratelimit-limit"), Some("5"));
+    assert_eq!(header_value(&response, "x-ratelimit-remaining"), Some("0"));
+    assert_eq!(header_value(&response, "x-ratelimit-reset"), Some("2"));
+    assert_eq!(header_value(&response, "content-type"), Some("application/json"));
+    let body: ErrorBody = serde_json::from_slice(&body_bytes(response).await).unwrap();
+    assert_eq!(body.error, "Rate limit exceeded");
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    // 不限流的路由不经过限流器，也没有限流句柄
+    let response = service.clone().oneshot(http_request("/health", 0)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header_value(&response, "x-ratelimit-limit"), None);
+    assert_eq!(body_bytes(response).await, b"no handle");
+}
+
+#[tokio::test]
+async fn test_grpc_allowed_and_rejected() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let service = layer().layer(inner(Arc::clone(&calls)));
+
+    // 按密钥分桶，使用密钥的专属速率
+    let response = service.clone().oneshot(grpc_request("GenerateProof")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header_value(&response, "x-ratelimit-limit"), Some("4"));
+    assert_eq!(header_value(&response, "x-ratelimit-remaining"), Some("1"));
+    assert_eq!(header_value(&response, "grpc-status"), None);
+    assert_eq!(body_bytes(response).await, b"charged");
+
+    // gRPC 客户端收到仅含头部的 RESOURCE_EXHAUSTED，HTTP 状态仍为 200
+    let response = service.clone().oneshot(grpc_request("CreateCommitment")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header_value(&response, "content-type"), Some("application/grpc"));
+    assert_eq!(header_value(&response, "grpc-status"), Some("8"));
+    assert_eq!(header_value(&response, "grpc-message"), Some("Rate%20limit%20exceeded"));
+    // 不补充的令牌桶永远等不到，重试时间封顶为一天
+    assert_eq!(header_value(&response, "retry-after"), Some("86400"));
+    assert_eq!(header_value(&response, "x-ratelimit-reset"), Some("86400"));
+    assert_eq!(header_value(&response, "x-ratelimit-limit"), Some("4"));
+    assert_eq!(header_value(&response, "x-ratelimit-remaining"), Some("0"));
+    assert!(body_bytes(response).await.is_empty());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // 同一密钥的 HTTP 请求共用令牌桶，被拒绝时返回 429
+    let mut request = http_request("/api/v1/verify", 0);
+    request.headers_mut().insert("x-api-key", "alice-key".parse().unwrap());
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut request = http_request("/api/v1/verify", 0);
+    request.headers_mut().insert("authorization", "Bearer alice-key".parse().unwrap());
+    let response = service.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(header_value(&response, "retry-after"), Some("86400"));
+}
+
+#[tokio::test]
+async fn test_batch_charge_and_refund_headers() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let service = layer().layer(inner(Arc::clone(&calls)));
+
+    // 准入 1 个条目、补扣 2 个后全部退还 (如任务队列已满)，余额恢复
+    let mut request = http_request("/api/v1/jobs", 2);
+    request.headers_mut().insert("x-refund", "3".parse().unwrap());
+    let response = service.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(header_value(&response, "x-ratelimit-remaining"), Some("5"));
+
+    // 未退还时余额按全部条目扣减
+    let response = service.clone().oneshot(http_request("/api/v1/batch", 4)).await.unwrap();
+    assert_eq!(header_value(&response, "x-ratelimit-remaining"), Some("0"));
+    assert_eq!(body_bytes(response).await, b"charged");
+}
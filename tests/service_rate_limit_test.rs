@@ -0,0 +1,114 @@
+// 服务速率限制测试
+// 覆盖加权令牌消耗、按客户端分桶、重试时间、状态回收以及路由计费
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+use rust_kzg_tutorial::service::{
+    config::{RateLimitConfig, RequestCosts},
+    rate_limit_layer::route_cost,
+    ClientId, ClientRate, ProductionConfig, RateLimitError, RateLimiter, TokenBucket,
+};
+
+fn config(requests_per_second: u64, burst_size: u64, per_client: u64) -> RateLimitConfig {
+    RateLimitConfig {
+        requests_per_second,
+        burst_size,
+        per_client_requests_per_second: 0,
+        per_client_burst_size: per_client,
+        ..ProductionConfig::default().security.rate_limit
+    }
+}
+
+#[test]
+fn test_token_bucket_weighted_costs() {
+    let mut bucket = TokenBucket::new(10, 10);
+    assert_eq!(bucket.capacity(), 10);
+    assert!(bucket.try_acquire(7.0).is_ok());
+    assert_eq!(bucket.remaining(), 3);
+
+    // 不足时不扣减，并给出补足所需时间
+    let retry_after = bucket.try_acquire(5.0).unwrap_err();
+    assert!(retry_after > Duration::from_millis(150) && retry_after <= Duration::from_millis(200));
+    assert_eq!(bucket.remaining(), 3);
+
+    // 不补充的桶无法恢复
+    let mut empty = TokenBucket::new(0, 1);
+    assert!(empty.try_acquire(1.0).is_ok());
+    assert_eq!(empty.try_acquire(1.0), Err(Duration::MAX));
+}
+
+#[test]
+fn test_clients_have_separate_buckets() {
+    let limiter = RateLimiter::new(&config(0, 1000, 5));
+    let alice = ClientId::ApiKey("alice".to_string());
+    let bob = ClientId::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST));
+
+    let status = limiter.check(Some(&alice), None, 3).unwrap();
+    assert_eq!((status.limit, status.remaining), (5, 2));
+    assert!(matches!(
+        limiter.check(Some(&alice), None, 3),
+        Err(RateLimitError::ClientLimitExceeded { limit: 5, .. })
+    ));
+    // 其他客户端不受影响
+    assert_eq!(limiter.check(Some(&bob), None, 5).unwrap().remaining, 0);
+
+    // 密钥的专属速率覆盖默认值
+    let vip = ClientId::ApiKey("vip".to_string());
+    let rate = ClientRate {
+        requests_per_second: 0,
+        burst_size: 50,
+    };
+    assert_eq!(limiter.check(Some(&vip), Some(rate), 20).unwrap().limit, 50);
+    assert_eq!(limiter.tracked_clients(), 3);
+}
+
+#[test]
+fn test_global_limit_and_rejected_requests_cost_nothing() {
+    let limiter = RateLimiter::new(&config(0, 4, 100));
+    let client = ClientId::ApiKey("a".to_string());
+
+    assert!(limiter.check(Some(&client), None, 3).is_ok());
+    let error = limiter.check(Some(&client), None, 2).unwrap_err();
+    assert!(matches!(error, RateLimitError::GlobalLimitExceeded { .. }));
+    assert_eq!(error.retry_after(), Duration::MAX);
+
+    // 全局拒绝时客户端令牌未被扣减
+    assert_eq!(limiter.check(Some(&client), None, 1).unwrap().remaining, 96);
+    // 匿名请求只受全局限制
+    assert!(limiter.check(None, None, 1).is_err());
+}
+
+#[test]
+fn test_idle_clients_are_collected() {
+    let limiter = RateLimiter::new(&RateLimitConfig {
+        per_client_requests_per_second: 1000,
+        per_client_burst_size: 10,
+        client_idle_seconds: 0,
+        ..ProductionConfig::default().security.rate_limit
+    });
+    let drained = ClientId::ApiKey("drained".to_string());
+    let no_refill = ClientRate {
+        requests_per_second: 0,
+        burst_size: 10,
+    };
+    limiter.check(Some(&drained), Some(no_refill), 5).unwrap();
+    limiter.check(Some(&ClientId::ApiKey("idle".to_string())), None, 1).unwrap();
+
+    std::thread::sleep(Duration::from_millis(20));
+    // 已补满的空闲客户端被回收，未补满的保留以免绕过限制
+    assert_eq!(limiter.gc(), 1);
+    assert_eq!(limiter.tracked_clients(), 1);
+}
+
+#[test]
+fn test_route_costs() {
+    let costs = RequestCosts::default();
+    assert!(costs.proof > costs.verification);
+    assert_eq!(route_cost("/api/v1/proof", &costs), Some(costs.proof));
+    assert_eq!(route_cost("/api/v1/batch", &costs), Some(costs.batch_item));
+    assert_eq!(route_cost("/kzg.v1.KzgService/VerifyProof", &costs), Some(costs.verification));
+    assert_eq!(route_cost("/admin/stats", &costs), Some(costs.other));
+    assert_eq!(route_cost("/health/ready", &costs), None);
+    assert_eq!(route_cost("/metrics", &costs), None);
+}
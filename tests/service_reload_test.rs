@@ -0,0 +1,138 @@
+// 服务热重载测试
+// 覆盖配置合并、缓存容量调整、限流重新配置、密钥重载与 CORS 配置校验
+
+use rust_kzg_tutorial::service::{
+    blob_digest,
+    config::{ApiKeyConfig, CorsConfig},
+    reload::{cors_layer, merge_reloadable},
+    AuthError, CacheKey, CacheManager, ClientId, ProductionConfig, RateLimitError, RateLimiter, Scope,
+    SecurityManager,
+};
+
+mod common;
+use common::key_config;
+
+#[test]
+fn test_merge_applies_safe_subset_only() {
+    let current = ProductionConfig::default();
+    let mut new = current.clone();
+    new.security.rate_limit.requests_per_second = 5;
+    new.security.enable_auth = true;
+    new.performance.cache_size = 10;
+    new.logging.level = "debug".to_string();
+    new.server.port = 9000;
+    new.kzg.thread_pool_size = Some(2);
+    new.performance.max_batch_size = 1;
+
+    let (merged, restart_required) = merge_reloadable(&current, &new);
+    assert_eq!(merged.security.rate_limit.requests_per_second, 5);
+    assert!(merged.security.enable_auth);
+    assert_eq!(merged.performance.cache_size, 10);
+    assert_eq!(merged.logging.level, "debug");
+
+    // 需要重启的字段保持原值并被列出
+    assert_eq!(merged.server.port, current.server.port);
+    assert_eq!(merged.kzg.thread_pool_size, None);
+    assert_eq!(merged.performance.max_batch_size, current.performance.max_batch_size);
+    assert_eq!(
+        restart_required,
+        vec!["kzg.thread_pool_size", "performance.max_batch_size", "server.port"]
+    );
+
+    let (_, unchanged) = merge_reloadable(&current, &current);
+    assert!(unchanged.is_empty());
+}
+
+#[tokio::test]
+async fn test_cache_resize_keeps_recent_entries() {
+    let cache = CacheManager::new(4, 300, 1 << 20);
+    let keys: Vec<_> = (0u8..4).map(|i| CacheKey::Commitment(blob_digest(&[i]))).collect();
+    for key in &keys {
+        cache.set(*key, vec![0; 48], 0).await;
+    }
+    cache.get(&keys[0], 0).await;
+
+    // 缩小到两个条目时淘汰最久未用的条目
+    assert_eq!(cache.resize(2, 300, 1 << 20).await, 2);
+    assert!(cache.get(&keys[0], 0).await.is_some());
+    assert!(cache.get(&keys[3], 0).await.is_some());
+    assert!(cache.get(&keys[1], 0).await.is_none());
+    assert_eq!(cache.stats().await.evictions, 2);
+
+    // 有效期为 0 时现有条目立即过期
+    cache.resize(2, 0, 1 << 20).await;
+    assert!(cache.get(&keys[0], 0).await.is_none());
+}
+
+#[test]
+fn test_rate_limiter_reconfigure() {
+    let mut config = ProductionConfig::default().security.rate_limit;
+    config.requests_per_second = 0;
+    config.per_client_requests_per_second = 0;
+    config.per_client_burst_size = 2;
+    let limiter = RateLimiter::new(&config);
+    let client = ClientId::ApiKey("a".to_string());
+    limiter.check(Some(&client), None, 2).unwrap();
+    assert!(matches!(
+        limiter.check(Some(&client), None, 1),
+        Err(RateLimitError::ClientLimitExceeded { limit: 2, .. })
+    ));
+
+    // 客户端令牌桶保留已耗尽的令牌，只更新容量，重载不会重置受限客户端
+    config.per_client_burst_size = 10;
+    config.costs.proof = 7;
+    config.enable_per_ip = false;
+    limiter.reconfigure(&config);
+    assert_eq!(limiter.tracked_clients(), 1);
+    assert!(matches!(
+        limiter.check(Some(&client), None, 1),
+        Err(RateLimitError::ClientLimitExceeded { limit: 10, .. })
+    ));
+    assert_eq!(limiter.costs().proof, 7);
+    assert!(!limiter.per_ip());
+
+    // 新客户端按新速率建立；容量缩小时现有令牌随之截断
+    let fresh = ClientId::ApiKey("b".to_string());
+    assert_eq!(limiter.check(Some(&fresh), None, 1).unwrap().remaining, 9);
+    config.per_client_burst_size = 3;
+    limiter.reconfigure(&config);
+    assert_eq!(limiter.check(Some(&fresh), None, 1).unwrap().remaining, 2);
+}
+
+#[tokio::test]
+async fn test_key_reload_preserves_quota() {
+    let ci = |key: &str| ApiKeyConfig {
+        daily_quota: Some(2),
+        ..key_config("ci", key, &[Scope::Commit])
+    };
+    let manager = SecurityManager::new(&[ci("old")]).unwrap();
+    manager.authenticate("old", &[Scope::Commit]).await.unwrap();
+
+    // 轮换密钥后同一标识的当日用量保留
+    let count = manager
+        .reload_keys(&[ci("new"), key_config("ops", "ops", &[Scope::Commit])])
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(manager.authenticate("old", &[]).await, Err(AuthError::InvalidKey));
+    manager.authenticate("new", &[Scope::Commit]).await.unwrap();
+    assert_eq!(manager.authenticate("new", &[Scope::Commit]).await, Err(AuthError::QuotaExceeded));
+
+    // 无效哈希使整次重载失败，原有密钥不变
+    let mut invalid = key_config("bad", "x", &[Scope::Commit]);
+    invalid.hash = "plaintext".to_string();
+    assert!(manager.reload_keys(&[invalid]).await.is_err());
+    assert!(manager.validate_api_key("ops").await);
+}
+
+#[test]
+fn test_cors_config_validation() {
+    let default = ProductionConfig::default().security.cors;
+    assert!(cors_layer(&default).is_ok());
+
+    let invalid = CorsConfig {
+        allow_methods: vec!["NOT A METHOD".to_string()],
+        ..default
+    };
+    assert!(cors_layer(&invalid).is_err());
+}
@@ -0,0 +1,128 @@
+// 服务 API 密钥测试
+// 覆盖加盐哈希、权限范围、按密钥的速率设置与每日配额、旧式明文密钥以及密钥文件加载
+
+use rust_kzg_tutorial::service::{
+    config::load_key_file,
+    hash_api_key, legacy_key_id, AuthError, ClientRate, ProductionConfig, Scope, SecurityManager,
+};
+
+mod common;
+use common::key_config;
+
+#[tokio::test]
+async fn test_hashed_keys_and_scopes() {
+    let hash = hash_api_key("secret");
+    assert!(hash.starts_with("sha256$"));
+    assert!(!hash.contains("secret"));
+    // 盐随机，同一密钥两次哈希不同
+    assert_ne!(hash, hash_api_key("secret"));
+
+    let manager = SecurityManager::new(&[
+        key_config("indexer", "secret", &[Scope::Commit, Scope::Verify]),
+        key_config("ops", "root", &[Scope::Admin]),
+    ])
+    .unwrap();
+
+    let identity = manager.authenticate("secret", &[Scope::Verify]).await.unwrap();
+    assert_eq!(identity.id, "indexer");
+    assert_eq!(
+        manager.authenticate("secret", &[Scope::Admin]).await,
+        Err(AuthError::MissingScope(Scope::Admin))
+    );
+    assert_eq!(manager.authenticate("root", &[Scope::Admin]).await.unwrap().id, "ops");
+    assert_eq!(manager.authenticate("wrong", &[]).await, Err(AuthError::InvalidKey));
+
+    assert!(manager.validate_api_key("root").await);
+    assert!(!manager.validate_api_key("roo").await);
+}
+
+#[tokio::test]
+async fn test_per_key_rate_limit_and_quota() {
+    let mut limited = key_config("limited", "a", &[Scope::Commit]);
+    limited.requests_per_second = Some(0);
+    limited.burst_size = Some(2);
+    let mut quota = key_config("quota", "b", &[Scope::Commit]);
+    quota.daily_quota = Some(3);
+    let manager = SecurityManager::new(&[limited, quota]).unwrap();
+
+    // 密钥的专属速率随身份交给限流器执行，识别密钥不计入配额
+    let identity = manager.identify("a").await.unwrap();
+    assert_eq!(identity.id, "limited");
+    assert_eq!(
+        identity.rate,
+        Some(ClientRate {
+            requests_per_second: 0,
+            burst_size: 2
+        })
+    );
+    assert_eq!(manager.identify("b").await.unwrap().rate, None);
+    assert!(manager.identify("z").await.is_none());
+
+    // 一个密钥受限不影响其他密钥
+    for _ in 0..3 {
+        assert!(manager.authenticate("b", &[Scope::Commit]).await.is_ok());
+    }
+    assert_eq!(manager.authenticate("b", &[Scope::Commit]).await, Err(AuthError::QuotaExceeded));
+
+    // 批量请求按条目计入，超出剩余配额时整体拒绝；不计条目的请求 (任务状态查询) 不受配额限制
+    let mut items = key_config("items", "d", &[Scope::Commit]);
+    items.daily_quota = Some(10);
+    let manager = SecurityManager::new(&[items]).unwrap();
+    manager.authenticate_items("d", &[], 1).await.unwrap();
+    manager.charge_quota("items", 7).await.unwrap();
+    assert_eq!(manager.charge_quota("items", 3).await, Err(AuthError::QuotaExceeded));
+    manager.refund_quota("items", 2).await;
+    manager.charge_quota("items", 3).await.unwrap();
+    assert_eq!(manager.authenticate("d", &[]).await, Err(AuthError::QuotaExceeded));
+    assert!(manager.authenticate_items("d", &[], 0).await.is_ok());
+
+    // 权限不足的请求不计入配额
+    let mut scoped = key_config("scoped", "c", &[Scope::Verify]);
+    scoped.daily_quota = Some(1);
+    let manager = SecurityManager::new(&[scoped]).unwrap();
+    assert!(manager.authenticate("c", &[Scope::Commit]).await.is_err());
+    assert!(manager.authenticate("c", &[Scope::Verify]).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_legacy_plaintext_keys() {
+    let mut config = ProductionConfig::default().security;
+    config.api_keys = vec!["first".to_string(), "second".to_string()];
+    let manager = SecurityManager::from_config(&config).unwrap();
+
+    let identity = manager.authenticate("second", &[Scope::Commit, Scope::Prove, Scope::Verify]).await.unwrap();
+    assert_eq!(identity.id, legacy_key_id("second"));
+    assert!(identity.id.starts_with("legacy-"));
+    assert!(!identity.id.contains("second"));
+    assert_ne!(legacy_key_id("first"), legacy_key_id("second"));
+
+    // 同一进程内标识只取决于密钥本身，删除或调整其他密钥后不变
+    config.api_keys = vec!["second".to_string()];
+    manager.reload_from_config(&config).await.unwrap();
+    assert_eq!(manager.identify("second").await.unwrap().id, identity.id);
+    assert!(manager.identify("first").await.is_none());
+}
+
+#[test]
+fn test_key_file_and_invalid_hashes() {
+    let path = std::env::temp_dir().join(format!("kzg_keys_{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        format!(
+            "[[keys]]\nid = \"ci\"\nhash = \"{}\"\nscopes = [\"prove\", \"admin\"]\ndaily_quota = 10\n",
+            hash_api_key("ci-key")
+        ),
+    )
+    .unwrap();
+    let keys = load_key_file(path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].scopes, vec![Scope::Prove, Scope::Admin]);
+    assert_eq!(keys[0].daily_quota, Some(10));
+    assert!(SecurityManager::new(&keys).is_ok());
+
+    let mut invalid = key_config("bad", "x", &[]);
+    invalid.hash = "plaintext".to_string();
+    assert!(matches!(SecurityManager::new(&[invalid]), Err(AuthError::InvalidHash(_))));
+}
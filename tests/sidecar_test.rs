@@ -0,0 +1,146 @@
+// BlobSidecar 加载测试
+// 覆盖信标 API 响应 / 数组 / 单对象三种格式、字段校验以及 KZG 证明验证
+
+use kzg::eip_4844::{blob_to_kzg_commitment_rust, compute_blob_kzg_proof_rust};
+use kzg::G1;
+use rust_kzg_blst::types::kzg_settings::FsKZGSettings;
+use rust_kzg_tutorial::{
+    blob::{blob_from_bytes, create_test_blob_bytes},
+    sidecar::{load_sidecars_from_file, parse_sidecars, read_sidecars, BlobSidecar, SidecarError},
+    trusted_setup::load_trusted_setup_from_file,
+};
+use serde_json::{json, Value};
+
+fn hex0x(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// 构造与信标节点 API 格式一致的 sidecar JSON
+fn sidecar_json(settings: &FsKZGSettings, seed: u8, index: u64) -> Value {
+    let blob_bytes = create_test_blob_bytes(seed as usize);
+    let blob = blob_from_bytes(&blob_bytes).unwrap();
+    let commitment = blob_to_kzg_commitment_rust(&blob, settings).unwrap();
+    let proof = compute_blob_kzg_proof_rust(&blob, &commitment, settings).unwrap();
+
+    json!({
+        "index": index.to_string(),
+        "blob": hex0x(&blob_bytes),
+        "kzg_commitment": hex0x(&commitment.to_bytes()),
+        "kzg_proof": hex0x(&proof.to_bytes()),
+        "signed_block_header": {
+            "message": {
+                "slot": "8626176",
+                "proposer_index": "1234",
+                "parent_root": hex0x(&[1u8; 32]),
+                "state_root": hex0x(&[2u8; 32]),
+                "body_root": hex0x(&[3u8; 32]),
+            },
+            "signature": hex0x(&[4u8; 96]),
+        },
+        "kzg_commitment_inclusion_proof": (0..17).map(|i| hex0x(&[i as u8; 32])).collect::<Vec<_>>(),
+    })
+}
+
+#[test]
+fn test_parse_beacon_api_response() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let response = json!({
+        "version": "deneb",
+        "execution_optimistic": false,
+        "finalized": true,
+        "data": [sidecar_json(&settings, 0, 0), sidecar_json(&settings, 1, 1)],
+    });
+
+    let sidecars = parse_sidecars(&response.to_string()).unwrap();
+    assert_eq!(sidecars.len(), 2);
+    assert_eq!(sidecars[1].index, 1);
+    assert_eq!(sidecars[0].slot(), 8626176);
+    assert_eq!(sidecars[0].signed_block_header.message.proposer_index, 1234);
+    assert_eq!(sidecars[0].signed_block_header.message.body_root, [3u8; 32]);
+    assert_eq!(sidecars[0].kzg_commitment_inclusion_proof[16], [16u8; 32]);
+    assert_eq!(sidecars[0].blob, create_test_blob_bytes(0));
+
+    for sidecar in &sidecars {
+        assert!(sidecar.verify_kzg_proof(&settings).unwrap());
+    }
+
+    // 数组和单个对象形式，整数字段也可以是 JSON 数字
+    let mut single = sidecar_json(&settings, 2, 3);
+    single["index"] = json!(3);
+    let from_array = parse_sidecars(&Value::Array(vec![single.clone()]).to_string()).unwrap();
+    let from_object = parse_sidecars(&single.to_string()).unwrap();
+    assert_eq!(from_array, from_object);
+    assert_eq!(from_object[0], BlobSidecar::from_json(&single.to_string()).unwrap());
+    assert_eq!(from_object[0].index, 3);
+
+    let from_reader = read_sidecars(response.to_string().as_bytes()).unwrap();
+    assert_eq!(from_reader, sidecars);
+}
+
+#[test]
+fn test_tampered_sidecar_fails_verification() {
+    let settings = load_trusted_setup_from_file().unwrap();
+
+    // 证明属于另一个 blob
+    let mut json = sidecar_json(&settings, 0, 0);
+    json["kzg_proof"] = sidecar_json(&settings, 1, 0)["kzg_proof"].clone();
+    let sidecar = BlobSidecar::from_json(&json.to_string()).unwrap();
+    assert!(!sidecar.verify_kzg_proof(&settings).unwrap());
+
+    // blob 内容被修改
+    let mut sidecar = BlobSidecar::from_json(&sidecar_json(&settings, 0, 0).to_string()).unwrap();
+    sidecar.blob[31] ^= 1;
+    assert!(!sidecar.verify_kzg_proof(&settings).unwrap());
+}
+
+#[test]
+fn test_malformed_fields_rejected() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let base = sidecar_json(&settings, 0, 0);
+
+    let mut short_blob = base.clone();
+    short_blob["blob"] = json!("0x00ff");
+    assert!(matches!(
+        parse_sidecars(&short_blob.to_string()),
+        Err(SidecarError::InvalidLength { field, expected: 131072, actual: 2 }) if field == "blob"
+    ));
+
+    let mut bad_hex = base.clone();
+    bad_hex["kzg_commitment"] = json!("0xzz");
+    assert!(matches!(
+        parse_sidecars(&bad_hex.to_string()),
+        Err(SidecarError::InvalidHex { field, .. }) if field == "kzg_commitment"
+    ));
+
+    let mut bad_slot = base.clone();
+    bad_slot["signed_block_header"]["message"]["slot"] = json!("-1");
+    assert!(matches!(
+        parse_sidecars(&bad_slot.to_string()),
+        Err(SidecarError::InvalidNumber { field, .. }) if field == "slot"
+    ));
+
+    let mut short_proof = base.clone();
+    short_proof["kzg_commitment_inclusion_proof"].as_array_mut().unwrap().pop();
+    assert!(matches!(
+        parse_sidecars(&short_proof.to_string()),
+        Err(SidecarError::InvalidLength { expected: 17, actual: 16, .. })
+    ));
+
+    let mut missing = base;
+    missing.as_object_mut().unwrap().remove("kzg_proof");
+    assert!(matches!(parse_sidecars(&missing.to_string()), Err(SidecarError::Json(_))));
+}
+
+#[test]
+fn test_load_from_file() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let path = std::env::temp_dir().join(format!("kzg_sidecar_test_{}.json", std::process::id()));
+    std::fs::write(&path, json!({ "data": [sidecar_json(&settings, 5, 0)] }).to_string()).unwrap();
+
+    let sidecars = load_sidecars_from_file(&path).unwrap();
+    assert_eq!(sidecars.len(), 1);
+    assert!(sidecars[0].verify_kzg_proof(&settings).unwrap());
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(load_sidecars_from_file(&path), Err(SidecarError::Io { .. })));
+}
@@ -0,0 +1,135 @@
+// 存储证明审计测试
+// 覆盖挑战派生、诚实/损坏节点的应答验证以及信誉更新
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use kzg::Fr;
+use rust_kzg_blst::types::{fr::FsFr, kzg_settings::FsKZGSettings};
+use rust_kzg_tutorial::{
+    storage::{
+        respond_to_challenge, AuditConfig, AuditError, Auditor, DataShard, FileManifest, NodeManager,
+        NodeSelectionStrategy, ShardConfig, ShardManager, StorageNode, MAX_PAYLOAD_BYTES_PER_BLOB,
+    },
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+async fn setup() -> (Arc<FsKZGSettings>, Vec<DataShard>, FileManifest, Auditor) {
+    let settings = Arc::new(load_trusted_setup_from_file().unwrap());
+    let manager = ShardManager::new(
+        Arc::clone(&settings),
+        ShardConfig {
+            shard_size: MAX_PAYLOAD_BYTES_PER_BLOB,
+            redundancy_factor: 1.0,
+            min_replicas: 1,
+        },
+    );
+
+    let file: Vec<u8> = (0..MAX_PAYLOAD_BYTES_PER_BLOB + 5000).map(|i| (i * 13 % 256) as u8).collect();
+    let shards = manager.shard_file(&file).await.unwrap();
+    let manifest = FileManifest::from_shards(&shards, manager.erasure_params(file.len()), file.len() as u64).unwrap();
+    let auditor = Auditor::with_seed(Arc::clone(&settings), AuditConfig::default(), [7u8; 32]);
+
+    (settings, shards, manifest, auditor)
+}
+
+#[tokio::test]
+async fn test_honest_node_passes_audit() {
+    let (settings, shards, manifest, auditor) = setup().await;
+    let node_id = [1u8; 32];
+
+    for shard in &shards {
+        let challenge = auditor.challenge(&manifest, shard.index, &node_id, 42).unwrap();
+        assert_eq!(challenge.points.len(), auditor.config().points_per_challenge);
+
+        let response = respond_to_challenge(shard, &challenge, &settings).unwrap();
+        assert!(auditor.verify_response(&manifest, &challenge, &response).unwrap());
+    }
+}
+
+#[tokio::test]
+async fn test_corrupted_or_stale_response_fails() {
+    let (settings, shards, manifest, auditor) = setup().await;
+    let node_id = [2u8; 32];
+    let challenge = auditor.challenge(&manifest, 0, &node_id, 1).unwrap();
+
+    // 节点保存的数据已损坏
+    let mut corrupted = shards[0].clone();
+    corrupted.data_chunk[63] ^= 0x01;
+    let response = respond_to_challenge(&corrupted, &challenge, &settings).unwrap();
+    assert!(!auditor.verify_response(&manifest, &challenge, &response).unwrap());
+
+    // 用另一个分片冒充
+    let mut impostor = shards[1].clone();
+    impostor.index = 0;
+    let response = respond_to_challenge(&impostor, &challenge, &settings).unwrap();
+    assert!(!auditor.verify_response(&manifest, &challenge, &response).unwrap());
+
+    // 重放上一纪元的应答
+    let old_challenge = auditor.challenge(&manifest, 0, &node_id, 0).unwrap();
+    let old_response = respond_to_challenge(&shards[0], &old_challenge, &settings).unwrap();
+    assert!(!auditor.verify_response(&manifest, &challenge, &old_response).unwrap());
+
+    // 篡改打开值
+    let mut forged = respond_to_challenge(&shards[0], &challenge, &settings).unwrap();
+    forged.openings[0].value = forged.openings[0].value.add(&FsFr::one());
+    assert!(!auditor.verify_response(&manifest, &challenge, &forged).unwrap());
+
+    // 分片与挑战不对应
+    assert!(matches!(
+        respond_to_challenge(&shards[1], &challenge, &settings),
+        Err(AuditError::ChallengeMismatch(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_challenge_points_vary_per_epoch_and_node() {
+    let (settings, _, manifest, auditor) = setup().await;
+
+    let a = auditor.challenge(&manifest, 0, &[1u8; 32], 10).unwrap();
+    assert_eq!(a, auditor.challenge(&manifest, 0, &[1u8; 32], 10).unwrap());
+    assert_ne!(a.points, auditor.challenge(&manifest, 0, &[1u8; 32], 11).unwrap().points);
+    assert_ne!(a.points, auditor.challenge(&manifest, 0, &[2u8; 32], 10).unwrap().points);
+    assert_ne!(a.points, auditor.challenge(&manifest, 1, &[1u8; 32], 10).unwrap().points);
+
+    // 不同种子的审计方给出不同的挑战
+    let other = Auditor::with_seed(settings, AuditConfig::default(), [8u8; 32]);
+    assert_ne!(a.points, other.challenge(&manifest, 0, &[1u8; 32], 10).unwrap().points);
+
+    assert!(matches!(
+        auditor.challenge(&manifest, manifest.shard_count(), &[1u8; 32], 10),
+        Err(AuditError::UnknownShard { .. })
+    ));
+
+    let epoch = auditor.epoch_at(UNIX_EPOCH + Duration::from_secs(3600 * 5 + 10));
+    assert_eq!(epoch, 5);
+}
+
+#[tokio::test]
+async fn test_audit_results_feed_reputation() {
+    let node_id = [3u8; 32];
+    let node = StorageNode {
+        node_id,
+        address: "node-3.storage.local:8080".to_string(),
+        capacity: 1 << 30,
+        used_capacity: 0,
+        reputation: 0.9,
+        is_online: true,
+    };
+    let manager = NodeManager::new(HashMap::from([(node_id, node)]), NodeSelectionStrategy::Hybrid).unwrap();
+
+    let after_failure = manager.record_audit(&node_id, false, 0.1).await.unwrap().unwrap();
+    assert!((after_failure - 0.81).abs() < 1e-9);
+
+    let after_success = manager.record_audit(&node_id, true, 0.1).await.unwrap().unwrap();
+    assert!(after_success > after_failure);
+
+    for _ in 0..100 {
+        manager.record_audit(&node_id, false, 0.1).await.unwrap();
+    }
+    let reputation = manager.nodes().read().await[&node_id].reputation;
+    assert!((0.0..0.01).contains(&reputation));
+
+    assert_eq!(manager.record_audit(&[9u8; 32], true, 0.1).await.unwrap(), None);
+}
@@ -0,0 +1,138 @@
+// 存储分片编码测试
+// 覆盖 31 字节/域元素编码的往返、边界长度和篡改检测
+
+use std::sync::Arc;
+
+use kzg::eip_4844::{blob_to_kzg_commitment_rust, BYTES_PER_BLOB};
+use rand::{Rng, RngCore, SeedableRng};
+use rust_kzg_tutorial::{
+    blob::blob_from_bytes,
+    storage::{
+        decode_blob, encode_blob, EncodingError, ShardConfig, ShardManager,
+        MAX_PAYLOAD_BYTES_PER_BLOB,
+    },
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+fn random_bytes(len: usize, seed: u64) -> Vec<u8> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let mut data = vec![0u8; len];
+    rng.fill_bytes(&mut data);
+    data
+}
+
+#[test]
+fn test_round_trip_boundary_lengths() {
+    for len in [0, 1, 30, 31, 32, 62, 1000, MAX_PAYLOAD_BYTES_PER_BLOB - 1, MAX_PAYLOAD_BYTES_PER_BLOB] {
+        let payload = random_bytes(len, len as u64);
+        let encoded = encode_blob(&payload).unwrap();
+
+        assert_eq!(encoded.len(), BYTES_PER_BLOB);
+        assert!(blob_from_bytes(&encoded).is_ok(), "长度 {} 的编码应为规范域元素", len);
+        assert_eq!(decode_blob(&encoded).unwrap(), payload, "长度 {} 往返失败", len);
+    }
+}
+
+#[test]
+fn test_all_ones_payload_stays_canonical() {
+    let payload = vec![0xff; MAX_PAYLOAD_BYTES_PER_BLOB];
+    let encoded = encode_blob(&payload).unwrap();
+
+    assert!(blob_from_bytes(&encoded).is_ok());
+    assert_eq!(decode_blob(&encoded).unwrap(), payload);
+}
+
+#[test]
+fn test_trailing_zeros_are_preserved() {
+    let payload = vec![1, 2, 3, 0, 0, 0];
+    let decoded = decode_blob(&encode_blob(&payload).unwrap()).unwrap();
+    assert_eq!(decoded, payload);
+}
+
+#[test]
+fn test_oversized_payload_rejected() {
+    let payload = vec![0u8; MAX_PAYLOAD_BYTES_PER_BLOB + 1];
+    assert_eq!(
+        encode_blob(&payload),
+        Err(EncodingError::PayloadTooLarge {
+            size: MAX_PAYLOAD_BYTES_PER_BLOB + 1,
+            max: MAX_PAYLOAD_BYTES_PER_BLOB,
+        })
+    );
+}
+
+#[test]
+fn test_tampered_blob_rejected() {
+    let payload = random_bytes(100, 7);
+    let encoded = encode_blob(&payload).unwrap();
+
+    // 错误的长度
+    assert!(matches!(decode_blob(&encoded[1..]), Err(EncodingError::InvalidBlobSize { .. })));
+
+    // 篡改头部长度
+    let mut bad_header = encoded.clone();
+    bad_header[5] ^= 1;
+    assert!(matches!(decode_blob(&bad_header), Err(EncodingError::InvalidHeader { .. })));
+
+    // 未知版本
+    let mut bad_version = encoded.clone();
+    bad_version[1] = 0xee;
+    assert_eq!(decode_blob(&bad_version), Err(EncodingError::UnsupportedVersion(0xee)));
+
+    // 填充区写入数据
+    let mut bad_padding = encoded.clone();
+    let last = bad_padding.len() - 1;
+    bad_padding[last] = 1;
+    assert_eq!(decode_blob(&bad_padding), Err(EncodingError::NonZeroPadding));
+
+    // 最高字节非零
+    let mut non_canonical = encoded;
+    non_canonical[32 * 10] = 1;
+    assert_eq!(decode_blob(&non_canonical), Err(EncodingError::NonCanonicalElement(10)));
+}
+
+#[tokio::test]
+async fn test_data_shard_returns_committed_bytes() {
+    let settings = Arc::new(load_trusted_setup_from_file().unwrap());
+    let manager = ShardManager::new(
+        Arc::clone(&settings),
+        ShardConfig {
+            shard_size: MAX_PAYLOAD_BYTES_PER_BLOB,
+            redundancy_factor: 0.0,
+            min_replicas: 1,
+        },
+    );
+
+    let len = rand::thread_rng().gen_range(1..MAX_PAYLOAD_BYTES_PER_BLOB);
+    let payload = random_bytes(len, 42);
+    let shard = manager.create_data_shard(&payload, 0).await.unwrap();
+
+    assert_eq!(shard.payload().unwrap(), payload);
+
+    let blob = shard.to_blob().unwrap();
+    let commitment = blob_to_kzg_commitment_rust(&blob, &*settings).unwrap();
+    assert_eq!(commitment, shard.commitment);
+}
+
+#[tokio::test]
+async fn test_shard_file_concatenates_back() {
+    let settings = Arc::new(load_trusted_setup_from_file().unwrap());
+    let manager = ShardManager::new(
+        settings,
+        ShardConfig {
+            shard_size: MAX_PAYLOAD_BYTES_PER_BLOB,
+            redundancy_factor: 0.0,
+            min_replicas: 1,
+        },
+    );
+
+    let file = random_bytes(2 * MAX_PAYLOAD_BYTES_PER_BLOB + 123, 9);
+    let shards = manager.shard_file(&file).await.unwrap();
+    assert_eq!(shards.len(), 3);
+
+    let restored: Vec<u8> = shards
+        .iter()
+        .flat_map(|shard| shard.payload().unwrap())
+        .collect();
+    assert_eq!(restored, file);
+}
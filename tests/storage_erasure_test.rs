@@ -0,0 +1,144 @@
+// Reed–Solomon 纠删码测试
+// 覆盖任意 k 个分片恢复、参数边界和 ShardManager 的文件级恢复
+
+use std::sync::Arc;
+
+use kzg::{eip_4844::blob_to_kzg_commitment_rust, Fr};
+use rand::{RngCore, SeedableRng};
+use rust_kzg_blst::types::fr::FsFr;
+use rust_kzg_tutorial::{
+    storage::{
+        DataShard, ErasureError, ErasureParams, ReedSolomon, ShardConfig, ShardError, ShardKind,
+        ShardManager, MAX_PAYLOAD_BYTES_PER_BLOB,
+    },
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+fn sample_shards(count: usize, len: usize) -> Vec<Vec<FsFr>> {
+    (0..count)
+        .map(|i| (0..len).map(|j| FsFr::from_u64((i * 1000 + j * 7 + 1) as u64)).collect())
+        .collect()
+}
+
+/// 枚举 n 选 k 的所有组合
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    if n < k {
+        return Vec::new();
+    }
+    let mut result = combinations(n - 1, k);
+    for mut combo in combinations(n - 1, k - 1) {
+        combo.push(n - 1);
+        result.push(combo);
+    }
+    result
+}
+
+#[test]
+fn test_any_k_of_n_reconstructs() {
+    for (k, m) in [(1, 2), (3, 3), (4, 2), (5, 3)] {
+        let rs = ReedSolomon::new(ErasureParams::new(k, m)).unwrap();
+        let data = sample_shards(k, 8);
+        let parity = rs.encode(&data).unwrap();
+        assert_eq!(parity.len(), m);
+
+        let all: Vec<Vec<FsFr>> = data.iter().chain(&parity).cloned().collect();
+        for subset in combinations(k + m, k) {
+            let available: Vec<(usize, Vec<FsFr>)> =
+                subset.iter().map(|&i| (i, all[i].clone())).collect();
+            assert_eq!(rs.reconstruct(&available).unwrap(), data, "k={} m={} 子集 {:?}", k, m, subset);
+        }
+    }
+}
+
+#[test]
+fn test_reconstruct_rejects_bad_input() {
+    let rs = ReedSolomon::new(ErasureParams::new(3, 2)).unwrap();
+    let data = sample_shards(3, 4);
+    let parity = rs.encode(&data).unwrap();
+
+    // 重复分片不计入
+    let duplicated = vec![(0, data[0].clone()), (0, data[0].clone()), (3, parity[0].clone())];
+    assert_eq!(
+        rs.reconstruct(&duplicated),
+        Err(ErasureError::InsufficientShards { required: 3, available: 2 })
+    );
+
+    let out_of_range = vec![(5, data[0].clone())];
+    assert_eq!(
+        rs.reconstruct(&out_of_range),
+        Err(ErasureError::InvalidShardIndex { index: 5, total: 5 })
+    );
+
+    let mismatched = vec![(0, data[0].clone()), (1, vec![FsFr::zero(); 2]), (2, data[2].clone())];
+    assert!(matches!(rs.reconstruct(&mismatched), Err(ErasureError::InvalidShardLength { .. })));
+
+    assert!(ReedSolomon::new(ErasureParams::new(0, 1)).is_err());
+}
+
+fn shard_manager(redundancy_factor: f64) -> ShardManager {
+    ShardManager::new(
+        Arc::new(load_trusted_setup_from_file().unwrap()),
+        ShardConfig {
+            shard_size: MAX_PAYLOAD_BYTES_PER_BLOB,
+            redundancy_factor,
+            min_replicas: 1,
+        },
+    )
+}
+
+#[tokio::test]
+async fn test_reconstruct_file_from_arbitrary_subset() {
+    let manager = shard_manager(1.0);
+    let mut file = vec![0u8; 2 * MAX_PAYLOAD_BYTES_PER_BLOB + 4321];
+    rand::rngs::StdRng::seed_from_u64(3).fill_bytes(&mut file);
+
+    let shards = manager.shard_file(&file).await.unwrap();
+    let params = manager.erasure_params(file.len());
+    assert_eq!(params, ErasureParams::new(3, 3));
+    assert_eq!(shards.len(), 6);
+    assert!(shards[..3].iter().all(|s| s.kind == ShardKind::Data));
+    assert!(shards[3..].iter().all(|s| s.kind == ShardKind::Parity));
+
+    // 只保留 1 个数据分片和 2 个校验分片，且顺序打乱
+    let subset: Vec<DataShard> = [5, 1, 3].iter().map(|&i| shards[i].clone()).collect();
+    assert_eq!(manager.reconstruct_file(&subset, &params).unwrap(), file);
+
+    // 全部校验分片
+    assert_eq!(manager.reconstruct_file(&shards[3..], &params).unwrap(), file);
+
+    // 不足 k 个
+    assert!(matches!(
+        manager.reconstruct_file(&shards[..2], &params),
+        Err(ShardError::Erasure(ErasureError::InsufficientShards { required: 3, available: 2 }))
+    ));
+}
+
+#[tokio::test]
+async fn test_parity_shards_have_own_commitment() {
+    let manager = shard_manager(0.5);
+    let settings = load_trusted_setup_from_file().unwrap();
+    let file = vec![0xabu8; MAX_PAYLOAD_BYTES_PER_BLOB + 1];
+
+    let shards = manager.shard_file(&file).await.unwrap();
+    let parity: Vec<&DataShard> = shards.iter().filter(|s| s.kind == ShardKind::Parity).collect();
+    assert_eq!(parity.len(), 1);
+
+    for shard in &shards {
+        let commitment = blob_to_kzg_commitment_rust(&shard.to_blob().unwrap(), &settings).unwrap();
+        assert_eq!(commitment, shard.commitment, "分片 {} 的承诺不匹配", shard.index);
+    }
+    assert_ne!(parity[0].commitment, shards[0].commitment);
+}
+
+#[tokio::test]
+async fn test_empty_file_round_trip() {
+    let manager = shard_manager(1.0);
+    let shards = manager.shard_file(&[]).await.unwrap();
+    let params = manager.erasure_params(0);
+
+    assert_eq!(params, ErasureParams::new(1, 1));
+    assert_eq!(manager.reconstruct_file(&shards[1..], &params).unwrap(), Vec::<u8>::new());
+}
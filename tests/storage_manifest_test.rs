@@ -0,0 +1,117 @@
+// 文件承诺清单测试
+// 覆盖清单构建、JSON 往返、根哈希篡改检测和按清单校验分片
+
+use std::sync::Arc;
+
+use rust_kzg_tutorial::{
+    storage::{
+        verify_manifest, DataShard, ErasureParams, FileManifest, ManifestError, ShardConfig,
+        ShardKind, ShardManager, MAX_PAYLOAD_BYTES_PER_BLOB,
+    },
+    trusted_setup::load_trusted_setup_from_file,
+};
+use rust_kzg_blst::types::kzg_settings::FsKZGSettings;
+
+async fn shard_test_file() -> (Arc<FsKZGSettings>, Vec<u8>, Vec<DataShard>, FileManifest) {
+    let settings = Arc::new(load_trusted_setup_from_file().unwrap());
+    let manager = ShardManager::new(
+        Arc::clone(&settings),
+        ShardConfig {
+            shard_size: MAX_PAYLOAD_BYTES_PER_BLOB,
+            redundancy_factor: 0.5,
+            min_replicas: 1,
+        },
+    );
+
+    let file: Vec<u8> = (0..MAX_PAYLOAD_BYTES_PER_BLOB + 500).map(|i| (i % 251) as u8).collect();
+    let shards = manager.shard_file(&file).await.unwrap();
+    let manifest =
+        FileManifest::from_shards(&shards, manager.erasure_params(file.len()), file.len() as u64).unwrap();
+
+    (settings, file, shards, manifest)
+}
+
+#[tokio::test]
+async fn test_manifest_binds_ordered_commitments() {
+    let (_, file, shards, manifest) = shard_test_file().await;
+
+    assert_eq!(manifest.erasure, ErasureParams::new(2, 1));
+    assert_eq!(manifest.original_length, file.len() as u64);
+    assert_eq!(manifest.shard_count(), shards.len());
+    for shard in &shards {
+        assert_eq!(manifest.shard_commitments[shard.index], shard.commitment);
+        assert_eq!(manifest.shard_kind(shard.index), Some(shard.kind));
+    }
+    assert!(manifest.verify_integrity().is_ok());
+
+    // 分片顺序不影响清单
+    let reversed: Vec<DataShard> = shards.iter().rev().cloned().collect();
+    let from_reversed = FileManifest::from_shards(&reversed, manifest.erasure, manifest.original_length).unwrap();
+    assert_eq!(from_reversed, manifest);
+
+    // 缺少分片时无法构建清单
+    assert!(matches!(
+        FileManifest::from_shards(&shards[1..], manifest.erasure, manifest.original_length),
+        Err(ManifestError::InvalidShardCount { expected: 3, actual: 2 })
+    ));
+}
+
+#[tokio::test]
+async fn test_manifest_json_round_trip() {
+    let (_, _, _, manifest) = shard_test_file().await;
+
+    let json = manifest.to_json().unwrap();
+    assert_eq!(FileManifest::from_json(&json).unwrap(), manifest);
+
+    // 篡改原始长度后根哈希不再匹配
+    let tampered = json.replacen(
+        &format!("\"original_length\": {}", manifest.original_length),
+        "\"original_length\": 1",
+        1,
+    );
+    assert_ne!(tampered, json);
+    assert_eq!(FileManifest::from_json(&tampered), Err(ManifestError::RootMismatch));
+
+    assert!(matches!(FileManifest::from_json("{}"), Err(ManifestError::Serialization(_))));
+}
+
+#[tokio::test]
+async fn test_verify_manifest_checks_downloaded_shards() {
+    let (settings, _, shards, manifest) = shard_test_file().await;
+
+    for shard in &shards {
+        verify_manifest(&manifest, shard, &settings).unwrap();
+    }
+
+    // 数据被篡改，即使分片自带的承诺未变也会被发现
+    let mut tampered = shards[0].clone();
+    tampered.data_chunk[64] ^= 0x01;
+    assert_eq!(
+        verify_manifest(&manifest, &tampered, &settings),
+        Err(ManifestError::CommitmentMismatch(0))
+    );
+
+    // 分片被放错位置
+    let mut misplaced = shards[0].clone();
+    misplaced.index = 1;
+    assert_eq!(
+        verify_manifest(&manifest, &misplaced, &settings),
+        Err(ManifestError::CommitmentMismatch(1))
+    );
+
+    let mut wrong_kind = shards[2].clone();
+    wrong_kind.kind = ShardKind::Data;
+    assert_eq!(verify_manifest(&manifest, &wrong_kind, &settings), Err(ManifestError::KindMismatch(2)));
+
+    let mut unknown = shards[0].clone();
+    unknown.index = 7;
+    assert_eq!(
+        verify_manifest(&manifest, &unknown, &settings),
+        Err(ManifestError::UnknownShard { index: 7, total: 3 })
+    );
+
+    // 被篡改的清单不能用于校验
+    let mut bad_manifest = manifest.clone();
+    bad_manifest.shard_commitments.swap(0, 1);
+    assert_eq!(verify_manifest(&bad_manifest, &shards[0], &settings), Err(ManifestError::RootMismatch));
+}
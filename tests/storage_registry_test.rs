@@ -0,0 +1,242 @@
+// 节点注册表测试
+// 覆盖文件注册表的跨重启恢复、放置更新、审计衰减、节点流失、写入失败时的回滚、无效节点和信誉选择
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use kzg::eip_4844::BYTES_PER_BLOB;
+use rust_kzg_blst::types::g1::FsG1;
+use rust_kzg_tutorial::storage::{
+    DataShard, FileRegistry, InMemoryRegistry, NodeError, NodeManager, NodeRegistry,
+    NodeSelectionStrategy, RegistryError, RegistryState, ShardKind, StorageNode,
+};
+
+fn registry_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "kzg_registry_test_{}_{}.json",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn node(i: u8) -> StorageNode {
+    StorageNode {
+        node_id: [i; 32],
+        address: format!("node-{}.storage.local:8080", i),
+        capacity: 10 * BYTES_PER_BLOB as u64,
+        used_capacity: 0,
+        reputation: 0.9,
+        is_online: true,
+    }
+}
+
+fn shard(id: u8) -> DataShard {
+    DataShard {
+        shard_id: [id; 32],
+        index: 0,
+        kind: ShardKind::Data,
+        data_chunk: vec![0u8; BYTES_PER_BLOB],
+        commitment: FsG1::default(),
+        storage_locations: Vec::new(),
+        created_at: 0,
+    }
+}
+
+/// 可切换为写入失败的内存注册表
+#[derive(Default)]
+struct FlakyRegistry {
+    inner: InMemoryRegistry,
+    failing: AtomicBool,
+}
+
+impl NodeRegistry for FlakyRegistry {
+    fn load(&self) -> Result<RegistryState, RegistryError> {
+        self.inner.load()
+    }
+
+    fn save(&self, state: &RegistryState) -> Result<(), RegistryError> {
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(RegistryError::Io {
+                path: "flaky".to_string(),
+                source: std::io::Error::other("disk full"),
+            });
+        }
+        self.inner.save(state)
+    }
+}
+
+async fn open(path: &PathBuf) -> NodeManager {
+    NodeManager::with_registry(Arc::new(FileRegistry::new(path)), NodeSelectionStrategy::LoadBalanced).unwrap()
+}
+
+#[tokio::test]
+async fn test_file_registry_survives_restart() {
+    let path = registry_path("restart");
+
+    {
+        let manager = open(&path).await;
+        for i in 1..=4 {
+            manager.register_node(node(i)).await.unwrap();
+        }
+
+        let selected = manager.select_storage_nodes(&shard(9), 2).await.unwrap();
+        manager.record_placement(&shard(9), &selected).await.unwrap();
+        manager.record_audit(&[1; 32], false, 0.5).await.unwrap();
+        manager.set_online(&[2; 32], false).await.unwrap();
+    }
+
+    // 重新打开：节点状态和放置决策都应恢复
+    let manager = open(&path).await;
+    let nodes = manager.nodes().read().await.clone();
+    assert_eq!(nodes.len(), 4);
+    assert!((nodes[&[1; 32]].reputation - 0.45).abs() < 1e-9);
+    assert!(!nodes[&[2; 32]].is_online);
+
+    let placement = manager.placement(&[9; 32]).await.unwrap();
+    assert_eq!(placement.nodes.len(), 2);
+    assert_eq!(placement.size, BYTES_PER_BLOB as u64);
+    for node_id in &placement.nodes {
+        assert_eq!(nodes[node_id].used_capacity, BYTES_PER_BLOB as u64);
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_placement_updates_capacity() {
+    let manager = NodeManager::with_registry(Arc::new(InMemoryRegistry::new()), NodeSelectionStrategy::Hybrid).unwrap();
+    manager.register_node(node(1)).await.unwrap();
+    manager.register_node(node(2)).await.unwrap();
+
+    // 重复放置不重复计入容量
+    manager.record_placement(&shard(5), &[[1; 32], [2; 32]]).await.unwrap();
+    manager.record_placement(&shard(5), &[[1; 32]]).await.unwrap();
+    assert_eq!(manager.nodes().read().await[&[1; 32]].used_capacity, BYTES_PER_BLOB as u64);
+
+    assert!(manager.remove_replica(&[5; 32], &[1; 32]).await.unwrap());
+    assert!(!manager.remove_replica(&[5; 32], &[1; 32]).await.unwrap());
+    assert_eq!(manager.nodes().read().await[&[1; 32]].used_capacity, 0);
+    assert_eq!(manager.placement(&[5; 32]).await.unwrap().nodes, vec![[2; 32]]);
+
+    assert!(matches!(
+        manager.record_placement(&shard(6), &[[7; 32]]).await,
+        Err(NodeError::UnknownNode(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_node_churn() {
+    let registry = Arc::new(InMemoryRegistry::new());
+    let manager = NodeManager::with_registry(registry.clone(), NodeSelectionStrategy::LoadBalanced).unwrap();
+    for i in 1..=3 {
+        manager.register_node(node(i)).await.unwrap();
+    }
+    manager.record_placement(&shard(8), &[[1; 32], [2; 32], [3; 32]]).await.unwrap();
+
+    // 临时下线的节点不参与选择，但副本记录保留
+    manager.set_online(&[1; 32], false).await.unwrap();
+    assert!(matches!(
+        manager.select_storage_nodes(&shard(9), 3).await,
+        Err(NodeError::InsufficientNodes { required: 3, available: 2 })
+    ));
+    assert_eq!(manager.placement(&[8; 32]).await.unwrap().nodes.len(), 3);
+
+    // 永久离开的节点从放置记录中移除
+    let removed = manager.remove_node(&[2; 32]).await.unwrap();
+    assert_eq!(removed.map(|n| n.node_id), Some([2; 32]));
+    assert_eq!(manager.placement(&[8; 32]).await.unwrap().nodes, vec![[1; 32], [3; 32]]);
+
+    // 快照与内存状态一致
+    let state = registry.load().unwrap();
+    assert_eq!(state.nodes.len(), 2);
+    assert_eq!(state.placements[0].nodes.len(), 2);
+
+    assert!(matches!(manager.set_online(&[2; 32], true).await, Err(NodeError::UnknownNode(_))));
+}
+
+#[test]
+fn test_corrupted_registry_file_rejected() {
+    let path = registry_path("corrupted");
+    std::fs::write(&path, "not json").unwrap();
+
+    let result = NodeManager::with_registry(Arc::new(FileRegistry::new(&path)), NodeSelectionStrategy::Hybrid);
+    assert!(matches!(result, Err(NodeError::Registry(RegistryError::Format(_)))));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_failed_save_leaves_memory_unchanged() {
+    let registry = Arc::new(FlakyRegistry::default());
+    let manager = NodeManager::with_registry(registry.clone(), NodeSelectionStrategy::LoadBalanced).unwrap();
+    manager.register_node(node(1)).await.unwrap();
+    manager.register_node(node(2)).await.unwrap();
+    manager.record_placement(&shard(5), &[[1; 32]]).await.unwrap();
+    let nodes_before = manager.nodes().read().await.clone();
+    let placements_before = manager.placements().await;
+
+    registry.failing.store(true, Ordering::SeqCst);
+    assert!(matches!(manager.register_node(node(3)).await, Err(NodeError::Registry(_))));
+    assert!(manager.set_online(&[1; 32], false).await.is_err());
+    assert!(manager.record_audit(&[1; 32], false, 0.5).await.is_err());
+    assert!(manager.record_placement(&shard(6), &[[2; 32]]).await.is_err());
+    assert!(manager.remove_replica(&[5; 32], &[1; 32]).await.is_err());
+    assert!(manager.remove_node(&[2; 32]).await.is_err());
+
+    // 内存状态与最后一次成功写入的快照一致
+    assert_eq!(*manager.nodes().read().await, nodes_before);
+    assert_eq!(manager.placements().await, placements_before);
+    assert_eq!(registry.load().unwrap().nodes.len(), 2);
+
+    registry.failing.store(false, Ordering::SeqCst);
+    manager.set_online(&[1; 32], false).await.unwrap();
+    assert!(!manager.nodes().read().await[&[1; 32]].is_online);
+}
+
+#[tokio::test]
+async fn test_invalid_nodes_rejected() {
+    let manager = NodeManager::with_registry(Arc::new(InMemoryRegistry::new()), NodeSelectionStrategy::Hybrid).unwrap();
+    let empty = StorageNode { capacity: 0, ..node(1) };
+    assert!(matches!(manager.register_node(empty.clone()).await, Err(NodeError::InvalidNode(_))));
+    let unrated = StorageNode { reputation: f64::NAN, ..node(2) };
+    assert!(matches!(manager.register_node(unrated.clone()).await, Err(NodeError::InvalidNode(_))));
+    assert!(manager.nodes().read().await.is_empty());
+
+    // 注册表中的损坏记录在加载时被拒绝
+    for bad in [empty, unrated] {
+        let registry = InMemoryRegistry::new();
+        registry
+            .save(&RegistryState {
+                nodes: vec![node(3), bad.clone()],
+                placements: Vec::new(),
+            })
+            .unwrap();
+        let result = NodeManager::with_registry(Arc::new(registry), NodeSelectionStrategy::Hybrid);
+        assert!(matches!(result, Err(NodeError::InvalidNode(_))));
+
+        // 直接传入的节点同样校验
+        let nodes = HashMap::from([(node(3).node_id, node(3)), (bad.node_id, bad)]);
+        let result = NodeManager::new(nodes, NodeSelectionStrategy::Hybrid);
+        assert!(matches!(result, Err(NodeError::InvalidNode(_))));
+    }
+}
+
+#[tokio::test]
+async fn test_reputation_selection_requires_enough_qualified_nodes() {
+    let nodes = [(1, 0.6), (2, 0.85), (3, 0.95)]
+        .into_iter()
+        .map(|(i, reputation)| ([i; 32], StorageNode { reputation, ..node(i) }))
+        .collect();
+    let manager = NodeManager::new(nodes, NodeSelectionStrategy::ReputationBased { min_reputation: 0.8 }).unwrap();
+
+    // 节点 2 和 3 达标，按信誉从高到低选择
+    assert_eq!(manager.select_storage_nodes(&shard(9), 2).await.unwrap(), vec![[3; 32], [2; 32]]);
+    assert!(matches!(
+        manager.select_storage_nodes(&shard(9), 3).await,
+        Err(NodeError::InsufficientNodes { required: 3, available: 2 })
+    ));
+}
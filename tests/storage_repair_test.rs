@@ -0,0 +1,249 @@
+// 分片修复测试
+// 覆盖副本复制、丢失与损坏副本检测、纠删码重建、损坏副本跳过、限速顺延和修复指标
+
+use std::sync::Arc;
+
+use kzg::eip_4844::BYTES_PER_BLOB;
+use rust_kzg_blst::types::kzg_settings::FsKZGSettings;
+use rust_kzg_tutorial::{
+    storage::{
+        verify_manifest, DataShard, FileManifest, InMemoryRegistry, InMemoryShardStore, NodeId, NodeManager,
+        NodeSelectionStrategy, RepairConfig, RepairError, RepairScheduler, ShardConfig, ShardManager, ShardStore,
+        StorageNode, MAX_PAYLOAD_BYTES_PER_BLOB,
+    },
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+struct Network {
+    settings: Arc<FsKZGSettings>,
+    shards: Vec<DataShard>,
+    manifest: FileManifest,
+    node_manager: Arc<NodeManager>,
+    store: Arc<InMemoryShardStore>,
+}
+
+fn node_id(i: u8) -> NodeId {
+    [i; 32]
+}
+
+/// 2 数据 + 2 校验分片，分片 i 放在节点 i+1 和 i+2 上，节点 6 空闲
+async fn setup() -> Network {
+    let settings = Arc::new(load_trusted_setup_from_file().unwrap());
+    let shard_manager = ShardManager::new(
+        Arc::clone(&settings),
+        ShardConfig {
+            shard_size: MAX_PAYLOAD_BYTES_PER_BLOB,
+            redundancy_factor: 1.0,
+            min_replicas: 2,
+        },
+    );
+
+    let file: Vec<u8> = (0..MAX_PAYLOAD_BYTES_PER_BLOB + 5000).map(|i| (i * 7 % 251) as u8).collect();
+    let shards = shard_manager.shard_file(&file).await.unwrap();
+    let manifest =
+        FileManifest::from_shards(&shards, shard_manager.erasure_params(file.len()), file.len() as u64).unwrap();
+
+    let node_manager = Arc::new(
+        NodeManager::with_registry(Arc::new(InMemoryRegistry::new()), NodeSelectionStrategy::LoadBalanced).unwrap(),
+    );
+    for i in 1..=6 {
+        node_manager
+            .register_node(StorageNode {
+                node_id: node_id(i),
+                address: format!("node-{}.storage.local:8080", i),
+                capacity: 10 * BYTES_PER_BLOB as u64,
+                used_capacity: 0,
+                reputation: 0.9,
+                is_online: true,
+            })
+            .await
+            .unwrap();
+    }
+
+    let store = Arc::new(InMemoryShardStore::new());
+    for (i, shard) in shards.iter().enumerate() {
+        let holders = [node_id(i as u8 + 1), node_id(i as u8 + 2)];
+        for holder in &holders {
+            store.put(holder, shard).unwrap();
+        }
+        node_manager.record_placement(shard, &holders).await.unwrap();
+    }
+
+    Network {
+        settings,
+        shards,
+        manifest,
+        node_manager,
+        store,
+    }
+}
+
+async fn scheduler(network: &Network, max_repairs_per_round: usize) -> RepairScheduler {
+    let scheduler = RepairScheduler::new(
+        Arc::clone(&network.settings),
+        Arc::clone(&network.node_manager),
+        network.store.clone(),
+        RepairConfig {
+            min_replicas: 2,
+            max_repairs_per_round,
+            ..Default::default()
+        },
+    );
+    scheduler
+        .track_file(network.manifest.clone(), &network.shards)
+        .await
+        .unwrap();
+    scheduler
+}
+
+/// 分片的在线副本
+async fn live_holders(network: &Network, shard: &DataShard) -> Vec<NodeId> {
+    let nodes = network.node_manager.nodes().read().await;
+    network
+        .node_manager
+        .placement(&shard.shard_id)
+        .await
+        .unwrap()
+        .nodes
+        .into_iter()
+        .filter(|id| nodes[id].is_online)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_offline_node_triggers_re_replication() {
+    let network = setup().await;
+    let scheduler = scheduler(&network, 16).await;
+    assert!(scheduler.find_under_replicated().await.is_empty());
+
+    // 节点 2 下线，分片 0 和 1 各剩一个在线副本
+    network.node_manager.set_online(&node_id(2), false).await.unwrap();
+    let under = scheduler.find_under_replicated().await;
+    assert_eq!(under.iter().map(|s| s.index).collect::<Vec<_>>(), vec![0, 1]);
+    assert!(under.iter().all(|s| s.live_replicas == 1));
+
+    let report = scheduler.run_once().await;
+    assert_eq!(report.scanned, 4);
+    assert_eq!(report.repaired, 2);
+    assert_eq!(report.replicas_created, 2);
+    assert!(report.failed.is_empty());
+
+    for shard in &network.shards[..2] {
+        let holders = live_holders(&network, shard).await;
+        assert_eq!(holders.len(), 2);
+        for holder in holders {
+            let copy = network.store.get(&holder, &shard.shard_id).unwrap();
+            assert_eq!(copy.data_chunk, shard.data_chunk);
+        }
+    }
+    // 离线节点的副本记录保留，新副本不会放回该节点
+    assert!(network.node_manager.placement(&network.shards[0].shard_id).await.unwrap().nodes.contains(&node_id(2)));
+
+    let stats = scheduler.stats();
+    assert_eq!(stats.rounds, 1);
+    assert_eq!(stats.repairs_succeeded, 2);
+    assert_eq!(stats.replicas_created, 2);
+    assert_eq!(stats.shards_reconstructed, 0);
+    assert!(stats.bytes_transferred > 0);
+
+    assert_eq!(scheduler.run_once().await.under_replicated, 0);
+}
+
+#[tokio::test]
+async fn test_missing_replica_on_online_node_re_replicated() {
+    let network = setup().await;
+    let scheduler = scheduler(&network, 16).await;
+
+    // 节点都在线，但节点 3 丢了分片 2，节点 1 上的分片 0 已损坏
+    network.store.remove(&node_id(3), &network.shards[2].shard_id).unwrap();
+    let mut corrupted = network.shards[0].clone();
+    corrupted.data_chunk[7] ^= 1;
+    network.store.put(&node_id(1), &corrupted).unwrap();
+
+    let under = scheduler.find_under_replicated().await;
+    assert_eq!(under.iter().map(|s| s.index).collect::<Vec<_>>(), vec![0, 2]);
+    assert!(under.iter().all(|s| s.live_replicas == 1));
+
+    let report = scheduler.run_once().await;
+    assert_eq!((report.repaired, report.replicas_created), (2, 2));
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+
+    // 新副本放在其他节点上，丢失和损坏数据的节点不会被重新选中
+    for (shard, bad) in [(&network.shards[0], node_id(1)), (&network.shards[2], node_id(3))] {
+        let placement = network.node_manager.placement(&shard.shard_id).await.unwrap();
+        assert_eq!(placement.nodes.len(), 3);
+        for holder in placement.nodes.into_iter().filter(|id| *id != bad) {
+            let copy = network.store.get(&holder, &shard.shard_id).unwrap();
+            assert_eq!(copy.data_chunk, shard.data_chunk);
+        }
+    }
+    assert_eq!(scheduler.run_once().await.under_replicated, 0);
+}
+
+#[tokio::test]
+async fn test_lost_shard_rebuilt_from_erasure_peers() {
+    let network = setup().await;
+    let scheduler = scheduler(&network, 16).await;
+
+    // 分片 3 (校验分片) 的两个副本所在节点都离开网络
+    network.node_manager.remove_node(&node_id(4)).await.unwrap();
+    network.node_manager.remove_node(&node_id(5)).await.unwrap();
+    // 节点 2 上的分片 1 副本已损坏，只能从节点 3 取
+    let mut corrupted = network.shards[1].clone();
+    corrupted.data_chunk[100] ^= 1;
+    network.store.put(&node_id(2), &corrupted).unwrap();
+
+    let report = scheduler.run_once().await;
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    assert_eq!(scheduler.stats().shards_reconstructed, 1);
+
+    let lost = &network.shards[3];
+    let holders = live_holders(&network, lost).await;
+    assert_eq!(holders.len(), 2);
+    for holder in holders {
+        let rebuilt = network.store.get(&holder, &lost.shard_id).unwrap();
+        assert_eq!(rebuilt.data_chunk, lost.data_chunk);
+        verify_manifest(&network.manifest, &rebuilt, &network.settings).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_repairs_are_rate_limited() {
+    let network = setup().await;
+    let scheduler = scheduler(&network, 1).await;
+
+    network.node_manager.set_online(&node_id(2), false).await.unwrap();
+
+    let first = scheduler.run_once().await;
+    assert_eq!((first.under_replicated, first.repaired, first.deferred), (2, 1, 1));
+
+    let second = scheduler.run_once().await;
+    assert_eq!((second.under_replicated, second.repaired, second.deferred), (1, 1, 0));
+
+    let stats = scheduler.stats();
+    assert_eq!(stats.rounds, 2);
+    assert_eq!(stats.repairs_succeeded, 2);
+    assert_eq!(stats.repairs_deferred, 1);
+    assert_eq!(stats.under_replicated, 1);
+}
+
+#[tokio::test]
+async fn test_unrecoverable_shard_reported() {
+    let network = setup().await;
+    let scheduler = scheduler(&network, 16).await;
+
+    // 只剩节点 1 上的分片 0，低于 k = 2
+    for i in 2..=5 {
+        network.node_manager.set_online(&node_id(i), false).await.unwrap();
+    }
+
+    let report = scheduler.run_once().await;
+    assert_eq!(report.under_replicated, 4);
+    assert_eq!(report.repaired, 1);
+    assert_eq!(report.failed.len(), 3);
+    assert!(report
+        .failed
+        .iter()
+        .all(|(_, e)| matches!(e, RepairError::Unrecoverable { required: 2, available: 1, .. })));
+    assert_eq!(scheduler.stats().repairs_failed, 3);
+}
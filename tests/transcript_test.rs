@@ -0,0 +1,119 @@
+// Fiat–Shamir 挑战测试
+// 覆盖模 r 约简、EIP-4844 compute_challenge 测试向量以及与 blob 证明的一致性
+//
+// 测试向量按 consensus-specs 中 compute_challenge / hash_to_bls_field 的定义生成
+
+use kzg::eip_4844::{
+    blob_to_kzg_commitment_rust, compute_blob_kzg_proof_rust, compute_kzg_proof_rust,
+    verify_blob_kzg_proof_rust, BYTES_PER_BLOB,
+};
+use kzg::Fr;
+use rust_kzg_blst::types::fr::FsFr;
+use rust_kzg_tutorial::{
+    blob::create_test_blob,
+    transcript::{compute_challenge, compute_challenge_from_bytes, hash_to_bls_field, Transcript},
+    trusted_setup::load_trusted_setup_from_file,
+};
+
+/// 无穷远点的压缩表示
+fn identity_commitment() -> [u8; 48] {
+    let mut bytes = [0u8; 48];
+    bytes[0] = 0xc0;
+    bytes
+}
+
+fn challenge_hex(blob: &[u8], commitment: &[u8; 48]) -> String {
+    hex::encode(compute_challenge_from_bytes(blob, commitment).unwrap().to_bytes())
+}
+
+#[test]
+fn test_hash_to_bls_field_reduces_modulo_r() {
+    assert_eq!(hash_to_bls_field(&[0u8; 32]), FsFr::zero());
+
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    assert_eq!(hash_to_bls_field(&one), FsFr::one());
+
+    // 2^256 - 1 mod r
+    assert_eq!(
+        hex::encode(hash_to_bls_field(&[0xff; 32]).to_bytes()),
+        "1824b159acc5056f998c4fefecbc4ff55884b7fa0003480200000001fffffffd"
+    );
+}
+
+#[test]
+fn test_compute_challenge_vectors() {
+    // 全零 blob + 无穷远点
+    assert_eq!(
+        challenge_hex(&vec![0u8; BYTES_PER_BLOB], &identity_commitment()),
+        "04b7b22af63d2b2f1ced8d550560e5d1e4b01e355903dee22781e87826856096"
+    );
+
+    // 第 i 个域元素为 i + G1 生成元
+    let blob: Vec<u8> = (0..4096u64)
+        .flat_map(|i| {
+            let mut element = [0u8; 32];
+            element[24..].copy_from_slice(&i.to_be_bytes());
+            element
+        })
+        .collect();
+    let generator: [u8; 48] = hex::decode(
+        "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap();
+    assert_eq!(
+        challenge_hex(&blob, &generator),
+        "16e13374f0f11047e3c9b05295c0cf68fe8e85ea4018726711508d96f72d0f70"
+    );
+
+    // 哈希值 (0xac58…) 大于 r，必须约简而不是回退
+    let mut blob = vec![0u8; BYTES_PER_BLOB];
+    blob[31] = 1;
+    assert_eq!(
+        challenge_hex(&blob, &identity_commitment()),
+        "386b1d8f7e0ea48155adc975d8bc0e0faec3142f2d786bab94da92e564d13bee"
+    );
+
+    assert!(compute_challenge_from_bytes(&blob[1..], &identity_commitment()).is_err());
+}
+
+#[test]
+fn test_challenge_matches_blob_proof() {
+    let settings = load_trusted_setup_from_file().unwrap();
+    let blob = create_test_blob().unwrap();
+    let commitment = blob_to_kzg_commitment_rust(&blob, &settings).unwrap();
+
+    let z = compute_challenge(&blob, &commitment);
+    let blob_bytes: Vec<u8> = blob.iter().flat_map(|fr| fr.to_bytes()).collect();
+    let commitment_bytes: [u8; 48] = kzg::G1::to_bytes(&commitment);
+    assert_eq!(z, compute_challenge_from_bytes(&blob_bytes, &commitment_bytes).unwrap());
+
+    // blob 证明就是挑战点处的打开证明
+    let (proof, _) = compute_kzg_proof_rust(&blob, &z, &settings).unwrap();
+    assert_eq!(proof, compute_blob_kzg_proof_rust(&blob, &commitment, &settings).unwrap());
+    assert!(verify_blob_kzg_proof_rust(&blob, &commitment, &proof, &settings).unwrap());
+
+    // 其他点的打开证明不能当作 blob 证明
+    let (other, _) = compute_kzg_proof_rust(&blob, &z.add(&FsFr::one()), &settings).unwrap();
+    assert!(!verify_blob_kzg_proof_rust(&blob, &commitment, &other, &settings).unwrap());
+}
+
+#[test]
+fn test_transcript_domain_separation() {
+    let mut a = Transcript::new(b"PROTOCOL_A");
+    a.append_u64(7);
+    let mut b = Transcript::new(b"PROTOCOL_B");
+    b.append_u64(7);
+    assert_ne!(a.challenge_bytes(), b.challenge_bytes());
+
+    // 导出挑战不改变记录
+    assert_eq!(a.challenge_fr(), a.challenge_fr());
+
+    let challenges = a.challenge_frs(3);
+    assert_eq!(challenges.len(), 3);
+    assert_ne!(challenges[0], challenges[1]);
+    assert_ne!(challenges[1], challenges[2]);
+    assert_eq!(challenges, a.challenge_frs(3));
+}
@@ -0,0 +1,129 @@
+// 版本化哈希测试
+// 覆盖 kzg_to_versioned_hash 测试向量、十六进制解析以及交易哈希与 sidecar 承诺的校验
+
+use kzg::eip_4844::blob_to_kzg_commitment_rust;
+use kzg::G1;
+use rust_kzg_tutorial::{
+    blob::{blob_from_bytes, create_test_blob_bytes},
+    sidecar::{validate_transaction_sidecars, BeaconBlockHeader, BlobSidecar, SignedBeaconBlockHeader},
+    trusted_setup::load_trusted_setup_from_file,
+    versioned_hash::{
+        kzg_to_versioned_hash, parse_versioned_hash, validate_blob_versioned_hashes, VersionedHashError,
+        VERSIONED_HASH_VERSION_KZG,
+    },
+};
+
+/// 无穷远点 (全零 blob 的承诺) 的压缩表示
+fn identity_commitment() -> [u8; 48] {
+    let mut bytes = [0u8; 48];
+    bytes[0] = 0xc0;
+    bytes
+}
+
+fn generator_commitment() -> [u8; 48] {
+    hex::decode(
+        "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+    )
+    .unwrap()
+    .try_into()
+    .unwrap()
+}
+
+fn sidecar_with_commitment(index: u64, kzg_commitment: [u8; 48]) -> BlobSidecar {
+    BlobSidecar {
+        index,
+        blob: Vec::new(),
+        kzg_commitment,
+        kzg_proof: identity_commitment(),
+        signed_block_header: SignedBeaconBlockHeader {
+            message: BeaconBlockHeader {
+                slot: 1,
+                proposer_index: 0,
+                parent_root: [0u8; 32],
+                state_root: [0u8; 32],
+                body_root: [0u8; 32],
+            },
+            signature: vec![0u8; 96],
+        },
+        kzg_commitment_inclusion_proof: vec![[0u8; 32]; 17],
+    }
+}
+
+#[test]
+fn test_kzg_to_versioned_hash_vectors() {
+    assert_eq!(
+        hex::encode(kzg_to_versioned_hash(&identity_commitment())),
+        "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014"
+    );
+    assert_eq!(
+        hex::encode(kzg_to_versioned_hash(&generator_commitment())),
+        "01cf478a431837728dcec3461f4f53b8749cdc4e03496dcaed459dea82b82eb8"
+    );
+
+    // 与实际计算出的承诺保持一致
+    let settings = load_trusted_setup_from_file().unwrap();
+    let blob = blob_from_bytes(&create_test_blob_bytes(0)).unwrap();
+    let commitment = blob_to_kzg_commitment_rust(&blob, &settings).unwrap().to_bytes();
+    let hash = kzg_to_versioned_hash(&commitment);
+    assert_eq!(hash[0], VERSIONED_HASH_VERSION_KZG);
+    assert_eq!(sidecar_with_commitment(0, commitment).versioned_hash(), hash);
+}
+
+#[test]
+fn test_parse_versioned_hash() {
+    let text = "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014";
+    let hash = parse_versioned_hash(text).unwrap();
+    assert_eq!(hash, kzg_to_versioned_hash(&identity_commitment()));
+    assert_eq!(parse_versioned_hash(&format!("0x{}", text)).unwrap(), hash);
+
+    assert!(matches!(parse_versioned_hash("0xzz"), Err(VersionedHashError::InvalidFormat(_))));
+    assert!(matches!(parse_versioned_hash(&text[2..]), Err(VersionedHashError::InvalidFormat(_))));
+}
+
+#[test]
+fn test_validate_blob_versioned_hashes() {
+    let commitments = [identity_commitment(), generator_commitment()];
+    let hashes: Vec<[u8; 32]> = commitments.iter().map(kzg_to_versioned_hash).collect();
+    assert_eq!(validate_blob_versioned_hashes(&hashes, &commitments), Ok(()));
+    assert_eq!(validate_blob_versioned_hashes(&[], &[]), Ok(()));
+
+    assert_eq!(
+        validate_blob_versioned_hashes(&hashes[..1], &commitments),
+        Err(VersionedHashError::CountMismatch { hashes: 1, commitments: 2 })
+    );
+
+    // 顺序必须一致
+    let swapped = [hashes[1], hashes[0]];
+    assert!(matches!(
+        validate_blob_versioned_hashes(&swapped, &commitments),
+        Err(VersionedHashError::Mismatch { index: 0, .. })
+    ));
+
+    let mut wrong_version = hashes.clone();
+    wrong_version[1][0] = 0x02;
+    assert_eq!(
+        validate_blob_versioned_hashes(&wrong_version, &commitments),
+        Err(VersionedHashError::UnsupportedVersion { index: 1, version: 0x02 })
+    );
+}
+
+#[test]
+fn test_validate_transaction_sidecars() {
+    let sidecars = vec![
+        sidecar_with_commitment(0, identity_commitment()),
+        sidecar_with_commitment(1, generator_commitment()),
+    ];
+    let hashes: Vec<[u8; 32]> = sidecars.iter().map(BlobSidecar::versioned_hash).collect();
+    assert_eq!(validate_transaction_sidecars(&hashes, &sidecars), Ok(()));
+
+    let mut tampered = hashes.clone();
+    tampered[1][31] ^= 1;
+    assert!(matches!(
+        validate_transaction_sidecars(&tampered, &sidecars),
+        Err(VersionedHashError::Mismatch { index: 1, .. })
+    ));
+    assert!(matches!(
+        validate_transaction_sidecars(&hashes, &sidecars[..1]),
+        Err(VersionedHashError::CountMismatch { hashes: 2, commitments: 1 })
+    ));
+}